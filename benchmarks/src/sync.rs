@@ -1,6 +1,6 @@
 use crate::random_txn;
 use actix::{Addr, System};
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use criterion::{BatchSize, Bencher};
 use libp2p::multiaddr::Multiaddr;
 use starcoin_bus::BusActor;
@@ -11,10 +11,7 @@ use starcoin_genesis::Genesis;
 use starcoin_network::{NetworkActor, NetworkAsyncService, RawRpcRequestMessage};
 use starcoin_network_api::NetworkService;
 use starcoin_sync::Downloader;
-use starcoin_sync::{
-    helper::{get_block_by_hash, get_hash_by_number},
-    ProcessActor,
-};
+use starcoin_sync::ProcessActor;
 use starcoin_sync_api::SyncMetadata;
 use starcoin_txpool::{TxPool, TxPoolService};
 use starcoin_wallet_api::WalletAccount;
@@ -43,7 +40,7 @@ impl SyncBencher {
                 create_node(None, Some((addr_1, network_1)), handle.clone())
                     .await
                     .unwrap();
-            let downloader = Arc::new(Downloader::new(chain_2.clone()));
+            let downloader = Arc::new(Downloader::new(chain_2.clone(), vec![]));
             SyncBencher::sync_block_inner(downloader, network_2)
                 .await
                 .unwrap();
@@ -67,58 +64,31 @@ impl SyncBencher {
     ) -> Result<()> {
         if let Some(best_peer) = network.best_peer().await? {
             if let Some(header) = downloader.get_chain_reader().master_head_header().await? {
-                let mut begin_number = header.number();
-
                 if let Some(hash_number) = Downloader::find_ancestor(
                     downloader.clone(),
                     best_peer.get_peer_id(),
                     network.clone(),
-                    begin_number,
+                    header.number(),
                     true,
                     true,
                 )
                 .await?
                 {
-                    begin_number = hash_number.number + 1;
-                    while let Some((get_hash_by_number_msg, end, next_number)) =
-                        Downloader::<DummyConsensus>::get_hash_by_number_msg_forward(
-                            network.clone(),
-                            best_peer.get_peer_id(),
-                            begin_number,
-                        )
+                    let ancestor = downloader
+                        .get_chain_reader()
+                        .get_header_by_hash(&hash_number.hash)
                         .await?
-                    {
-                        begin_number = next_number;
-                        let batch_hash_by_number_msg = get_hash_by_number(
-                            &network,
-                            best_peer.get_peer_id(),
-                            get_hash_by_number_msg,
-                        )
-                        .await?;
-
-                        Downloader::put_hash_2_hash_pool(
-                            downloader.clone(),
-                            best_peer.get_peer_id(),
-                            batch_hash_by_number_msg,
-                        );
-
-                        let hashs = Downloader::take_task_from_hash_pool(downloader.clone());
-                        if !hashs.is_empty() {
-                            let (headers, bodies, infos) =
-                                get_block_by_hash(&network, best_peer.get_peer_id(), hashs).await?;
-                            Downloader::do_blocks(
-                                downloader.clone(),
-                                headers.headers,
-                                bodies.bodies,
-                                infos.infos,
-                            )
-                            .await;
-                        }
-
-                        if end {
-                            break;
-                        }
-                    }
+                        .ok_or_else(|| {
+                            format_err!("Ancestor header {:?} is none.", hash_number.hash)
+                        })?;
+                    Downloader::sync_blocks_from_peers(
+                        downloader.clone(),
+                        network.clone(),
+                        best_peer.clone(),
+                        vec![best_peer],
+                        ancestor,
+                    )
+                    .await?;
                 }
             }
         }
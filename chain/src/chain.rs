@@ -9,16 +9,15 @@ use executor::block_executor::BlockExecutor;
 use logger::prelude::*;
 use starcoin_accumulator::node::AccumulatorStoreType;
 use starcoin_accumulator::{Accumulator, AccumulatorTreeStore, MerkleAccumulator};
-use starcoin_state_api::{ChainState, ChainStateReader, ChainStateWriter};
+use starcoin_state_api::{ChainState, ChainStateReader};
 use starcoin_statedb::ChainStateDB;
-use std::{convert::TryInto, marker::PhantomData, sync::Arc};
-use storage::Store;
+use std::{collections::BTreeMap, convert::TryInto, marker::PhantomData, sync::Arc};
+use storage::{BlockData, Store};
 use traits::{ChainReader, ChainWriter, Consensus, ExcludedTxns};
 use types::{
     account_address::AccountAddress,
     accumulator_info::AccumulatorInfo,
     block::{Block, BlockHeader, BlockInfo, BlockNumber, BlockState, BlockTemplate},
-    error::BlockExecutorError,
     transaction::{SignedUserTransaction, Transaction, TransactionInfo},
     U512,
 };
@@ -81,23 +80,23 @@ where
         Self::new(self.config.clone(), head_block_hash, self.storage.clone())
     }
 
-    pub fn save_block(&self, block: &Block, block_state: BlockState) {
-        if let Err(e) = self.storage.commit_block(block.clone(), block_state) {
-            error!("save block {:?} failed : {:?}", block.id(), e);
-        }
-    }
-
     fn get_block_info(&self, block_id: HashValue) -> Result<BlockInfo> {
         Ok(self
             .storage
             .get_block_info(block_id)?
             .ok_or_else(|| format_err!("Can not find block info by hash {}", block_id))?)
     }
-    pub fn save_block_info(&self, block_info: BlockInfo) {
-        let block_id = *block_info.block_id();
-        if let Err(e) = self.storage.save_block_info(block_info) {
-            error!("save block info {:?} failed : {:?}", block_id, e);
-        }
+
+    /// Save the block and its data by one atomic write batch, then switch head to the block.
+    fn commit_block_data(&mut self, block_data: BlockData) -> Result<()> {
+        let block = block_data.block.clone();
+        let block_id = block.id();
+        self.storage.commit_block_data(block_data)?;
+        self.head = block;
+        self.chain_state =
+            ChainStateDB::new(self.storage.clone(), Some(self.head.header().state_root()));
+        debug!("save block {:?} succ.", block_id);
+        Ok(())
     }
 
     pub fn create_block_template_inner(
@@ -374,15 +373,6 @@ where
             "verify block: txn accumulator root mismatch"
         );
 
-        // If chain state is matched, and accumulator is matched,
-        // then, we save flush states, and save block data.
-        self.txn_accumulator
-            .flush()
-            .map_err(|_err| BlockExecutorError::BlockAccumulatorFlushErr)?;
-        self.chain_state
-            .flush()
-            .map_err(BlockExecutorError::BlockChainStateErr)?;
-
         let total_difficulty = {
             let pre_total_difficulty = self
                 .get_block_info(block.header().parent_hash())?
@@ -391,7 +381,6 @@ where
         };
 
         self.block_accumulator.append(&[block.id()])?;
-        self.block_accumulator.flush()?;
         let txn_accumulator_info: AccumulatorInfo = (&self.txn_accumulator).try_into()?;
         let block_accumulator_info: AccumulatorInfo = (&self.block_accumulator).try_into()?;
         let block_info = BlockInfo::new_with_accumulator_info(
@@ -400,10 +389,30 @@ where
             block_accumulator_info,
             total_difficulty,
         );
-        // save block's transaction relationship and save transaction
-        self.save(header.id(), txns)?;
-        self.storage.save_transaction_infos(vec_transaction_info)?;
-        self.commit(block.clone(), block_info, BlockState::Executed)?;
+        // If chain state is matched, and accumulator is matched, then save the
+        // accumulator nodes, state nodes and block data in one atomic batch.
+        let accumulator_nodes = self
+            .txn_accumulator
+            .pop_unsaved_nodes()
+            .into_iter()
+            .map(|node| (AccumulatorStoreType::Transaction, node))
+            .chain(
+                self.block_accumulator
+                    .pop_unsaved_nodes()
+                    .into_iter()
+                    .map(|node| (AccumulatorStoreType::Block, node)),
+            )
+            .collect();
+        let state_nodes = self.chain_state.pop_unsaved_nodes();
+        self.commit_block_data(BlockData::new(
+            block.clone(),
+            BlockState::Executed,
+            block_info,
+            txns,
+            vec_transaction_info,
+            accumulator_nodes,
+            state_nodes,
+        ))?;
         Ok(true)
     }
 
@@ -413,14 +422,15 @@ where
         block_info: BlockInfo,
         block_state: BlockState,
     ) -> Result<()> {
-        let block_id = block.id();
-        self.save_block(&block, block_state);
-        self.head = block;
-        self.save_block_info(block_info);
-        self.chain_state =
-            ChainStateDB::new(self.storage.clone(), Some(self.head.header().state_root()));
-        debug!("save block {:?} succ.", block_id);
-        Ok(())
+        self.commit_block_data(BlockData::new(
+            block,
+            block_state,
+            block_info,
+            vec![],
+            vec![],
+            vec![],
+            BTreeMap::new(),
+        ))
     }
 
     fn save(&mut self, block_id: HashValue, transactions: Vec<Transaction>) -> Result<()> {
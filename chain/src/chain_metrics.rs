@@ -16,6 +16,7 @@ pub struct ChainMetrics {
     pub rollback_count: IntCounter,
     pub broadcast_head_count: IntCounter,
     pub verify_fail_count: IntCounter,
+    pub reject_reorg_count: IntCounter,
     pub exe_block_time: HistogramVec,
     pub branch_total_count: IntGauge,
 }
@@ -52,6 +53,12 @@ impl ChainMetrics {
         )
         .namespace(SC_NS))?;
 
+        let reject_reorg_count = register_int_counter!(Opts::new(
+            format!("{}{}", PRIFIX, "reject_reorg_count"),
+            "chain reject reorg count".to_string()
+        )
+        .namespace(SC_NS))?;
+
         let exe_block_time = register_histogram_vec!(
             HistogramOpts::new(
                 format!("{}{}", PRIFIX, "exe_block_time"),
@@ -73,6 +80,7 @@ impl ChainMetrics {
             rollback_count,
             broadcast_head_count,
             verify_fail_count,
+            reject_reorg_count,
             exe_block_time,
             branch_total_count,
         })
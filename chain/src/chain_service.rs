@@ -93,6 +93,51 @@ where
         Ok((block_exist, block_chain))
     }
 
+    /// Refuse the block if it conflicts with a checkpoint, or if it forks from master below
+    /// the latest checkpoint or deeper than the max reorg depth.
+    fn verify_fork(&self, header: &BlockHeader) -> Result<ConnectResult<()>> {
+        let checkpoints = self.config.checkpoints();
+        if checkpoints.iter().any(|checkpoint| {
+            checkpoint.number == header.number() && checkpoint.hash != header.id()
+        }) {
+            return Ok(ConnectResult::Err(ConnectBlockError::CheckpointConflict(
+                header.number(),
+            )));
+        }
+
+        let master_header = self.get_master().current_header();
+        if header.parent_hash() == master_header.id() {
+            return Ok(ConnectResult::Ok(()));
+        }
+        let ancestor = self
+            .storage
+            .get_common_ancestor(header.parent_hash(), master_header.id())?
+            .ok_or_else(|| {
+                format_err!(
+                    "Can not find ancestor with {:?} and {:?}.",
+                    header.parent_hash(),
+                    master_header.id()
+                )
+            })?;
+        let fork_number = self
+            .storage
+            .get_block_header_by_hash(ancestor)?
+            .ok_or_else(|| format_err!("Can not find block header {:?}.", ancestor))?
+            .number();
+        let below_checkpoint = checkpoints.iter().any(|checkpoint| {
+            checkpoint.number <= master_header.number() && fork_number < checkpoint.number
+        });
+        if below_checkpoint
+            || master_header.number() - fork_number > self.config.sync.max_reorg_depth()
+        {
+            CHAIN_METRICS.reject_reorg_count.inc();
+            return Ok(ConnectResult::Err(ConnectBlockError::ReorgTooDeep(
+                fork_number,
+            )));
+        }
+        Ok(ConnectResult::Ok(()))
+    }
+
     pub fn block_exist(&self, block_id: HashValue) -> bool {
         if let Ok(Some(_)) = self.storage.get_block_info(block_id) {
             true
@@ -285,6 +330,9 @@ where
                     CHAIN_METRICS.duplicate_conn_count.inc();
                     Ok(ConnectResult::Err(ConnectBlockError::DuplicateConn))
                 } else if let Some(mut branch) = fork {
+                    if let Err(e) = self.verify_fork(block.header())? {
+                        return Ok(ConnectResult::Err(e));
+                    }
                     let timer = CHAIN_METRICS
                         .exe_block_time
                         .with_label_values(&["time"])
@@ -339,6 +387,9 @@ where
                         CHAIN_METRICS.duplicate_conn_count.inc();
                         Ok(ConnectResult::Err(ConnectBlockError::DuplicateConn))
                     } else if let Some(mut branch) = fork {
+                        if let Err(e) = self.verify_fork(block.header())? {
+                            return Ok(ConnectResult::Err(e));
+                        }
                         if C::verify_header(self.config.clone(), &branch, block.header()).is_ok() {
                             // 2. commit block
                             if pivot_flag {
@@ -416,6 +467,10 @@ where
         self.storage.get_block_info(hash)
     }
 
+    fn verify_header(&self, header: &BlockHeader) -> Result<()> {
+        C::verify_header(self.config.clone(), self.get_master(), header)
+    }
+
     fn create_block_template(
         &self,
         author: AccountAddress,
@@ -171,6 +171,10 @@ where
             ChainRequest::GetTransactionIdByBlock(block_id) => Ok(
                 ChainResponse::VecTransactionInfo(self.service.get_block_txn_ids(block_id)?),
             ),
+            ChainRequest::VerifyHeader(header) => {
+                self.service.verify_header(&header)?;
+                Ok(ChainResponse::None)
+            }
         }
     }
 }
@@ -308,6 +312,18 @@ where
         Ok(None)
     }
 
+    async fn verify_header(self, header: BlockHeader) -> Result<()> {
+        if let ChainResponse::None = self
+            .address
+            .send(ChainRequest::VerifyHeader(Box::new(header)))
+            .await??
+        {
+            Ok(())
+        } else {
+            bail!("verify header error.")
+        }
+    }
+
     async fn master_head_header(self) -> Result<Option<BlockHeader>> {
         if let Ok(ChainResponse::BlockHeader(header)) =
             self.address.send(ChainRequest::CurrentHeader()).await?
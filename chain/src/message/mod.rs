@@ -32,6 +32,7 @@ pub enum ChainRequest {
     GetTransactionIdByBlock(HashValue),
     GetBlocksByNumber(Option<BlockNumber>, u64),
     GetBlockStateByHash(HashValue),
+    VerifyHeader(Box<BlockHeader>),
 }
 
 impl Message for ChainRequest {
@@ -15,7 +15,20 @@ use types::{
 
 //TODO implement Mock service
 #[derive(Clone, Default)]
-pub struct MockChainService;
+pub struct MockChainService {
+    head_header: Option<BlockHeader>,
+    head_block_info: Option<BlockInfo>,
+}
+
+impl MockChainService {
+    /// Mock a chain with the given head, `head_block_info` None mock a broken storage.
+    pub fn new_with_head(head_header: BlockHeader, head_block_info: Option<BlockInfo>) -> Self {
+        Self {
+            head_header: Some(head_header),
+            head_block_info,
+        }
+    }
+}
 
 #[async_trait::async_trait]
 impl ChainAsyncService for MockChainService {
@@ -43,12 +56,18 @@ impl ChainAsyncService for MockChainService {
         unimplemented!()
     }
 
-    async fn get_block_info_by_hash(self, _hash: &HashValue) -> Result<Option<BlockInfo>> {
+    async fn get_block_info_by_hash(self, hash: &HashValue) -> Result<Option<BlockInfo>> {
+        Ok(self
+            .head_block_info
+            .filter(|block_info| block_info.block_id() == hash))
+    }
+
+    async fn verify_header(self, _header: BlockHeader) -> Result<()> {
         unimplemented!()
     }
 
     async fn master_head_header(self) -> Result<Option<BlockHeader>> {
-        unimplemented!()
+        Ok(self.head_header)
     }
 
     async fn master_head_block(self) -> Result<Option<Block>> {
@@ -1,24 +1,31 @@
 use crate::{test_helper, BlockChain, ChainActor, ChainActorRef, ChainAsyncService, SyncMetadata};
 use anyhow::Result;
 use bus::BusActor;
-use config::NodeConfig;
+use config::{Checkpoint, NodeConfig, StarcoinOpt};
 use consensus::dev::{DevConsensus, DummyHeader};
+use crypto::HashValue;
 use futures_timer::Delay;
 use logger::prelude::*;
 use starcoin_genesis::Genesis;
 use starcoin_wallet_api::WalletAccount;
 use std::{sync::Arc, time::Duration};
 use storage::{cache_storage::CacheStorage, storage::StorageInstance, Storage};
-use traits::{ChainReader, ChainWriter, Consensus};
+use traits::{ChainReader, ChainWriter, ConnectBlockError, Consensus};
 use txpool::TxPool;
-use types::U256;
+use types::{block::Block, U256};
 
 async fn gen_master_chain(
     times: u64,
     delay: bool,
 ) -> (ChainActorRef<DevConsensus>, Arc<NodeConfig>) {
-    let node_config = NodeConfig::random_for_test();
-    let node_config = Arc::new(node_config);
+    gen_master_chain_with_config(Arc::new(NodeConfig::random_for_test()), times, delay).await
+}
+
+async fn gen_master_chain_with_config(
+    node_config: Arc<NodeConfig>,
+    times: u64,
+    delay: bool,
+) -> (ChainActorRef<DevConsensus>, Arc<NodeConfig>) {
     let storage =
         Arc::new(Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap());
     let genesis = Genesis::build(node_config.net()).unwrap();
@@ -131,6 +138,71 @@ async fn test_block_chain_forks() {
     )
 }
 
+async fn gen_fork_block(chain: &ChainActorRef<DevConsensus>, parent_hash: HashValue) -> Block {
+    let miner_account = WalletAccount::random();
+    chain
+        .clone()
+        .create_block_template(
+            *miner_account.address(),
+            Some(miner_account.get_auth_key().prefix().to_vec()),
+            Some(parent_hash),
+            Vec::new(),
+        )
+        .await
+        .unwrap()
+        .unwrap()
+        .into_block(DummyHeader {}, U256::max_value())
+}
+
+#[actix_rt::test]
+async fn test_checkpoint_conflict() {
+    ::logger::init_for_test();
+    let mut opt = StarcoinOpt::default();
+    opt.checkpoints = vec![Checkpoint::new(2, HashValue::random())];
+    let config = NodeConfig::load_with_opt(&opt).unwrap();
+    let (chain, _) = gen_master_chain_with_config(Arc::new(config), 1, false).await;
+    let head = chain.clone().master_head_header().await.unwrap().unwrap();
+
+    let block = gen_fork_block(&chain, head.id()).await;
+    assert_eq!(block.header().number(), 2);
+    match chain.clone().try_connect(block).await.unwrap() {
+        Err(ConnectBlockError::CheckpointConflict(number)) => assert_eq!(number, 2),
+        result => panic!("Unexpected connect result: {:?}", result),
+    }
+    assert_eq!(
+        chain.master_head_header().await.unwrap().unwrap().id(),
+        head.id()
+    );
+}
+
+#[actix_rt::test]
+async fn test_reorg_too_deep() {
+    ::logger::init_for_test();
+    let times = 5;
+    let mut opt = StarcoinOpt::default();
+    opt.max_reorg_depth = Some(2);
+    let config = NodeConfig::load_with_opt(&opt).unwrap();
+    let (chain, _) = gen_master_chain_with_config(Arc::new(config), times, false).await;
+    let head = chain.clone().master_head_header().await.unwrap().unwrap();
+    assert_eq!(head.number(), times);
+
+    // fork from block 1 rolls back 4 master blocks.
+    let fork_point = chain.clone().master_block_by_number(1).await.unwrap();
+    let block = gen_fork_block(&chain, fork_point.id()).await;
+    match chain.clone().try_connect(block).await.unwrap() {
+        Err(ConnectBlockError::ReorgTooDeep(number)) => assert_eq!(number, 1),
+        result => panic!("Unexpected connect result: {:?}", result),
+    }
+
+    // fork from block 4 rolls back 1 master block.
+    let fork_point = chain.clone().master_block_by_number(4).await.unwrap();
+    let block = gen_fork_block(&chain, fork_point.id()).await;
+    if let Err(ConnectBlockError::ReorgTooDeep(_)) = chain.clone().try_connect(block).await.unwrap()
+    {
+        panic!("Fork within the max reorg depth should not be refused.");
+    }
+}
+
 #[stest::test]
 async fn test_chain_apply() -> Result<()> {
     let config = Arc::new(NodeConfig::random_for_test());
@@ -1,52 +1,208 @@
 use anyhow::{format_err, Result};
 
+use serde::Serialize;
 use starcoin_executor::executor::Executor;
 use starcoin_executor::TransactionExecutor;
+use starcoin_logger::prelude::*;
 use starcoin_rpc_client::{RemoteStateReader, RpcClient};
 use starcoin_state_api::AccountStateReader;
 use starcoin_types::{account_address::AccountAddress, transaction::RawUserTransaction};
 use starcoin_wallet_api::WalletAccount;
+use std::collections::VecDeque;
+
+#[derive(Clone, Debug)]
+pub struct FundRequest {
+    pub amount: u64,
+    pub receiver: AccountAddress,
+    pub auth_key: Vec<u8>,
+}
+
+/// Max times to retry a fund request whose transfer failed.
+const MAX_FUND_RETRIES: u32 = 3;
+
+struct QueuedRequest {
+    request: FundRequest,
+    retries: u32,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FaucetStatus {
+    pub address: AccountAddress,
+    pub balance: u64,
+    pub max_amount: u64,
+    pub queue_size: usize,
+    pub next_sequence_number: Option<u64>,
+}
+
+/// Keep the faucet account's sequence number locally, so transfers submitted in a batch do not
+/// reuse the same sequence number. It re-syncs from the txpool/chain after a failed submit.
+#[derive(Default)]
+struct NonceManager {
+    next: Option<u64>,
+}
+
+impl NonceManager {
+    fn next(&mut self, client: &RpcClient, address: AccountAddress) -> Result<u64> {
+        let next = match self.next {
+            Some(next) => next,
+            None => Self::sync(client, address)?,
+        };
+        self.next = Some(next + 1);
+        Ok(next)
+    }
+
+    fn sync(client: &RpcClient, address: AccountAddress) -> Result<u64> {
+        if let Some(seq_num) = client.next_sequence_number_in_txpool(address)? {
+            return Ok(seq_num);
+        }
+        let chain_state_reader = RemoteStateReader::new(client);
+        let account_state_reader = AccountStateReader::new(&chain_state_reader);
+        let account_resource = account_state_reader
+            .get_account_resource(&address)?
+            .ok_or_else(|| format_err!("Can not find account on chain by address:{}", address))?;
+        Ok(account_resource.sequence_number())
+    }
+
+    fn reset(&mut self) {
+        self.next = None;
+    }
+}
 
 pub struct Faucet {
     client: RpcClient,
     faucet_account: WalletAccount,
+    max_amount: u64,
+    max_queue_size: usize,
+    queue: VecDeque<QueuedRequest>,
+    nonce_manager: NonceManager,
 }
 
 impl Faucet {
-    pub fn new(client: RpcClient, faucet_account: WalletAccount) -> Self {
+    pub fn new(
+        client: RpcClient,
+        faucet_account: WalletAccount,
+        max_amount: u64,
+        max_queue_size: usize,
+    ) -> Self {
         Faucet {
             client,
             faucet_account,
+            max_amount,
+            max_queue_size,
+            queue: VecDeque::new(),
+            nonce_manager: NonceManager::default(),
         }
     }
 
-    pub fn transfer(
-        &self,
-        amount: u64,
-        receiver: AccountAddress,
-        auth_key: Vec<u8>,
-    ) -> Result<bool> {
-        let chain_state_reader = RemoteStateReader::new(&self.client);
-        let account_state_reader = AccountStateReader::new(&chain_state_reader);
-        let account_resource = account_state_reader
-            .get_account_resource(self.faucet_account.address())?
-            .ok_or_else(|| {
-                format_err!(
-                    "Can not find account on chain by address:{}",
-                    self.faucet_account.address()
-                )
-            })?;
+    pub fn max_amount(&self) -> u64 {
+        self.max_amount
+    }
+
+    pub fn queue_size(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.queue.len() >= self.max_queue_size
+    }
+
+    /// Push a fund request to the queue, return the position in queue.
+    pub fn enqueue(&mut self, request: FundRequest) -> Result<usize> {
+        if request.amount == 0 || request.amount > self.max_amount {
+            return Err(format_err!(
+                "Invalid amount {}, should in (0, {}]",
+                request.amount,
+                self.max_amount
+            ));
+        }
+        if self.is_full() {
+            return Err(format_err!("Faucet queue is full, please retry later."));
+        }
+        self.queue.push_back(QueuedRequest {
+            request,
+            retries: 0,
+        });
+        Ok(self.queue.len())
+    }
+
+    /// Sign and submit at most `batch_size` queued requests with consecutive sequence numbers,
+    /// return the number of submitted transactions.
+    pub fn process_batch(&mut self, batch_size: usize) -> usize {
+        let mut submitted = 0;
+        while submitted < batch_size {
+            let mut queued = match self.queue.pop_front() {
+                Some(queued) => queued,
+                None => break,
+            };
+            match self.transfer(&queued.request) {
+                Ok(()) => submitted += 1,
+                Err(e) => {
+                    // the local sequence number may be out of date, sync it before next transfer.
+                    self.nonce_manager.reset();
+                    if queued.retries < MAX_FUND_RETRIES {
+                        warn!(
+                            "Faucet transfer {:?} failed: {:?}, retry it later.",
+                            queued.request, e
+                        );
+                        queued.retries += 1;
+                        self.queue.push_front(queued);
+                    } else {
+                        error!(
+                            "Faucet transfer {:?} failed {} times, drop it: {:?}",
+                            queued.request,
+                            queued.retries + 1,
+                            e
+                        );
+                    }
+                    break;
+                }
+            }
+        }
+        if submitted > 0 {
+            info!(
+                "Faucet submitted {} txns, remain {} in queue.",
+                submitted,
+                self.queue.len()
+            );
+        }
+        submitted
+    }
 
+    fn transfer(&mut self, request: &FundRequest) -> Result<()> {
+        let seq_num = self
+            .nonce_manager
+            .next(&self.client, *self.faucet_account.address())?;
         let raw_tx = transfer_tx(
             &self.faucet_account,
-            amount,
-            receiver,
-            account_resource.sequence_number(),
-            auth_key,
+            request.amount,
+            request.receiver,
+            seq_num,
+            request.auth_key.clone(),
         );
         let signed_tx = self.client.wallet_sign_txn(raw_tx)?;
-        let ret = self.client.submit_transaction(signed_tx)?;
-        Ok(ret)
+        if !self.client.submit_transaction(signed_tx)? {
+            return Err(format_err!(
+                "Submit txn with sequence number {} is rejected by txpool.",
+                seq_num
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn status(&self) -> Result<FaucetStatus> {
+        let address = *self.faucet_account.address();
+        let chain_state_reader = RemoteStateReader::new(&self.client);
+        let account_state_reader = AccountStateReader::new(&chain_state_reader);
+        let balance = account_state_reader
+            .get_balance(&address)?
+            .unwrap_or_default();
+        Ok(FaucetStatus {
+            address,
+            balance,
+            max_amount: self.max_amount,
+            queue_size: self.queue.len(),
+            next_sequence_number: self.nonce_manager.next,
+        })
     }
 }
 
@@ -1,4 +1,5 @@
 pub mod faucet;
+pub mod limiter;
 pub mod web;
 
 #[macro_export]
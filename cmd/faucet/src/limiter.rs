@@ -0,0 +1,243 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use starcoin_logger::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should after unix epoch.")
+        .as_secs()
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BucketConfig {
+    /// Max tokens in the bucket, is the max burst requests.
+    pub capacity: u64,
+    /// Seconds to refill one token.
+    pub refill_secs: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TokenBucket {
+    tokens: u64,
+    last_refill: u64,
+}
+
+impl TokenBucket {
+    pub fn new(config: &BucketConfig, now: u64) -> Self {
+        Self {
+            tokens: config.capacity,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, config: &BucketConfig, now: u64) {
+        if config.refill_secs == 0 {
+            self.tokens = config.capacity;
+            self.last_refill = now;
+            return;
+        }
+        let elapsed = now.saturating_sub(self.last_refill);
+        let new_tokens = elapsed / config.refill_secs;
+        if new_tokens > 0 {
+            self.tokens = std::cmp::min(config.capacity, self.tokens.saturating_add(new_tokens));
+            self.last_refill += new_tokens * config.refill_secs;
+        }
+        if self.tokens == config.capacity {
+            self.last_refill = now;
+        }
+    }
+
+    pub fn available(&mut self, config: &BucketConfig, now: u64) -> bool {
+        self.refill(config, now);
+        self.tokens > 0
+    }
+
+    pub fn try_take(&mut self, config: &BucketConfig, now: u64) -> bool {
+        if self.available(config, now) {
+            self.tokens -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Give back a token taken by `try_take`.
+    pub fn refund(&mut self, config: &BucketConfig, now: u64) {
+        self.refill(config, now);
+        self.tokens = std::cmp::min(config.capacity, self.tokens.saturating_add(1));
+    }
+
+    /// A full bucket is same as a new bucket, so it is no need to keep it.
+    fn is_full(&mut self, config: &BucketConfig, now: u64) -> bool {
+        self.refill(config, now);
+        self.tokens >= config.capacity
+    }
+}
+
+#[derive(Default, Debug, Serialize, Deserialize)]
+struct LimiterState {
+    address: HashMap<String, TokenBucket>,
+    ip: HashMap<String, TokenBucket>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LimitKind {
+    Address,
+    IP,
+}
+
+/// Per-address and per-ip token bucket rate limiter, the buckets are persisted to a json file,
+/// so restart the faucet can not reset the limits.
+pub struct RateLimiter {
+    address_config: BucketConfig,
+    ip_config: BucketConfig,
+    store_path: Option<PathBuf>,
+    state: LimiterState,
+}
+
+impl RateLimiter {
+    pub fn new(
+        address_config: BucketConfig,
+        ip_config: BucketConfig,
+        store_path: Option<PathBuf>,
+    ) -> Result<Self> {
+        let state = match store_path.as_ref() {
+            Some(path) if path.exists() => {
+                let content = std::fs::read(path)?;
+                serde_json::from_slice(content.as_slice())?
+            }
+            _ => LimiterState::default(),
+        };
+        Ok(Self {
+            address_config,
+            ip_config,
+            store_path,
+            state,
+        })
+    }
+
+    /// Take a token from both the address bucket and the ip bucket.
+    /// No token is taken if any of the bucket is empty, and the empty one is returned.
+    pub fn try_acquire(&mut self, address: &str, ip: &str) -> Result<Option<LimitKind>> {
+        let now = now_secs();
+        let address_config = self.address_config;
+        let ip_config = self.ip_config;
+        let address_bucket = self
+            .state
+            .address
+            .entry(address.to_string())
+            .or_insert_with(|| TokenBucket::new(&address_config, now));
+        if !address_bucket.available(&address_config, now) {
+            return Ok(Some(LimitKind::Address));
+        }
+        let ip_bucket = self
+            .state
+            .ip
+            .entry(ip.to_string())
+            .or_insert_with(|| TokenBucket::new(&ip_config, now));
+        if !ip_bucket.try_take(&ip_config, now) {
+            return Ok(Some(LimitKind::IP));
+        }
+        self.state
+            .address
+            .get_mut(address)
+            .expect("address bucket must exist.")
+            .try_take(&address_config, now);
+        self.persist()?;
+        Ok(None)
+    }
+
+    /// Give back the tokens taken by a successful `try_acquire`, when the request is not served.
+    pub fn release(&mut self, address: &str, ip: &str) -> Result<()> {
+        let now = now_secs();
+        if let Some(bucket) = self.state.address.get_mut(address) {
+            bucket.refund(&self.address_config, now);
+        }
+        if let Some(bucket) = self.state.ip.get_mut(ip) {
+            bucket.refund(&self.ip_config, now);
+        }
+        self.persist()
+    }
+
+    fn persist(&mut self) -> Result<()> {
+        let now = now_secs();
+        let address_config = self.address_config;
+        let ip_config = self.ip_config;
+        self.state
+            .address
+            .retain(|_, bucket| !bucket.is_full(&address_config, now));
+        self.state
+            .ip
+            .retain(|_, bucket| !bucket.is_full(&ip_config, now));
+        if let Some(path) = self.store_path.as_ref() {
+            let tmp_path = path.with_extension("tmp");
+            std::fs::write(&tmp_path, serde_json::to_vec(&self.state)?)?;
+            std::fs::rename(&tmp_path, path)?;
+            debug!(
+                "Persist faucet limiter, address: {}, ip: {}",
+                self.state.address.len(),
+                self.state.ip.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let config = BucketConfig {
+            capacity: 2,
+            refill_secs: 10,
+        };
+        let mut bucket = TokenBucket::new(&config, 100);
+        assert!(bucket.try_take(&config, 100));
+        assert!(bucket.try_take(&config, 101));
+        assert!(!bucket.try_take(&config, 105));
+        assert!(bucket.try_take(&config, 110));
+        assert!(!bucket.try_take(&config, 119));
+        assert!(bucket.try_take(&config, 120));
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let address_config = BucketConfig {
+            capacity: 1,
+            refill_secs: 3600,
+        };
+        let ip_config = BucketConfig {
+            capacity: 2,
+            refill_secs: 3600,
+        };
+        let mut limiter = RateLimiter::new(address_config, ip_config, None).unwrap();
+        assert_eq!(limiter.try_acquire("a", "ip1").unwrap(), None);
+        assert_eq!(
+            limiter.try_acquire("a", "ip1").unwrap(),
+            Some(LimitKind::Address)
+        );
+        assert_eq!(limiter.try_acquire("b", "ip1").unwrap(), None);
+        assert_eq!(
+            limiter.try_acquire("c", "ip1").unwrap(),
+            Some(LimitKind::IP)
+        );
+        assert_eq!(limiter.try_acquire("c", "ip2").unwrap(), None);
+
+        limiter.release("c", "ip2").unwrap();
+        assert_eq!(limiter.try_acquire("c", "ip2").unwrap(), None);
+        limiter.release("d", "ip3").unwrap();
+        assert_eq!(
+            limiter.try_acquire("a", "ip3").unwrap(),
+            Some(LimitKind::Address)
+        );
+    }
+}
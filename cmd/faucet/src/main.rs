@@ -1,12 +1,16 @@
 use futures::executor;
+use starcoin_faucet::limiter::{BucketConfig, RateLimiter};
 use starcoin_faucet::{faucet::Faucet, web};
 use starcoin_rpc_client::RpcClient;
 use starcoin_types::account_address::AccountAddress;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 use structopt::StructOpt;
 use tiny_http::Server;
 
+const DEFAULT_LIMITER_STORE: &str = "faucet_limiter.json";
+
 #[derive(Debug, Clone, StructOpt, Default)]
 #[structopt(name = "starcoin", about = "Starcoin")]
 pub struct FaucetOpt {
@@ -16,6 +20,36 @@ pub struct FaucetOpt {
     pub server_addr: String,
     #[structopt(long, short = "d")]
     pub faucet_address: String,
+    #[structopt(long, default_value = "10000000")]
+    /// Max amount of one fund request.
+    pub max_amount: u64,
+    #[structopt(long, default_value = "1")]
+    /// Max fund requests per address in a burst.
+    pub address_capacity: u64,
+    #[structopt(long, default_value = "86400")]
+    /// Seconds to restore one fund request for an address.
+    pub address_refill_secs: u64,
+    #[structopt(long, default_value = "10")]
+    /// Max fund requests per ip in a burst.
+    pub ip_capacity: u64,
+    #[structopt(long, default_value = "3600")]
+    /// Seconds to restore one fund request for an ip.
+    pub ip_refill_secs: u64,
+    #[structopt(long, parse(from_os_str))]
+    /// File to persist the rate limit state, default is `faucet_limiter.json` in the data dir of the node, where the ipc file is.
+    pub limiter_store: Option<PathBuf>,
+    #[structopt(long)]
+    /// Use the first ip in X-Forwarded-For header as client ip, only enable it behind a trusted proxy.
+    pub trust_forwarded_for: bool,
+    #[structopt(long, default_value = "100")]
+    /// Max pending fund requests in queue.
+    pub max_queue_size: usize,
+    #[structopt(long, default_value = "20")]
+    /// Max txns submitted in one batch.
+    pub batch_size: usize,
+    #[structopt(long, default_value = "1000")]
+    /// Interval between two batches in milliseconds.
+    pub batch_interval: u64,
 }
 
 fn main() {
@@ -25,13 +59,41 @@ fn main() {
         AccountAddress::from_str(&opts.faucet_address).expect("Invalid faucet address");
     let server = Server::http(&opts.server_addr)
         .unwrap_or_else(|_| panic!("Faild to serve on {}", opts.server_addr));
+    let limiter_store = opts.limiter_store.clone().unwrap_or_else(|| {
+        opts.ipc_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(DEFAULT_LIMITER_STORE)
+    });
     let client = RpcClient::connect_ipc(opts.ipc_path).expect("Failed to connect ipc");
     let account = client
         .wallet_get(account_address)
         .unwrap()
         .expect("Invaid faucet account address");
-    let faucet = Faucet::new(client, account);
-    let fut = web::run(server, faucet);
+    let faucet = Faucet::new(client, account, opts.max_amount, opts.max_queue_size);
+    let limiter = RateLimiter::new(
+        BucketConfig {
+            capacity: opts.address_capacity,
+            refill_secs: opts.address_refill_secs,
+        },
+        BucketConfig {
+            capacity: opts.ip_capacity,
+            refill_secs: opts.ip_refill_secs,
+        },
+        Some(limiter_store),
+    )
+    .expect("Failed to load faucet limiter");
+    let batch_config = web::BatchConfig {
+        batch_size: opts.batch_size,
+        interval: Duration::from_millis(opts.batch_interval),
+    };
+    let fut = web::run(
+        server,
+        faucet,
+        limiter,
+        batch_config,
+        opts.trust_forwarded_for,
+    );
     println!(
         "Faucet serve on: {}, with faucet account: {}",
         opts.server_addr, opts.faucet_address
@@ -1,3 +1,5 @@
+use crate::faucet::FundRequest;
+use crate::limiter::{LimitKind, RateLimiter};
 use crate::{faucet::Faucet, unwrap_or_return};
 use anyhow::Result;
 use ascii::AsciiString;
@@ -7,7 +9,8 @@ use starcoin_types::account_address::AccountAddress;
 use std::fmt::{Debug, Formatter};
 use std::io::Cursor;
 use std::str::FromStr;
-use tiny_http::{Header, Response, Server};
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Request, Response, Server};
 
 #[derive(RustEmbed)]
 #[folder = "src/static/"]
@@ -27,53 +30,152 @@ fn response_custom(status_code: u16, data: &str) -> Response<Cursor<String>> {
         .with_header(Header::from_str("Access-Control-Allow-Origin: *").unwrap())
 }
 
-async fn handle_fund(faucet: &Faucet, query: &str) -> Response<Cursor<String>> {
+#[derive(Clone, Copy, Debug)]
+pub struct BatchConfig {
+    /// Max txns submitted in one batch.
+    pub batch_size: usize,
+    /// Interval between two batches.
+    pub interval: Duration,
+}
+
+fn client_ip(request: &Request, trust_forwarded_for: bool) -> String {
+    if trust_forwarded_for {
+        let forwarded_ip = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("X-Forwarded-For"))
+            .and_then(|header| header.value.as_str().split(',').next())
+            .map(|ip| ip.trim().to_string());
+        if let Some(ip) = forwarded_ip {
+            return ip;
+        }
+    }
+    request.remote_addr().ip().to_string()
+}
+
+async fn handle_fund(
+    faucet: &mut Faucet,
+    limiter: &mut RateLimiter,
+    query: &str,
+    ip: &str,
+) -> Response<Cursor<String>> {
     let query_param =
         unwrap_or_return!(parse_query(query), response_custom(400, "Invalid request"));
-    info!("Fund query params: {:?}", query_param);
-    let ret = unwrap_or_return!(
-        faucet.transfer(
-            query_param.amount,
-            query_param.address,
-            query_param.auth_key
-        ),
+    info!("Fund query params: {:?}, ip: {}", query_param, ip);
+    if query_param.amount == 0 || query_param.amount > faucet.max_amount() {
+        return response_custom(
+            400,
+            format!("Invalid amount, max amount is {}", faucet.max_amount()).as_str(),
+        );
+    }
+    if faucet.is_full() {
+        return response_custom(503, "Faucet queue is full, please retry later.");
+    }
+    let limited = unwrap_or_return!(
+        limiter.try_acquire(query_param.address.to_string().as_str(), ip),
         response_custom(500, "Inner error")
     );
-    if !ret {
-        return response_custom(400, "Fund too frequently");
+    match limited {
+        Some(LimitKind::Address) => {
+            return response_custom(429, "Fund too frequently for this address")
+        }
+        Some(LimitKind::IP) => return response_custom(429, "Fund too frequently from this ip"),
+        None => {}
     }
-    response_custom(200, "Success")
+    let position = match faucet.enqueue(FundRequest {
+        amount: query_param.amount,
+        receiver: query_param.address,
+        auth_key: query_param.auth_key,
+    }) {
+        Ok(position) => position,
+        Err(e) => {
+            if let Err(release_err) = limiter.release(query_param.address.to_string().as_str(), ip)
+            {
+                error!("Release faucet limiter failed: {:?}", release_err);
+            }
+            return response_custom(503, e.to_string().as_str());
+        }
+    };
+    response_custom(
+        200,
+        format!("Success, queue position: {}", position).as_str(),
+    )
 }
 
-pub async fn run(server: Server, faucet: Faucet) {
-    for request in server.incoming_requests() {
-        let pos = request
-            .url()
-            .find('?')
-            .unwrap_or_else(|| request.url().len());
-        let url = &request.url()[..pos];
-        let query = request.url()[pos..].trim_start_matches('?');
-        match url {
-            "/" => {
-                let response = Response::from_string(index_html()).with_header(Header {
-                    field: "Content-Type".parse().unwrap(),
-                    value: AsciiString::from_ascii("text/html; charset=utf8").unwrap(),
-                });
+fn handle_status(faucet: &Faucet) -> Response<Cursor<String>> {
+    let status = unwrap_or_return!(faucet.status(), response_custom(500, "Inner error"));
+    let data = unwrap_or_return!(
+        serde_json::to_string(&status),
+        response_custom(500, "Inner error")
+    );
+    response_custom(200, data.as_str())
+        .with_header(Header::from_str("Content-Type: application/json").unwrap())
+}
 
-                request.respond(response).unwrap();
-            }
-            "/api/fund" => {
-                let resp = handle_fund(&faucet, query).await;
-                //todo:: handle io error
-                request.respond(resp).unwrap();
-            }
-            _ => {
-                let _ = request.respond(response_custom(404, "Not found"));
+pub async fn run(
+    server: Server,
+    mut faucet: Faucet,
+    mut limiter: RateLimiter,
+    batch_config: BatchConfig,
+    trust_forwarded_for: bool,
+) {
+    let mut last_batch = Instant::now();
+    loop {
+        let request = match server.recv_timeout(batch_config.interval) {
+            Ok(request) => request,
+            Err(e) => {
+                error!("Faucet server receive request error: {:?}", e);
+                None
             }
         };
+        if let Some(request) = request {
+            handle_request(request, &mut faucet, &mut limiter, trust_forwarded_for).await;
+        }
+        if faucet.queue_size() >= batch_config.batch_size
+            || last_batch.elapsed() >= batch_config.interval
+        {
+            faucet.process_batch(batch_config.batch_size);
+            last_batch = Instant::now();
+        }
     }
 }
 
+async fn handle_request(
+    request: Request,
+    faucet: &mut Faucet,
+    limiter: &mut RateLimiter,
+    trust_forwarded_for: bool,
+) {
+    let pos = request
+        .url()
+        .find('?')
+        .unwrap_or_else(|| request.url().len());
+    let url = request.url()[..pos].to_string();
+    let query = request.url()[pos..].trim_start_matches('?').to_string();
+    match url.as_str() {
+        "/" => {
+            let response = Response::from_string(index_html()).with_header(Header {
+                field: "Content-Type".parse().unwrap(),
+                value: AsciiString::from_ascii("text/html; charset=utf8").unwrap(),
+            });
+
+            request.respond(response).unwrap();
+        }
+        "/api/fund" => {
+            let ip = client_ip(&request, trust_forwarded_for);
+            let resp = handle_fund(faucet, limiter, query.as_str(), ip.as_str()).await;
+            //todo:: handle io error
+            request.respond(resp).unwrap();
+        }
+        "/api/status" => {
+            let _ = request.respond(handle_status(faucet));
+        }
+        _ => {
+            let _ = request.respond(response_custom(404, "Not found"));
+        }
+    };
+}
+
 struct QueryParam {
     address: AccountAddress,
     amount: u64,
@@ -25,7 +25,7 @@ impl CommandAction for BranchesCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         client.chain_branches()
     }
 }
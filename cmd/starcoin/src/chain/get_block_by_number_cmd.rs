@@ -27,7 +27,7 @@ impl CommandAction for GetBlockByNumberCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let opt = ctx.opt();
         let block = client.chain_get_block_by_number(opt.number as u64)?;
 
@@ -28,7 +28,7 @@ impl CommandAction for GetBlockCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let opt = ctx.opt();
         let block = client.chain_get_block_by_hash(HashValue::from_hex(&opt.hash).unwrap())?;
 
@@ -28,7 +28,7 @@ impl CommandAction for GetTxnByBlockCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let opt = ctx.opt();
         let vec_transaction_info =
             client.chain_get_txn_by_block(HashValue::from_hex(&opt.hash).unwrap())?;
@@ -28,7 +28,7 @@ impl CommandAction for GetTransactionCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let opt = ctx.opt();
         let transaction_info =
             client.chain_get_transaction(HashValue::from_hex(&opt.hash).unwrap())?;
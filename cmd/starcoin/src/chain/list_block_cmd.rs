@@ -31,7 +31,7 @@ impl CommandAction for ListBlockCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let opt = ctx.opt();
         let blocks = client.chain_get_blocks_by_number(opt.number, opt.count)?;
         let block_view = blocks
@@ -12,7 +12,7 @@ use std::time::Duration;
 
 pub struct CliState {
     net: ChainNetwork,
-    client: RpcClient,
+    client: Option<RpcClient>,
     join_handle: Option<NodeHandle>,
     temp_dir: DataDirPath,
 }
@@ -23,18 +23,34 @@ impl CliState {
     pub fn new(net: ChainNetwork, client: RpcClient, join_handle: Option<NodeHandle>) -> CliState {
         Self {
             net,
-            client,
+            client: Some(client),
             join_handle,
             temp_dir: starcoin_config::temp_path(),
         }
     }
 
+    /// Create a state without node connection, only offline commands can be executed.
+    pub fn offline(net: ChainNetwork) -> CliState {
+        Self {
+            net,
+            client: None,
+            join_handle: None,
+            temp_dir: starcoin_config::temp_path(),
+        }
+    }
+
     pub fn net(&self) -> ChainNetwork {
         self.net
     }
 
-    pub fn client(&self) -> &RpcClient {
-        &self.client
+    pub fn client(&self) -> Result<&RpcClient> {
+        self.client.as_ref().ok_or_else(|| {
+            format_err!("This command require a node connection, but the cli is in offline mode.")
+        })
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.client.is_none()
     }
 
     pub fn temp_dir(&self) -> &Path {
@@ -42,7 +58,7 @@ impl CliState {
     }
 
     pub fn default_account(&self) -> Result<WalletAccount> {
-        self.client
+        self.client()?
             .wallet_default()?
             .ok_or_else(|| format_err!("Can not find default account, Please input from account."))
     }
@@ -52,7 +68,7 @@ impl CliState {
         account_address: Option<AccountAddress>,
     ) -> Result<WalletAccount> {
         if let Some(account_address) = account_address {
-            self.client.wallet_get(account_address)?.ok_or_else(|| {
+            self.client()?.wallet_get(account_address)?.ok_or_else(|| {
                 format_err!("Can not find WalletAccount by address: {}", account_address)
             })
         } else {
@@ -60,9 +76,17 @@ impl CliState {
         }
     }
 
+    /// The given gas price, or the median of the node's gas price suggestion.
+    pub fn gas_price_or_suggestion(&self, gas_price: Option<u64>) -> Result<u64> {
+        match gas_price {
+            Some(gas_price) => Ok(gas_price),
+            None => Ok(self.client()?.gas_price_suggestion()?.median),
+        }
+    }
+
     pub fn watch_txn(&self, txn_hash: HashValue) -> Result<()> {
         let block = self
-            .client
+            .client()?
             .watch_txn(txn_hash, Some(Self::DEFAULT_WATCH_TIMEOUT))?;
         println!(
             "txn mined in block hight: {}, hash: {:#x}",
@@ -72,7 +96,7 @@ impl CliState {
         Ok(())
     }
 
-    pub fn into_inner(self) -> (ChainNetwork, RpcClient, Option<NodeHandle>) {
+    pub fn into_inner(self) -> (ChainNetwork, Option<RpcClient>, Option<NodeHandle>) {
         (self.net, self.client, self.join_handle)
     }
 }
@@ -0,0 +1,43 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::db::resolve_db_path;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::migration::{MigrationRegistry, MigrationReport};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Migrate the db schema to the current version.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "migrate")]
+pub struct MigrateOpt {
+    #[structopt(long = "db-path", parse(from_os_str))]
+    /// path of the rocksdb dir, default is the db of the node data dir.
+    db_path: Option<PathBuf>,
+
+    #[structopt(long = "dry-run")]
+    /// only report what would change, do not modify the db.
+    dry_run: bool,
+}
+
+pub struct MigrateCommand;
+
+impl CommandAction for MigrateCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = MigrateOpt;
+    type ReturnItem = MigrationReport;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let db_path = resolve_db_path(ctx.state(), ctx.global_opt(), opt.db_path.as_ref())?;
+        DBStorage::migrate(db_path, &MigrationRegistry::new(), opt.dry_run)
+    }
+}
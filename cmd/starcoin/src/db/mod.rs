@@ -0,0 +1,32 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{ensure, Result};
+use starcoin_config::NodeConfig;
+use starcoin_storage::db_storage::DBStorage;
+use std::path::PathBuf;
+
+mod migrate_cmd;
+mod verify_cmd;
+
+pub use migrate_cmd::*;
+pub use verify_cmd::*;
+
+/// Db commands operate the db files directly, so the node must be stopped,
+/// and the cli should run in offline mode.
+pub(crate) fn resolve_db_path(
+    state: &CliState,
+    global_opt: &StarcoinOpt,
+    db_path: Option<&PathBuf>,
+) -> Result<PathBuf> {
+    ensure!(
+        state.is_offline(),
+        "db command should run in offline mode, please stop the node and use `starcoin -c offline`."
+    );
+    Ok(match db_path {
+        Some(db_path) => db_path.clone(),
+        None => DBStorage::db_path(NodeConfig::load_with_opt(global_opt)?.storage.dir()),
+    })
+}
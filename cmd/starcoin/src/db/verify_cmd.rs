@@ -0,0 +1,61 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::db::resolve_db_path;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::verify::{StorageVerifier, VerifyOptions, VerifyReport};
+use starcoin_storage::Storage;
+use std::path::PathBuf;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// Verify the consistency of the chain data in db.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "verify")]
+pub struct VerifyOpt {
+    #[structopt(long = "db-path", parse(from_os_str))]
+    /// path of the rocksdb dir, default is the db of the node data dir.
+    db_path: Option<PathBuf>,
+
+    #[structopt(long = "state-sample-interval", default_value = "1000")]
+    /// verify the state tree of every N blocks, the head state is always verified, 0 means only the head.
+    state_sample_interval: u64,
+
+    #[structopt(long = "repair")]
+    /// rebuild the broken derived indexes, the db is opened in read-only mode without this flag.
+    repair: bool,
+}
+
+pub struct VerifyCommand;
+
+impl CommandAction for VerifyCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = VerifyOpt;
+    type ReturnItem = VerifyReport;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let db_path = resolve_db_path(ctx.state(), ctx.global_opt(), opt.db_path.as_ref())?;
+        let db = DBStorage::open(db_path, !opt.repair)?;
+        let storage = Arc::new(Storage::new(StorageInstance::new_db_instance(Arc::new(
+            db,
+        )))?);
+        StorageVerifier::new(
+            storage,
+            VerifyOptions {
+                state_sample_interval: opt.state_sample_interval,
+                repair: opt.repair,
+            },
+        )
+        .verify()
+    }
+}
@@ -65,7 +65,7 @@ impl CommandAction for GenTxnCommand {
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
         let opt = ctx.opt();
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let net = ctx.state().net();
         if !net.is_dev() && !net.is_halley() {
             bail!("This command only work for dev or halley network");
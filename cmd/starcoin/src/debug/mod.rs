@@ -4,8 +4,10 @@
 mod gen_txn_cmd;
 mod log_cmd;
 mod panic_cmd;
+mod trace_txn_cmd;
 
 pub use gen_txn_cmd::*;
 pub use log_cmd::*;
 pub use panic_cmd::*;
 pub use panic_cmd::*;
+pub use trace_txn_cmd::*;
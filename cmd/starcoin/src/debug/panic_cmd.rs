@@ -29,7 +29,7 @@ impl CommandAction for PanicCommand {
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
         let opt = ctx.opt();
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let net = ctx.state().net();
         if !net.is_dev() {
             bail!("This command only work for dev network");
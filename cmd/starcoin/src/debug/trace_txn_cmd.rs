@@ -0,0 +1,37 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::view::TransactionTraceView;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_crypto::HashValue;
+use structopt::StructOpt;
+
+///Re-execute a committed txn, show its status, abort location, resources read and written.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "trace_txn")]
+pub struct TraceTxnOpt {
+    #[structopt(name = "hash")]
+    hash: String,
+}
+
+pub struct TraceTxnCommand;
+
+impl CommandAction for TraceTxnCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = TraceTxnOpt;
+    type ReturnItem = TransactionTraceView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client()?;
+        let opt = ctx.opt();
+        let trace = client.debug_trace_transaction(HashValue::from_hex(&opt.hash)?)?;
+        Ok(trace.into())
+    }
+}
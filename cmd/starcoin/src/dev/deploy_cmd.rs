@@ -30,10 +30,9 @@ pub struct DeployOpt {
         short = "p",
         long = "gas-price",
         name = "price of gas",
-        default_value = "1",
-        help = "gas price used to deploy the module"
+        help = "gas price used to deploy the module, default to the suggested gas price of the node"
     )]
-    gas_price: u64,
+    gas_price: Option<u64>,
 
     #[structopt(
         name = "expiration_time",
@@ -83,7 +82,8 @@ impl CommandAction for DeployCommand {
         let module_address = *compiled_module.address();
         // from libra address to our address
         let module_address = AccountAddress::new(module_address.into());
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
+        let gas_price = ctx.state().gas_price_or_suggestion(opt.gas_price)?;
         let chain_state_reader = RemoteStateReader::new(client);
         let account_state_reader = AccountStateReader::new(&chain_state_reader);
         let account_resource = account_state_reader.get_account_resource(&module_address)?;
@@ -103,7 +103,7 @@ impl CommandAction for DeployCommand {
             account_resource.sequence_number(),
             Module::new(bytecode),
             opt.max_gas_amount,
-            opt.gas_price,
+            gas_price,
             expiration_time,
         );
 
@@ -56,10 +56,9 @@ pub struct ExecuteOpt {
         short = "p",
         long = "gas-price",
         name = "price of gas",
-        default_value = "1",
-        help = "gas price used to execute the script"
+        help = "gas price used to execute the script, default to the suggested gas price of the node"
     )]
-    gas_price: u64,
+    gas_price: Option<u64>,
     #[structopt(
         short = "b",
         name = "blocking-mode",
@@ -110,7 +109,8 @@ impl CommandAction for ExecuteCommand {
 
         let args = opt.args.clone();
 
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
+        let gas_price = ctx.state().gas_price_or_suggestion(opt.gas_price)?;
         let chain_state_reader = RemoteStateReader::new(client);
         let account_state_reader = AccountStateReader::new(&chain_state_reader);
         let account_resource = account_state_reader.get_account_resource(&sender)?;
@@ -125,7 +125,7 @@ impl CommandAction for ExecuteCommand {
             account_resource.sequence_number(),
             Script::new(bytecode, opt.type_tags.clone(), args),
             opt.max_gas_amount,
-            opt.gas_price,
+            gas_price,
             expiration_time,
         );
 
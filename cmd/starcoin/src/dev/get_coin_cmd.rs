@@ -47,7 +47,7 @@ impl CommandAction for GetCoinCommand {
                 net
             );
         }
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let to = client.wallet_default()?.ok_or_else(|| {
             format_err!("Can not find default account, Please create account first.")
         })?;
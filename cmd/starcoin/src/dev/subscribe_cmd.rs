@@ -61,7 +61,7 @@ impl CommandAction for SubscribeEventCommand {
             limit: ctx.opt().limit,
         };
 
-        let event_stream = ctx.state().client().subscribe_events(filter)?;
+        let event_stream = ctx.state().client()?.subscribe_events(filter)?;
         println!("Subscribe successful, Press `q` and Enter to quit");
         blocking_display_notification(event_stream, |evt| {
             serde_json::to_string(&evt).expect("should never fail")
@@ -84,7 +84,7 @@ impl CommandAction for SubscribeBlockCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let event_stream = ctx.state().client().subscribe_new_blocks()?;
+        let event_stream = ctx.state().client()?.subscribe_new_blocks()?;
         println!("Subscribe successful, Press `q` and Enter to quit");
         blocking_display_notification(event_stream, |evt| {
             serde_json::to_string(&evt).expect("should never fail")
@@ -105,7 +105,7 @@ impl CommandAction for SubscribeNewTxnCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let event_stream = ctx.state().client().subscribe_new_transactions()?;
+        let event_stream = ctx.state().client()?.subscribe_new_transactions()?;
         println!("Subscribe successful, Press `q` and Enter to quit");
         blocking_display_notification(event_stream, |evt| {
             serde_json::to_string(&evt).expect("should never fail")
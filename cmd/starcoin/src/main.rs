@@ -11,11 +11,13 @@ use starcoin_rpc_client::RpcClient;
 mod chain;
 mod cli_state;
 mod crash_handler;
+mod db;
 mod debug;
 mod dev;
 mod helper;
 mod node;
 mod state;
+mod txn;
 mod view;
 mod wallet;
 
@@ -26,6 +28,10 @@ fn run() -> Result<()> {
             info!("Starcoin opts: {:?}", opt);
             let connect = opt.connect.as_ref().unwrap_or(&Connect::IPC(None));
             let (client, node_handle) = match connect {
+                Connect::Offline => {
+                    info!("Run in offline mode, do not connect to any node.");
+                    return Ok(CliState::offline(opt.net.unwrap_or_default()));
+                }
                 Connect::IPC(ipc_file) => {
                     if let Some(ipc_file) = ipc_file {
                         info!("Try to connect node by ipc: {:?}", ipc_file);
@@ -95,7 +101,8 @@ fn run() -> Result<()> {
                 .subcommand(wallet::SignTxnCommand)
                 .subcommand(wallet::UnlockCommand)
                 .subcommand(wallet::ExportCommand)
-                .subcommand(wallet::ImportCommand),
+                .subcommand(wallet::ImportCommand)
+                .subcommand(wallet::ChangePasswordCommand),
         )
         .command(
             Command::with_name("state")
@@ -107,8 +114,10 @@ fn run() -> Result<()> {
         .command(
             Command::with_name("node")
                 .subcommand(node::InfoCommand)
+                .subcommand(node::HealthCommand)
                 .subcommand(node::PeersCommand)
-                .subcommand(node::MetricsCommand),
+                .subcommand(node::MetricsCommand)
+                .subcommand(node::NetworkStateCommand),
         )
         .command(
             Command::with_name("chain")
@@ -120,6 +129,12 @@ fn run() -> Result<()> {
                 .subcommand(chain::GetBlockCommand)
                 .subcommand(chain::BranchesCommand),
         )
+        .command(
+            Command::with_name("txn")
+                .subcommand(txn::BuildCommand)
+                .subcommand(txn::SignCommand)
+                .subcommand(txn::SubmitCommand),
+        )
         .command(
             Command::with_name("dev")
                 .subcommand(dev::GetCoinCommand)
@@ -133,11 +148,17 @@ fn run() -> Result<()> {
                         .subcommand(dev::SubscribeNewTxnCommand),
                 ),
         )
+        .command(
+            Command::with_name("db")
+                .subcommand(db::MigrateCommand)
+                .subcommand(db::VerifyCommand),
+        )
         .command(
             Command::with_name("debug")
                 .subcommand(debug::LogLevelCommand)
                 .subcommand(debug::GenTxnCommand)
-                .subcommand(debug::PanicCommand),
+                .subcommand(debug::PanicCommand)
+                .subcommand(debug::TraceTxnCommand),
         )
         .exec();
     Ok(())
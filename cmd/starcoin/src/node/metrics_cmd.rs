@@ -24,7 +24,7 @@ impl CommandAction for MetricsCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let node_info = client.node_metrics()?;
         Ok(node_info)
     }
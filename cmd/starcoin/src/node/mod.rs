@@ -1,10 +1,14 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+mod health_cmd;
 mod info_cmd;
 mod metrics_cmd;
+mod network_state_cmd;
 mod peers_cmd;
 
+pub use health_cmd::*;
 pub use info_cmd::*;
 pub use metrics_cmd::*;
+pub use network_state_cmd::*;
 pub use peers_cmd::*;
@@ -0,0 +1,31 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_rpc_api::node::NetworkState;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "network-state")]
+pub struct NetworkStateOpt {}
+
+pub struct NetworkStateCommand;
+
+impl CommandAction for NetworkStateCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = NetworkStateOpt;
+    type ReturnItem = NetworkState;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client()?;
+        let state = client.node_network_state()?;
+        Ok(state)
+    }
+}
@@ -24,7 +24,7 @@ impl CommandAction for PeersCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let peers = client.node_peers()?;
         Ok(peers)
     }
@@ -28,7 +28,7 @@ impl CommandAction for GetCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let opt = ctx.opt();
         let state = client
             .state_get(AccessPath::new_for_account(opt.account_address))?
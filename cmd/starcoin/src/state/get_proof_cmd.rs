@@ -29,7 +29,7 @@ impl CommandAction for GetProofCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let opt = ctx.opt();
         let proof =
             client.state_get_with_proof(AccessPath::new_for_account(opt.account_address))?;
@@ -24,7 +24,7 @@ impl CommandAction for GetRootCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let root = client.state_get_state_root().unwrap();
 
         Ok(root)
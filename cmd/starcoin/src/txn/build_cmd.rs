@@ -0,0 +1,192 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::txn::write_txn_file;
+use crate::StarcoinOpt;
+use anyhow::{bail, format_err, Result};
+use scmd::{CommandAction, ExecContext};
+use serde::Serialize;
+use starcoin_crypto::hash::{HashValue, PlainCryptoHash};
+use starcoin_crypto::{ed25519::Ed25519PublicKey, ValidCryptoMaterialStringExt};
+use starcoin_executor::executor::Executor;
+use starcoin_rpc_client::RemoteStateReader;
+use starcoin_state_api::AccountStateReader;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::transaction::authenticator::AuthenticationKey;
+use starcoin_types::transaction::{
+    parse_as_transaction_argument, RawUserTransaction, Script, TransactionArgument,
+};
+use starcoin_vm_types::account_config::stc_type_tag;
+use starcoin_vm_types::{language_storage::TypeTag, parser::parse_type_tag};
+use std::path::PathBuf;
+use std::time::Duration;
+use structopt::StructOpt;
+
+/// Build an unsigned txn and write it to file.
+/// In offline mode, `sender`, `sequence-number` and `gas-price` must be provided.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "build")]
+pub struct BuildOpt {
+    #[structopt(short = "s", long = "sender")]
+    /// if `sender` is absent, use default account of the connected node.
+    sender: Option<AccountAddress>,
+
+    #[structopt(long = "sequence-number")]
+    /// if `sequence-number` is absent, get it from the connected node.
+    sequence_number: Option<u64>,
+
+    #[structopt(short = "r", long = "receiver", conflicts_with = "script")]
+    /// build a transfer txn to the receiver.
+    receiver: Option<AccountAddress>,
+
+    #[structopt(short = "k", long = "public-key")]
+    /// public key of the receiver, required if the receiver not exist on chain.
+    public_key: Option<String>,
+
+    #[structopt(short = "v", long = "amount", default_value = "0")]
+    /// amount of the transfer txn.
+    amount: u64,
+
+    #[structopt(
+    short = "c",
+    long = "coin",
+    name = "coin_type",
+    help = "coin's type tag of the transfer txn, for example: 0x0::STC::T, default is STC",
+    parse(try_from_str = parse_type_tag)
+    )]
+    coin_type: Option<TypeTag>,
+
+    #[structopt(long = "script", name = "script", parse(from_os_str))]
+    /// build a script txn by the script bytecode file.
+    script_file: Option<PathBuf>,
+
+    #[structopt(
+    short = "t",
+    long = "type_tag",
+    name = "type-tag",
+    help = "can specify multi type_tag",
+    parse(try_from_str = parse_type_tag)
+    )]
+    type_tags: Vec<TypeTag>,
+
+    #[structopt(long = "arg", name = "transaction-args", help = "can specify multi arg", parse(try_from_str = parse_as_transaction_argument))]
+    args: Vec<TransactionArgument>,
+
+    #[structopt(
+        name = "expiration_time",
+        long = "timeout",
+        default_value = "3000",
+        help = "how long(in seconds) the txn stay alive"
+    )]
+    expiration_time: u64,
+
+    #[structopt(
+        short = "g",
+        long = "max-gas",
+        name = "max-gas-amount",
+        default_value = "1000000",
+        help = "max gas used to execute the txn"
+    )]
+    max_gas_amount: u64,
+
+    #[structopt(
+        short = "p",
+        long = "gas-price",
+        name = "price of gas",
+        help = "gas price used, default to the suggested gas price of the node"
+    )]
+    gas_price: Option<u64>,
+
+    #[structopt(name = "file", parse(from_os_str))]
+    /// file to write the unsigned txn.
+    file: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TxnFileView {
+    pub file: PathBuf,
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub raw_txn_hash: HashValue,
+}
+
+pub struct BuildCommand;
+
+impl CommandAction for BuildCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = BuildOpt;
+    type ReturnItem = TxnFileView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let state = ctx.state();
+        let sender = match opt.sender {
+            Some(sender) => sender,
+            None => state.default_account()?.address,
+        };
+        let sequence_number = match opt.sequence_number {
+            Some(sequence_number) => sequence_number,
+            None => {
+                let chain_state_reader = RemoteStateReader::new(state.client()?);
+                let account_state_reader = AccountStateReader::new(&chain_state_reader);
+                account_state_reader
+                    .get_account_resource(&sender)?
+                    .ok_or_else(|| format_err!("address {} not exists on chain", sender))?
+                    .sequence_number()
+            }
+        };
+        let gas_price = state.gas_price_or_suggestion(opt.gas_price)?;
+        let raw_txn = match (opt.receiver, opt.script_file.as_ref()) {
+            (Some(receiver), None) => {
+                let receiver_auth_key_prefix = match opt.public_key.as_ref() {
+                    Some(public_key) => AuthenticationKey::ed25519(
+                        &Ed25519PublicKey::from_encoded_string(public_key)?,
+                    )
+                    .prefix()
+                    .to_vec(),
+                    None => vec![],
+                };
+                Executor::build_transfer_txn_by_coin_type(
+                    sender,
+                    receiver,
+                    receiver_auth_key_prefix,
+                    sequence_number,
+                    opt.amount,
+                    gas_price,
+                    opt.max_gas_amount,
+                    opt.coin_type.clone().unwrap_or_else(stc_type_tag),
+                    Duration::from_secs(opt.expiration_time),
+                )
+            }
+            (None, Some(script_file)) => {
+                let bytecode = std::fs::read(script_file)?;
+                if let Err(e) =
+                    starcoin_vm_types::file_format::CompiledScript::deserialize(bytecode.as_slice())
+                {
+                    bail!("invalid bytecode file, cannot deserialize as script, {}", e);
+                }
+                RawUserTransaction::new_script(
+                    sender,
+                    sequence_number,
+                    Script::new(bytecode, opt.type_tags.clone(), opt.args.clone()),
+                    opt.max_gas_amount,
+                    gas_price,
+                    Duration::from_secs(opt.expiration_time),
+                )
+            }
+            _ => bail!("one of receiver or script should be provided"),
+        };
+        write_txn_file(opt.file.as_path(), &raw_txn)?;
+        Ok(TxnFileView {
+            file: opt.file.clone(),
+            sender,
+            sequence_number,
+            raw_txn_hash: raw_txn.crypto_hash(),
+        })
+    }
+}
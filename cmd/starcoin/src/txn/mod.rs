@@ -0,0 +1,31 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline transaction workflow: `build` a raw txn to file, `sign` it by a local keystore
+//! without node, then `submit` the signed txn file by a networked node.
+
+use anyhow::{format_err, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::Path;
+
+mod build_cmd;
+mod sign_cmd;
+mod submit_cmd;
+
+pub use build_cmd::*;
+pub use sign_cmd::*;
+pub use submit_cmd::*;
+
+/// Txn file content is the hex encoded scs bytes, so it can be copied by text.
+pub(crate) fn write_txn_file<T: Serialize>(path: &Path, txn: &T) -> Result<()> {
+    let bytes = scs::to_bytes(txn)?;
+    std::fs::write(path, hex::encode(bytes))?;
+    Ok(())
+}
+
+pub(crate) fn read_txn_file<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let content = std::fs::read_to_string(path)?;
+    let bytes = hex::decode(content.trim())
+        .map_err(|e| format_err!("invalid txn file {:?}, {}", path, e))?;
+    scs::from_bytes(bytes.as_slice())
+}
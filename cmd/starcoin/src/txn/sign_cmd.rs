@@ -0,0 +1,72 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::txn::{read_txn_file, write_txn_file};
+use crate::view::TransactionView;
+use crate::StarcoinOpt;
+use anyhow::{bail, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
+use starcoin_wallet_api::Wallet;
+use starcoin_wallet_lib::file_wallet_store::FileWalletStore;
+use starcoin_wallet_lib::keystore_wallet::KeyStoreWallet;
+use std::path::PathBuf;
+use std::time::Duration;
+use structopt::StructOpt;
+
+/// Sign a unsigned txn file by the local keystore, without node connection.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "sign")]
+pub struct SignOpt {
+    #[structopt(long = "keystore", parse(from_os_str))]
+    /// the account vault dir which contains the sender's encrypted key.
+    keystore: PathBuf,
+
+    #[structopt(short = "P", long = "password", default_value = "")]
+    /// password of the sender account.
+    password: String,
+
+    #[structopt(name = "raw-txn-file", parse(from_os_str))]
+    /// the unsigned txn file built by `txn build`.
+    raw_txn_file: PathBuf,
+
+    #[structopt(name = "signed-txn-file", parse(from_os_str))]
+    /// file to write the signed txn.
+    signed_txn_file: PathBuf,
+}
+
+pub struct SignCommand;
+
+impl CommandAction for SignCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = SignOpt;
+    type ReturnItem = TransactionView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        if !opt.keystore.is_dir() {
+            bail!("keystore dir {:?} not exists", opt.keystore);
+        }
+        let raw_txn: RawUserTransaction = read_txn_file(opt.raw_txn_file.as_path())?;
+        let sender = raw_txn.sender();
+        let wallet = KeyStoreWallet::new(FileWalletStore::new(opt.keystore.as_path()))?;
+        if !wallet.contains(&sender)? {
+            bail!(
+                "can not find sender {} in keystore {:?}",
+                sender,
+                opt.keystore
+            );
+        }
+        wallet.unlock_account(sender, opt.password.as_str(), Duration::from_secs(60))?;
+        let signed_txn = wallet.sign_txn(raw_txn);
+        wallet.lock_account(sender)?;
+        let signed_txn: SignedUserTransaction = signed_txn?;
+        write_txn_file(opt.signed_txn_file.as_path(), &signed_txn)?;
+        Ok(signed_txn.into())
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::txn::read_txn_file;
+use crate::view::TransactionView;
+use crate::StarcoinOpt;
+use anyhow::{bail, Result};
+use scmd::{CommandAction, ExecContext};
+use starcoin_crypto::hash::PlainCryptoHash;
+use starcoin_types::transaction::SignedUserTransaction;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Submit a signed txn file to the connected node.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "submit")]
+pub struct SubmitOpt {
+    #[structopt(
+        short = "b",
+        name = "blocking-mode",
+        long = "blocking",
+        help = "blocking wait txn mined"
+    )]
+    blocking: bool,
+
+    #[structopt(name = "signed-txn-file", parse(from_os_str))]
+    /// the signed txn file produced by `txn sign`.
+    signed_txn_file: PathBuf,
+}
+
+pub struct SubmitCommand;
+
+impl CommandAction for SubmitCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = SubmitOpt;
+    type ReturnItem = TransactionView;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client()?;
+        let signed_txn: SignedUserTransaction = read_txn_file(opt.signed_txn_file.as_path())?;
+        if signed_txn.clone().check_signature().is_err() {
+            bail!("invalid signature of txn in {:?}", opt.signed_txn_file);
+        }
+        let txn_hash = signed_txn.crypto_hash();
+        if !client.submit_transaction(signed_txn.clone())? {
+            bail!("txn {:#x} is rejected by node", txn_hash);
+        }
+        if opt.blocking {
+            ctx.state().watch_txn(txn_hash)?;
+        }
+        Ok(signed_txn.into())
+    }
+}
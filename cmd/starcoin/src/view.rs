@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 use starcoin_crypto::{hash::PlainCryptoHash, HashValue};
 use starcoin_state_api::StateWithProof;
 use starcoin_types::block::Block;
+use starcoin_types::transaction::{AbortLocation, TransactionTrace};
+use starcoin_types::write_set::WriteOp;
 use starcoin_types::{account_address::AccountAddress, transaction::SignedUserTransaction};
 use starcoin_wallet_api::WalletAccount;
 use std::collections::HashMap;
@@ -84,3 +86,88 @@ impl From<SignedUserTransaction> for TransactionView {
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransactionTraceView {
+    pub txn_hash: HashValue,
+    pub block_id: Option<HashValue>,
+    pub state_root: Option<HashValue>,
+    pub kept: bool,
+    pub gas_used: u64,
+    pub status: String,
+    pub status_message: Option<String>,
+    pub abort_location: Option<String>,
+    pub reads: Vec<String>,
+    pub writes: Vec<String>,
+    pub events: Vec<String>,
+}
+
+impl From<TransactionTrace> for TransactionTraceView {
+    fn from(trace: TransactionTrace) -> Self {
+        let status = match trace.sub_status {
+            Some(sub_status) => format!("{:?}({})", trace.major_status, sub_status),
+            None => format!("{:?}", trace.major_status),
+        };
+        Self {
+            txn_hash: trace.txn_hash,
+            block_id: trace.block_id,
+            state_root: trace.state_root,
+            kept: trace.kept,
+            gas_used: trace.gas_used,
+            status,
+            status_message: trace.status_message,
+            abort_location: trace.abort_location.as_ref().map(format_abort_location),
+            reads: trace
+                .reads
+                .iter()
+                .map(|access_path| {
+                    format!(
+                        "{}/{}",
+                        access_path.address,
+                        hex::encode(access_path.path.as_slice())
+                    )
+                })
+                .collect(),
+            writes: trace
+                .writes
+                .iter()
+                .map(|(access_path, write_op)| {
+                    let op = match write_op {
+                        WriteOp::Value(value) => format!("write {} bytes", value.len()),
+                        WriteOp::Deletion => "delete".to_string(),
+                    };
+                    format!(
+                        "{}/{} {}",
+                        access_path.address,
+                        hex::encode(access_path.path.as_slice()),
+                        op
+                    )
+                })
+                .collect(),
+            events: trace
+                .events
+                .iter()
+                .map(|event| {
+                    format!(
+                        "{:#x} seq {} {:?}: {}",
+                        event.key(),
+                        event.sequence_number(),
+                        event.type_tag(),
+                        hex::encode(event.event_data())
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+fn format_abort_location(location: &AbortLocation) -> String {
+    let mut result = format!("{:?}", location.phase);
+    if let (Some(module), Some(function)) = (&location.module, &location.function) {
+        result.push_str(&format!(" {}::{}", module, function));
+    }
+    if let Some(code_offset) = location.code_offset {
+        result.push_str(&format!(" at code offset {}", code_offset));
+    }
+    result
+}
@@ -31,10 +31,9 @@ pub struct AcceptCoinOpt {
         short = "p",
         long = "gas-price",
         name = "price of gas",
-        default_value = "1",
-        help = "gas price used to deploy the module"
+        help = "gas price used to deploy the module, default to the suggested gas price of the node"
     )]
-    gas_price: u64,
+    gas_price: Option<u64>,
 
     #[structopt(
     name = "coin_type",
@@ -65,7 +64,8 @@ impl CommandAction for AcceptCoinCommand {
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
         let opt = ctx.opt();
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
+        let gas_price = ctx.state().gas_price_or_suggestion(opt.gas_price)?;
 
         let sender = ctx.state().wallet_account_or_default(opt.sender.clone())?;
         let chain_state_reader = RemoteStateReader::new(client);
@@ -84,7 +84,7 @@ impl CommandAction for AcceptCoinCommand {
         let accept_coin_txn = Executor::build_accept_coin_txn(
             sender.address,
             account_resource.sequence_number(),
-            opt.gas_price,
+            gas_price,
             opt.max_gas_amount,
             opt.coin_type.clone(),
         );
@@ -0,0 +1,48 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use scmd::{CommandAction, ExecContext};
+use starcoin_types::account_address::AccountAddress;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "change-password")]
+pub struct ChangePasswordOpt {
+    #[structopt(short = "p", default_value = "", help = "the current password")]
+    password: String,
+    #[structopt(short = "n", default_value = "", help = "the new password")]
+    new_password: String,
+    #[structopt(
+        name = "account_address",
+        help = "The wallet account address to change password, if absent, use the default wallet."
+    )]
+    account_address: Option<AccountAddress>,
+}
+
+pub struct ChangePasswordCommand;
+
+impl CommandAction for ChangePasswordCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = ChangePasswordOpt;
+    type ReturnItem = String;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client()?;
+        let opt: &ChangePasswordOpt = ctx.opt();
+
+        let account = ctx.state().wallet_account_or_default(opt.account_address)?;
+        client.wallet_change_password(
+            account.address,
+            opt.password.clone(),
+            opt.new_password.clone(),
+        )?;
+        Ok(format!("password of account {} changed", account.address))
+    }
+}
@@ -27,7 +27,7 @@ impl CommandAction for CreateCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<WalletAccount> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let account = client.wallet_create(ctx.opt().password.clone())?;
         Ok(account)
     }
@@ -6,6 +6,7 @@ use crate::StarcoinOpt;
 use anyhow::{bail, Result};
 use scmd::{CommandAction, ExecContext};
 use starcoin_crypto::{ed25519, ValidCryptoMaterialStringExt};
+use starcoin_decrypt::{secret_storage::SecretStorage, KdfParams};
 use starcoin_types::account_address::AccountAddress;
 use std::convert::TryFrom;
 use std::path::PathBuf;
@@ -20,6 +21,11 @@ pub struct ExportOpt {
     password: String,
     #[structopt(short = "o", parse(from_os_str))]
     output_file: Option<PathBuf>,
+    #[structopt(
+        long = "keystore",
+        help = "export as the secret storage json, encrypted by the account password"
+    )]
+    keystore: bool,
 }
 
 pub struct ExportCommand;
@@ -31,14 +37,27 @@ impl CommandAction for ExportCommand {
     type ReturnItem = ();
 
     fn run(&self, ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>) -> Result<()> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let opt: &ExportOpt = ctx.opt();
         let data = client.wallet_export(opt.account_address, opt.password.clone())?;
         let private_key = ed25519::Ed25519PrivateKey::try_from(data.as_slice())?;
-        let encoded = private_key.to_encoded_string()?;
+        let encoded = if opt.keystore {
+            SecretStorage::encrypt(
+                opt.password.as_bytes(),
+                data.as_slice(),
+                KdfParams::default(),
+                Some(opt.account_address.to_string()),
+            )?
+            .to_json()?
+        } else {
+            private_key.to_encoded_string()?
+        };
         if let Some(output_file) = &opt.output_file {
             if output_file.exists() {
-                bail!("the output_file {} is already exists, please change a name");
+                bail!(
+                    "the output_file {} is already exists, please change a name",
+                    output_file.display()
+                );
             }
             std::fs::write(output_file, encoded.clone())?;
             println!("private key saved to {}", output_file.as_path().display());
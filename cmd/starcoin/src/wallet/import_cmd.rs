@@ -7,8 +7,10 @@ use anyhow::{bail, Result};
 use scmd::{CommandAction, ExecContext};
 use starcoin_crypto::ed25519::Ed25519PrivateKey;
 use starcoin_crypto::{PrivateKey, ValidCryptoMaterialStringExt};
+use starcoin_decrypt::secret_storage::SecretStorage;
 use starcoin_types::account_address::{self, AccountAddress};
 use starcoin_wallet_api::WalletAccount;
+use std::convert::TryFrom;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
@@ -29,6 +31,14 @@ pub struct ImportOpt {
     )]
     from_file: Option<PathBuf>,
 
+    #[structopt(
+        long = "keystore-file",
+        help = "file path of the secret storage json, decrypt it by the password",
+        parse(from_os_str),
+        conflicts_with_all(&["input", "from-file"])
+    )]
+    from_keystore_file: Option<PathBuf>,
+
     /// if account_address is absent, generate address by public_key.
     #[structopt(name = "account_address")]
     account_address: Option<AccountAddress>,
@@ -46,18 +56,28 @@ impl CommandAction for ImportCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let opt: &ImportOpt = ctx.opt();
 
-        let private_key = match (opt.from_input.as_ref(), opt.from_file.as_ref()) {
-            (Some(p), _) => Ed25519PrivateKey::from_encoded_string(p)?,
-            (None, Some(p)) => {
+        let private_key = match (
+            opt.from_input.as_ref(),
+            opt.from_file.as_ref(),
+            opt.from_keystore_file.as_ref(),
+        ) {
+            (Some(p), _, _) => Ed25519PrivateKey::from_encoded_string(p)?,
+            (None, Some(p), _) => {
                 let data = std::fs::read_to_string(p)?;
                 Ed25519PrivateKey::from_encoded_string(data.as_str())?
             }
-            (None, None) => {
-                bail!("private key should be specified, use one of <input>, <from-file>")
+            (None, None, Some(p)) => {
+                let data = std::fs::read_to_string(p)?;
+                let key =
+                    SecretStorage::from_json(data.as_str())?.decrypt(opt.password.as_bytes())?;
+                Ed25519PrivateKey::try_from(key.as_slice())?
             }
+            (None, None, None) => bail!(
+                "private key should be specified, use one of <input>, <from-file>, <keystore-file>"
+            ),
         };
 
         let address = opt
@@ -24,7 +24,7 @@ impl CommandAction for ListCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let accounts = client.wallet_list()?;
         Ok(accounts)
     }
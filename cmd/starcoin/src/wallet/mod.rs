@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod accept_coin_cmd;
+mod change_password_cmd;
 mod create_cmd;
 mod export_cmd;
 mod import_cmd;
@@ -12,6 +13,7 @@ mod transfer_cmd;
 mod unlock_cmd;
 
 pub use accept_coin_cmd::*;
+pub use change_password_cmd::*;
 pub use create_cmd::*;
 pub use export_cmd::*;
 pub use import_cmd::*;
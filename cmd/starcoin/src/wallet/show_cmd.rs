@@ -31,7 +31,7 @@ impl CommandAction for ShowCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let opt = ctx.opt();
         let account_address = if let Some(account_address) = opt.account_address {
             account_address
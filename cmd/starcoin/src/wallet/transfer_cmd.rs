@@ -14,8 +14,10 @@ use starcoin_state_api::AccountStateReader;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::language_storage::TypeTag;
 use starcoin_types::transaction::authenticator::AuthenticationKey;
+use starcoin_vm_runtime::common_transactions::DEFAULT_EXPIRATION_TIME;
 use starcoin_vm_types::account_config::stc_type_tag;
 use starcoin_vm_types::parser::parse_type_tag;
+use std::time::Duration;
 use structopt::StructOpt;
 
 //TODO this command should be a wallet sub command?
@@ -45,10 +47,9 @@ pub struct TransferOpt {
         short = "p",
         long = "gas-price",
         name = "price of gas",
-        default_value = "1",
-        help = "gas price used"
+        help = "gas price used, default to the suggested gas price of the node"
     )]
-    gas_price: u64,
+    gas_price: Option<u64>,
 
     #[structopt(
     short = "c",
@@ -80,8 +81,9 @@ impl CommandAction for TransferCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let opt = ctx.opt();
+        let gas_price = ctx.state().gas_price_or_suggestion(opt.gas_price)?;
         let sender = match opt.sender {
             Some(from) => client
                 .wallet_get(from)?
@@ -133,9 +135,10 @@ impl CommandAction for TransferCommand {
             receiver_auth_key_prefix,
             account_resource.sequence_number(),
             opt.amount,
-            opt.gas_price,
+            gas_price,
             opt.max_gas_amount,
             coin_type,
+            Duration::from_secs(DEFAULT_EXPIRATION_TIME),
         );
         let txn = client.wallet_sign_txn(raw_txn)?;
         client.submit_transaction(txn.clone())?;
@@ -39,7 +39,7 @@ impl CommandAction for UnlockCommand {
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
-        let client = ctx.state().client();
+        let client = ctx.state().client()?;
         let opt: &UnlockOpt = ctx.opt();
 
         let account = ctx
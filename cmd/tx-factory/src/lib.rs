@@ -1 +1,4 @@
+pub mod load;
+pub mod scenario;
+pub mod stats;
 pub mod txn_generator;
@@ -0,0 +1,312 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::scenario::{Scenario, TxnKind};
+use crate::stats::{TxnReport, TxnStats};
+use anyhow::{bail, format_err, Result};
+use futures::TryStreamExt;
+use rand::Rng;
+use starcoin_crypto::hash::PlainCryptoHash;
+use starcoin_executor::executor::Executor;
+use starcoin_executor::TransactionExecutor;
+use starcoin_logger::prelude::*;
+use starcoin_rpc_client::{RemoteStateReader, RpcClient};
+use starcoin_state_api::AccountStateReader;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::transaction::{RawUserTransaction, Script, TransactionArgument};
+use starcoin_vm_types::language_storage::TypeTag;
+use starcoin_wallet_api::WalletAccount;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+const TICK: Duration = Duration::from_millis(10);
+
+fn get_next_sequence_number(client: &RpcClient, address: AccountAddress) -> Result<u64> {
+    if let Some(n) = client.next_sequence_number_in_txpool(address)? {
+        return Ok(n);
+    }
+    let state_reader = RemoteStateReader::new(client);
+    let account_state_reader = AccountStateReader::new(&state_reader);
+    match account_state_reader.get_account_resource(&address)? {
+        Some(resource) => Ok(resource.sequence_number()),
+        None => bail!("account {} not exists, please faucet it", address),
+    }
+}
+
+struct Sender {
+    account: WalletAccount,
+    next_sequence_number: u64,
+}
+
+/// Generate txns described by a `Scenario` from many sender accounts,
+/// and measure the submit-to-inclusion latency by subscribing new blocks.
+pub struct LoadGenerator {
+    client: RpcClient,
+    ipc_path: PathBuf,
+    scenario: Scenario,
+    senders: Vec<Sender>,
+    accept_coin_type: TypeTag,
+    script: Option<(Vec<u8>, Vec<TypeTag>, Vec<TransactionArgument>)>,
+    stats: Arc<Mutex<TxnStats>>,
+}
+
+impl LoadGenerator {
+    pub fn new(client: RpcClient, ipc_path: PathBuf, scenario: Scenario) -> Result<Self> {
+        let accept_coin_type = scenario.accept_coin_type()?;
+        let script = match scenario.script.as_ref() {
+            Some(script) => Some((script.bytecode()?, script.type_tags()?, script.args()?)),
+            None => None,
+        };
+        Ok(Self {
+            client,
+            ipc_path,
+            scenario,
+            senders: vec![],
+            accept_coin_type,
+            script,
+            stats: Arc::new(Mutex::new(TxnStats::new())),
+        })
+    }
+
+    /// Create the sender accounts, and fund them from `funder`.
+    pub fn prepare_accounts(
+        &mut self,
+        funder: &WalletAccount,
+        funder_password: &str,
+    ) -> Result<()> {
+        let unlock_duration = self.unlock_duration();
+        self.client
+            .wallet_unlock(funder.address, funder_password.to_string(), unlock_duration)?;
+        let mut funder_seq = get_next_sequence_number(&self.client, funder.address)?;
+        let mut last_fund_txn = None;
+        for _ in 0..self.scenario.accounts {
+            let account = self
+                .client
+                .wallet_create(self.scenario.account_password.clone())?;
+            let fund_txn = Executor::build_transfer_txn(
+                funder.address,
+                account.address,
+                account.get_auth_key().prefix().to_vec(),
+                funder_seq,
+                self.scenario.fund_amount,
+                self.scenario.gas_price,
+                self.scenario.max_gas_amount,
+            );
+            let signed_txn = self.client.wallet_sign_txn(fund_txn)?;
+            let txn_hash = signed_txn.crypto_hash();
+            if !self.client.submit_transaction(signed_txn)? {
+                bail!("fund txn to {} is rejected by txpool", account.address);
+            }
+            funder_seq += 1;
+            last_fund_txn = Some(txn_hash);
+            self.client.wallet_unlock(
+                account.address,
+                self.scenario.account_password.clone(),
+                unlock_duration,
+            )?;
+            self.senders.push(Sender {
+                account,
+                next_sequence_number: 0,
+            });
+        }
+        if let Some(txn_hash) = last_fund_txn {
+            info!("wait fund txns to be included, last txn: {:?}", txn_hash);
+            self.client
+                .watch_txn(txn_hash, Some(Duration::from_secs(120)))?;
+        }
+        for sender in self.senders.iter_mut() {
+            sender.next_sequence_number =
+                get_next_sequence_number(&self.client, sender.account.address)?;
+        }
+        info!("{} sender accounts are funded.", self.senders.len());
+        Ok(())
+    }
+
+    fn unlock_duration(&self) -> Duration {
+        // the accounts should keep unlocked during the whole load.
+        self.scenario.duration()
+            + Duration::from_secs(self.scenario.drain_timeout)
+            + Duration::from_secs(600)
+    }
+
+    fn spawn_block_watcher(&self, stopping: Arc<AtomicBool>) -> JoinHandle<Result<()>> {
+        let ipc_path = self.ipc_path.clone();
+        let stats = self.stats.clone();
+        std::thread::spawn(move || {
+            let client = RpcClient::connect_ipc(ipc_path)?;
+            let mut block_stream = client.subscribe_new_blocks()?;
+            let mut rt = tokio::runtime::Builder::new()
+                .basic_scheduler()
+                .enable_time()
+                .build()?;
+            rt.block_on(async move {
+                while !stopping.load(Ordering::SeqCst) {
+                    let block =
+                        match tokio::time::timeout(Duration::from_secs(1), block_stream.try_next())
+                            .await
+                        {
+                            Err(_timeout) => continue,
+                            Ok(block) => block?,
+                        };
+                    match block {
+                        Some(block) => {
+                            let included = stats
+                                .lock()
+                                .expect("lock stats should success.")
+                                .on_block(block.body(), Instant::now());
+                            debug!(
+                                "block {} include {} generated txns.",
+                                block.header().number(),
+                                included
+                            );
+                        }
+                        None => break,
+                    }
+                }
+                Ok(())
+            })
+        })
+    }
+
+    fn build_txn(&self, sender_idx: usize, kind: TxnKind) -> Result<RawUserTransaction> {
+        let sender = &self.senders[sender_idx];
+        let scenario = &self.scenario;
+        let txn = match kind {
+            TxnKind::Transfer => {
+                let receiver = &self.senders[(sender_idx + 1) % self.senders.len()].account;
+                Executor::build_transfer_txn(
+                    sender.account.address,
+                    receiver.address,
+                    vec![],
+                    sender.next_sequence_number,
+                    1,
+                    scenario.gas_price,
+                    scenario.max_gas_amount,
+                )
+            }
+            TxnKind::AcceptCoin => Executor::build_accept_coin_txn(
+                sender.account.address,
+                sender.next_sequence_number,
+                scenario.gas_price,
+                scenario.max_gas_amount,
+                self.accept_coin_type.clone(),
+            ),
+            TxnKind::Script => {
+                let (bytecode, type_tags, args) = self
+                    .script
+                    .clone()
+                    .ok_or_else(|| format_err!("script is not configured"))?;
+                RawUserTransaction::new_script(
+                    sender.account.address,
+                    sender.next_sequence_number,
+                    Script::new(bytecode, type_tags, args),
+                    scenario.max_gas_amount,
+                    scenario.gas_price,
+                    Duration::from_secs(scenario.expiration_time),
+                )
+            }
+        };
+        Ok(txn)
+    }
+
+    fn submit_one(&mut self, sender_idx: usize, kind: TxnKind) {
+        let result = self
+            .build_txn(sender_idx, kind)
+            .and_then(|raw_txn| self.client.wallet_sign_txn(raw_txn))
+            .and_then(|signed_txn| {
+                let txn_hash = signed_txn.crypto_hash();
+                let submit_at = Instant::now();
+                self.client
+                    .submit_transaction(signed_txn)
+                    .map(|accepted| (accepted, txn_hash, submit_at))
+            });
+        let mut stats = self.stats.lock().expect("lock stats should success.");
+        let sender = &mut self.senders[sender_idx];
+        match result {
+            Ok((true, txn_hash, submit_at)) => {
+                stats.on_submitted(kind, txn_hash, submit_at);
+                sender.next_sequence_number += 1;
+                return;
+            }
+            Ok((false, _, _)) => stats.on_rejected(),
+            Err(e) => {
+                debug!("submit txn from {} error: {:?}", sender.account.address, e);
+                stats.on_error();
+            }
+        }
+        drop(stats);
+        // resync the sequence number, in case of the sequence number is gap-ed.
+        match get_next_sequence_number(&self.client, sender.account.address) {
+            Ok(n) => sender.next_sequence_number = n,
+            Err(e) => error!("fail to recheck sequence number, err: {:?}", e),
+        }
+    }
+
+    /// Run the load until finished or `stopping` is set, then wait the pending txns and report.
+    pub fn run(mut self, stopping: Arc<AtomicBool>) -> Result<TxnReport> {
+        if self.senders.is_empty() {
+            bail!("no sender account, please prepare accounts first");
+        }
+        let watcher_stopping = Arc::new(AtomicBool::new(false));
+        let watcher = self.spawn_block_watcher(watcher_stopping.clone());
+
+        let mut rng = rand::thread_rng();
+        let total_weight = self.scenario.mix.total();
+        let duration = self.scenario.duration();
+        let start = Instant::now();
+        let mut sent: u64 = 0;
+        let mut last_log = start;
+        while !stopping.load(Ordering::SeqCst) && start.elapsed() < duration {
+            let expected = self.scenario.tps.expected_txns(start.elapsed());
+            while sent < expected && !stopping.load(Ordering::SeqCst) {
+                let sender_idx = (sent % self.senders.len() as u64) as usize;
+                let kind = self.scenario.mix.pick(rng.gen_range(0, total_weight));
+                self.submit_one(sender_idx, kind);
+                sent += 1;
+            }
+            if last_log.elapsed() >= Duration::from_secs(10) {
+                let stats = self.stats.lock().expect("lock stats should success.");
+                info!(
+                    "txfactory progress, elapsed: {:?}, sent: {}, pending: {}",
+                    start.elapsed(),
+                    sent,
+                    stats.pending_count()
+                );
+                last_log = Instant::now();
+            }
+            std::thread::sleep(TICK);
+        }
+        let load_elapsed = start.elapsed();
+
+        info!("load finished, wait pending txns to be included.");
+        let drain_start = Instant::now();
+        let drain_timeout = Duration::from_secs(self.scenario.drain_timeout);
+        while drain_start.elapsed() < drain_timeout {
+            if self
+                .stats
+                .lock()
+                .expect("lock stats should success.")
+                .pending_count()
+                == 0
+            {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        }
+        watcher_stopping.store(true, Ordering::SeqCst);
+        match watcher.join() {
+            Ok(Err(e)) => error!("block watcher exit with error: {:?}", e),
+            Err(e) => error!("block watcher panic: {:?}", e),
+            Ok(Ok(())) => {}
+        }
+        let report = self
+            .stats
+            .lock()
+            .expect("lock stats should success.")
+            .report(load_elapsed);
+        Ok(report)
+    }
+}
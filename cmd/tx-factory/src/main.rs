@@ -9,6 +9,8 @@ use starcoin_logger::prelude::*;
 use starcoin_rpc_client::RemoteStateReader;
 use starcoin_rpc_client::RpcClient;
 use starcoin_state_api::AccountStateReader;
+use starcoin_tx_factory::load::LoadGenerator;
+use starcoin_tx_factory::scenario::Scenario;
 use starcoin_tx_factory::txn_generator::MockTxnGenerator;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_config::association_address;
@@ -56,6 +58,14 @@ pub struct TxFactoryOpt {
         help = "public key(hex encoded) of address to receive balance, default to none"
     )]
     pub receiver_public_key: Option<String>,
+
+    #[structopt(
+        long,
+        short = "s",
+        parse(from_os_str),
+        help = "scenario file(toml) of the load, the account is used to fund the sender accounts in scenario"
+    )]
+    pub scenario: Option<PathBuf>,
 }
 
 fn get_wallet_account(
@@ -90,9 +100,32 @@ fn main() {
     let interval = Duration::from_millis(opts.interval);
     let account_password = opts.account_password.clone();
 
-    let client = RpcClient::connect_ipc(opts.ipc_path).expect("ipc connect success");
+    let client = RpcClient::connect_ipc(opts.ipc_path.clone()).expect("ipc connect success");
     let account = get_wallet_account(&client, account_address).unwrap();
 
+    let stopping_signal = Arc::new(AtomicBool::new(false));
+    let stopping_signal_clone = stopping_signal.clone();
+    ctrlc::set_handler(move || {
+        stopping_signal_clone.store(true, Ordering::SeqCst);
+    })
+    .unwrap();
+
+    if let Some(scenario_path) = opts.scenario.as_ref() {
+        let scenario = Scenario::load(scenario_path).expect("load scenario file fail");
+        if let Err(e) = run_scenario(
+            client,
+            opts.ipc_path.clone(),
+            scenario,
+            &account,
+            account_password.as_str(),
+            stopping_signal,
+        ) {
+            error!("txfactory run scenario error: {:?}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let receiver_address = opts.receiver_address.unwrap_or_else(association_address);
     let receiver_public_key = opts.receiver_public_key;
     let receiver_auth_key_prefix = receiver_public_key
@@ -120,12 +153,6 @@ fn main() {
         }
     };
 
-    let stopping_signal = Arc::new(AtomicBool::new(false));
-    let stopping_signal_clone = stopping_signal.clone();
-    ctrlc::set_handler(move || {
-        stopping_signal_clone.store(true, Ordering::SeqCst);
-    })
-    .unwrap();
     let handle = std::thread::spawn(move || {
         while !stopping_signal.load(Ordering::SeqCst) {
             let success = tx_mocker.gen_and_submit_txn();
@@ -150,6 +177,21 @@ fn main() {
     info!("txfactory: stop now");
 }
 
+fn run_scenario(
+    client: RpcClient,
+    ipc_path: PathBuf,
+    scenario: Scenario,
+    funder: &WalletAccount,
+    funder_password: &str,
+    stopping_signal: Arc<AtomicBool>,
+) -> Result<()> {
+    let mut generator = LoadGenerator::new(client, ipc_path, scenario)?;
+    generator.prepare_accounts(funder, funder_password)?;
+    let report = generator.run(stopping_signal)?;
+    println!("{}", report);
+    Ok(())
+}
+
 struct TxnMocker {
     client: RpcClient,
     generator: MockTxnGenerator,
@@ -0,0 +1,261 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+use starcoin_types::transaction::{parse_as_transaction_argument, TransactionArgument};
+use starcoin_vm_types::account_config::stc_type_tag;
+use starcoin_vm_types::language_storage::TypeTag;
+use starcoin_vm_types::parser::parse_type_tag;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// A load scenario, loaded from a toml file, for example:
+///
+/// ```toml
+/// accounts = 20
+/// fund_amount = 100000000
+/// duration = 300
+///
+/// [tps]
+/// start = 5
+/// target = 100
+/// ramp_up = 60
+///
+/// [mix]
+/// transfer = 80
+/// accept_coin = 10
+/// script = 10
+///
+/// [script]
+/// bytecode_file = "./my_script.mv"
+/// args = ["0x1", "100"]
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scenario {
+    /// Number of sender accounts, load is spread across these accounts.
+    pub accounts: usize,
+    /// Amount funded to every sender account before the load start.
+    pub fund_amount: u64,
+    /// Password of the created sender accounts.
+    pub account_password: String,
+    /// Duration of the load in seconds, not include the ramp-up.
+    pub duration: u64,
+    /// Seconds to wait for pending txns to be included after the load finished.
+    pub drain_timeout: u64,
+    pub tps: TpsConfig,
+    pub mix: TxnMix,
+    pub gas_price: u64,
+    pub max_gas_amount: u64,
+    /// Txn expiration time in seconds.
+    pub expiration_time: u64,
+    /// Coin type used by accept coin txn, default is STC.
+    pub accept_coin_type: Option<String>,
+    pub script: Option<ScriptConfig>,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            accounts: 10,
+            fund_amount: 100_000_000,
+            account_password: "".to_string(),
+            duration: 60,
+            drain_timeout: 30,
+            tps: TpsConfig::default(),
+            mix: TxnMix::default(),
+            gas_price: 1,
+            max_gas_amount: 1_000_000,
+            expiration_time: 3_000,
+            accept_coin_type: None,
+            script: None,
+        }
+    }
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let scenario: Scenario = toml::from_str(content.as_str())?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        ensure!(self.accounts > 0, "accounts should be greater than 0");
+        ensure!(self.tps.target > 0, "target tps should be greater than 0");
+        ensure!(
+            self.mix.total() > 0,
+            "at least one txn kind should have positive weight"
+        );
+        ensure!(
+            self.mix.script == 0 || self.script.is_some(),
+            "script config is required when script weight is positive"
+        );
+        Ok(())
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.tps.ramp_up + self.duration)
+    }
+
+    pub fn accept_coin_type(&self) -> Result<TypeTag> {
+        match self.accept_coin_type.as_ref() {
+            Some(coin_type) => parse_type_tag(coin_type),
+            None => Ok(stc_type_tag()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TpsConfig {
+    /// Tps at the begin of ramp-up.
+    pub start: u64,
+    /// Tps after ramp-up.
+    pub target: u64,
+    /// Ramp-up seconds, tps increase linearly from start to target during ramp-up.
+    pub ramp_up: u64,
+}
+
+impl Default for TpsConfig {
+    fn default() -> Self {
+        Self {
+            start: 1,
+            target: 10,
+            ramp_up: 0,
+        }
+    }
+}
+
+impl TpsConfig {
+    /// Total txns should be submitted after `elapsed` since the load start.
+    pub fn expected_txns(&self, elapsed: Duration) -> u64 {
+        let elapsed = elapsed.as_secs_f64();
+        let start = self.start.min(self.target) as f64;
+        let target = self.target as f64;
+        let ramp_up = self.ramp_up as f64;
+        let total = if elapsed < ramp_up {
+            // area of the trapezoid under the ramp-up line.
+            let current = start + (target - start) * elapsed / ramp_up;
+            (start + current) * elapsed / 2.0
+        } else {
+            (start + target) * ramp_up / 2.0 + target * (elapsed - ramp_up)
+        };
+        total as u64
+    }
+}
+
+/// Weights of every txn kind.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TxnMix {
+    pub transfer: u32,
+    pub accept_coin: u32,
+    pub script: u32,
+}
+
+impl Default for TxnMix {
+    fn default() -> Self {
+        Self {
+            transfer: 100,
+            accept_coin: 0,
+            script: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum TxnKind {
+    Transfer,
+    AcceptCoin,
+    Script,
+}
+
+impl TxnMix {
+    pub fn total(&self) -> u32 {
+        self.transfer + self.accept_coin + self.script
+    }
+
+    /// Pick a txn kind by weight, `seed` should be in [0, total).
+    pub fn pick(&self, seed: u32) -> TxnKind {
+        if seed < self.transfer {
+            TxnKind::Transfer
+        } else if seed < self.transfer + self.accept_coin {
+            TxnKind::AcceptCoin
+        } else {
+            TxnKind::Script
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptConfig {
+    /// Compiled script bytecode file.
+    pub bytecode_file: PathBuf,
+    #[serde(default)]
+    pub type_tags: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl ScriptConfig {
+    pub fn bytecode(&self) -> Result<Vec<u8>> {
+        Ok(std::fs::read(&self.bytecode_file)?)
+    }
+
+    pub fn type_tags(&self) -> Result<Vec<TypeTag>> {
+        self.type_tags.iter().map(|t| parse_type_tag(t)).collect()
+    }
+
+    pub fn args(&self) -> Result<Vec<TransactionArgument>> {
+        self.args
+            .iter()
+            .map(|arg| parse_as_transaction_argument(arg))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_txns() {
+        let tps = TpsConfig {
+            start: 0,
+            target: 10,
+            ramp_up: 10,
+        };
+        assert_eq!(tps.expected_txns(Duration::from_secs(0)), 0);
+        assert_eq!(tps.expected_txns(Duration::from_secs(10)), 50);
+        assert_eq!(tps.expected_txns(Duration::from_secs(20)), 150);
+        let tps = TpsConfig {
+            start: 5,
+            target: 5,
+            ramp_up: 0,
+        };
+        assert_eq!(tps.expected_txns(Duration::from_secs(3)), 15);
+    }
+
+    #[test]
+    fn test_load_scenario() {
+        let scenario: Scenario = toml::from_str(
+            r#"
+            accounts = 3
+            [tps]
+            target = 20
+            [mix]
+            transfer = 1
+            accept_coin = 1
+            "#,
+        )
+        .unwrap();
+        scenario.validate().unwrap();
+        assert_eq!(scenario.mix.script, 0);
+        assert_eq!(scenario.mix.pick(0), TxnKind::Transfer);
+        assert_eq!(scenario.mix.pick(1), TxnKind::AcceptCoin);
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::scenario::TxnKind;
+use starcoin_crypto::HashValue;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+/// Collect submit and inclusion results of the generated txns.
+#[derive(Default)]
+pub struct TxnStats {
+    pending: HashMap<HashValue, Instant>,
+    latencies: Vec<Duration>,
+    submitted: HashMap<TxnKind, u64>,
+    rejected: u64,
+    errors: u64,
+    blocks: u64,
+    included_txns: u64,
+}
+
+impl TxnStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_submitted(&mut self, kind: TxnKind, txn_hash: HashValue, submit_at: Instant) {
+        *self.submitted.entry(kind).or_default() += 1;
+        self.pending.insert(txn_hash, submit_at);
+    }
+
+    pub fn on_rejected(&mut self) {
+        self.rejected += 1;
+    }
+
+    pub fn on_error(&mut self) {
+        self.errors += 1;
+    }
+
+    /// Record a new block, return how many generated txns is included in the block.
+    pub fn on_block(&mut self, txn_hashes: &[HashValue], included_at: Instant) -> usize {
+        self.blocks += 1;
+        self.included_txns += txn_hashes.len() as u64;
+        let mut included = 0;
+        for txn_hash in txn_hashes {
+            if let Some(submit_at) = self.pending.remove(txn_hash) {
+                self.latencies
+                    .push(included_at.saturating_duration_since(submit_at));
+                included += 1;
+            }
+        }
+        included
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn report(&self, elapsed: Duration) -> TxnReport {
+        let mut latencies = self.latencies.clone();
+        latencies.sort();
+        let percentile = |p: usize| -> Option<Duration> {
+            if latencies.is_empty() {
+                None
+            } else {
+                let idx = (latencies.len() * p / 100).min(latencies.len() - 1);
+                Some(latencies[idx])
+            }
+        };
+        let submitted: u64 = self.submitted.values().sum();
+        let attempted = submitted + self.rejected + self.errors;
+        TxnReport {
+            elapsed,
+            submitted,
+            submitted_by_kind: self.submitted.clone(),
+            included: latencies.len() as u64,
+            pending: self.pending.len() as u64,
+            rejected: self.rejected,
+            errors: self.errors,
+            reject_rate: if attempted == 0 {
+                0.0
+            } else {
+                (self.rejected + self.errors) as f64 / attempted as f64
+            },
+            blocks: self.blocks,
+            included_txns: self.included_txns,
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: latencies.last().cloned(),
+        }
+    }
+}
+
+pub struct TxnReport {
+    pub elapsed: Duration,
+    pub submitted: u64,
+    pub submitted_by_kind: HashMap<TxnKind, u64>,
+    pub included: u64,
+    pub pending: u64,
+    pub rejected: u64,
+    pub errors: u64,
+    pub reject_rate: f64,
+    /// Blocks observed during the load.
+    pub blocks: u64,
+    /// All txns included in the observed blocks, include txns not generated by txfactory.
+    pub included_txns: u64,
+    pub p50: Option<Duration>,
+    pub p90: Option<Duration>,
+    pub p99: Option<Duration>,
+    pub max: Option<Duration>,
+}
+
+impl Display for TxnReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let secs = self.elapsed.as_secs_f64().max(1.0);
+        let fmt_latency = |d: Option<Duration>| -> String {
+            d.map(|d| format!("{}ms", d.as_millis()))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        writeln!(f, "========== txfactory report ==========")?;
+        writeln!(f, "elapsed:           {:.1}s", self.elapsed.as_secs_f64())?;
+        writeln!(
+            f,
+            "submitted:         {} ({:.2} tps)",
+            self.submitted,
+            self.submitted as f64 / secs
+        )?;
+        for (kind, count) in &self.submitted_by_kind {
+            writeln!(f, "  {:?}: {}", kind, count)?;
+        }
+        writeln!(
+            f,
+            "included:          {} ({:.2} tps)",
+            self.included,
+            self.included as f64 / secs
+        )?;
+        writeln!(f, "pending:           {}", self.pending)?;
+        writeln!(f, "rejected:          {}", self.rejected)?;
+        writeln!(f, "errors:            {}", self.errors)?;
+        writeln!(f, "reject rate:       {:.2}%", self.reject_rate * 100.0)?;
+        writeln!(
+            f,
+            "blocks:            {} (avg {:.1} txns/block)",
+            self.blocks,
+            self.included_txns as f64 / (self.blocks.max(1) as f64)
+        )?;
+        writeln!(
+            f,
+            "latency:           p50 {}, p90 {}, p99 {}, max {}",
+            fmt_latency(self.p50),
+            fmt_latency(self.p90),
+            fmt_latency(self.p99),
+            fmt_latency(self.max)
+        )
+    }
+}
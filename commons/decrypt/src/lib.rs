@@ -4,24 +4,113 @@ use byteorder::{ReadBytesExt, WriteBytesExt};
 use rand::RngCore;
 use std::io::{Cursor, Read, Write};
 
+pub mod secret_storage;
+
 pub const PBKDF2_DEFAULT_ITERATIONS: usize = 1000;
 pub const PBKDF2_SALT_SIZE: usize = 32;
 pub const AES_NONCE_SIZE: usize = 12;
 
+/// Magic prefix of the versioned encrypted data,
+/// the legacy data starts with the big endian pbkdf2 iterations, so never starts with it.
+const VERSIONED_MAGIC: &[u8; 3] = b"SCK";
+const VERSION_1: u8 = 1;
+
+const KDF_PBKDF2: u8 = 0;
+const KDF_SCRYPT: u8 = 1;
+
+/// Key derivation function and its parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KdfParams {
+    Pbkdf2 {
+        iterations: u32,
+    },
+    /// scrypt with `N = 2^log_n`.
+    Scrypt {
+        log_n: u8,
+        r: u32,
+        p: u32,
+    },
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams::Scrypt {
+            log_n: 15,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Derive a 256-bit key from `secret`.
+    pub fn derive_key(&self, secret: &[u8], salt: &[u8]) -> Result<[u8; 32]> {
+        let mut dk = [0u8; 32];
+        match self {
+            KdfParams::Pbkdf2 { iterations } => {
+                pbkdf2::pbkdf2::<hmac::Hmac<sha2::Sha256>>(
+                    secret,
+                    salt,
+                    *iterations as usize,
+                    &mut dk,
+                );
+            }
+            KdfParams::Scrypt { log_n, r, p } => {
+                let params = scrypt::ScryptParams::new(*log_n, *r, *p)
+                    .map_err(|_| format_err!("invalid scrypt params: {:?}", self))?;
+                scrypt::scrypt(secret, salt, &params, &mut dk)
+                    .map_err(|_| format_err!("invalid scrypt output length"))?;
+            }
+        }
+        Ok(dk)
+    }
+
+    fn encode<W: Write>(&self, buf: &mut W) -> Result<()> {
+        match self {
+            KdfParams::Pbkdf2 { iterations } => {
+                buf.write_u8(KDF_PBKDF2)?;
+                buf.write_u32::<byteorder::BigEndian>(*iterations)?;
+            }
+            KdfParams::Scrypt { log_n, r, p } => {
+                buf.write_u8(KDF_SCRYPT)?;
+                buf.write_u8(*log_n)?;
+                buf.write_u32::<byteorder::BigEndian>(*r)?;
+                buf.write_u32::<byteorder::BigEndian>(*p)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn decode<R: Read>(buf: &mut R) -> Result<Self> {
+        Ok(match buf.read_u8()? {
+            KDF_PBKDF2 => KdfParams::Pbkdf2 {
+                iterations: buf.read_u32::<byteorder::BigEndian>()?,
+            },
+            KDF_SCRYPT => KdfParams::Scrypt {
+                log_n: buf.read_u8()?,
+                r: buf.read_u32::<byteorder::BigEndian>()?,
+                p: buf.read_u32::<byteorder::BigEndian>()?,
+            },
+            kdf => bail!("unknown kdf type: {}", kdf),
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 struct KeyDerivationParams {
-    pbkdf2_iterations: u32,
-    pbkdf2_salt: [u8; PBKDF2_SALT_SIZE],
+    kdf: KdfParams,
+    salt: [u8; PBKDF2_SALT_SIZE],
 }
 
 impl KeyDerivationParams {
-    pub fn generate() -> Self {
+    pub fn generate(kdf: KdfParams) -> Self {
         let mut salt = [0u8; PBKDF2_SALT_SIZE];
         rand::thread_rng().fill_bytes(&mut salt);
-        Self {
-            pbkdf2_iterations: PBKDF2_DEFAULT_ITERATIONS as u32,
-            pbkdf2_salt: salt,
-        }
+        Self { kdf, salt }
+    }
+
+    pub fn derive_key(&self, secret: &[u8]) -> Result<[u8; 32]> {
+        self.kdf.derive_key(secret, &self.salt)
     }
 }
 
@@ -39,7 +128,8 @@ impl EncryptionParams {
     }
 }
 
-const META_LEN: usize = 4usize + PBKDF2_SALT_SIZE + AES_NONCE_SIZE;
+/// Meta length of the legacy format: pbkdf2 iterations, salt and nonce.
+const LEGACY_META_LEN: usize = 4usize + PBKDF2_SALT_SIZE + AES_NONCE_SIZE;
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 struct Meta {
     key_derive_params: KeyDerivationParams,
@@ -47,54 +137,74 @@ struct Meta {
     encryption_params: EncryptionParams,
 }
 impl Meta {
-    pub fn generate() -> Self {
+    pub fn generate(kdf: KdfParams) -> Self {
         Self {
-            key_derive_params: KeyDerivationParams::generate(),
+            key_derive_params: KeyDerivationParams::generate(kdf),
             encryption_params: EncryptionParams::generate(),
         }
     }
 
     pub fn encode(&self) -> Vec<u8> {
-        let mut buf = std::io::Cursor::new(Vec::with_capacity(META_LEN));
-        buf.write_u32::<byteorder::BigEndian>(self.key_derive_params.pbkdf2_iterations)
+        let mut buf = std::io::Cursor::new(Vec::new());
+        buf.write_all(VERSIONED_MAGIC).expect("should never fail");
+        buf.write_u8(VERSION_1).expect("should never fail");
+        self.key_derive_params
+            .kdf
+            .encode(&mut buf)
             .expect("should never fail");
-        buf.write_all(&self.key_derive_params.pbkdf2_salt)
+        buf.write_all(&self.key_derive_params.salt)
             .expect("should never fail");
         buf.write_all(&self.encryption_params.nonce)
             .expect("should never fail");
         buf.into_inner()
     }
 
-    pub fn decode(buf: &[u8]) -> Result<Self> {
+    /// Decode the meta, return the meta and the offset of the encrypted data.
+    pub fn decode(buf: &[u8]) -> Result<(Self, usize)> {
+        if buf.starts_with(VERSIONED_MAGIC) {
+            Self::decode_versioned(buf)
+        } else {
+            Self::decode_legacy(buf)
+        }
+    }
+
+    fn decode_versioned(buf: &[u8]) -> Result<(Self, usize)> {
+        let mut buf = Cursor::new(buf);
+        buf.set_position(VERSIONED_MAGIC.len() as u64);
+        let version = buf.read_u8()?;
+        if version != VERSION_1 {
+            bail!("unsupported encrypted data version: {}", version);
+        }
+        let kdf = KdfParams::decode(&mut buf)?;
+        let mut salt = [0u8; PBKDF2_SALT_SIZE];
+        buf.read_exact(&mut salt)?;
+        let mut nonce = [0u8; AES_NONCE_SIZE];
+        buf.read_exact(&mut nonce)?;
+        let meta = Self {
+            key_derive_params: KeyDerivationParams { kdf, salt },
+            encryption_params: EncryptionParams { nonce },
+        };
+        Ok((meta, buf.position() as usize))
+    }
+
+    fn decode_legacy(buf: &[u8]) -> Result<(Self, usize)> {
         let mut buf = Cursor::new(buf);
         let iterations = buf.read_u32::<byteorder::BigEndian>()?;
         let mut salt = [0u8; PBKDF2_SALT_SIZE];
         buf.read_exact(&mut salt)?;
         let mut nonce = [0u8; AES_NONCE_SIZE];
         buf.read_exact(&mut nonce)?;
-        Ok(Self {
+        let meta = Self {
             key_derive_params: KeyDerivationParams {
-                pbkdf2_salt: salt,
-                pbkdf2_iterations: iterations,
+                kdf: KdfParams::Pbkdf2 { iterations },
+                salt,
             },
             encryption_params: EncryptionParams { nonce },
-        })
+        };
+        Ok((meta, LEGACY_META_LEN))
     }
 }
 
-fn derive_key(derivation_param: &KeyDerivationParams, secret: &[u8]) -> [u8; 32] {
-    // 256-bit derived key
-    let mut dk = [0u8; 32];
-    // use secret to derive a key to encrypt plaintext
-    pbkdf2::pbkdf2::<hmac::Hmac<sha2::Sha256>>(
-        secret,
-        &derivation_param.pbkdf2_salt,
-        derivation_param.pbkdf2_iterations as usize,
-        &mut dk,
-    );
-    dk
-}
-
 fn aes_encrypt(encryption_param: &EncryptionParams, key: [u8; 32], plain: &[u8]) -> Vec<u8> {
     let key = GenericArray::from(key);
     let nonce = GenericArray::clone_from_slice(&encryption_param.nonce);
@@ -117,30 +227,63 @@ fn aes_decrypt(
     }
 }
 
+/// Encrypt `plain` by the key derived from `secret` with the default kdf.
 pub fn encrypt(secret: &[u8], plain: &[u8]) -> Vec<u8> {
-    let meta = Meta::generate();
+    encrypt_with_kdf(secret, plain, KdfParams::default())
+        .expect("encrypt with default kdf should never fail")
+}
+
+/// Encrypt `plain` by the key derived from `secret` with the `kdf`.
+pub fn encrypt_with_kdf(secret: &[u8], plain: &[u8], kdf: KdfParams) -> Result<Vec<u8>> {
+    let meta = Meta::generate(kdf);
     // 256-bit derived key
-    let dk = derive_key(&meta.key_derive_params, secret);
+    let dk = meta.key_derive_params.derive_key(secret)?;
     let mut ciphertext = aes_encrypt(&meta.encryption_params, dk, plain);
     let mut result = meta.encode();
     result.append(&mut ciphertext);
-    result
+    Ok(result)
 }
 
+/// Decrypt the data encrypted by `encrypt`, the legacy pbkdf2 only format is supported too.
 pub fn decrypt(secret: &[u8], encrypted: &[u8]) -> Result<Vec<u8>> {
-    if encrypted.len() <= META_LEN {
+    let (meta, offset) = Meta::decode(encrypted)?;
+    if encrypted.len() <= offset {
         bail!("invalid encrypted data");
     }
-    let meta = Meta::decode(&encrypted[0..META_LEN])?;
-    let crypted = &encrypted[META_LEN..];
+    let crypted = &encrypted[offset..];
 
-    let dk = derive_key(&meta.key_derive_params, secret);
+    let dk = meta.key_derive_params.derive_key(secret)?;
     aes_decrypt(&meta.encryption_params, dk, crypted)
 }
 
+/// The kdf used by the encrypted data, can be used to decide whether the data should be re-encrypted.
+pub fn kdf_of(encrypted: &[u8]) -> Result<KdfParams> {
+    Ok(Meta::decode(encrypted)?.0.key_derive_params.kdf)
+}
+
+/// Encrypt by the legacy format, only for test the compatibility.
+#[cfg(test)]
+fn legacy_encrypt(secret: &[u8], plain: &[u8]) -> Vec<u8> {
+    let meta = Meta::generate(KdfParams::Pbkdf2 {
+        iterations: PBKDF2_DEFAULT_ITERATIONS as u32,
+    });
+    let dk = meta.key_derive_params.derive_key(secret).unwrap();
+    let mut result = Vec::with_capacity(LEGACY_META_LEN);
+    result
+        .write_u32::<byteorder::BigEndian>(PBKDF2_DEFAULT_ITERATIONS as u32)
+        .unwrap();
+    result.extend_from_slice(&meta.key_derive_params.salt);
+    result.extend_from_slice(&meta.encryption_params.nonce);
+    result.append(&mut aes_encrypt(&meta.encryption_params, dk, plain));
+    result
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{decrypt, encrypt};
+    use crate::{
+        decrypt, encrypt, encrypt_with_kdf, kdf_of, legacy_encrypt, KdfParams,
+        PBKDF2_DEFAULT_ITERATIONS,
+    };
 
     #[test]
     fn test_encryption() {
@@ -152,4 +295,33 @@ mod tests {
         let decrypted = decrypt(secret.as_bytes(), encrypted.as_slice()).unwrap();
         assert_eq!(decrypted.as_slice(), plain.as_bytes());
     }
+
+    #[test]
+    fn test_decrypt_legacy() {
+        let secret = "hello";
+        let plain = "world";
+        let encrypted = legacy_encrypt(secret.as_bytes(), plain.as_bytes());
+        assert_eq!(
+            kdf_of(encrypted.as_slice()).unwrap(),
+            KdfParams::Pbkdf2 {
+                iterations: PBKDF2_DEFAULT_ITERATIONS as u32
+            }
+        );
+        let decrypted = decrypt(secret.as_bytes(), encrypted.as_slice()).unwrap();
+        assert_eq!(decrypted.as_slice(), plain.as_bytes());
+        assert!(decrypt(b"wrong", encrypted.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_encrypt_with_kdf() {
+        let kdf = KdfParams::Scrypt {
+            log_n: 10,
+            r: 8,
+            p: 1,
+        };
+        let encrypted = encrypt_with_kdf(b"hello", b"world", kdf).unwrap();
+        assert_eq!(kdf_of(encrypted.as_slice()).unwrap(), kdf);
+        assert_eq!(decrypt(b"hello", encrypted.as_slice()).unwrap(), b"world");
+        assert!(decrypt(b"wrong", encrypted.as_slice()).is_err());
+    }
 }
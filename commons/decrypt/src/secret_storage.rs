@@ -0,0 +1,265 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The JSON key file layout of the Web3 Secret Storage Definition (version 3),
+//! which is widely used by wallets and tools to move keys around.
+
+use crate::KdfParams;
+use aes_ctr::stream_cipher::generic_array::GenericArray;
+use aes_ctr::stream_cipher::{NewStreamCipher, SyncStreamCipher};
+use anyhow::{bail, ensure, format_err, Result};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+pub const SECRET_STORAGE_VERSION: u32 = 3;
+const CIPHER_AES_128_CTR: &str = "aes-128-ctr";
+const PRF_HMAC_SHA256: &str = "hmac-sha256";
+const DK_LEN: u32 = 32;
+const SALT_SIZE: usize = 32;
+const IV_SIZE: usize = 16;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CipherParams {
+    pub iv: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum KdfJsonParams {
+    Scrypt {
+        dklen: u32,
+        n: u32,
+        r: u32,
+        p: u32,
+        salt: String,
+    },
+    Pbkdf2 {
+        dklen: u32,
+        c: u32,
+        prf: String,
+        salt: String,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Crypto {
+    pub cipher: String,
+    pub cipherparams: CipherParams,
+    pub ciphertext: String,
+    pub kdf: String,
+    pub kdfparams: KdfJsonParams,
+    pub mac: String,
+}
+
+/// A key encrypted in the secret storage JSON layout.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SecretStorage {
+    pub crypto: Crypto,
+    pub id: String,
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+}
+
+impl SecretStorage {
+    /// Encrypt `plain` by the key derived from `secret` with `kdf`.
+    pub fn encrypt(
+        secret: &[u8],
+        plain: &[u8],
+        kdf: KdfParams,
+        address: Option<String>,
+    ) -> Result<Self> {
+        let mut salt = [0u8; SALT_SIZE];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let mut iv = [0u8; IV_SIZE];
+        rand::thread_rng().fill_bytes(&mut iv);
+
+        let dk = kdf.derive_key(secret, &salt)?;
+        let mut ciphertext = plain.to_vec();
+        aes_128_ctr(&dk[..16], &iv, ciphertext.as_mut_slice());
+        let mac = mac(&dk, ciphertext.as_slice());
+
+        let (kdf_name, kdfparams) = match kdf {
+            KdfParams::Scrypt { log_n, r, p } => (
+                "scrypt",
+                KdfJsonParams::Scrypt {
+                    dklen: DK_LEN,
+                    n: 1u32 << u32::from(log_n),
+                    r,
+                    p,
+                    salt: hex::encode(salt),
+                },
+            ),
+            KdfParams::Pbkdf2 { iterations } => (
+                "pbkdf2",
+                KdfJsonParams::Pbkdf2 {
+                    dklen: DK_LEN,
+                    c: iterations,
+                    prf: PRF_HMAC_SHA256.to_string(),
+                    salt: hex::encode(salt),
+                },
+            ),
+        };
+        Ok(Self {
+            crypto: Crypto {
+                cipher: CIPHER_AES_128_CTR.to_string(),
+                cipherparams: CipherParams {
+                    iv: hex::encode(iv),
+                },
+                ciphertext: hex::encode(ciphertext),
+                kdf: kdf_name.to_string(),
+                kdfparams,
+                mac: hex::encode(mac),
+            },
+            id: random_uuid(),
+            version: SECRET_STORAGE_VERSION,
+            address,
+        })
+    }
+
+    /// Decrypt the key by `secret`, fail if the mac mismatch.
+    pub fn decrypt(&self, secret: &[u8]) -> Result<Vec<u8>> {
+        ensure!(
+            self.version == SECRET_STORAGE_VERSION,
+            "unsupported secret storage version: {}",
+            self.version
+        );
+        ensure!(
+            self.crypto.cipher == CIPHER_AES_128_CTR,
+            "unsupported cipher: {}",
+            self.crypto.cipher
+        );
+        let (kdf, salt) = match &self.crypto.kdfparams {
+            KdfJsonParams::Scrypt {
+                dklen,
+                n,
+                r,
+                p,
+                salt,
+            } => {
+                ensure!(*dklen == DK_LEN, "unsupported dklen: {}", dklen);
+                ensure!(n.is_power_of_two(), "scrypt n should be power of two");
+                let log_n = n.trailing_zeros() as u8;
+                (
+                    KdfParams::Scrypt {
+                        log_n,
+                        r: *r,
+                        p: *p,
+                    },
+                    salt,
+                )
+            }
+            KdfJsonParams::Pbkdf2 {
+                dklen,
+                c,
+                prf,
+                salt,
+            } => {
+                ensure!(*dklen == DK_LEN, "unsupported dklen: {}", dklen);
+                ensure!(prf == PRF_HMAC_SHA256, "unsupported prf: {}", prf);
+                (KdfParams::Pbkdf2 { iterations: *c }, salt)
+            }
+        };
+        let salt = hex::decode(salt)?;
+        let iv = hex::decode(&self.crypto.cipherparams.iv)?;
+        ensure!(iv.len() == IV_SIZE, "invalid iv length: {}", iv.len());
+        let mut ciphertext = hex::decode(&self.crypto.ciphertext)?;
+        let expect_mac = hex::decode(&self.crypto.mac)?;
+
+        let dk = kdf.derive_key(secret, salt.as_slice())?;
+        if mac(&dk, ciphertext.as_slice()).as_slice() != expect_mac.as_slice() {
+            bail!("mac mismatch, the password may be wrong");
+        }
+        aes_128_ctr(&dk[..16], iv.as_slice(), ciphertext.as_mut_slice());
+        Ok(ciphertext)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| format_err!("{}", e))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| format_err!("invalid secret storage json: {}", e))
+    }
+}
+
+fn aes_128_ctr(key: &[u8], iv: &[u8], data: &mut [u8]) {
+    let mut cipher =
+        aes_ctr::Aes128Ctr::new(GenericArray::from_slice(key), GenericArray::from_slice(iv));
+    cipher.apply_keystream(data);
+}
+
+fn mac(dk: &[u8; 32], ciphertext: &[u8]) -> Vec<u8> {
+    let mut hasher = Keccak256::new();
+    hasher.input(&dk[16..32]);
+    hasher.input(ciphertext);
+    hasher.result().to_vec()
+}
+
+fn random_uuid() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    // version 4, variant RFC4122
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_storage() {
+        let kdf = KdfParams::Scrypt {
+            log_n: 10,
+            r: 8,
+            p: 1,
+        };
+        let storage = SecretStorage::encrypt(b"hello", b"world", kdf, None).unwrap();
+        let json = storage.to_json().unwrap();
+        let storage2 = SecretStorage::from_json(json.as_str()).unwrap();
+        assert_eq!(storage, storage2);
+        assert_eq!(storage2.decrypt(b"hello").unwrap(), b"world");
+        assert!(storage2.decrypt(b"wrong").is_err());
+    }
+
+    #[test]
+    fn test_decrypt_pbkdf2_test_vector() {
+        // The pbkdf2 test vector of the Web3 Secret Storage Definition, password "testpassword".
+        let json = r#"{
+            "crypto" : {
+                "cipher" : "aes-128-ctr",
+                "cipherparams" : {
+                    "iv" : "6087dab2f9fdbbfaddc31a909735c1e6"
+                },
+                "ciphertext" : "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                "kdf" : "pbkdf2",
+                "kdfparams" : {
+                    "c" : 262144,
+                    "dklen" : 32,
+                    "prf" : "hmac-sha256",
+                    "salt" : "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                },
+                "mac" : "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+            },
+            "id" : "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+            "version" : 3
+        }"#;
+        let storage = SecretStorage::from_json(json).unwrap();
+        let key = storage.decrypt(b"testpassword").unwrap();
+        assert_eq!(
+            hex::encode(key),
+            "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+        );
+    }
+}
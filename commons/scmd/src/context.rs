@@ -2,17 +2,22 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::error::CmdError;
-use crate::{print_action_result, Command, CommandAction, CommandExec, OutputFormat};
-use anyhow::Result;
+use crate::{
+    print_action_result, Command, CommandAction, CommandExec, OutputFormat, ScriptLine,
+    ScriptVariables, LAST_RESULT_VAR,
+};
+use anyhow::{format_err, Result};
 use clap::{crate_authors, crate_version, App, Arg, ArgMatches, SubCommand};
 use git_version::git_version;
 use lazy_static::lazy_static;
 use rustyline::{config::CompletionType, error::ReadlineError, Config, Editor};
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use structopt::StructOpt;
 
 static OUTPUT_FORMAT_ARG: &str = "output-format";
+static SCRIPT_ARG: &str = "script";
 static VERSION: &str = crate_version!();
 static GIT_VERSION: &str = git_version!();
 lazy_static! {
@@ -53,7 +58,7 @@ where
 
     /// default_action executed when no subcommand is provided.
     /// console_start_action executed when start a console.
-    /// console_quit_action executed when input quit subcommand at console.
+    /// console_quit_action executed when input quit subcommand at console, or a script is finished.
     // note: D and Q's fn signature is same but is different type.
     pub fn with_default_action<I, D, S, Q>(
         state_initializer: I,
@@ -75,9 +80,17 @@ where
             .arg(
                 Arg::with_name(OUTPUT_FORMAT_ARG)
                     .short("o")
-                    .help("set output-format, support [json|table]")
+                    .long("output")
+                    .help("set output-format, support [json|table|yaml]")
                     .takes_value(true)
+                    .possible_values(&OutputFormat::variants())
                     .default_value("table"),
+            )
+            .arg(
+                Arg::with_name(SCRIPT_ARG)
+                    .long("script")
+                    .help("execute commands in the script file line by line, then quit")
+                    .takes_value(true),
             );
         app = Self::set_app_author(app);
         app = app.subcommand(
@@ -135,9 +148,14 @@ where
         String::from_utf8(help_message).expect("help message should utf8")
     }
 
+    /// Execute the command from process args, exit the process with non-zero code if failed.
     pub fn exec(mut self) {
         if let Err(e) = self.exec_inner() {
-            println!("{}", e.to_string())
+            if let Some(clap_error) = e.downcast_ref::<clap::Error>() {
+                clap_error.exit();
+            }
+            eprintln!("{}", e.to_string());
+            std::process::exit(1);
         }
     }
 
@@ -145,17 +163,16 @@ where
         let matches = self
             .app
             .get_matches_from_safe_borrow(&mut std::env::args_os())?;
-        let output_format = matches
-            .value_of(OUTPUT_FORMAT_ARG)
-            .expect("output-format arg must exist")
-            .parse()
-            .expect("parse output-format must success.");
+        let output_format = Self::output_format(&matches)?;
 
         let (global_opt, state) = self.init_global_opt(&matches)?;
+        if let Some(script) = matches.value_of(SCRIPT_ARG) {
+            return self.script_inner(global_opt, state, script, output_format);
+        }
         let (cmd_name, arg_matches) = matches.subcommand();
         match cmd_name {
             "console" => {
-                self.console_inner(global_opt, state);
+                self.console_inner(global_opt, state, output_format);
             }
             "" => {
                 self.default_action.as_ref()(self.app.clone(), global_opt, state);
@@ -180,7 +197,100 @@ where
         Ok(())
     }
 
-    fn console_inner(&mut self, global_opt: GlobalOpt, state: State) {
+    fn output_format(matches: &ArgMatches) -> Result<OutputFormat> {
+        matches
+            .value_of(OUTPUT_FORMAT_ARG)
+            .expect("output-format arg must exist")
+            .parse()
+    }
+
+    fn exec_command_line(
+        &mut self,
+        state: Arc<State>,
+        global_opt: Arc<GlobalOpt>,
+        params: Vec<String>,
+    ) -> Result<serde_json::Value> {
+        let cmd_name = params.get(0).cloned().unwrap_or_default();
+        let cmd = self.commands.get_mut(cmd_name.as_str());
+        match cmd {
+            Some(cmd) => {
+                let app = cmd.get_app();
+                let arg_matches = app.get_matches_from_safe_borrow(params)?;
+                cmd.exec(state, global_opt, &arg_matches)
+            }
+            None => Err(CmdError::InvalidCommand {
+                cmd: cmd_name,
+                help: self.help_message(),
+            }
+            .into()),
+        }
+    }
+
+    /// Execute the script file line by line, stop at the first failed line.
+    fn script_inner<P: AsRef<Path>>(
+        &mut self,
+        global_opt: GlobalOpt,
+        state: State,
+        script: P,
+        output_format: OutputFormat,
+    ) -> Result<()> {
+        let content = std::fs::read_to_string(script.as_ref())?;
+        let global_opt = Arc::new(global_opt);
+        let state = Arc::new(state);
+        let mut variables = ScriptVariables::new();
+        let mut result = Ok(());
+        for (idx, line) in content.lines().enumerate() {
+            let line_no = idx + 1;
+            let script_line = match ScriptLine::parse(line_no, line) {
+                Ok(Some(script_line)) => script_line,
+                Ok(None) => continue,
+                Err(e) => {
+                    result = Err(CmdError::ScriptFailed {
+                        line: line_no,
+                        cmd: line.trim().to_string(),
+                        reason: e.to_string(),
+                    });
+                    break;
+                }
+            };
+            if script_line.args[0] == "quit" || script_line.args[0] == "exit" {
+                break;
+            }
+            let value = variables
+                .substitute_args(&script_line.args)
+                .and_then(|params| {
+                    self.exec_command_line(state.clone(), global_opt.clone(), params)
+                })
+                .and_then(|value| {
+                    print_action_result(value.clone(), output_format)?;
+                    Ok(value)
+                });
+            match value {
+                Ok(value) => {
+                    if let Some(binding) = script_line.binding.as_ref() {
+                        variables.set(binding, value.clone());
+                    }
+                    variables.set(LAST_RESULT_VAR, value);
+                }
+                Err(e) => {
+                    result = Err(CmdError::ScriptFailed {
+                        line: line_no,
+                        cmd: line.trim().to_string(),
+                        reason: e.to_string(),
+                    });
+                    break;
+                }
+            }
+        }
+        let global_opt = Arc::try_unwrap(global_opt)
+            .map_err(|_| format_err!("global opt is still in use when script finished."))?;
+        let state = Arc::try_unwrap(state)
+            .map_err(|_| format_err!("state is still in use when script finished."))?;
+        self.console_quit_action.as_ref()(self.app.clone(), global_opt, state);
+        result.map_err(Into::into)
+    }
+
+    fn console_inner(&mut self, global_opt: GlobalOpt, state: State, output_format: OutputFormat) {
         //TODO support use custom config
         let config = Config::builder()
             .history_ignore_space(true)
@@ -245,10 +355,8 @@ where
                                                 global_opt.clone(),
                                                 &arg_matches,
                                             ) {
-                                                Ok(v) => {
-                                                    print_action_result(v, OutputFormat::TABLE)
-                                                        .expect("Print result should success.")
-                                                }
+                                                Ok(v) => print_action_result(v, output_format)
+                                                    .expect("Print result should success."),
                                                 Err(e) => println!("{}", e),
                                             };
                                         }
@@ -292,9 +400,10 @@ where
             .app
             .get_matches_from_safe_borrow(&mut std::env::args_os())
             .unwrap_or_else(|e| panic!("{}", e));
+        let output_format = Self::output_format(&matches).unwrap_or_else(|e| panic!("{}", e));
         let (global_opt, state) = self
             .init_global_opt(&matches)
             .unwrap_or_else(|e| panic!("{}", e));
-        self.console_inner(global_opt, state);
+        self.console_inner(global_opt, state, output_format);
     }
 }
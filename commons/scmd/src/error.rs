@@ -9,6 +9,12 @@ pub enum CmdError {
     InvalidCommand { cmd: String, help: String },
     #[error("{help:?})")]
     NeedHelp { help: String },
+    #[error("Script failed at line {line}: `{cmd}`, {reason}")]
+    ScriptFailed {
+        line: usize,
+        cmd: String,
+        reason: String,
+    },
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -6,8 +6,10 @@ mod command;
 mod context;
 mod error;
 mod result;
+mod script;
 
 pub use action::*;
 pub use command::*;
 pub use context::*;
 pub use result::*;
+pub use script::*;
@@ -8,18 +8,38 @@ use flatten_json::flatten;
 use serde_json::{json, Value};
 use std::str::FromStr;
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum OutputFormat {
     JSON,
     TABLE,
+    YAML,
+}
+
+impl OutputFormat {
+    pub fn variants() -> [&'static str; 3] {
+        ["json", "table", "yaml"]
+    }
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::TABLE
+    }
 }
 
 impl FromStr for OutputFormat {
-    type Err = ();
+    type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
+        Ok(match s.to_lowercase().as_str() {
             "json" => OutputFormat::JSON,
-            _ => OutputFormat::TABLE,
+            "table" => OutputFormat::TABLE,
+            "yaml" => OutputFormat::YAML,
+            other => bail!(
+                "Unsupported output format: {}, support [{}]",
+                other,
+                Self::variants().join("|")
+            ),
         })
     }
 }
@@ -28,6 +48,7 @@ pub fn print_action_result(value: Value, format: OutputFormat) -> Result<()> {
     match format {
         OutputFormat::JSON => print_json(value),
         OutputFormat::TABLE => print_table(value),
+        OutputFormat::YAML => print_yaml(value),
     }
 }
 
@@ -38,6 +59,13 @@ pub fn print_json(value: Value) -> Result<()> {
     Ok(())
 }
 
+pub fn print_yaml(value: Value) -> Result<()> {
+    let result = json!({ "result": value });
+    let yaml = serde_yaml::to_string(&result)?;
+    println!("{}", yaml);
+    Ok(())
+}
+
 fn head_row(first_value: &Value) -> Result<(Row, Box<dyn RowBuilder>)> {
     let bold = CellFormat::builder().bold(true).build();
     let simple_value = first_value.is_number()
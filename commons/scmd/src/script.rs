@@ -0,0 +1,214 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Batch script support.
+//!
+//! A script is a text file with one command per line, written the same way as in the console.
+//! Empty lines and lines starting with `#` are ignored.
+//! A line like `let name = wallet list` (or just `name = wallet list`) binds the command result to `name`,
+//! and later lines can reference it by `${name}`, or a nested field by `${name.0.address}`.
+//! `${_}` always refers to the result of the previous command.
+
+use anyhow::{bail, format_err, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub const LAST_RESULT_VAR: &str = "_";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScriptLine {
+    /// line number in script file, start from 1.
+    pub line_no: usize,
+    /// variable name to bind the command result.
+    pub binding: Option<String>,
+    /// command args, the first arg is the command name.
+    pub args: Vec<String>,
+}
+
+impl ScriptLine {
+    /// Parse a script line, return None if the line is empty or a comment.
+    pub fn parse(line_no: usize, line: &str) -> Result<Option<Self>> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+        let (binding, cmd) = split_binding(line)?;
+        let args = split_args(cmd)?;
+        if args.is_empty() {
+            bail!("Missing command at line {}", line_no);
+        }
+        Ok(Some(Self {
+            line_no,
+            binding,
+            args,
+        }))
+    }
+}
+
+fn is_valid_var_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != LAST_RESULT_VAR
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name
+            .chars()
+            .next()
+            .map(|c| c.is_ascii_digit())
+            .unwrap_or(true)
+}
+
+fn split_binding(line: &str) -> Result<(Option<String>, &str)> {
+    let line = if line.starts_with("let ") {
+        line[4..].trim_start()
+    } else {
+        line
+    };
+    if let Some(idx) = line.find('=') {
+        let name = line[..idx].trim();
+        // `=` after command args, such as `--arg x=1`, is not a binding.
+        if name.contains(char::is_whitespace) || name.starts_with('-') {
+            return Ok((None, line));
+        }
+        if !is_valid_var_name(name) {
+            bail!("Invalid variable name: {:?}", name);
+        }
+        return Ok((Some(name.to_string()), line[idx + 1..].trim()));
+    }
+    Ok((None, line))
+}
+
+/// Split a command line into args, support single and double quotes.
+pub fn split_args(line: &str) -> Result<Vec<String>> {
+    let mut args = vec![];
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), '\\') => match chars.next() {
+                Some(next) => current.push(next),
+                None => bail!("Unexpected end of line after escape: {}", line),
+            },
+            (Some(_), c) => current.push(c),
+            (None, '\'') | (None, '"') => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        bail!("Unclosed quote in line: {}", line);
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+#[derive(Default, Debug)]
+pub struct ScriptVariables {
+    vars: HashMap<String, Value>,
+}
+
+impl ScriptVariables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, name: &str, value: Value) {
+        self.vars.insert(name.to_string(), value);
+    }
+
+    /// Get variable value by path, such as `name`, `name.field` or `name.0.field`.
+    pub fn get(&self, path: &str) -> Option<&Value> {
+        let mut parts = path.split('.');
+        let name = parts.next()?;
+        let mut value = self.vars.get(name)?;
+        for part in parts {
+            value = match value {
+                Value::Object(obj) => obj.get(part)?,
+                Value::Array(array) => array.get(part.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    /// Replace all `${path}` in the arg with the variable value.
+    /// String values are inserted as is, other values are inserted as json.
+    pub fn substitute(&self, arg: &str) -> Result<String> {
+        let mut result = String::with_capacity(arg.len());
+        let mut rest = arg;
+        while let Some(start) = rest.find("${") {
+            result.push_str(&rest[..start]);
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| format_err!("Unclosed variable reference in: {}", arg))?;
+            let path = rest[start + 2..start + end].trim();
+            let value = self
+                .get(path)
+                .ok_or_else(|| format_err!("Undefined variable: {}", path))?;
+            match value {
+                Value::String(s) => result.push_str(s),
+                Value::Null => {}
+                v => result.push_str(v.to_string().as_str()),
+            }
+            rest = &rest[start + end + 1..];
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+
+    pub fn substitute_args(&self, args: &[String]) -> Result<Vec<String>> {
+        args.iter().map(|arg| self.substitute(arg)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(ScriptLine::parse(1, "  ").unwrap(), None);
+        assert_eq!(ScriptLine::parse(1, "# comment").unwrap(), None);
+        let line = ScriptLine::parse(2, "let acc = wallet show -a 'a b'")
+            .unwrap()
+            .unwrap();
+        assert_eq!(line.binding, Some("acc".to_string()));
+        assert_eq!(line.args, vec!["wallet", "show", "-a", "a b"]);
+        let line = ScriptLine::parse(3, "dev execute --arg x=1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(line.binding, None);
+        assert_eq!(line.args, vec!["dev", "execute", "--arg", "x=1"]);
+        assert!(ScriptLine::parse(4, "wallet show \"abc").is_err());
+    }
+
+    #[test]
+    fn test_substitute() {
+        let mut vars = ScriptVariables::new();
+        vars.set(
+            "acc",
+            json!([{"address": "0x01", "balance": 10}, {"address": "0x02"}]),
+        );
+        vars.set(LAST_RESULT_VAR, json!("hello"));
+        assert_eq!(vars.substitute("${acc.1.address}").unwrap(), "0x02");
+        assert_eq!(vars.substitute("b=${acc.0.balance}").unwrap(), "b=10");
+        assert_eq!(vars.substitute("${_}-${_}").unwrap(), "hello-hello");
+        assert!(vars.substitute("${acc.3}").is_err());
+        assert!(vars.substitute("${acc").is_err());
+    }
+}
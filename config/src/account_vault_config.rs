@@ -3,12 +3,60 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+/// Key derivation function used to encrypt the account private keys.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(tag = "type")]
+pub enum KeyStoreKdf {
+    Pbkdf2 {
+        iterations: u32,
+    },
+    /// scrypt with `N = 2^log_n`.
+    Scrypt {
+        log_n: u8,
+        r: u32,
+        p: u32,
+    },
+}
+
+impl Default for KeyStoreKdf {
+    fn default() -> Self {
+        KeyStoreKdf::Scrypt {
+            log_n: 15,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+/// Which wallet backend holds the account private keys.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(tag = "type")]
+pub enum WalletBackend {
+    /// Private keys are encrypted and stored in the vault dir.
+    KeyStore,
+    /// Private keys are held by an external signer process, the node only holds public keys,
+    /// and forwards the txns to sign to the signer by the unix socket.
+    /// Relative socket path is relative to the data dir.
+    ExternalSigner { socket: PathBuf },
+}
+
+impl Default for WalletBackend {
+    fn default() -> Self {
+        WalletBackend::KeyStore
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct AccountVaultConfig {
     dir: PathBuf,
+    /// Newly encrypted keys use this kdf, keys encrypted by other kdf are re-encrypted on unlock.
+    pub kdf: KeyStoreKdf,
+    pub backend: WalletBackend,
     #[serde(skip)]
     absolute_dir: Option<PathBuf>,
+    #[serde(skip)]
+    absolute_signer_socket: Option<PathBuf>,
 }
 
 impl Default for AccountVaultConfig {
@@ -24,18 +72,38 @@ impl AccountVaultConfig {
             .cloned()
             .expect("config should init first.")
     }
+
+    /// The socket path of the external signer, None if the backend is not external signer.
+    pub fn external_signer_socket(&self) -> Option<PathBuf> {
+        self.absolute_signer_socket.clone()
+    }
+
+    fn init_signer_socket(&mut self, base: &BaseConfig) {
+        self.absolute_signer_socket = match &self.backend {
+            WalletBackend::KeyStore => None,
+            WalletBackend::ExternalSigner { socket } => Some(if socket.is_relative() {
+                base.data_dir().join(socket)
+            } else {
+                socket.clone()
+            }),
+        };
+    }
 }
 
 impl ConfigModule for AccountVaultConfig {
     fn default_with_net(_net: ChainNetwork) -> Self {
         Self {
             dir: PathBuf::from("account_vaults"),
+            kdf: KeyStoreKdf::default(),
+            backend: WalletBackend::default(),
             absolute_dir: None,
+            absolute_signer_socket: None,
         }
     }
 
     fn random(&mut self, base: &BaseConfig) {
         self.absolute_dir = Some(base.data_dir().join(self.dir.as_path()));
+        self.init_signer_socket(base);
     }
 
     fn load(&mut self, base: &BaseConfig, _opt: &StarcoinOpt) -> Result<()> {
@@ -44,6 +112,7 @@ impl ConfigModule for AccountVaultConfig {
         } else {
             self.dir.clone()
         });
+        self.init_signer_socket(base);
         Ok(())
     }
 }
@@ -8,10 +8,11 @@ use once_cell::sync::Lazy;
 use rand::{rngs::StdRng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use starcoin_crypto::{
-    ed25519::*, hash::PlainCryptoHash, PrivateKey, SigningKey, Uniform,
+    ed25519::*, hash::PlainCryptoHash, HashValue, PrivateKey, SigningKey, Uniform,
     ValidCryptoMaterialStringExt,
 };
 use starcoin_types::{
+    block::BlockNumber,
     transaction::{
         helpers::TransactionSigner,
         {RawUserTransaction, SignedUserTransaction},
@@ -150,6 +151,44 @@ impl TransactionSigner for PreMineConfig {
     }
 }
 
+/// A trusted block, chains which contain another block at the same number are refused.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Checkpoint {
+    pub number: BlockNumber,
+    pub hash: HashValue,
+}
+
+impl Checkpoint {
+    pub fn new(number: BlockNumber, hash: HashValue) -> Self {
+        Self { number, hash }
+    }
+}
+
+impl Display for Checkpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.number, self.hash.to_hex())
+    }
+}
+
+impl FromStr for Checkpoint {
+    type Err = anyhow::Error;
+
+    /// Parse checkpoint from `number:hash`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, ':');
+        match (parts.next(), parts.next()) {
+            (Some(number), Some(hash)) => Ok(Checkpoint::new(
+                number.parse()?,
+                HashValue::from_hex(hash.trim_start_matches("0x"))?,
+            )),
+            _ => Err(format_err!(
+                "Invalid checkpoint {:?}, should be number:hash",
+                s
+            )),
+        }
+    }
+}
+
 /// ChainConfig is a static hard code config.
 #[derive(Debug)]
 pub struct ChainConfig {
@@ -169,6 +208,8 @@ pub struct ChainConfig {
     pub pre_mine_config: Option<PreMineConfig>,
     /// List of initial node addresses
     pub boot_nodes: Vec<Multiaddr>,
+    /// Trusted blocks of the chain.
+    pub checkpoints: Vec<Checkpoint>,
 }
 
 pub static STARCOIN_TOTAL_SUPPLY: u64 = 2_100_000_000 * 1_000_000;
@@ -192,6 +233,7 @@ pub static DEV_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| {
             pre_mine_percent: 20,
         }),
         boot_nodes: vec![],
+        checkpoints: vec![],
     }
 });
 
@@ -214,6 +256,7 @@ pub static HALLEY_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| {
     boot_nodes: vec!["/dns4/halley1.seed.starcoin.org/tcp/9840/p2p/12D3KooWFvCKQ1n2JkSQpn8drqGwU27vTPkKx264zD4CFbgaKDJU".parse().expect("parse multi addr should be ok"),
                      "/dns4/halley2.seed.starcoin.org/tcp/9840/p2p/12D3KooWAua4KokJMiCodGPEF2n4yN42B2Q26KgwrQTntnrCDRHd".parse().expect("parse multi addr should be ok"),
                      "/dns4/halley3.seed.starcoin.org/tcp/9840/p2p/12D3KooW9vHQJk9o69tZPMM2viQ3eWpgp6veDBRz8tTvDFDBejwk".parse().expect("parse multi addr should be ok"),],
+    checkpoints: vec![],
 }
 });
 
@@ -229,6 +272,7 @@ pub static PROXIMA_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| {
     boot_nodes: vec!["/dns4/proxima1.seed.starcoin.org/tcp/9840/p2p/12D3KooW9vHQJk9o69tZPMM2viQ3eWpgp6veDBRz8tTvDFDBejwk".parse().expect("parse multi addr should be ok"),
                      "/dns4/proxima2.seed.starcoin.org/tcp/9840/p2p/12D3KooWAua4KokJMiCodGPEF2n4yN42B2Q26KgwrQTntnrCDRHd".parse().expect("parse multi addr should be ok"),
                      "/dns4/proxima3.seed.starcoin.org/tcp/9840/p2p/12D3KooWFvCKQ1n2JkSQpn8drqGwU27vTPkKx264zD4CFbgaKDJU".parse().expect("parse multi addr should be ok"),],
+    checkpoints: vec![],
 }
 });
 
@@ -241,4 +285,5 @@ pub static MAIN_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| ChainConfig {
     consensus_header: vec![],
     pre_mine_config: None,
     boot_nodes: vec![],
+    checkpoints: vec![],
 });
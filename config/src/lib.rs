@@ -1,7 +1,6 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::account_vault_config::AccountVaultConfig;
 use crate::sync_config::SyncConfig;
 use anyhow::{ensure, Result};
 use libp2p::core::Multiaddr;
@@ -32,21 +31,22 @@ mod storage_config;
 mod sync_config;
 mod txpool_config;
 
+pub use account_vault_config::{AccountVaultConfig, KeyStoreKdf, WalletBackend};
 pub use chain_config::{
-    ChainConfig, ChainNetwork, PreMineConfig, DEV_CHAIN_CONFIG, HALLEY_CHAIN_CONFIG,
+    ChainConfig, ChainNetwork, Checkpoint, PreMineConfig, DEV_CHAIN_CONFIG, HALLEY_CHAIN_CONFIG,
     MAIN_CHAIN_CONFIG, PROXIMA_CHAIN_CONFIG,
 };
 pub use libra_temppath::TempPath;
 pub use logger_config::LoggerConfig;
 pub use metrics_config::MetricsConfig;
 pub use miner_config::{ConsensusStrategy, MinerConfig, PacemakerStrategy};
-pub use network_config::NetworkConfig;
-pub use rpc_config::RpcConfig;
+pub use network_config::{MessageCompression, MessageSizeLimit, NetworkConfig};
+pub use rpc_config::{HealthCheckConfig, RpcConfig};
 use starcoin_crypto::keygen::KeyGen;
 use std::str::FromStr;
 pub use storage_config::StorageConfig;
-pub use sync_config::SyncMode;
-pub use txpool_config::TxPoolConfig;
+pub use sync_config::{SyncMode, DEFAULT_MAX_REORG_DEPTH};
+pub use txpool_config::{TxPoolConfig, TxPoolJournalMode};
 
 /// Default data dir
 static DEFAULT_BASE_DATA_DIR: Lazy<PathBuf> = Lazy::new(|| {
@@ -72,6 +72,8 @@ pub enum Connect {
     IPC(Option<PathBuf>),
     /// Connect by json rpc address.
     WebSocket(String),
+    /// Do not connect to any node, only offline commands are available.
+    Offline,
 }
 
 impl Default for Connect {
@@ -87,6 +89,9 @@ impl FromStr for Connect {
         if s.is_empty() {
             return Ok(Self::default());
         }
+        if s == "offline" {
+            return Ok(Connect::Offline);
+        }
         if s.starts_with("ws://") || s.starts_with("wss://") {
             Ok(Connect::WebSocket(s.to_string()))
         } else {
@@ -99,7 +104,7 @@ impl FromStr for Connect {
 #[structopt(name = "starcoin", about = "Starcoin")]
 pub struct StarcoinOpt {
     #[structopt(long, short = "c")]
-    /// Connect and attach to a node
+    /// Connect and attach to a node, use `offline` to run offline commands without a node
     pub connect: Option<Connect>,
 
     #[structopt(long, short = "d", parse(from_os_str))]
@@ -149,6 +154,34 @@ pub struct StarcoinOpt {
     #[structopt(long = "disable-seed")]
     /// Disable seed for seed node.
     pub disable_seed: bool,
+
+    #[structopt(long = "max-in-peers")]
+    /// Maximum number of inbound peer connections.
+    pub max_in_peers: Option<u32>,
+
+    #[structopt(long = "max-out-peers")]
+    /// Number of outbound peer connections to maintain.
+    pub max_out_peers: Option<u32>,
+
+    #[structopt(long = "reserved-node")]
+    /// Reserved node address, the last part must is p2p/peer_id, can be set multiple times.
+    pub reserved_nodes: Vec<Multiaddr>,
+
+    #[structopt(long = "reserved-only")]
+    /// Only connect to reserved nodes.
+    pub reserved_only: bool,
+
+    #[structopt(long = "enable-mdns")]
+    /// Discover peers in the local network by mDNS.
+    pub enable_mdns: bool,
+
+    #[structopt(long = "checkpoint")]
+    /// Trusted block as number:hash, chains conflict with it are refused, can be set multiple times.
+    pub checkpoints: Vec<Checkpoint>,
+
+    #[structopt(long = "max-reorg-depth")]
+    /// Max number of master blocks can be rolled back by a reorg, default is 1000.
+    pub max_reorg_depth: Option<u64>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -311,6 +344,18 @@ impl NodeConfig {
     pub fn net(&self) -> ChainNetwork {
         self.base.net
     }
+
+    /// Checkpoints of the chain network and the operator, sorted by block number.
+    pub fn checkpoints(&self) -> Vec<Checkpoint> {
+        let mut checkpoints = self.net().get_config().checkpoints.clone();
+        for checkpoint in self.sync.checkpoints() {
+            if !checkpoints.contains(checkpoint) {
+                checkpoints.push(*checkpoint);
+            }
+        }
+        checkpoints.sort_by_key(|checkpoint| checkpoint.number);
+        checkpoints
+    }
 }
 
 impl Default for NodeConfig {
@@ -474,6 +519,8 @@ fn get_ephemeral_port() -> ::std::io::Result<u16> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use starcoin_crypto::HashValue;
+    use starcoin_types::peer_info::PeerId;
 
     #[test]
     fn test_serialize() -> Result<()> {
@@ -485,4 +532,46 @@ mod tests {
         assert_eq!(config2, config3);
         Ok(())
     }
+
+    #[test]
+    fn test_reserved_nodes() -> Result<()> {
+        let mut opt = StarcoinOpt::default();
+        opt.reserved_only = true;
+        assert!(NodeConfig::load_with_opt(&opt).is_err());
+
+        let node: Multiaddr = format!("/ip4/127.0.0.1/tcp/9840/p2p/{}", PeerId::random())
+            .parse()
+            .unwrap();
+        opt.reserved_nodes = vec![node.clone(), node.clone()];
+        let config = NodeConfig::load_with_opt(&opt)?;
+        assert!(config.network.reserved_only);
+        assert_eq!(config.network.reserved_nodes, vec![node]);
+
+        opt.reserved_nodes = vec!["/ip4/127.0.0.1/tcp/9840".parse().unwrap()];
+        assert!(NodeConfig::load_with_opt(&opt).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_checkpoints() -> Result<()> {
+        let mut opt = StarcoinOpt::default();
+        let checkpoint1 = Checkpoint::new(100, HashValue::random());
+        let checkpoint2 = Checkpoint::new(10, HashValue::random());
+        assert_eq!(
+            Checkpoint::from_str(checkpoint1.to_string().as_str())?,
+            checkpoint1
+        );
+        assert!(Checkpoint::from_str("100").is_err());
+
+        opt.checkpoints = vec![checkpoint1, checkpoint2, checkpoint1];
+        opt.max_reorg_depth = Some(10);
+        let config = NodeConfig::load_with_opt(&opt)?;
+        assert_eq!(config.checkpoints(), vec![checkpoint2, checkpoint1]);
+        assert_eq!(config.sync.max_reorg_depth(), 10);
+
+        opt.checkpoints
+            .push(Checkpoint::new(checkpoint1.number, HashValue::random()));
+        assert!(NodeConfig::load_with_opt(&opt).is_err());
+        Ok(())
+    }
 }
@@ -20,6 +20,51 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 pub static DEFAULT_NETWORK_PORT: u16 = 9840;
+pub static DEFAULT_MAX_IN_PEERS: u32 = 25;
+pub static DEFAULT_MAX_OUT_PEERS: u32 = 75;
+pub static DEFAULT_MAX_INBOUND_MESSAGES_PER_SEC: u32 = 1000;
+
+/// Compression of network messages.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub enum MessageCompression {
+    None,
+    Snappy,
+}
+
+impl Default for MessageCompression {
+    fn default() -> Self {
+        MessageCompression::Snappy
+    }
+}
+
+/// Max size in bytes of a decoded network message, per message type.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MessageSizeLimit {
+    pub transactions: usize,
+    pub block: usize,
+    /// RPC requests and responses are not compressed.
+    pub rpc_request: usize,
+    pub rpc_response: usize,
+}
+
+impl MessageSizeLimit {
+    /// The max size of the notification message types.
+    pub fn max_notification(&self) -> usize {
+        self.transactions.max(self.block)
+    }
+}
+
+impl Default for MessageSizeLimit {
+    fn default() -> Self {
+        Self {
+            transactions: 1024 * 1024,
+            block: 4 * 1024 * 1024,
+            rpc_request: 64 * 1024,
+            rpc_response: 16 * 1024 * 1024,
+        }
+    }
+}
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -35,6 +80,26 @@ pub struct NetworkConfig {
     #[serde(skip)]
     pub self_address: Option<Multiaddr>,
     pub disable_seed: bool,
+    /// Maximum number of inbound peer connections.
+    pub max_in_peers: u32,
+    /// Number of outbound peer connections we try to maintain.
+    pub max_out_peers: u32,
+    /// Peers always connect to, the address must end with p2p/peer_id.
+    pub reserved_nodes: Vec<Multiaddr>,
+    /// Only connect to reserved nodes, and deny other peers.
+    pub reserved_only: bool,
+    /// Addresses to advertise to other peers, detected automatically if empty.
+    pub public_addresses: Vec<Multiaddr>,
+    /// Discover peers in the local network by mDNS.
+    pub enable_mdns: bool,
+    /// Allow connecting to private ipv4 addresses found by discovery,
+    /// seeds and reserved nodes are always allowed.
+    pub allow_private_ipv4: bool,
+    /// Compression of sent messages, received messages are always accepted compressed or not.
+    pub compression: MessageCompression,
+    pub max_message_size: MessageSizeLimit,
+    /// Max messages received from a peer per second, 0 means unlimited.
+    pub max_inbound_messages_per_sec: u32,
     #[serde(skip)]
     pub protocols: Vec<Cow<'static, [u8]>>,
 }
@@ -50,14 +115,32 @@ impl NetworkConfig {
         self.network_keypair.clone().unwrap()
     }
 
+    /// Change the listen address, and update self address with it.
+    pub fn set_listen(&mut self, listen: Multiaddr) {
+        self.listen = listen;
+        if self.network_keypair.is_some() {
+            self.set_peer_id();
+        }
+    }
+
+    /// Only nodes in the same process can connect when listen on a `/memory/` address.
+    pub fn is_memory_transport(&self) -> bool {
+        self.listen
+            .iter()
+            .any(|protocol| matches!(protocol, Protocol::Memory(_)))
+    }
+
     fn set_peer_id(&mut self) {
         let peer_id = PeerId::from_ed25519_public_key(self.network_keypair().public_key.clone());
-        //TODO use a more robust method to get local best advertise ip
-        let host = self
-            .listen
-            .clone()
-            .replace(0, |_p| Some(Protocol::Ip4(Ipv4Addr::new(127, 0, 0, 1))))
-            .expect("Replace multi address fail.");
+        let host = if self.is_memory_transport() {
+            self.listen.clone()
+        } else {
+            //TODO use a more robust method to get local best advertise ip
+            self.listen
+                .clone()
+                .replace(0, |_p| Some(Protocol::Ip4(Ipv4Addr::new(127, 0, 0, 1))))
+                .expect("Replace multi address fail.")
+        };
         let mut p2p_address = host;
         p2p_address.push(Protocol::P2p(peer_id.clone().into()));
         self.self_address = Some(p2p_address);
@@ -73,6 +156,16 @@ impl NetworkConfig {
             seed
         )
     }
+
+    fn check_reserved_node(node: &Multiaddr) -> Result<()> {
+        if let Some(Protocol::P2p(_peer_id)) = node.clone().pop() {
+            return Ok(());
+        }
+        bail!(
+            "Invalid reserved node {:?}, reserved node addr last part must is p2p/peer_id ",
+            node
+        )
+    }
 }
 
 impl ConfigModule for NetworkConfig {
@@ -91,6 +184,16 @@ impl ConfigModule for NetworkConfig {
             self_peer_id: None,
             self_address: None,
             disable_seed: false,
+            max_in_peers: DEFAULT_MAX_IN_PEERS,
+            max_out_peers: DEFAULT_MAX_OUT_PEERS,
+            reserved_nodes: vec![],
+            reserved_only: false,
+            public_addresses: vec![],
+            enable_mdns: false,
+            allow_private_ipv4: false,
+            compression: MessageCompression::default(),
+            max_message_size: MessageSizeLimit::default(),
+            max_inbound_messages_per_sec: DEFAULT_MAX_INBOUND_MESSAGES_PER_SEC,
             protocols: vec![
                 CHAIN_PROTOCOL_NAME.into(),
                 TXN_PROTOCOL_NAME.into(),
@@ -129,6 +232,30 @@ impl ConfigModule for NetworkConfig {
         for seed in &self.seeds {
             Self::check_seed(seed)?;
         }
+        if let Some(max_in_peers) = opt.max_in_peers {
+            self.max_in_peers = max_in_peers;
+        }
+        if let Some(max_out_peers) = opt.max_out_peers {
+            self.max_out_peers = max_out_peers;
+        }
+        for node in &opt.reserved_nodes {
+            if !self.reserved_nodes.contains(node) {
+                self.reserved_nodes.push(node.clone());
+            }
+        }
+        for node in &self.reserved_nodes {
+            Self::check_reserved_node(node)?;
+        }
+        if opt.reserved_only {
+            self.reserved_only = true;
+        }
+        ensure!(
+            !self.reserved_only || !self.reserved_nodes.is_empty(),
+            "reserved_only is set, but no reserved node is configured."
+        );
+        if opt.enable_mdns {
+            self.enable_mdns = true;
+        }
         let data_dir = base.data_dir();
         let path = data_dir.join(&self.network_key_file);
         let keypair = if path.exists() {
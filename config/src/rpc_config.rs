@@ -17,6 +17,8 @@ const DEFAULT_IPC_FILE: &str = "starcoin.ipc";
 const DEFAULT_HTTP_PORT: u16 = 9850;
 const DEFAULT_TCP_PORT: u16 = 9851;
 const DEFAULT_WEB_SOCKET_PORT: u16 = 9852;
+const DEFAULT_READY_MAX_HEAD_AGE: u64 = 600;
+const DEFAULT_READY_MAX_TXPOOL_USAGE: u8 = 95;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -29,10 +31,47 @@ pub struct RpcConfig {
     pub ws_address: Option<SocketAddr>,
     pub max_request_body_size: usize,
     pub threads: Option<usize>,
+    /// Thresholds used by the readiness check.
+    pub health: HealthCheckConfig,
     #[serde(skip)]
     ipc_file_path: Option<PathBuf>,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthCheckConfig {
+    /// Node is not ready if connected peers is less than this value.
+    pub ready_min_peers: usize,
+    /// Node is not ready if the head block is older than this value in seconds, 0 means no limit.
+    pub ready_max_head_age: u64,
+    /// Node is not ready if the txpool usage(percent of max count or max memory) is greater than this value.
+    pub ready_max_txpool_usage: u8,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self::default_with_net(ChainNetwork::default())
+    }
+}
+
+impl HealthCheckConfig {
+    pub fn default_with_net(net: ChainNetwork) -> Self {
+        match net {
+            // dev node may run alone, and only mine block when there are pending txns.
+            ChainNetwork::Dev => Self {
+                ready_min_peers: 0,
+                ready_max_head_age: 0,
+                ready_max_txpool_usage: DEFAULT_READY_MAX_TXPOOL_USAGE,
+            },
+            _ => Self {
+                ready_min_peers: 1,
+                ready_max_head_age: DEFAULT_READY_MAX_HEAD_AGE,
+                ready_max_txpool_usage: DEFAULT_READY_MAX_TXPOOL_USAGE,
+            },
+        }
+    }
+}
+
 impl Default for RpcConfig {
     fn default() -> Self {
         Self::default_with_net(ChainNetwork::default())
@@ -84,6 +123,7 @@ impl ConfigModule for RpcConfig {
             tcp_address: Some(tcp_address),
             max_request_body_size: DEFAULT_MAX_REQUEST_BODY_SIZE,
             threads: None,
+            health: HealthCheckConfig::default_with_net(net),
             ipc_file_path: None,
         }
     }
@@ -1,5 +1,5 @@
-use crate::{BaseConfig, ChainNetwork, ConfigModule, StarcoinOpt};
-use anyhow::{format_err, Result};
+use crate::{BaseConfig, ChainNetwork, Checkpoint, ConfigModule, StarcoinOpt};
+use anyhow::{ensure, format_err, Result};
 use serde::{Deserialize, Serialize};
 use starcoin_logger::prelude::*;
 use std::fmt::{Display, Formatter};
@@ -9,8 +9,14 @@ use std::str::FromStr;
 #[serde(default, deny_unknown_fields)]
 pub struct SyncConfig {
     sync_mode: SyncMode,
+    /// Trusted blocks supplied by operator, in addition to the checkpoints of the chain network.
+    checkpoints: Vec<Checkpoint>,
+    /// Max number of master blocks can be rolled back by a reorg.
+    max_reorg_depth: u64,
 }
 
+pub const DEFAULT_MAX_REORG_DEPTH: u64 = 1000;
+
 impl SyncConfig {
     pub fn is_state_sync(&self) -> bool {
         self.sync_mode == SyncMode::FAST
@@ -28,6 +34,14 @@ impl SyncConfig {
     pub fn full_sync_mode(&mut self) {
         self.sync_mode = SyncMode::FULL;
     }
+
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        self.checkpoints.as_slice()
+    }
+
+    pub fn max_reorg_depth(&self) -> u64 {
+        self.max_reorg_depth
+    }
 }
 
 impl ConfigModule for SyncConfig {
@@ -38,6 +52,8 @@ impl ConfigModule for SyncConfig {
             } else {
                 SyncMode::FAST
             },
+            checkpoints: vec![],
+            max_reorg_depth: DEFAULT_MAX_REORG_DEPTH,
         }
     }
 
@@ -48,6 +64,26 @@ impl ConfigModule for SyncConfig {
             opt.sync_mode.clone()
         };
         info!("sync mode : {:?} : {:?}", opt.sync_mode, self.sync_mode);
+        for checkpoint in &opt.checkpoints {
+            if !self.checkpoints.contains(checkpoint) {
+                self.checkpoints.push(*checkpoint);
+            }
+        }
+        for checkpoint in &self.checkpoints {
+            ensure!(
+                base.net
+                    .get_config()
+                    .checkpoints
+                    .iter()
+                    .chain(self.checkpoints.iter())
+                    .all(|other| other.number != checkpoint.number || other == checkpoint),
+                "Checkpoint {} conflicts with another checkpoint.",
+                checkpoint
+            );
+        }
+        if let Some(max_reorg_depth) = opt.max_reorg_depth {
+            self.max_reorg_depth = max_reorg_depth;
+        }
         Ok(())
     }
 }
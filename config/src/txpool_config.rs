@@ -1,5 +1,18 @@
-use crate::{ChainNetwork, ConfigModule};
+use crate::{BaseConfig, ChainNetwork, ConfigModule, StarcoinOpt};
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Which pending txns are journaled, journaled txns are re-imported after the node restart.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum TxPoolJournalMode {
+    Off,
+    /// Only txns submitted by this node.
+    Local,
+    /// All pending txns, include the txns from peers.
+    All,
+}
 
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -12,9 +25,18 @@ pub struct TxPoolConfig {
     pub max_mem_usage: u64,
     /// Minimal allowed gas price.
     pub minimal_gas_price: u64,
+    /// Minimal gas price bump in percent to replace a pending txn with the same sequence number.
+    pub price_bump_percent: u64,
     /// Maximal gas limit for a single transaction.
     #[serde(skip)]
     pub tx_gas_limit: u64,
+    /// Number of recent blocks sampled for the gas price suggestion.
+    pub gas_price_sample_blocks: u64,
+    pub journal: TxPoolJournalMode,
+    /// Journal file path, relative path is relative to the data dir.
+    pub journal_file: PathBuf,
+    #[serde(skip)]
+    absolute_journal_file: Option<PathBuf>,
 }
 
 impl Default for TxPoolConfig {
@@ -23,6 +45,16 @@ impl Default for TxPoolConfig {
     }
 }
 
+impl TxPoolConfig {
+    /// The journal file, None if journal is off or the config is not loaded.
+    pub fn journal_file(&self) -> Option<PathBuf> {
+        if self.journal == TxPoolJournalMode::Off {
+            return None;
+        }
+        self.absolute_journal_file.clone()
+    }
+}
+
 impl ConfigModule for TxPoolConfig {
     fn default_with_net(_net: ChainNetwork) -> Self {
         Self {
@@ -30,7 +62,25 @@ impl ConfigModule for TxPoolConfig {
             max_per_sender: 16,
             max_mem_usage: 64 * 1024 * 1024, // 64M
             minimal_gas_price: 0,
+            price_bump_percent: 10,
             tx_gas_limit: u64::max_value(),
+            gas_price_sample_blocks: 20,
+            journal: TxPoolJournalMode::Local,
+            journal_file: PathBuf::from("txpool_journal"),
+            absolute_journal_file: None,
         }
     }
+
+    fn random(&mut self, base: &BaseConfig) {
+        self.absolute_journal_file = Some(base.data_dir().join(self.journal_file.as_path()));
+    }
+
+    fn load(&mut self, base: &BaseConfig, _opt: &StarcoinOpt) -> Result<()> {
+        self.absolute_journal_file = Some(if self.journal_file.is_relative() {
+            base.data_dir().join(&self.journal_file)
+        } else {
+            self.journal_file.clone()
+        });
+        Ok(())
+    }
 }
@@ -129,6 +129,12 @@ impl MerkleAccumulator {
             .unwrap()
             .unwrap()
     }
+
+    /// Like `flush`, but return the nodes instead of saving them, so the caller
+    /// can save them together with other data in one batch.
+    pub fn pop_unsaved_nodes(&self) -> Vec<AccumulatorNode> {
+        self.tree.lock().pop_unsaved_nodes()
+    }
 }
 
 impl Accumulator for MerkleAccumulator {
@@ -228,6 +228,15 @@ impl AccumulatorTree {
         Ok(())
     }
 
+    /// Take the nodes not flushed yet, the caller should save them to storage.
+    pub(crate) fn pop_unsaved_nodes(&self) -> Vec<AccumulatorNode> {
+        self.update_nodes
+            .lock()
+            .drain()
+            .map(|(_, node)| node)
+            .collect()
+    }
+
     pub(crate) fn get_frozen_subtree_roots(&self) -> Result<Vec<HashValue>> {
         let result = FrozenSubTreeIterator::new(self.num_leaves)
             .map(|p| self.get_node_hash(p).unwrap())
@@ -1,109 +1,136 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-// // Copyright (c) The Libra Core Contributors
-// // SPDX-License-Identifier: Apache-2.0
-//
-// use crate::{
-//     mock_tree_store::MockTreeStore, restore::JellyfishMerkleRestore, test_helper::init_mock_db,
-//     JellyfishMerkleTree, TreeReader,
-// };
-// use libra_crypto::HashValue;
-// use libra_types::{account_state_blob::AccountStateBlob, transaction::Version};
-// use proptest::{collection::btree_map, prelude::*};
-// use std::collections::BTreeMap;
-//
-// proptest! {
-//     #![proptest_config(ProptestConfig::with_cases(10))]
-//
-//     #[test]
-//     fn test_restore_without_interruption(
-//         btree in btree_map(any::<HashValue>(), any::<AccountStateBlob>(), 1..1000),
-//     ) {
-//         let (db, version) = init_mock_db(&btree.clone().into_iter().collect());
-//         let tree = JellyfishMerkleTree::new(&db);
-//         let expected_root_hash = tree.get_root_hash(version).unwrap();
-//
-//         // For this test, restore everything without interruption.
-//         let restore_db = MockTreeStore::default();
-//         let mut restore =
-//             JellyfishMerkleRestore::new(&restore_db, version, expected_root_hash).unwrap();
-//         for (key, value) in &btree {
-//             let proof = tree.get_range_proof(*key, version).unwrap();
-//             restore
-//                 .add_chunk(vec![(*key, value.clone())], proof)
-//                 .unwrap();
-//         }
-//         restore.finish().unwrap();
-//
-//         assert_success(&restore_db, expected_root_hash, &btree, version);
-//     }
-//
-//     #[test]
-//     fn test_restore_with_interruption(
-//         (all, batch1_size) in btree_map(any::<HashValue>(), any::<AccountStateBlob>(), 2..1000)
-//             .prop_flat_map(|btree| {
-//                 let len = btree.len();
-//                 (Just(btree), 1..len)
-//             })
-//     ) {
-//         let (db, version) = init_mock_db(&all.clone().into_iter().collect());
-//         let tree = JellyfishMerkleTree::new(&db);
-//         let expected_root_hash = tree.get_root_hash(version).unwrap();
-//         let batch1: Vec<_> = all.clone().into_iter().take(batch1_size).collect();
-//
-//         let restore_db = MockTreeStore::default();
-//         {
-//             let mut restore =
-//                 JellyfishMerkleRestore::new(&restore_db, version, expected_root_hash).unwrap();
-//             let proof = tree
-//                 .get_range_proof(batch1.last().map(|(key, _value)| *key).unwrap(), version)
-//                 .unwrap();
-//             restore.add_chunk(batch1, proof).unwrap();
-//             // Do not call `finish`.
-//         }
-//
-//         {
-//             let rightmost_key = match restore_db.get_rightmost_leaf().unwrap() {
-//                 None => {
-//                     // Sometimes the batch is too small so nothing is written to DB.
-//                     return Ok(());
-//                 }
-//                 Some((_, node)) => node.account_key(),
-//             };
-//             let remaining_accounts: Vec<_> = all
-//                 .clone()
-//                 .into_iter()
-//                 .filter(|(k, _v)| *k > rightmost_key)
-//                 .collect();
-//
-//             let mut restore =
-//                 JellyfishMerkleRestore::new(&restore_db, version, expected_root_hash).unwrap();
-//             let proof = tree
-//                 .get_range_proof(
-//                     remaining_accounts.last().map(|(key, _value)| *key).unwrap(),
-//                     version,
-//                 )
-//                 .unwrap();
-//             restore.add_chunk(remaining_accounts, proof).unwrap();
-//             restore.finish().unwrap();
-//         }
-//
-//         assert_success(&restore_db, expected_root_hash, &all, version);
-//     }
-// }
-//
-// fn assert_success(
-//     db: &MockTreeStore,
-//     expected_root_hash: HashValue,
-//     btree: &BTreeMap<HashValue, AccountStateBlob>,
-//     version: Version,
-// ) {
-//     let tree = JellyfishMerkleTree::new(db);
-//     for (key, value) in btree {
-//         assert_eq!(tree.get(*key, version).unwrap(), Some(value.clone()));
-//     }
-//
-//     let actual_root_hash = tree.get_root_hash(version).unwrap();
-//     assert_eq!(actual_root_hash, expected_root_hash);
-// }
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    blob::Blob, mock_tree_store::MockTreeStore, restore::JellyfishMerkleRestore,
+    test_helper::init_mock_db, JellyfishMerkleTree,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use starcoin_crypto::HashValue;
+use std::{collections::BTreeMap, sync::Arc};
+
+fn random_kvs(seed: u8, num_keys: usize) -> BTreeMap<HashValue, Blob> {
+    let mut rng = StdRng::from_seed([seed; 32]);
+    let mut btree = BTreeMap::new();
+    for _ in 0..num_keys {
+        let key = HashValue::random_with_rng(&mut rng);
+        let value = Blob::from(HashValue::random_with_rng(&mut rng).to_vec());
+        btree.insert(key, value);
+    }
+    btree
+}
+
+fn restore_in_chunks(btree: &BTreeMap<HashValue, Blob>, chunk_size: usize) {
+    let (db, root) = init_mock_db(&btree.clone().into_iter().collect());
+    let expected_root_hash = root.unwrap();
+    let tree = JellyfishMerkleTree::new(&db);
+
+    let restore_db = Arc::new(MockTreeStore::default());
+    let mut restore = JellyfishMerkleRestore::new(restore_db.clone(), expected_root_hash);
+    let all: Vec<_> = btree.clone().into_iter().collect();
+    for chunk in all.chunks(chunk_size) {
+        let rightmost_key = chunk.last().map(|(key, _value)| *key).unwrap();
+        let proof = tree
+            .get_range_proof(expected_root_hash, rightmost_key)
+            .unwrap();
+        restore.add_chunk(chunk.to_vec(), proof).unwrap();
+        assert_eq!(restore.previous_key(), Some(rightmost_key));
+    }
+    assert_eq!(restore.num_keys_received(), btree.len() as u64);
+    assert_eq!(restore.finish().unwrap(), expected_root_hash);
+
+    assert_success(&restore_db, expected_root_hash, btree);
+}
+
+#[test]
+fn test_restore_single_key() {
+    restore_in_chunks(&random_kvs(1, 1), 1);
+}
+
+#[test]
+fn test_restore_one_key_per_chunk() {
+    for (seed, num_keys) in [(2, 2), (3, 17), (4, 300)].iter() {
+        restore_in_chunks(&random_kvs(*seed, *num_keys), 1);
+    }
+}
+
+#[test]
+fn test_restore_random_chunk_size() {
+    let mut rng = StdRng::from_seed([5; 32]);
+    for seed in 6..16 {
+        let num_keys = rng.gen_range(2, 1000);
+        let chunk_size = rng.gen_range(1, num_keys + 1);
+        restore_in_chunks(&random_kvs(seed, num_keys), chunk_size);
+    }
+}
+
+#[test]
+fn test_restore_with_bad_proof() {
+    let btree = random_kvs(16, 100);
+    let (db, root) = init_mock_db(&btree.clone().into_iter().collect());
+    let expected_root_hash = root.unwrap();
+    let tree = JellyfishMerkleTree::new(&db);
+    let all: Vec<_> = btree.into_iter().collect();
+    let (first, second) = all.split_at(50);
+
+    // A proof for a different range does not verify.
+    let mut restore =
+        JellyfishMerkleRestore::new(Arc::new(MockTreeStore::default()), expected_root_hash);
+    let wrong_proof = tree
+        .get_range_proof(expected_root_hash, second.last().unwrap().0)
+        .unwrap();
+    assert!(restore.add_chunk(first.to_vec(), wrong_proof).is_err());
+
+    // A tampered value does not verify.
+    let mut restore =
+        JellyfishMerkleRestore::new(Arc::new(MockTreeStore::default()), expected_root_hash);
+    let proof = tree
+        .get_range_proof(expected_root_hash, first.last().unwrap().0)
+        .unwrap();
+    let mut tampered = first.to_vec();
+    tampered[10].1 = Blob::from(vec![1, 2, 3]);
+    assert!(restore.add_chunk(tampered, proof.clone()).is_err());
+
+    // Keys out of order are rejected.
+    let mut restore =
+        JellyfishMerkleRestore::new(Arc::new(MockTreeStore::default()), expected_root_hash);
+    let mut reversed = first.to_vec();
+    reversed.reverse();
+    assert!(restore.add_chunk(reversed, proof).is_err());
+}
+
+#[test]
+fn test_restore_missing_keys() {
+    let btree = random_kvs(17, 100);
+    let (db, root) = init_mock_db(&btree.clone().into_iter().collect());
+    let expected_root_hash = root.unwrap();
+    let tree = JellyfishMerkleTree::new(&db);
+    let all: Vec<_> = btree.into_iter().collect();
+    let chunk = all[..50].to_vec();
+
+    let mut restore =
+        JellyfishMerkleRestore::new(Arc::new(MockTreeStore::default()), expected_root_hash);
+    let proof = tree
+        .get_range_proof(expected_root_hash, chunk.last().unwrap().0)
+        .unwrap();
+    restore.add_chunk(chunk, proof).unwrap();
+    // The remaining keys never come, the restored root does not match.
+    assert!(restore.finish().is_err());
+}
+
+fn assert_success(
+    db: &MockTreeStore,
+    expected_root_hash: HashValue,
+    btree: &BTreeMap<HashValue, Blob>,
+) {
+    let tree = JellyfishMerkleTree::new(db);
+    for (key, value) in btree {
+        assert_eq!(
+            tree.get(expected_root_hash, *key).unwrap(),
+            Some(value.clone())
+        );
+    }
+}
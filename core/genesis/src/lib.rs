@@ -15,7 +15,7 @@ use starcoin_state_api::ChainState;
 use starcoin_statedb::ChainStateDB;
 use starcoin_storage::cache_storage::CacheStorage;
 use starcoin_storage::storage::StorageInstance;
-use starcoin_storage::{Storage, Store};
+use starcoin_storage::{BlockData, Storage, Store};
 use starcoin_types::block::{BlockInfo, BlockState};
 use starcoin_types::startup_info::StartupInfo;
 use starcoin_types::transaction::{ChangeSet, TransactionInfo};
@@ -188,8 +188,6 @@ impl Genesis {
             "Genesis block accumulator root mismatch."
         );
         //TODO verify consensus header
-        storage.commit_block(block.clone(), BlockState::Executed)?;
-
         let startup_info = StartupInfo::new(block.header().id(), vec![]);
         let block_info = BlockInfo::new_with_accumulator_info(
             block.header().id(),
@@ -198,7 +196,13 @@ impl Genesis {
             U512::zero(),
         );
         debug!("Genesis block_info: {:?}", block_info);
-        storage.save_block_info(block_info)?;
+        storage.commit_block_data(BlockData::new(
+            block.clone(),
+            BlockState::Executed,
+            block_info,
+            vec![],
+            vec![],
+        ))?;
         storage.save_startup_info(startup_info.clone())?;
         Ok(startup_info)
     }
@@ -27,6 +27,8 @@ pub trait ChainService {
         block_info: BlockInfo,
     ) -> Result<ConnectResult<()>>;
     fn get_block_info_by_hash(&self, hash: HashValue) -> Result<Option<BlockInfo>>;
+    /// verify header difficulty and consensus proof against master
+    fn verify_header(&self, header: &BlockHeader) -> Result<()>;
 
     /// for master
     fn master_head_header(&self) -> BlockHeader;
@@ -67,6 +69,7 @@ pub trait ChainAsyncService:
         block_info: BlockInfo,
     ) -> Result<ConnectResult<()>>;
     async fn get_block_info_by_hash(self, hash: &HashValue) -> Result<Option<BlockInfo>>;
+    async fn verify_header(self, header: BlockHeader) -> Result<()>;
 
     /// for master
     async fn master_head_header(self) -> Result<Option<BlockHeader>>;
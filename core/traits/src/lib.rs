@@ -8,6 +8,7 @@ mod consensus;
 pub use chain::{Chain, ChainReader, ChainWriter, ExcludedTxns};
 pub use chain_service::{ChainAsyncService, ChainService};
 pub use consensus::{Consensus, ConsensusHeader};
+use starcoin_types::block::BlockNumber;
 use thiserror::Error;
 
 pub type ConnectResult<T> = anyhow::Result<T, ConnectBlockError>;
@@ -20,6 +21,10 @@ pub enum ConnectBlockError {
     FutureBlock,
     #[error("block verify failed.")]
     VerifyFailed,
+    #[error("block conflicts with checkpoint at {0}.")]
+    CheckpointConflict(BlockNumber),
+    #[error("fork point {0} is too deep to reorg.")]
+    ReorgTooDeep(BlockNumber),
     #[error("connect failed, cause : {0:?}")]
     Other(String),
 }
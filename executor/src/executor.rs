@@ -7,15 +7,19 @@ use starcoin_config::ChainConfig;
 use starcoin_types::language_storage::TypeTag;
 use starcoin_types::{
     account_address::AccountAddress,
-    transaction::{RawUserTransaction, SignedUserTransaction, Transaction, TransactionOutput},
+    transaction::{
+        RawUserTransaction, SignedUserTransaction, Transaction, TransactionOutput, TransactionTrace,
+    },
     vm_error::VMStatus,
 };
 use starcoin_vm_types::account_config::stc_type_tag;
 use starcoin_vm_types::{state_view::StateView, transaction::ChangeSet};
+use std::time::Duration;
 use vm_runtime::genesis::generate_genesis_state_set;
 use vm_runtime::{
     common_transactions::{
         peer_to_peer_txn_sent_as_association, raw_accept_coin_txn, raw_peer_to_peer_txn,
+        DEFAULT_EXPIRATION_TIME,
     },
     counters::TXN_EXECUTION_HISTOGRAM,
     starcoin_vm::StarcoinVM,
@@ -40,6 +44,7 @@ impl Executor {
         gas_price: u64,
         max_gas: u64,
         coin_type: TypeTag,
+        expiration_time: Duration,
     ) -> RawUserTransaction {
         raw_peer_to_peer_txn(
             sender,
@@ -50,6 +55,7 @@ impl Executor {
             gas_price,
             max_gas,
             coin_type,
+            expiration_time,
         )
     }
 
@@ -118,6 +124,14 @@ impl TransactionExecutor for Executor {
         result
     }
 
+    fn trace_transaction(
+        chain_state: &dyn StateView,
+        txn: SignedUserTransaction,
+    ) -> TransactionTrace {
+        let mut vm = StarcoinVM::new();
+        vm.trace_user_transaction(chain_state, txn)
+    }
+
     fn build_mint_txn(
         addr: AccountAddress,
         auth_key_prefix: Vec<u8>,
@@ -150,6 +164,7 @@ impl TransactionExecutor for Executor {
             gas_price,
             max_gas,
             stc_type_tag(),
+            Duration::from_secs(DEFAULT_EXPIRATION_TIME),
         )
     }
 }
@@ -19,7 +19,7 @@ use starcoin_types::{
     block_metadata::BlockMetadata,
     transaction::Transaction,
     transaction::TransactionStatus,
-    transaction::{Module, TransactionPayload},
+    transaction::{Module, Script, TransactionPayload, TransactionPhase},
     vm_error::{StatusCode, VMStatus},
 };
 use starcoin_vm_types::parser;
@@ -274,6 +274,93 @@ fn test_execute_transfer_txn_with_starcoin_vm() -> Result<()> {
     Ok(())
 }
 
+#[stest::test]
+fn test_trace_transaction() -> Result<()> {
+    let chain_state = prepare_genesis();
+
+    let account1 = Account::new();
+    let txn1 = Transaction::UserTransaction(create_account_txn_sent_as_association(
+        &account1, 1, // fix me
+        50_000_000,
+    ));
+    execute_and_apply(&chain_state, txn1);
+
+    let account2 = Account::new();
+    let raw_txn = Executor::build_transfer_txn(
+        *account1.address(),
+        *account2.address(),
+        account2.auth_key_prefix(),
+        0,
+        1000,
+        1,
+        TXN_RESERVED,
+    );
+    let signed_txn = account1.sign_txn(raw_txn.clone());
+    let txn = Transaction::UserTransaction(signed_txn.clone());
+    let output = Executor::execute_transactions(&chain_state, vec![txn.clone()])?
+        .pop()
+        .expect("Output must exist.");
+
+    let trace = Executor::trace_transaction(&chain_state, signed_txn);
+    assert_eq!(trace.txn_hash, txn.id());
+    assert!(trace.kept);
+    assert!(trace.is_executed());
+    assert!(trace.abort_location.is_none());
+    assert_eq!(trace.gas_used, output.gas_used());
+    assert_eq!(trace.events, output.events().to_vec());
+    assert_eq!(
+        trace.writes,
+        output.write_set().clone().into_iter().collect::<Vec<_>>()
+    );
+    // the sender account is read by the prologue.
+    assert!(trace
+        .reads
+        .iter()
+        .any(|access_path| access_path.address == *account1.address()));
+
+    // the sequence number is used, so the txn is discarded by the prologue.
+    execute_and_apply(&chain_state, txn);
+    let trace = Executor::trace_transaction(&chain_state, account1.sign_txn(raw_txn));
+    assert!(!trace.kept);
+    assert_eq!(trace.major_status, StatusCode::SEQUENCE_NUMBER_TOO_OLD);
+    assert_eq!(
+        trace.abort_location.map(|location| location.phase),
+        Some(TransactionPhase::Validation)
+    );
+
+    // the script aborts, the txn is kept and the abort is located in the execution phase.
+    let compiler = Compiler {
+        address: *account1.address(),
+        ..Compiler::default()
+    };
+    let script = compiler.into_script_blob(
+        "file_name",
+        "
+        main() {
+            abort 42;
+        }
+        ",
+    )?;
+    let signed_txn = account1.create_signed_txn_impl(
+        *account1.address(),
+        TransactionPayload::Script(Script::new(script, vec![], vec![])),
+        1,
+        100_000,
+        1,
+    );
+    let trace = Executor::trace_transaction(&chain_state, signed_txn);
+    assert!(trace.kept);
+    assert_eq!(trace.major_status, StatusCode::ABORTED);
+    assert_eq!(trace.sub_status, Some(42));
+    let location = trace.abort_location.expect("abort location must exist.");
+    assert_eq!(location.phase, TransactionPhase::Execution);
+    // the location is parsed from the message of the vm status.
+    assert!(location.module.is_some());
+    assert_eq!(location.function.as_deref(), Some("main"));
+    assert!(location.code_offset.is_some());
+    Ok(())
+}
+
 #[stest::test]
 fn test_execute_multi_txn_with_same_account() -> Result<()> {
     let chain_state = prepare_genesis();
@@ -5,7 +5,9 @@ use anyhow::Result;
 use starcoin_config::ChainConfig;
 use starcoin_types::{
     account_address::AccountAddress,
-    transaction::{RawUserTransaction, SignedUserTransaction, Transaction, TransactionOutput},
+    transaction::{
+        RawUserTransaction, SignedUserTransaction, Transaction, TransactionOutput, TransactionTrace,
+    },
     vm_error::VMStatus,
 };
 use starcoin_vm_types::state_view::StateView;
@@ -42,6 +44,12 @@ pub trait TransactionExecutor: std::marker::Unpin + Clone {
         txn: SignedUserTransaction,
     ) -> Option<VMStatus>;
 
+    /// Re-execute a user transaction and record its call tree, resources read and written.
+    fn trace_transaction(
+        state_view: &dyn StateView,
+        txn: SignedUserTransaction,
+    ) -> TransactionTrace;
+
     fn build_mint_txn(
         addr: AccountAddress,
         auth_key_prefix: Vec<u8>,
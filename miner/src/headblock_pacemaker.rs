@@ -22,7 +22,7 @@ impl HeadBlockPacemaker {
     }
 
     pub fn send_event(&mut self) {
-        if let Err(e) = self.sender.try_send(GenerateBlockEvent {}) {
+        if let Err(e) = self.sender.try_send(GenerateBlockEvent::default()) {
             trace!("err : {:?}", e);
         }
     }
@@ -33,7 +33,17 @@ pub(crate) type TransactionStatusEvent = Arc<Vec<(HashValue, TxStatus)>>;
 
 #[derive(Default, Debug, Message)]
 #[rtype(result = "Result<()>")]
-pub struct GenerateBlockEvent {}
+pub struct GenerateBlockEvent {
+    /// Generate block even if mining is paused.
+    pub force: bool,
+}
+
+/// Pause or resume generating block when pacemaker trigger.
+#[derive(Debug, Message)]
+#[rtype(result = "()")]
+pub struct SetMiningEvent {
+    pub enable: bool,
+}
 
 pub struct MinerActor<C, P, CS, S>
 where
@@ -51,6 +61,7 @@ where
     stratum: Arc<Stratum>,
     miner_account: WalletAccount,
     arbiter: Arbiter,
+    enable: bool,
 }
 
 impl<C, P, CS, S> MinerActor<C, P, CS, S>
@@ -110,6 +121,7 @@ where
                 stratum,
                 miner_account,
                 arbiter,
+                enable: true,
             }
         });
         Ok(actor)
@@ -130,6 +142,21 @@ where
     }
 }
 
+impl<C, P, CS, S> Handler<SetMiningEvent> for MinerActor<C, P, CS, S>
+where
+    C: Consensus + Sync + Send + 'static,
+    P: TxPoolSyncService + Sync + Send + 'static,
+    CS: ChainAsyncService + Sync + Send + 'static,
+    S: Store + Sync + Send + 'static,
+{
+    type Result = ();
+
+    fn handle(&mut self, event: SetMiningEvent, _ctx: &mut Self::Context) -> Self::Result {
+        info!("Set mining enable: {}", event.enable);
+        self.enable = event.enable;
+    }
+}
+
 impl<C, P, CS, S> Handler<GenerateBlockEvent> for MinerActor<C, P, CS, S>
 where
     C: Consensus + Sync + Send + 'static,
@@ -139,7 +166,11 @@ where
 {
     type Result = Result<()>;
 
-    fn handle(&mut self, _event: GenerateBlockEvent, _ctx: &mut Self::Context) -> Self::Result {
+    fn handle(&mut self, event: GenerateBlockEvent, _ctx: &mut Self::Context) -> Self::Result {
+        if !event.force && !self.enable {
+            debug!("Mining is paused, ignore GenerateBlockEvent.");
+            return Ok(());
+        }
         let txpool = self.txpool.clone();
         let storage = self.storage.clone();
         let chain = self.chain.clone();
@@ -21,7 +21,7 @@ impl SchedulePacemaker {
     }
 
     pub fn send_event(&mut self) {
-        match self.sender.try_send(GenerateBlockEvent {}) {
+        match self.sender.try_send(GenerateBlockEvent::default()) {
             Ok(()) => {}
             Err(e) => trace!("Send GenerateBlockEvent error: {:?}", e),
         };
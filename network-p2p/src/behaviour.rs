@@ -16,10 +16,12 @@
 
 use crate::discovery::DiscoveryConfig;
 use crate::protocol::{CustomMessageOutcome, Protocol};
+use crate::request_responses::{self, ProtocolConfig, RequestFailure, RequestResponsesBehaviour};
 use crate::{
     debug_info, discovery::DiscoveryBehaviour, discovery::DiscoveryOut, protocol::event::DhtEvent,
     protocol::event::Event, DiscoveryNetBehaviour, ProtocolId,
 };
+use futures::channel::oneshot;
 use libp2p::core::{Multiaddr, PeerId, PublicKey};
 use libp2p::kad::record;
 use libp2p::swarm::{
@@ -27,7 +29,9 @@ use libp2p::swarm::{
 };
 use libp2p::NetworkBehaviour;
 use log::debug;
+use std::borrow::Cow;
 use std::collections::VecDeque;
+use std::time::Duration;
 use std::{iter, task::Context, task::Poll};
 
 /// General behaviour of the network. Combines all protocols together.
@@ -40,6 +44,8 @@ pub struct Behaviour {
     debug_info: debug_info::DebugInfoBehaviour,
     /// Discovers nodes of the network.
     discovery: DiscoveryBehaviour,
+    /// Generic request-response protocols.
+    request_responses: RequestResponsesBehaviour,
     /// Queue of events to produce for the outside.
     #[behaviour(ignore)]
     events: VecDeque<BehaviourOut>,
@@ -50,6 +56,19 @@ pub struct Behaviour {
 pub enum BehaviourOut {
     Event(Event),
     RandomKademliaStarted(ProtocolId),
+    /// An inbound request has been answered, or failed.
+    InboundRequest {
+        peer: PeerId,
+        protocol: Cow<'static, str>,
+        result: Result<(), request_responses::ResponseFailure>,
+    },
+    /// An outbound request has finished, successfully or not.
+    RequestFinished {
+        peer: PeerId,
+        protocol: Cow<'static, str>,
+        duration: Duration,
+        result: Result<(), RequestFailure>,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -65,11 +84,13 @@ impl Behaviour {
         user_agent: String,
         local_public_key: PublicKey,
         disco_config: DiscoveryConfig,
+        request_response_protocols: Vec<ProtocolConfig>,
     ) -> Self {
         Behaviour {
             protocol,
             debug_info: debug_info::DebugInfoBehaviour::new(user_agent, local_public_key),
             discovery: disco_config.finish(),
+            request_responses: RequestResponsesBehaviour::new(request_response_protocols),
             events: VecDeque::new(),
         }
     }
@@ -112,6 +133,19 @@ impl Behaviour {
         self.discovery.put_value(key, value);
     }
 
+    /// Send a request to the peer on a request-response protocol, the result is sent to
+    /// `pending_response`.
+    pub fn send_request(
+        &mut self,
+        target: &PeerId,
+        protocol: &str,
+        request: Vec<u8>,
+        pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+    ) {
+        self.request_responses
+            .send_request(target, protocol, request, pending_response)
+    }
+
     /// Returns a shared reference to the user protocol.
     pub fn user_protocol(&self) -> &Protocol {
         &self.protocol
@@ -154,6 +188,33 @@ impl NetworkBehaviourEventProcess<CustomMessageOutcome> for Behaviour {
     }
 }
 
+impl NetworkBehaviourEventProcess<request_responses::Event> for Behaviour {
+    fn inject_event(&mut self, event: request_responses::Event) {
+        match event {
+            request_responses::Event::InboundRequest {
+                peer,
+                protocol,
+                result,
+            } => self.events.push_back(BehaviourOut::InboundRequest {
+                peer,
+                protocol,
+                result,
+            }),
+            request_responses::Event::RequestFinished {
+                peer,
+                protocol,
+                duration,
+                result,
+            } => self.events.push_back(BehaviourOut::RequestFinished {
+                peer,
+                protocol,
+                duration,
+                result,
+            }),
+        }
+    }
+}
+
 impl NetworkBehaviourEventProcess<debug_info::DebugInfoEvent> for Behaviour {
     fn inject_event(&mut self, event: debug_info::DebugInfoEvent) {
         let debug_info::DebugInfoEvent::Identified { peer_id, mut info } = event;
@@ -3,6 +3,7 @@
 
 //! Libp2p network configuration.
 
+pub use crate::request_responses::ProtocolConfig as RequestResponseConfig;
 use crypto::HashValue;
 use libp2p::{
     core::Multiaddr,
@@ -94,6 +95,9 @@ pub struct NetworkConfiguration {
     pub disable_seed: bool,
 
     pub protocols: Vec<Cow<'static, [u8]>>,
+
+    /// Request-response protocols to support.
+    pub request_response_protocols: Vec<RequestResponseConfig>,
 }
 
 /// Configuration for the transport layer.
@@ -151,6 +155,7 @@ impl Default for NetworkConfiguration {
             self_info: PeerInfo::default(),
             disable_seed: false,
             protocols: vec![],
+            request_response_protocols: vec![],
         }
     }
 }
@@ -8,16 +8,23 @@ extern crate prometheus;
 #[macro_use]
 extern crate starcoin_metrics;
 
+pub use crate::network_state::NetworkState;
 pub use crate::protocol::event::Event;
 pub use crate::protocol::generic_proto::GenericProtoOut;
+pub use crate::protocol::{CURRENT_VERSION, MIN_VERSION};
+pub use crate::request_responses::{IncomingRequest, RequestFailure};
 pub use crate::service::{NetworkService, NetworkWorker};
-pub use config::{NetworkConfiguration, NodeKeyConfig, Params, ProtocolId, Secret};
+pub use config::{
+    NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, Params, ProtocolId,
+    RequestResponseConfig, Secret, TransportConfig,
+};
 pub use libp2p::{
     core::{
         ConnectedPoint, {identity, multiaddr, Multiaddr, PeerId, PublicKey},
     },
     multiaddr as build_multiaddr,
 };
+pub use peerset::ReputationChange;
 
 mod behaviour;
 mod config;
@@ -25,19 +32,15 @@ mod debug_info;
 mod discovery;
 mod metrics;
 mod net_error;
-mod network_state;
+pub mod network_state;
 mod protocol;
+mod request_responses;
 mod service;
 mod service_test;
 mod transport;
 mod utils;
 
-use serde::Serialize;
-use std::{
-    collections::{HashMap, HashSet},
-    error, fmt,
-    time::Duration,
-};
+use std::{error, fmt};
 
 const MAX_CONNECTIONS_PER_PEER: usize = 2;
 pub const PROTOCOL_NAME: &[u8] = b"/starcoin/consensus/1";
@@ -114,84 +117,3 @@ impl From<multiaddr::Error> for ParseErr {
         ParseErr::MultiaddrParse(err)
     }
 }
-
-/// Returns general information about the networking.
-///
-/// Meant for general diagnostic purposes.
-///
-/// **Warning**: This API is not stable.
-#[derive(Debug, PartialEq, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct NetworkState {
-    /// PeerId of the local node.
-    pub peer_id: String,
-    /// List of addresses the node is currently listening on.
-    pub listened_addresses: HashSet<Multiaddr>,
-    /// List of addresses the node knows it can be reached as.
-    pub external_addresses: HashSet<Multiaddr>,
-    /// List of node we're connected to.
-    pub connected_peers: HashMap<String, NetworkStatePeer>,
-    /// List of node that we know of but that we're not connected to.
-    pub not_connected_peers: HashMap<String, NetworkStateNotConnectedPeer>,
-    /// Downloaded bytes per second averaged over the past few seconds.
-    pub average_download_per_sec: u64,
-    /// Uploaded bytes per second averaged over the past few seconds.
-    pub average_upload_per_sec: u64,
-    /// State of the peerset manager.
-    pub peerset: serde_json::Value,
-}
-
-#[derive(Debug, PartialEq, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct NetworkStatePeer {
-    /// How we are connected to the node.
-    pub endpoint: NetworkStatePeerEndpoint,
-    /// Node information, as provided by the node itself. Can be empty if not known yet.
-    pub version_string: Option<String>,
-    /// Latest ping duration with this node.
-    pub latest_ping_time: Option<Duration>,
-    /// If true, the peer is "enabled", which means that we try to open stargate related protocols
-    /// with this peer. If false, we stick to Kademlia and/or other network-only protocols.
-    pub enabled: bool,
-    /// If true, the peer is "open", which means that we have a stargate related protocol
-    /// with this peer.
-    pub open: bool,
-    /// List of addresses known for this node.
-    pub known_addresses: HashSet<Multiaddr>,
-}
-
-#[derive(Debug, PartialEq, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct NetworkStateNotConnectedPeer {
-    /// List of addresses known for this node.
-    pub known_addresses: HashSet<Multiaddr>,
-}
-
-#[derive(Debug, PartialEq, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub enum NetworkStatePeerEndpoint {
-    /// We are dialing the given address.
-    Dialing(Multiaddr),
-    /// We are listening.
-    Listening {
-        /// Address we're listening on that received the connection.
-        local_addr: Multiaddr,
-        /// Address data is sent back to.
-        send_back_addr: Multiaddr,
-    },
-}
-
-impl From<ConnectedPoint> for NetworkStatePeerEndpoint {
-    fn from(endpoint: ConnectedPoint) -> Self {
-        match endpoint {
-            ConnectedPoint::Dialer { address } => NetworkStatePeerEndpoint::Dialing(address),
-            ConnectedPoint::Listener {
-                local_addr,
-                send_back_addr,
-            } => NetworkStatePeerEndpoint::Listening {
-                local_addr,
-                send_back_addr,
-            },
-        }
-    }
-}
@@ -1,4 +1,6 @@
-use starcoin_metrics::{IntCounterVec, IntGauge, Opts, PrometheusError, UIntGaugeVec};
+use starcoin_metrics::{
+    HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, PrometheusError, UIntGaugeVec,
+};
 
 #[derive(Clone)]
 pub struct Metrics {
@@ -6,6 +8,9 @@ pub struct Metrics {
     pub connections_closed_total: IntCounterVec,
     pub connections_opened_total: IntCounterVec,
     pub peers_count: IntGauge,
+    pub requests_in_total: IntCounterVec,
+    pub requests_out_total: IntCounterVec,
+    pub requests_out_duration: HistogramVec,
 }
 
 impl Metrics {
@@ -40,11 +45,41 @@ impl Metrics {
         let peers_count =
             register_int_gauge!(Opts::new("peers_count", "peers count").namespace("starcoin"))?;
 
+        let requests_in_total = register_int_counter_vec!(
+            Opts::new(
+                "sub_libp2p_requests_in_total",
+                "Counters of inbound requests by protocol and result"
+            )
+            .namespace("starcoin"),
+            &["protocol", "result"]
+        )?;
+
+        let requests_out_total = register_int_counter_vec!(
+            Opts::new(
+                "sub_libp2p_requests_out_total",
+                "Counters of outbound requests by protocol and result"
+            )
+            .namespace("starcoin"),
+            &["protocol", "result"]
+        )?;
+
+        let requests_out_duration = register_histogram_vec!(
+            HistogramOpts::new(
+                "sub_libp2p_requests_out_duration",
+                "Duration of successful outbound requests in seconds"
+            )
+            .namespace("starcoin"),
+            &["protocol"]
+        )?;
+
         Ok(Self {
             network_per_sec_bytes,
             connections_closed_total,
             connections_opened_total,
             peers_count,
+            requests_in_total,
+            requests_out_total,
+            requests_out_duration,
         })
     }
 }
@@ -70,6 +70,12 @@ pub struct Peer {
     pub open: bool,
     /// List of addresses known for this node.
     pub known_addresses: HashSet<Multiaddr>,
+    /// Notification bytes sent to this node since the notification substream opened,
+    /// request-response bytes are not counted.
+    pub bytes_sent: u64,
+    /// Notification bytes received from this node since the notification substream opened,
+    /// request-response bytes are not counted.
+    pub bytes_received: u64,
 }
 
 /// Part of the `NetworkState` struct. Unstable.
@@ -20,7 +20,9 @@ use libp2p::swarm::{NetworkBehaviour, NetworkBehaviourAction, PollParameters};
 use libp2p::PeerId;
 use log::Level;
 
-use crate::protocol::message::generic::{ConsensusMessage, Message, Status};
+use crate::protocol::message::generic::{
+    ConsensusMessage, DisconnectReason, Message, Status, StatusVersion,
+};
 use crypto::HashValue;
 use scs::SCSCodec;
 use std::borrow::Cow;
@@ -30,21 +32,26 @@ use std::str;
 use std::sync::Arc;
 use std::task::Poll;
 use std::time;
-use types::peer_info::PeerInfo;
+use types::peer_info::{PeerCapabilities, PeerInfo};
 use wasm_timer::Instant;
 
 const REQUEST_TIMEOUT_SEC: u64 = 40;
 /// Interval at which we perform time based maintenance
 const TICK_TIMEOUT: time::Duration = time::Duration::from_millis(1100);
 /// Current protocol version.
-pub(crate) const CURRENT_VERSION: u32 = 1;
+/// Version 2 adds capabilities to the end of the status message, and compression to `PeerMessage`.
+/// Version 3 moves rpc from `PeerMessage` to request-response protocols, moves the capabilities
+/// into the peer info of the status message, and adds the disconnect reason message.
+pub const CURRENT_VERSION: u32 = 3;
 /// Lowest version we support
-pub(crate) const MIN_VERSION: u32 = 1;
+pub const MIN_VERSION: u32 = 3;
 
 pub use generic_proto::LegacyConnectionKillError;
 
+use peerset::ReputationChange as Rep;
+
 mod rep {
-    use peerset::ReputationChange as Rep;
+    use super::Rep;
     /// Reputation change when a peer is "clogged", meaning that it's not fast enough to process our
     /// messages.
     pub const CLOGGED_PEER: Rep = Rep::new(-(1 << 12), "Clogged message queue");
@@ -82,6 +89,9 @@ pub enum CustomMessageOutcome {
 /// and from whom we have not yet received a Status message.
 struct HandshakingPeer {
     timestamp: Instant,
+    /// Set if the handshake is refused, the peer is disconnected with the reputation change by
+    /// the next tick, after the disconnect reason is sent.
+    refused: Option<Rep>,
 }
 
 struct ContextData {
@@ -341,6 +351,13 @@ impl Protocol {
         let message = match Message::decode(&data[..]) {
             Ok(message) => message,
             Err(err) => {
+                // the status of an incompatible version is rejected by its version, instead of
+                // as a bad message.
+                if let Some(version) = StatusVersion::decode_from_message(&data[..]) {
+                    if !self.check_version(&who, version.version, version.min_supported_version) {
+                        return CustomMessageOutcome::None;
+                    }
+                }
                 info!(target: "sync", "Couldn't decode packet sent by {}: {:?}: {}", who, data, err);
                 self.peerset_handle.report_peer(who, rep::BAD_MESSAGE);
                 return CustomMessageOutcome::None;
@@ -353,13 +370,30 @@ impl Protocol {
                 messages: vec![Bytes::from(msg.data)],
             },
             Message::Status(status) => self.on_status_message(who, status),
+            Message::Disconnect(reason) => {
+                log!(
+                    target: "sync",
+                    if self.important_peers.contains(&who) { Level::Warn } else { Level::Info },
+                    "Peer {} refused the handshake: {:?}, ours: genesis {}, version {} (min supported {})",
+                    who,
+                    reason,
+                    self.chain_info.genesis_hash,
+                    CURRENT_VERSION,
+                    MIN_VERSION,
+                );
+                self.handshaking_peers.remove(&who);
+                self.peerset_handle
+                    .report_peer(who.clone(), rep::BAD_PROTOCOL);
+                self.behaviour.disconnect_peer(&who);
+                CustomMessageOutcome::None
+            }
         }
     }
 
     /// Called by peer to report status
     fn on_status_message(&mut self, who: PeerId, status: Status) -> CustomMessageOutcome {
         trace!(target: "sync", "New peer {} {:?}", who, status);
-        let _protocol_version = {
+        let protocol_version = {
             if self.context_data.peers.contains_key(&who) {
                 log!(
                     target: "sync",
@@ -374,9 +408,11 @@ impl Protocol {
                     "Peer is on different chain (our genesis: {} theirs: {})",
                     self.chain_info.genesis_hash, status.genesis_hash
                 );
-                self.peerset_handle
-                    .report_peer(who.clone(), rep::GENESIS_MISMATCH);
-                self.behaviour.disconnect_peer(&who);
+                self.refuse_peer(
+                    &who,
+                    DisconnectReason::GenesisMismatch(self.chain_info.genesis_hash),
+                    rep::GENESIS_MISMATCH,
+                );
 
                 if self.boot_node_ids.contains(&who) {
                     error!(
@@ -390,15 +426,7 @@ impl Protocol {
 
                 return CustomMessageOutcome::None;
             }
-            if status.version < MIN_VERSION && CURRENT_VERSION < status.min_supported_version {
-                log!(
-                    target: "sync",
-                    if self.important_peers.contains(&who) { Level::Warn } else { Level::Trace },
-                    "Peer {:?} using unsupported protocol version {}", who, status.version
-                );
-                self.peerset_handle
-                    .report_peer(who.clone(), rep::BAD_PROTOCOL);
-                self.behaviour.disconnect_peer(&who);
+            if !self.check_version(&who, status.version, status.min_supported_version) {
                 return CustomMessageOutcome::None;
             }
 
@@ -411,12 +439,57 @@ impl Protocol {
             };
 
             debug!(target: "sync", "Connected {}", who);
-            status.version
+            // talk in the lower version if the peer is newer.
+            status.version.min(CURRENT_VERSION)
         };
         // Notify all the notification protocols as open.
         CustomMessageOutcome::NotificationStreamOpened {
             remote: who,
-            info: Box::new(status.info),
+            info: Box::new({
+                let capabilities = status.info.capabilities;
+                status.info.with_protocol(protocol_version, capabilities)
+            }),
+        }
+    }
+
+    /// Disconnect the peer and return false if its protocol version is incompatible.
+    fn check_version(&mut self, who: &PeerId, version: u32, min_supported_version: u32) -> bool {
+        if version >= MIN_VERSION && CURRENT_VERSION >= min_supported_version {
+            return true;
+        }
+        log!(
+            target: "sync",
+            if self.important_peers.contains(who) { Level::Warn } else { Level::Info },
+            "Disconnect peer {}: incompatible protocol version, ours: {} (min supported {}), theirs: {} (min supported {})",
+            who,
+            CURRENT_VERSION,
+            MIN_VERSION,
+            version,
+            min_supported_version,
+        );
+        self.refuse_peer(
+            who,
+            DisconnectReason::IncompatibleVersion(StatusVersion {
+                version: CURRENT_VERSION,
+                min_supported_version: MIN_VERSION,
+            }),
+            rep::BAD_PROTOCOL,
+        );
+        false
+    }
+
+    /// Send the reason to the peer, and disconnect it by the next tick, so the reason is flushed
+    /// before the substream is closed. A peer which is not handshaking is disconnected at once.
+    fn refuse_peer(&mut self, who: &PeerId, reason: DisconnectReason, change: Rep) {
+        if let Err(e) = self.send_message(who, Message::Disconnect(reason)) {
+            debug!(target: "sync", "Send disconnect reason to {} error: {:?}", who, e);
+        }
+        match self.handshaking_peers.get_mut(who) {
+            Some(handshaking) => handshaking.refused = Some(change),
+            None => {
+                self.peerset_handle.report_peer(who.clone(), change);
+                self.behaviour.disconnect_peer(who);
+            }
         }
     }
 
@@ -432,6 +505,7 @@ impl Protocol {
             who.clone(),
             HandshakingPeer {
                 timestamp: Instant::now(),
+                refused: None,
             },
         );
         self.send_status(who);
@@ -443,7 +517,11 @@ impl Protocol {
             version: CURRENT_VERSION,
             min_supported_version: MIN_VERSION,
             genesis_hash: self.chain_info.genesis_hash,
-            info: self.chain_info.self_info.clone(),
+            info: self
+                .chain_info
+                .self_info
+                .clone()
+                .with_protocol(CURRENT_VERSION, PeerCapabilities::all()),
         };
 
         self.send_message(&who, Message::Status(status))
@@ -481,20 +559,23 @@ impl Protocol {
         let tick = Instant::now();
         let mut aborting = Vec::new();
         {
-            for (who, _) in self.handshaking_peers.iter().filter(|(_, handshaking)| {
-                (tick - handshaking.timestamp).as_secs() > REQUEST_TIMEOUT_SEC
-            }) {
-                debug!(
-                    target: "sync",
-                    "Handshake timeout {}", who
-                );
-                aborting.push(who.clone());
+            for (who, handshaking) in self.handshaking_peers.iter() {
+                if let Some(change) = handshaking.refused {
+                    aborting.push((who.clone(), change));
+                } else if (tick - handshaking.timestamp).as_secs() > REQUEST_TIMEOUT_SEC {
+                    debug!(
+                        target: "sync",
+                        "Handshake timeout {}", who
+                    );
+                    aborting.push((who.clone(), rep::TIMEOUT));
+                }
             }
         }
 
-        for p in aborting {
+        for (p, change) in aborting {
+            self.handshaking_peers.remove(&p);
             self.behaviour.disconnect_peer(&p);
-            self.peerset_handle.report_peer(p, rep::TIMEOUT);
+            self.peerset_handle.report_peer(p, change);
         }
     }
 
@@ -548,6 +629,7 @@ impl Protocol {
             .collect()
     }
 
+    /// The protocol version and capabilities of `self_info` are set by the protocol on sending.
     pub fn update_self_info(&mut self, self_info: PeerInfo) {
         self.chain_info.self_info = self_info;
     }
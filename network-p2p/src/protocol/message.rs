@@ -1,6 +1,7 @@
 /// Generic types.
 pub mod generic {
     use crypto::HashValue;
+    use scs::SCSCodec;
     use serde::{Deserialize, Serialize};
     use types::peer_info::PeerInfo;
 
@@ -20,15 +21,151 @@ pub mod generic {
         pub min_supported_version: u32,
         /// Genesis block hash.
         pub genesis_hash: HashValue,
-
+        /// Chain status of the node, with its protocol version and capabilities.
         pub info: PeerInfo,
     }
 
+    /// Why a node refuses the handshake, sent to the peer before it disconnects the peer.
+    #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+    pub enum DisconnectReason {
+        /// The peer is on another chain, with the genesis hash of the sender.
+        GenesisMismatch(HashValue),
+        /// The protocol versions are incompatible, with the versions of the sender.
+        IncompatibleVersion(StatusVersion),
+    }
+
+    /// The leading fields of `Status`, which keep their layout in all the protocol versions.
+    #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+    pub struct StatusVersion {
+        pub version: u32,
+        pub min_supported_version: u32,
+    }
+
+    /// Same layout as the leading bytes of `Message`.
+    #[derive(Serialize, Deserialize)]
+    enum MessageVersion {
+        Consensus(ConsensusMessage),
+        Status(StatusVersion),
+    }
+
+    impl StatusVersion {
+        /// Read the versions of a status message, even if the rest of the status has the layout
+        /// of another protocol version and can not be decoded.
+        pub fn decode_from_message(data: &[u8]) -> Option<StatusVersion> {
+            let prefix_len = MessageVersion::Status(StatusVersion {
+                version: 0,
+                min_supported_version: 0,
+            })
+            .encode()
+            .ok()?
+            .len();
+            if data.len() < prefix_len {
+                return None;
+            }
+            match MessageVersion::decode(&data[..prefix_len]) {
+                Ok(MessageVersion::Status(version)) => Some(version),
+                _ => None,
+            }
+        }
+    }
+
     #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
     pub enum Message {
         /// Consensus protocol message.
         Consensus(ConsensusMessage),
         /// Status message for handshake
         Status(Status),
+        /// Refuse the handshake, the sender disconnects after it.
+        Disconnect(DisconnectReason),
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use types::block::BlockNumber;
+        use types::peer_info::{PeerCapabilities, PeerId};
+        use types::U512;
+
+        /// `PeerInfo` of version 1.
+        #[derive(Serialize, Deserialize)]
+        struct PeerInfoV1 {
+            peer_id: PeerId,
+            block_number: BlockNumber,
+            total_difficulty: U512,
+            block_id: HashValue,
+        }
+
+        /// `Status` of version 1.
+        #[derive(Serialize, Deserialize)]
+        struct StatusV1 {
+            version: u32,
+            min_supported_version: u32,
+            genesis_hash: HashValue,
+            info: PeerInfoV1,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        enum MessageV1 {
+            Consensus(ConsensusMessage),
+            Status(StatusV1),
+        }
+
+        #[test]
+        fn test_decode_status_version() {
+            let status = Status {
+                version: 3,
+                min_supported_version: 2,
+                genesis_hash: HashValue::random(),
+                info: PeerInfo::default().with_protocol(3, PeerCapabilities::all()),
+            };
+            let data = Message::Status(status.clone()).encode().unwrap();
+            assert_eq!(Message::decode(&data).unwrap(), Message::Status(status));
+            assert_eq!(
+                StatusVersion::decode_from_message(&data),
+                Some(StatusVersion {
+                    version: 3,
+                    min_supported_version: 2,
+                })
+            );
+
+            // the status of version 1 can not be decoded, but its version can.
+            let data = MessageV1::Status(StatusV1 {
+                version: 1,
+                min_supported_version: 1,
+                genesis_hash: HashValue::random(),
+                info: PeerInfoV1 {
+                    peer_id: PeerId::random(),
+                    block_number: 1,
+                    total_difficulty: U512::zero(),
+                    block_id: HashValue::random(),
+                },
+            })
+            .encode()
+            .unwrap();
+            assert!(Message::decode(&data).is_err());
+            assert_eq!(
+                StatusVersion::decode_from_message(&data),
+                Some(StatusVersion {
+                    version: 1,
+                    min_supported_version: 1,
+                })
+            );
+
+            let data = Message::Consensus(ConsensusMessage { data: vec![1; 16] })
+                .encode()
+                .unwrap();
+            assert_eq!(StatusVersion::decode_from_message(&data), None);
+        }
+
+        #[test]
+        fn test_decode_disconnect() {
+            let msg = Message::Disconnect(DisconnectReason::IncompatibleVersion(StatusVersion {
+                version: 4,
+                min_supported_version: 4,
+            }));
+            let data = msg.encode().unwrap();
+            assert_eq!(Message::decode(&data).unwrap(), msg);
+            assert_eq!(StatusVersion::decode_from_message(&data), None);
+        }
     }
 }
@@ -0,0 +1,772 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Request-response protocols.
+//!
+//! Every request opens a new substream of its protocol. The requester writes the request and
+//! closes its writing side, then the responder writes back the response and closes the substream.
+//! So a response never waits behind notifications, and every request succeeds or fails on its own.
+//!
+//! Protocol names should contain a version, such as `/starcoin/sync/headers/1`, and a new name
+//! must be used for any incompatible change of the request or response.
+
+use futures::{
+    channel::{mpsc, oneshot},
+    future::{self, BoxFuture, Either},
+    prelude::*,
+    stream::FuturesUnordered,
+};
+use futures_timer::Delay;
+use libp2p::core::{
+    connection::ConnectionId,
+    upgrade::{self, InboundUpgrade, OutboundUpgrade, UpgradeInfo},
+    ConnectedPoint, Multiaddr, PeerId,
+};
+use libp2p::swarm::{
+    IntoProtocolsHandler, KeepAlive, NegotiatedSubstream, NetworkBehaviour, NetworkBehaviourAction,
+    NotifyHandler, PollParameters, ProtocolsHandler, ProtocolsHandlerEvent,
+    ProtocolsHandlerUpgrErr, SubstreamProtocol,
+};
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use std::{fmt, iter};
+use wasm_timer::Instant;
+
+/// Keep the connection handler alive for this long after the last request.
+const INACTIVE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configuration of a request-response protocol.
+#[derive(Debug, Clone)]
+pub struct ProtocolConfig {
+    /// Name of the protocol on the wire.
+    pub name: Cow<'static, str>,
+    /// Maximum size of a request, larger requests are dropped by the responder.
+    pub max_request_size: usize,
+    /// Maximum size of a response, larger responses fail the request.
+    pub max_response_size: usize,
+    /// Timeout of a request, from sending the request to receiving the whole response.
+    pub request_timeout: Duration,
+    /// Maximum number of requests in progress with one peer, for each direction.
+    pub max_concurrent_requests_per_peer: usize,
+    /// Channel to send inbound requests to. Inbound requests are refused if `None`.
+    /// When the channel is full, new inbound requests are refused.
+    pub inbound_queue: Option<mpsc::Sender<IncomingRequest>>,
+}
+
+/// A request received from a remote peer.
+#[derive(Debug)]
+pub struct IncomingRequest {
+    pub peer: PeerId,
+    pub protocol: Cow<'static, str>,
+    pub payload: Vec<u8>,
+    /// Send the response back to the remote. Dropping it refuses the request.
+    pub pending_response: oneshot::Sender<Vec<u8>>,
+}
+
+/// Error of an outbound request.
+#[derive(Debug, Clone, derive_more::Display)]
+pub enum RequestFailure {
+    #[display(fmt = "Request protocol is not registered")]
+    UnknownProtocol,
+    #[display(fmt = "Peer is not connected")]
+    NotConnected,
+    #[display(fmt = "Too many requests in progress with the peer")]
+    TooManyRequests,
+    #[display(fmt = "Connection closed before receiving the response")]
+    ConnectionClosed,
+    #[display(fmt = "Request timeout")]
+    Timeout,
+    #[display(fmt = "Request failed: {}", _0)]
+    Network(String),
+}
+
+impl std::error::Error for RequestFailure {}
+
+impl RequestFailure {
+    /// Short reason used as metrics label.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            RequestFailure::UnknownProtocol => "unknown-protocol",
+            RequestFailure::NotConnected => "not-connected",
+            RequestFailure::TooManyRequests => "too-many-requests",
+            RequestFailure::ConnectionClosed => "connection-closed",
+            RequestFailure::Timeout => "timeout",
+            RequestFailure::Network(_) => "network",
+        }
+    }
+}
+
+/// Error of responding an inbound request.
+#[derive(Debug, Clone, derive_more::Display)]
+pub enum ResponseFailure {
+    #[display(fmt = "Inbound request is not accepted on the protocol")]
+    Refused,
+    #[display(fmt = "Too many inbound requests in progress")]
+    Busy,
+    #[display(fmt = "Local node did not respond")]
+    NoResponse,
+    #[display(fmt = "Response timeout")]
+    Timeout,
+    #[display(fmt = "Response failed: {}", _0)]
+    Network(String),
+}
+
+impl ResponseFailure {
+    /// Short reason used as metrics label.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            ResponseFailure::Refused => "refused",
+            ResponseFailure::Busy => "busy",
+            ResponseFailure::NoResponse => "no-response",
+            ResponseFailure::Timeout => "timeout",
+            ResponseFailure::Network(_) => "network",
+        }
+    }
+}
+
+/// Event generated by `RequestResponsesBehaviour`.
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// An inbound request has been answered, or failed.
+    InboundRequest {
+        peer: PeerId,
+        protocol: Cow<'static, str>,
+        result: Result<(), ResponseFailure>,
+    },
+    /// An outbound request has finished, successfully or not.
+    RequestFinished {
+        peer: PeerId,
+        protocol: Cow<'static, str>,
+        duration: Duration,
+        result: Result<(), RequestFailure>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RequestId(u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Direction {
+    Inbound,
+    Outbound,
+}
+
+struct PendingRequest {
+    peer: PeerId,
+    connection: ConnectionId,
+    protocol: Cow<'static, str>,
+    started: Instant,
+    timeout: Duration,
+    max_response_size: usize,
+    pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+}
+
+struct InboundFinished {
+    peer: PeerId,
+    protocol: Cow<'static, str>,
+    result: Result<(), ResponseFailure>,
+}
+
+/// Implementation of `NetworkBehaviour` for the request-response protocols.
+pub struct RequestResponsesBehaviour {
+    protocols: HashMap<Cow<'static, str>, ProtocolConfig>,
+    /// Open connections with each peer, requests are sent on the first one.
+    connections: HashMap<PeerId, Vec<ConnectionId>>,
+    /// Outbound requests waiting for the response.
+    pending_requests: HashMap<RequestId, PendingRequest>,
+    /// Timeouts of outbound requests, armed when the request is sent.
+    pending_timeouts: FuturesUnordered<BoxFuture<'static, RequestId>>,
+    /// Reading the responses of outbound requests.
+    pending_reads:
+        FuturesUnordered<BoxFuture<'static, (RequestId, Result<Vec<u8>, RequestFailure>)>>,
+    /// Waiting for the local responses of inbound requests, and writing them back.
+    pending_responses: FuturesUnordered<BoxFuture<'static, InboundFinished>>,
+    /// Number of requests in progress with each peer, by protocol and direction.
+    in_progress: HashMap<(PeerId, Cow<'static, str>, Direction), usize>,
+    /// Actions to return from `poll`.
+    actions: VecDeque<NetworkBehaviourAction<OutboundProtocol, Event>>,
+    next_request_id: u64,
+}
+
+impl RequestResponsesBehaviour {
+    pub fn new(protocols: impl IntoIterator<Item = ProtocolConfig>) -> Self {
+        Self {
+            protocols: protocols
+                .into_iter()
+                .map(|config| (config.name.clone(), config))
+                .collect(),
+            connections: HashMap::new(),
+            pending_requests: HashMap::new(),
+            pending_timeouts: FuturesUnordered::new(),
+            pending_reads: FuturesUnordered::new(),
+            pending_responses: FuturesUnordered::new(),
+            in_progress: HashMap::new(),
+            actions: VecDeque::new(),
+            next_request_id: 0,
+        }
+    }
+
+    /// Send a request to the peer, the result is sent to `pending_response`.
+    pub fn send_request(
+        &mut self,
+        target: &PeerId,
+        protocol: &str,
+        request: Vec<u8>,
+        pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+    ) {
+        let config = match self.protocols.get(protocol) {
+            Some(config) => config,
+            None => {
+                let _ = pending_response.send(Err(RequestFailure::UnknownProtocol));
+                return;
+            }
+        };
+        let connection = match self.connections.get(target).and_then(|c| c.first()) {
+            Some(connection) => *connection,
+            None => {
+                let _ = pending_response.send(Err(RequestFailure::NotConnected));
+                return;
+            }
+        };
+        let count = self
+            .in_progress
+            .entry((target.clone(), config.name.clone(), Direction::Outbound))
+            .or_insert(0);
+        if *count >= config.max_concurrent_requests_per_peer {
+            let _ = pending_response.send(Err(RequestFailure::TooManyRequests));
+            return;
+        }
+        *count += 1;
+
+        let request_id = RequestId(self.next_request_id);
+        self.next_request_id += 1;
+        self.pending_requests.insert(
+            request_id,
+            PendingRequest {
+                peer: target.clone(),
+                connection,
+                protocol: config.name.clone(),
+                started: Instant::now(),
+                timeout: config.request_timeout,
+                max_response_size: config.max_response_size,
+                pending_response,
+            },
+        );
+        // the timeout covers opening the substream too, which may never finish.
+        let timeout = config.request_timeout;
+        self.pending_timeouts.push(Box::pin(async move {
+            Delay::new(timeout).await;
+            request_id
+        }));
+        self.actions
+            .push_back(NetworkBehaviourAction::NotifyHandler {
+                peer_id: target.clone(),
+                handler: NotifyHandler::One(connection),
+                event: OutboundProtocol {
+                    request_id,
+                    protocol: config.name.clone(),
+                    request,
+                    timeout: config.request_timeout,
+                },
+            });
+    }
+
+    fn on_request_sent(
+        &mut self,
+        request_id: RequestId,
+        result: Result<NegotiatedSubstream, RequestFailure>,
+    ) {
+        let mut substream = match result {
+            Ok(substream) => substream,
+            Err(e) => {
+                self.finish_request(request_id, Err(e));
+                return;
+            }
+        };
+        // the request may have been failed by disconnection or timeout.
+        let (max_response_size, remaining) = match self.pending_requests.get(&request_id) {
+            Some(request) => (
+                request.max_response_size,
+                request
+                    .timeout
+                    .checked_sub(request.started.elapsed())
+                    .unwrap_or_default(),
+            ),
+            None => return,
+        };
+        self.pending_reads.push(Box::pin(async move {
+            let read = Box::pin(async move {
+                upgrade::read_one(&mut substream, max_response_size)
+                    .await
+                    .map_err(|e| RequestFailure::Network(e.to_string()))
+            });
+            let result = match future::select(read, Delay::new(remaining)).await {
+                Either::Left((result, _)) => result,
+                Either::Right(_) => Err(RequestFailure::Timeout),
+            };
+            (request_id, result)
+        }));
+    }
+
+    fn finish_request(&mut self, request_id: RequestId, result: Result<Vec<u8>, RequestFailure>) {
+        if let Some(request) = self.pending_requests.remove(&request_id) {
+            self.decrease_in_progress(&request.peer, &request.protocol, Direction::Outbound);
+            self.actions
+                .push_back(NetworkBehaviourAction::GenerateEvent(
+                    Event::RequestFinished {
+                        peer: request.peer,
+                        protocol: request.protocol,
+                        duration: request.started.elapsed(),
+                        result: result.as_ref().map(|_| ()).map_err(Clone::clone),
+                    },
+                ));
+            let _ = request.pending_response.send(result);
+        }
+    }
+
+    fn on_inbound_request(
+        &mut self,
+        peer: PeerId,
+        protocol: Cow<'static, str>,
+        payload: Result<Vec<u8>, String>,
+        mut substream: NegotiatedSubstream,
+    ) {
+        let result = match payload {
+            Ok(payload) => self.accept_inbound_request(&peer, &protocol, payload),
+            Err(e) => Err(ResponseFailure::Network(e)),
+        };
+        match result {
+            Ok((timeout, pending_response)) => {
+                *self
+                    .in_progress
+                    .entry((peer.clone(), protocol.clone(), Direction::Inbound))
+                    .or_insert(0) += 1;
+                self.pending_responses.push(Box::pin(async move {
+                    let result = match future::select(pending_response, Delay::new(timeout)).await {
+                        Either::Left((Ok(response), _)) => {
+                            upgrade::write_one(&mut substream, response)
+                                .await
+                                .map_err(|e| ResponseFailure::Network(e.to_string()))
+                        }
+                        Either::Left((Err(_), _)) => Err(ResponseFailure::NoResponse),
+                        Either::Right(_) => Err(ResponseFailure::Timeout),
+                    };
+                    InboundFinished {
+                        peer,
+                        protocol,
+                        result,
+                    }
+                }));
+            }
+            Err(e) => {
+                debug!(target: "sub-libp2p", "Drop inbound request {} from {}: {}", protocol, peer, e);
+                self.actions
+                    .push_back(NetworkBehaviourAction::GenerateEvent(
+                        Event::InboundRequest {
+                            peer,
+                            protocol,
+                            result: Err(e),
+                        },
+                    ));
+            }
+        }
+    }
+
+    /// Send the inbound request to the queue of its protocol, return the timeout and the
+    /// receiver of the local response.
+    fn accept_inbound_request(
+        &mut self,
+        peer: &PeerId,
+        protocol: &Cow<'static, str>,
+        payload: Vec<u8>,
+    ) -> Result<(Duration, oneshot::Receiver<Vec<u8>>), ResponseFailure> {
+        let config = self
+            .protocols
+            .get_mut(protocol)
+            .ok_or(ResponseFailure::Refused)?;
+        let count = self
+            .in_progress
+            .get(&(peer.clone(), protocol.clone(), Direction::Inbound))
+            .copied()
+            .unwrap_or(0);
+        if count >= config.max_concurrent_requests_per_peer {
+            return Err(ResponseFailure::Busy);
+        }
+        let queue = config
+            .inbound_queue
+            .as_mut()
+            .ok_or(ResponseFailure::Refused)?;
+        let (tx, rx) = oneshot::channel();
+        queue
+            .try_send(IncomingRequest {
+                peer: peer.clone(),
+                protocol: protocol.clone(),
+                payload,
+                pending_response: tx,
+            })
+            .map_err(|_| ResponseFailure::Busy)?;
+        Ok((config.request_timeout, rx))
+    }
+
+    /// Fail the outbound requests sent on the connection.
+    fn fail_requests_of_connection(&mut self, peer_id: &PeerId, conn: &ConnectionId) {
+        let request_ids = self
+            .pending_requests
+            .iter()
+            .filter(|(_, request)| &request.peer == peer_id && &request.connection == conn)
+            .map(|(request_id, _)| *request_id)
+            .collect::<Vec<_>>();
+        for request_id in request_ids {
+            self.finish_request(request_id, Err(RequestFailure::ConnectionClosed));
+        }
+    }
+
+    fn decrease_in_progress(
+        &mut self,
+        peer: &PeerId,
+        protocol: &Cow<'static, str>,
+        direction: Direction,
+    ) {
+        let key = (peer.clone(), protocol.clone(), direction);
+        if let Some(count) = self.in_progress.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                self.in_progress.remove(&key);
+            }
+        }
+    }
+}
+
+impl NetworkBehaviour for RequestResponsesBehaviour {
+    type ProtocolsHandler = RequestResponsesHandler;
+    type OutEvent = Event;
+
+    fn new_handler(&mut self) -> Self::ProtocolsHandler {
+        // accept all protocols, even the ones without inbound queue, so the requester get a
+        // refused request instead of a failed negotiation which would close the connection.
+        let protocol = InboundProtocol {
+            protocols: self
+                .protocols
+                .values()
+                .map(|config| (config.name.clone(), config.max_request_size))
+                .collect(),
+        };
+        RequestResponsesHandler::new(protocol)
+    }
+
+    fn addresses_of_peer(&mut self, _peer_id: &PeerId) -> Vec<Multiaddr> {
+        Vec::new()
+    }
+
+    fn inject_connected(&mut self, _peer_id: &PeerId) {}
+
+    fn inject_disconnected(&mut self, peer_id: &PeerId) {
+        // the requests are failed when each connection is closed.
+        self.connections.remove(peer_id);
+    }
+
+    fn inject_connection_established(
+        &mut self,
+        peer_id: &PeerId,
+        conn: &ConnectionId,
+        _endpoint: &ConnectedPoint,
+    ) {
+        self.connections
+            .entry(peer_id.clone())
+            .or_default()
+            .push(*conn);
+    }
+
+    fn inject_connection_closed(
+        &mut self,
+        peer_id: &PeerId,
+        conn: &ConnectionId,
+        _endpoint: &ConnectedPoint,
+    ) {
+        if let Some(connections) = self.connections.get_mut(peer_id) {
+            connections.retain(|c| c != conn);
+        }
+        self.fail_requests_of_connection(peer_id, conn);
+    }
+
+    fn inject_event(&mut self, peer_id: PeerId, _connection: ConnectionId, event: HandlerEvent) {
+        match event {
+            HandlerEvent::Request {
+                protocol,
+                payload,
+                substream,
+            } => self.on_inbound_request(peer_id, protocol, payload, substream),
+            HandlerEvent::RequestSent { request_id, result } => {
+                self.on_request_sent(request_id, result)
+            }
+        }
+    }
+
+    fn poll(
+        &mut self,
+        cx: &mut Context,
+        _params: &mut impl PollParameters,
+    ) -> Poll<
+        NetworkBehaviourAction<
+            <<Self::ProtocolsHandler as IntoProtocolsHandler>::Handler as ProtocolsHandler>::InEvent,
+            Self::OutEvent,
+        >,
+>{
+        while let Poll::Ready(Some(request_id)) = self.pending_timeouts.poll_next_unpin(cx) {
+            // no-op if the request has finished.
+            self.finish_request(request_id, Err(RequestFailure::Timeout));
+        }
+        while let Poll::Ready(Some((request_id, result))) = self.pending_reads.poll_next_unpin(cx) {
+            self.finish_request(request_id, result);
+        }
+        while let Poll::Ready(Some(finished)) = self.pending_responses.poll_next_unpin(cx) {
+            self.decrease_in_progress(&finished.peer, &finished.protocol, Direction::Inbound);
+            self.actions
+                .push_back(NetworkBehaviourAction::GenerateEvent(
+                    Event::InboundRequest {
+                        peer: finished.peer,
+                        protocol: finished.protocol,
+                        result: finished.result,
+                    },
+                ));
+        }
+        match self.actions.pop_front() {
+            Some(action) => Poll::Ready(action),
+            None => Poll::Pending,
+        }
+    }
+}
+
+/// Output of the substream upgrades, passed from the handler to the behaviour.
+pub enum HandlerEvent {
+    /// Read a request from an inbound substream, the response should be written to the substream.
+    Request {
+        protocol: Cow<'static, str>,
+        payload: Result<Vec<u8>, String>,
+        substream: NegotiatedSubstream,
+    },
+    /// Wrote a request to an outbound substream, the response should be read from the substream.
+    /// Or failed to open the substream, only this request fails.
+    RequestSent {
+        request_id: RequestId,
+        result: Result<NegotiatedSubstream, RequestFailure>,
+    },
+}
+
+impl fmt::Debug for HandlerEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HandlerEvent::Request { protocol, .. } => f
+                .debug_struct("Request")
+                .field("protocol", protocol)
+                .finish(),
+            HandlerEvent::RequestSent { request_id, .. } => f
+                .debug_struct("RequestSent")
+                .field("request_id", request_id)
+                .finish(),
+        }
+    }
+}
+
+/// Connection handler of the request-response protocols.
+///
+/// Unlike `OneShotHandler`, a failure to open or negotiate an outbound substream is reported to
+/// the behaviour as the failure of its request, and does not close the connection.
+pub struct RequestResponsesHandler {
+    listen_protocol: SubstreamProtocol<InboundProtocol>,
+    /// Requests waiting to open their outbound substream.
+    pending_requests: VecDeque<OutboundProtocol>,
+    /// Number of outbound substreams being opened.
+    opening: usize,
+    /// Events to return from `poll`.
+    events: VecDeque<HandlerEvent>,
+    keep_alive: KeepAlive,
+}
+
+impl RequestResponsesHandler {
+    fn new(protocol: InboundProtocol) -> Self {
+        Self {
+            listen_protocol: SubstreamProtocol::new(protocol),
+            pending_requests: VecDeque::new(),
+            opening: 0,
+            events: VecDeque::new(),
+            keep_alive: KeepAlive::Yes,
+        }
+    }
+
+    fn refresh_keep_alive(&mut self) {
+        if self.opening == 0 && self.pending_requests.is_empty() {
+            self.keep_alive = KeepAlive::Until(Instant::now() + INACTIVE_TIMEOUT);
+        } else {
+            self.keep_alive = KeepAlive::Yes;
+        }
+    }
+}
+
+impl ProtocolsHandler for RequestResponsesHandler {
+    type InEvent = OutboundProtocol;
+    type OutEvent = HandlerEvent;
+    type Error = void::Void;
+    type InboundProtocol = InboundProtocol;
+    type OutboundProtocol = OutboundProtocol;
+    type OutboundOpenInfo = RequestId;
+
+    fn listen_protocol(&self) -> SubstreamProtocol<Self::InboundProtocol> {
+        self.listen_protocol.clone()
+    }
+
+    fn inject_fully_negotiated_inbound(&mut self, event: HandlerEvent) {
+        self.events.push_back(event);
+        self.refresh_keep_alive();
+    }
+
+    fn inject_fully_negotiated_outbound(&mut self, event: HandlerEvent, _request_id: RequestId) {
+        self.opening -= 1;
+        self.events.push_back(event);
+        self.refresh_keep_alive();
+    }
+
+    fn inject_event(&mut self, request: OutboundProtocol) {
+        self.pending_requests.push_back(request);
+        self.keep_alive = KeepAlive::Yes;
+    }
+
+    fn inject_dial_upgrade_error(
+        &mut self,
+        request_id: RequestId,
+        error: ProtocolsHandlerUpgrErr<void::Void>,
+    ) {
+        self.opening -= 1;
+        let failure = match error {
+            ProtocolsHandlerUpgrErr::Timeout => RequestFailure::Timeout,
+            error => RequestFailure::Network(error.to_string()),
+        };
+        self.events.push_back(HandlerEvent::RequestSent {
+            request_id,
+            result: Err(failure),
+        });
+        self.refresh_keep_alive();
+    }
+
+    fn connection_keep_alive(&self) -> KeepAlive {
+        self.keep_alive
+    }
+
+    fn poll(
+        &mut self,
+        _cx: &mut Context,
+    ) -> Poll<
+        ProtocolsHandlerEvent<
+            Self::OutboundProtocol,
+            Self::OutboundOpenInfo,
+            Self::OutEvent,
+            Self::Error,
+        >,
+    > {
+        if let Some(event) = self.events.pop_front() {
+            return Poll::Ready(ProtocolsHandlerEvent::Custom(event));
+        }
+        if let Some(request) = self.pending_requests.pop_front() {
+            self.opening += 1;
+            let request_id = request.request_id;
+            let timeout = request.timeout;
+            return Poll::Ready(ProtocolsHandlerEvent::OutboundSubstreamRequest {
+                protocol: SubstreamProtocol::new(request).with_timeout(timeout),
+                info: request_id,
+            });
+        }
+        Poll::Pending
+    }
+}
+
+fn protocol_name(name: &Cow<'static, str>) -> Cow<'static, [u8]> {
+    match name {
+        Cow::Borrowed(name) => Cow::Borrowed(name.as_bytes()),
+        Cow::Owned(name) => Cow::Owned(name.clone().into_bytes()),
+    }
+}
+
+/// Accept inbound substreams of all registered protocols.
+#[derive(Debug, Clone)]
+pub struct InboundProtocol {
+    /// Name and max request size of the protocols.
+    protocols: Vec<(Cow<'static, str>, usize)>,
+}
+
+impl UpgradeInfo for InboundProtocol {
+    type Info = Cow<'static, [u8]>;
+    type InfoIter = std::vec::IntoIter<Self::Info>;
+
+    fn protocol_info(&self) -> Self::InfoIter {
+        self.protocols
+            .iter()
+            .map(|(name, _)| protocol_name(name))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+impl InboundUpgrade<NegotiatedSubstream> for InboundProtocol {
+    type Output = HandlerEvent;
+    type Error = void::Void;
+    type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+    fn upgrade_inbound(self, mut socket: NegotiatedSubstream, info: Self::Info) -> Self::Future {
+        let (protocol, max_request_size) = self
+            .protocols
+            .into_iter()
+            .find(|(name, _)| name.as_bytes() == info.as_ref())
+            .expect("The negotiated protocol must be one of the protocols we accept.");
+        Box::pin(async move {
+            let payload = upgrade::read_one(&mut socket, max_request_size)
+                .await
+                .map_err(|e| e.to_string());
+            Ok(HandlerEvent::Request {
+                protocol,
+                payload,
+                substream: socket,
+            })
+        })
+    }
+}
+
+/// Open a substream of the protocol and write the request.
+#[derive(Debug, Clone)]
+pub struct OutboundProtocol {
+    request_id: RequestId,
+    protocol: Cow<'static, str>,
+    request: Vec<u8>,
+    /// Timeout of opening the substream and writing the request.
+    timeout: Duration,
+}
+
+impl UpgradeInfo for OutboundProtocol {
+    type Info = Cow<'static, [u8]>;
+    type InfoIter = iter::Once<Self::Info>;
+
+    fn protocol_info(&self) -> Self::InfoIter {
+        iter::once(protocol_name(&self.protocol))
+    }
+}
+
+impl OutboundUpgrade<NegotiatedSubstream> for OutboundProtocol {
+    type Output = HandlerEvent;
+    type Error = void::Void;
+    type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+    fn upgrade_outbound(self, mut socket: NegotiatedSubstream, _info: Self::Info) -> Self::Future {
+        let request_id = self.request_id;
+        let request = self.request;
+        Box::pin(async move {
+            // `write_one` also closes the writing side, the response is read by the behaviour,
+            // so a slow response does not hit the timeout of the substream upgrade.
+            let result = upgrade::write_one(&mut socket, request)
+                .await
+                .map(|_| socket)
+                .map_err(|e| RequestFailure::Network(e.to_string()));
+            Ok(HandlerEvent::RequestSent { request_id, result })
+        })
+    }
+}
@@ -31,7 +31,12 @@ use std::sync::{
     Arc,
 };
 use std::task::Poll;
-use std::{borrow::Cow, collections::HashSet, fs, io, path::Path};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::Path,
+};
 
 use futures::{
     channel::{mpsc, oneshot},
@@ -56,6 +61,7 @@ use crate::network_state::{
 };
 use crate::protocol::event::Event;
 use crate::protocol::{ChainInfo, Protocol};
+use crate::request_responses::RequestFailure;
 use crate::{
     behaviour::{Behaviour, BehaviourOut},
     parse_addr, parse_str_addr, ConnectedPoint,
@@ -229,6 +235,7 @@ impl NetworkWorker {
                 user_agent,
                 local_public,
                 discovery_config,
+                params.network_config.request_response_protocols,
             ));
 
             let (transport, bandwidth) = {
@@ -276,6 +283,7 @@ impl NetworkWorker {
             from_worker,
             event_streams: Vec::new(),
             metrics: Metrics::register().ok(),
+            peer_traffic: HashMap::new(),
         })
     }
 
@@ -301,6 +309,7 @@ impl NetworkWorker {
     /// everywhere about this. Please don't use this function to retrieve actual information.
     pub fn network_state(&mut self) -> NetworkState {
         let swarm = &mut self.network_service;
+        let peer_traffic = &self.peer_traffic;
         let open = swarm
             .user_protocol()
             .open_peers()
@@ -313,6 +322,7 @@ impl NetworkWorker {
         	let known_addresses = NetworkBehaviour::addresses_of_peer(&mut **swarm, peer_id)
         		.into_iter().collect();
 
+        	let traffic = peer_traffic.get(peer_id).cloned().unwrap_or_default();
         	let endpoint = if let Some(e) = swarm.node(peer_id).map(|i| i.endpoint()) {
         		e.clone().into()
         	} else {
@@ -329,6 +339,8 @@ impl NetworkWorker {
         		enabled: swarm.user_protocol().is_enabled(&peer_id),
         		open: swarm.user_protocol().is_open(&peer_id),
         		known_addresses,
+        		bytes_sent: traffic.bytes_sent,
+        		bytes_received: traffic.bytes_received,
         	}))
         }).collect()
         };
@@ -473,6 +485,15 @@ impl NetworkService {
         }
     }
 
+    /// Get network state for diagnostic, see `NetworkWorker::network_state`.
+    pub async fn network_state(&self) -> Result<NetworkState, oneshot::Canceled> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self
+            .to_worker
+            .unbounded_send(ServiceToWorkerMsg::NetworkState(tx));
+        rx.await
+    }
+
     pub fn update_self_info(&self, info: PeerInfo) {
         let _ = self
             .to_worker
@@ -510,6 +531,31 @@ impl NetworkService {
             });
     }
 
+    /// Send a request to the peer on a request-response protocol, and wait for the response.
+    ///
+    /// The protocol must have been registered in `NetworkConfiguration`, and the peer must be
+    /// connected, otherwise the request fails immediately.
+    pub async fn request(
+        &self,
+        target: PeerId,
+        protocol: impl Into<Cow<'static, str>>,
+        request: Vec<u8>,
+    ) -> Result<Vec<u8>, RequestFailure> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::Request {
+            target,
+            protocol: protocol.into(),
+            request,
+            pending_response: tx,
+        });
+        match rx.await {
+            Ok(result) => result,
+            Err(_) => Err(RequestFailure::Network(
+                "Network worker is stopped".to_string(),
+            )),
+        }
+    }
+
     /// Report a given peer as either beneficial (+) or costly (-) according to the
     /// given scalar.
     pub fn report_peer(&self, who: PeerId, cost_benefit: ReputationChange) {
@@ -654,6 +700,22 @@ enum ServiceToWorkerMsg {
     ConnectedPeers(oneshot::Sender<HashSet<PeerId>>),
     SelfInfo(PeerInfo),
     AddressByPeerID(PeerId, oneshot::Sender<Vec<Multiaddr>>),
+    NetworkState(oneshot::Sender<NetworkState>),
+    Request {
+        target: PeerId,
+        protocol: Cow<'static, str>,
+        request: Vec<u8>,
+        pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+    },
+}
+
+/// Notification traffic with a peer, counted since the notification substream opened.
+/// The libp2p bandwidth sinks only count the traffic of all peers, so request-response and other
+/// protocols are not counted per peer.
+#[derive(Clone, Debug, Default)]
+struct PeerTraffic {
+    bytes_sent: u64,
+    bytes_received: u64,
 }
 
 /// Main network worker. Must be polled in order for the network to advance.
@@ -671,6 +733,8 @@ pub struct NetworkWorker {
     event_streams: Vec<mpsc::UnboundedSender<Event>>,
     /// Prometheus network metrics.
     metrics: Option<Metrics>,
+    /// Notification traffic of the peers with an open notification substream.
+    peer_traffic: HashMap<PeerId, PeerTraffic>,
 }
 
 impl Future for NetworkWorker {
@@ -700,11 +764,16 @@ impl Future for NetworkWorker {
                     message,
                     protocol_name,
                     target,
-                } => this.network_service.user_protocol_mut().write_notification(
-                    target,
-                    protocol_name,
-                    message,
-                ),
+                } => {
+                    if let Some(traffic) = this.peer_traffic.get_mut(&target) {
+                        traffic.bytes_sent += message.len() as u64;
+                    }
+                    this.network_service.user_protocol_mut().write_notification(
+                        target,
+                        protocol_name,
+                        message,
+                    )
+                }
                 ServiceToWorkerMsg::RegisterNotifProtocol { protocol_name } => {
                     let events = this
                         .network_service
@@ -738,6 +807,22 @@ impl Future for NetworkWorker {
                 ServiceToWorkerMsg::AddressByPeerID(peer_id, tx) => {
                     let _ = tx.send(this.network_service.get_address(&peer_id));
                 }
+                ServiceToWorkerMsg::NetworkState(tx) => {
+                    let _ = tx.send(this.network_state());
+                }
+                ServiceToWorkerMsg::Request {
+                    target,
+                    protocol,
+                    request,
+                    pending_response,
+                } => {
+                    this.network_service.send_request(
+                        &target,
+                        &protocol,
+                        request,
+                        pending_response,
+                    );
+                }
             }
         }
 
@@ -749,10 +834,74 @@ impl Future for NetworkWorker {
 
             match poll_value {
                 Poll::Pending => break,
-                Poll::Ready(SwarmEvent::Behaviour(BehaviourOut::Event(ev))) => this
-                    .event_streams
-                    .retain(|sender| sender.unbounded_send(ev.clone()).is_ok()),
+                Poll::Ready(SwarmEvent::Behaviour(BehaviourOut::Event(ev))) => {
+                    match &ev {
+                        Event::NotificationStreamOpened { remote, .. } => {
+                            this.peer_traffic
+                                .insert(remote.clone(), PeerTraffic::default());
+                        }
+                        Event::NotificationStreamClosed { remote } => {
+                            this.peer_traffic.remove(remote);
+                        }
+                        Event::NotificationsReceived { remote, messages } => {
+                            if let Some(traffic) = this.peer_traffic.get_mut(remote) {
+                                traffic.bytes_received += messages
+                                    .iter()
+                                    .map(|message| message.len() as u64)
+                                    .sum::<u64>();
+                            }
+                        }
+                        Event::Dht(_) => {}
+                    }
+                    this.event_streams
+                        .retain(|sender| sender.unbounded_send(ev.clone()).is_ok())
+                }
                 Poll::Ready(SwarmEvent::Behaviour(BehaviourOut::RandomKademliaStarted(_))) => {}
+                Poll::Ready(SwarmEvent::Behaviour(BehaviourOut::InboundRequest {
+                    peer,
+                    protocol,
+                    result,
+                })) => {
+                    let label = match &result {
+                        Ok(()) => "success",
+                        Err(e) => {
+                            debug!(target: "sub-libp2p", "Inbound request {} from {} failed: {}", protocol, peer, e);
+                            e.reason()
+                        }
+                    };
+                    if let Some(metrics) = this.metrics.as_ref() {
+                        metrics
+                            .requests_in_total
+                            .with_label_values(&[protocol.as_ref(), label])
+                            .inc();
+                    }
+                }
+                Poll::Ready(SwarmEvent::Behaviour(BehaviourOut::RequestFinished {
+                    peer,
+                    protocol,
+                    duration,
+                    result,
+                })) => {
+                    let label = match &result {
+                        Ok(()) => "success",
+                        Err(e) => {
+                            debug!(target: "sub-libp2p", "Request {} to {} failed: {}", protocol, peer, e);
+                            e.reason()
+                        }
+                    };
+                    if let Some(metrics) = this.metrics.as_ref() {
+                        metrics
+                            .requests_out_total
+                            .with_label_values(&[protocol.as_ref(), label])
+                            .inc();
+                        if result.is_ok() {
+                            metrics
+                                .requests_out_duration
+                                .with_label_values(&[protocol.as_ref()])
+                                .observe(duration.as_secs_f64());
+                        }
+                    }
+                }
                 Poll::Ready(SwarmEvent::ConnectionEstablished {
                     peer_id, endpoint, ..
                 }) => {
@@ -4,9 +4,11 @@
 #[cfg(test)]
 mod tests {
     use crate::service::NetworkStateInfo;
-    use crate::{Event, Multiaddr, NodeKeyConfig, ProtocolId, Secret};
+    use crate::{Event, Multiaddr, NodeKeyConfig, ProtocolId, RequestResponseConfig, Secret};
+    use crate::{IncomingRequest, RequestFailure};
     use crate::{NetworkConfiguration, NetworkWorker, Params};
     use crypto::HashValue;
+    use futures::channel::mpsc;
     use futures::stream::StreamExt;
     use libp2p::identity;
     use std::thread;
@@ -14,6 +16,18 @@ mod tests {
     use tokio::runtime::Runtime;
 
     const PROTOCOL_NAME: &[u8] = b"/starcoin/notify/1";
+    const RPC_PROTOCOL_NAME: &str = "/starcoin/test/echo/1";
+
+    fn rpc_config(inbound_queue: Option<mpsc::Sender<IncomingRequest>>) -> RequestResponseConfig {
+        RequestResponseConfig {
+            name: RPC_PROTOCOL_NAME.into(),
+            max_request_size: 1024,
+            max_response_size: 1024,
+            request_timeout: Duration::from_secs(5),
+            max_concurrent_requests_per_peer: 2,
+            inbound_queue,
+        }
+    }
 
     #[ignore]
     #[stest::test(timeout = 5)]
@@ -146,6 +160,72 @@ mod tests {
         rt.block_on(fut);
     }
 
+    #[stest::test(timeout = 30)]
+    fn test_request_response() {
+        let mut rt = Runtime::new().unwrap();
+        let handle = rt.handle().clone();
+
+        let protocol = ProtocolId::from(b"stargate".as_ref());
+        let (request_tx, mut request_rx) = mpsc::channel(8);
+        let mut config1 = generate_config(vec![]);
+        config1.request_response_protocols = vec![rpc_config(Some(request_tx))];
+
+        let worker1 = NetworkWorker::new(Params::new(config1.clone(), protocol.clone())).unwrap();
+        let service1 = worker1.service().clone();
+        handle.spawn(worker1);
+
+        let seed: Multiaddr = format!(
+            "{}/p2p/{}",
+            &config1.listen_addresses.get(0).expect("should have"),
+            service1.peer_id().to_base58()
+        )
+        .parse()
+        .unwrap();
+        let mut config2 = generate_config(vec![seed]);
+        config2.request_response_protocols = vec![rpc_config(None)];
+
+        let worker2 = NetworkWorker::new(Params::new(config2, protocol)).unwrap();
+        let service2 = worker2.service().clone();
+        handle.spawn(worker2);
+
+        // echo the request, but refuse the empty request by dropping the response sender.
+        handle.spawn(async move {
+            while let Some(request) = request_rx.next().await {
+                if !request.payload.is_empty() {
+                    let _ = request.pending_response.send(request.payload.clone());
+                }
+            }
+        });
+
+        let peer1 = service1.peer_id().clone();
+        rt.block_on(async move {
+            while !service2.is_connected(peer1.clone()).await {
+                futures_timer::Delay::new(Duration::from_millis(100)).await;
+            }
+            let response = service2
+                .request(peer1.clone(), RPC_PROTOCOL_NAME, vec![1, 2, 3])
+                .await
+                .unwrap();
+            assert_eq!(response, vec![1, 2, 3]);
+
+            assert!(service2
+                .request(peer1.clone(), RPC_PROTOCOL_NAME, vec![])
+                .await
+                .is_err());
+            assert!(matches!(
+                service2
+                    .request(peer1.clone(), "/starcoin/test/unknown/1", vec![1])
+                    .await,
+                Err(RequestFailure::UnknownProtocol)
+            ));
+            // service2 has no inbound queue, so refuse all requests.
+            assert!(service1
+                .request(service2.peer_id().clone(), RPC_PROTOCOL_NAME, vec![1])
+                .await
+                .is_err());
+        });
+    }
+
     fn generate_config(boot_nodes: Vec<Multiaddr>) -> NetworkConfiguration {
         let mut config = NetworkConfiguration::default();
         let listen = format!("/ip4/127.0.0.1/tcp/{}", sg_config::get_available_port());
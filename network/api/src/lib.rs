@@ -1,8 +1,8 @@
 use crate::messages::PeerMessage;
 use anyhow::*;
 use libp2p::PeerId;
+use serde::{de::DeserializeOwned, Serialize};
 use starcoin_types::system_events::NewHeadBlock;
-use std::time::Duration;
 
 pub mod messages;
 
@@ -10,6 +10,33 @@ use async_trait::async_trait;
 use starcoin_types::peer_info::PeerInfo;
 use std::borrow::Cow;
 
+/// A typed request-response protocol.
+///
+/// The name is used as the libp2p protocol name, so it should contain a version, such as
+/// `/starcoin/sync/headers/1`, and a new name must be used for any incompatible change.
+pub trait RpcProtocol {
+    const NAME: &'static str;
+    type Request: Serialize + DeserializeOwned + Send + Sync;
+    type Response: Serialize + DeserializeOwned + Send + Sync;
+}
+
+/// Send a request of protocol `P` to the peer, and decode the response.
+pub async fn send_request<N, P>(
+    network: &N,
+    peer_id: PeerId,
+    request: &P::Request,
+) -> Result<P::Response>
+where
+    N: NetworkService,
+    P: RpcProtocol,
+{
+    let request = scs::to_bytes(request)?;
+    let response = network
+        .send_request_bytes(P::NAME.into(), peer_id, request)
+        .await?;
+    scs::from_bytes(&response)
+}
+
 #[async_trait]
 pub trait NetworkService: Send + Sync + Clone + Sized + std::marker::Unpin {
     async fn send_peer_message(
@@ -26,12 +53,12 @@ pub trait NetworkService: Send + Sync + Clone + Sized + std::marker::Unpin {
 
     fn identify(&self) -> &PeerId;
 
+    /// Send a request on the request-response protocol, and wait for the response.
     async fn send_request_bytes(
         &self,
-        protocol_name: Cow<'static, [u8]>,
+        protocol: Cow<'static, str>,
         peer_id: PeerId,
         message: Vec<u8>,
-        time_out: Duration,
     ) -> Result<Vec<u8>>;
 
     async fn peer_set(&self) -> Result<Vec<PeerInfo>>;
@@ -84,10 +111,9 @@ impl NetworkService for DummyNetworkService {
 
     async fn send_request_bytes(
         &self,
-        _protocol_name: Cow<'static, [u8]>,
+        _protocol: Cow<'static, str>,
         _peer_id: PeerId,
         _message: Vec<u8>,
-        _time_out: Duration,
     ) -> Result<Vec<u8>> {
         Ok(vec![])
     }
@@ -113,6 +139,6 @@ impl NetworkService for DummyNetworkService {
     }
 
     async fn get_peer_set_size(&self) -> Result<usize> {
-        Ok(0)
+        Ok(self.peers.len())
     }
 }
@@ -22,15 +22,16 @@ pub struct GetCounterMessage {}
 pub enum PeerMessage {
     UserTransactions(Vec<SignedUserTransaction>),
     Block(Arc<BlockDetail>),
-    RawRPCRequest(u128, Vec<u8>),
-    RawRPCResponse(u128, Vec<u8>),
 }
 
+/// A request received on a request-response protocol, the response should be sent to `responder`.
 #[rtype(result = "Result<()>")]
 #[derive(Debug, Message, Clone)]
 pub struct RawRpcRequestMessage {
+    pub peer_id: PeerId,
+    pub protocol: Cow<'static, str>,
     pub request: Vec<u8>,
-    pub responder: Sender<(Cow<'static, [u8]>, Vec<u8>)>,
+    pub responder: Sender<Vec<u8>>,
 }
 
 #[rtype(result = "Result<()>")]
@@ -7,7 +7,7 @@ extern crate log;
 extern crate prometheus;
 
 mod helper;
-mod message_processor;
+mod message_codec;
 mod net;
 mod net_test;
 pub mod network;
@@ -20,7 +20,7 @@ pub use helper::get_unix_ts;
 
 pub use net::{build_network_service, SNetworkService};
 pub use network::NetworkAsyncService;
-pub use network_p2p::PeerId;
+pub use network_p2p::{network_state, PeerId};
 
 use anyhow::*;
 use parity_codec::{Decode, Encode};
@@ -0,0 +1,239 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compression, size limit and inbound rate limit of `PeerMessage`.
+//!
+//! An encoded message is a compression tag byte followed by the (maybe compressed) scs bytes
+//! of the `PeerMessage`, so the receiver can decode it whatever compression the sender uses.
+//! RPC requests and responses are sent by the request-response protocols uncompressed, they are
+//! only limited by `MessageSizeLimit::rpc_request` and `MessageSizeLimit::rpc_response`.
+
+use anyhow::{bail, format_err, Result};
+use config::{MessageCompression, MessageSizeLimit, NetworkConfig};
+use libp2p::PeerId;
+use network_api::messages::PeerMessage;
+use scs::SCSCodec;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const TAG_NONE: u8 = 0;
+const TAG_SNAPPY: u8 = 1;
+
+#[derive(Debug)]
+pub(crate) enum DecodeError {
+    /// The message exceeds the size limit of its type.
+    Oversized(usize),
+    /// The message can not be decompressed or decoded.
+    Invalid(anyhow::Error),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct PeerMessageCodec {
+    compression: MessageCompression,
+    size_limit: MessageSizeLimit,
+}
+
+impl PeerMessageCodec {
+    pub fn new(config: &NetworkConfig) -> Self {
+        Self {
+            compression: config.compression,
+            size_limit: config.max_message_size.clone(),
+        }
+    }
+
+    pub fn encode(&self, msg: &PeerMessage) -> Result<Vec<u8>> {
+        let data = msg.encode()?;
+        let mut bytes = Vec::with_capacity(data.len() + 1);
+        match self.compression {
+            MessageCompression::None => {
+                bytes.push(TAG_NONE);
+                bytes.extend_from_slice(&data);
+            }
+            MessageCompression::Snappy => {
+                bytes.push(TAG_SNAPPY);
+                bytes.extend(snap::raw::Encoder::new().compress_vec(&data)?);
+            }
+        }
+        Ok(bytes)
+    }
+
+    pub fn decode(&self, bytes: &[u8]) -> Result<PeerMessage, DecodeError> {
+        let max_size = self.size_limit.max_notification();
+        let (tag, body) = bytes
+            .split_first()
+            .ok_or_else(|| DecodeError::Invalid(format_err!("Empty message")))?;
+        let data = match *tag {
+            TAG_NONE => {
+                if body.len() > max_size {
+                    return Err(DecodeError::Oversized(body.len()));
+                }
+                body.to_vec()
+            }
+            TAG_SNAPPY => {
+                // check the size before decompress, avoid allocating for a compression bomb.
+                let len =
+                    snap::raw::decompress_len(body).map_err(|e| DecodeError::Invalid(e.into()))?;
+                if len > max_size {
+                    return Err(DecodeError::Oversized(len));
+                }
+                snap::raw::Decoder::new()
+                    .decompress_vec(body)
+                    .map_err(|e| DecodeError::Invalid(e.into()))?
+            }
+            tag => {
+                return Err(DecodeError::Invalid(format_err!(
+                    "Unknown compression tag {}",
+                    tag
+                )))
+            }
+        };
+        let msg = PeerMessage::decode(&data).map_err(DecodeError::Invalid)?;
+        if data.len() > self.limit_of(&msg) {
+            return Err(DecodeError::Oversized(data.len()));
+        }
+        Ok(msg)
+    }
+
+    fn limit_of(&self, msg: &PeerMessage) -> usize {
+        match msg {
+            PeerMessage::UserTransactions(_) => self.size_limit.transactions,
+            PeerMessage::Block(_) => self.size_limit.block,
+        }
+    }
+}
+
+/// Limit the number of messages received from each peer in one second.
+pub(crate) struct PeerRateLimiter {
+    max_per_sec: u32,
+    windows: HashMap<PeerId, (Instant, u32)>,
+}
+
+impl PeerRateLimiter {
+    /// `max_per_sec` 0 means unlimited.
+    pub fn new(max_per_sec: u32) -> Self {
+        Self {
+            max_per_sec,
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Record a message from the peer, return error if the peer exceed the limit.
+    pub fn check(&mut self, peer_id: &PeerId) -> Result<()> {
+        if self.max_per_sec == 0 {
+            return Ok(());
+        }
+        let now = Instant::now();
+        let (start, count) = self.windows.entry(peer_id.clone()).or_insert((now, 0));
+        if now.duration_since(*start) >= Duration::from_secs(1) {
+            *start = now;
+            *count = 0;
+        }
+        *count += 1;
+        if *count > self.max_per_sec {
+            bail!(
+                "Peer {} send more than {} messages per second",
+                peer_id,
+                self.max_per_sec
+            );
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, peer_id: &PeerId) {
+        self.windows.remove(peer_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::transaction::SignedUserTransaction;
+
+    fn codec(compression: MessageCompression) -> PeerMessageCodec {
+        let mut config = NetworkConfig::default();
+        config.compression = compression;
+        config.max_message_size.transactions = 1024;
+        PeerMessageCodec::new(&config)
+    }
+
+    #[test]
+    fn test_codec_compression() {
+        let txn = SignedUserTransaction::mock_from(vec![1u8; 800]);
+        let msg = PeerMessage::UserTransactions(vec![txn.clone()]);
+        let raw = codec(MessageCompression::None);
+        let snappy = codec(MessageCompression::Snappy);
+        let raw_bytes = raw.encode(&msg).unwrap();
+        let snappy_bytes = snappy.encode(&msg).unwrap();
+        assert!(snappy_bytes.len() < raw_bytes.len());
+        // the receiver accept both, whatever its own compression config.
+        for bytes in &[raw_bytes, snappy_bytes] {
+            for codec in &[&raw, &snappy] {
+                match codec.decode(bytes).unwrap() {
+                    PeerMessage::UserTransactions(txns) => assert_eq!(txns, vec![txn.clone()]),
+                    msg => panic!("Unexpect message {:?}", msg),
+                }
+            }
+        }
+        assert!(matches!(
+            raw.decode(&[]).unwrap_err(),
+            DecodeError::Invalid(_)
+        ));
+        assert!(matches!(
+            raw.decode(&[TAG_SNAPPY, 1, 2, 3]).unwrap_err(),
+            DecodeError::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn test_codec_size_limit() {
+        let msg =
+            PeerMessage::UserTransactions(vec![SignedUserTransaction::mock_from(vec![1u8; 2000])]);
+        let snappy = codec(MessageCompression::Snappy);
+        let bytes = snappy.encode(&msg).unwrap();
+        assert!(matches!(
+            snappy.decode(&bytes).unwrap_err(),
+            DecodeError::Oversized(_)
+        ));
+        // the same message is allowed by the default limit.
+        let default_codec = PeerMessageCodec::new(&NetworkConfig::default());
+        assert!(default_codec.decode(&bytes).is_ok());
+    }
+
+    #[test]
+    fn test_codec_size_limit_ignore_rpc() {
+        let mut config = NetworkConfig::default();
+        config.compression = MessageCompression::None;
+        config.max_message_size.transactions = 1024;
+        config.max_message_size.block = 1024;
+        let codec = PeerMessageCodec::new(&config);
+        let msg =
+            PeerMessage::UserTransactions(vec![SignedUserTransaction::mock_from(vec![1u8; 2000])]);
+        let bytes = codec.encode(&msg).unwrap();
+        // rejected before decode, the larger rpc response limit does not apply to notifications.
+        assert!(bytes.len() - 1 > config.max_message_size.max_notification());
+        assert!(bytes.len() < config.max_message_size.rpc_response);
+        assert!(matches!(
+            codec.decode(&bytes).unwrap_err(),
+            DecodeError::Oversized(size) if size == bytes.len() - 1
+        ));
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = PeerRateLimiter::new(3);
+        let peer_1 = PeerId::random();
+        let peer_2 = PeerId::random();
+        for _ in 0..3 {
+            limiter.check(&peer_1).unwrap();
+        }
+        assert!(limiter.check(&peer_1).is_err());
+        limiter.check(&peer_2).unwrap();
+        limiter.remove(&peer_1);
+        limiter.check(&peer_1).unwrap();
+
+        let mut unlimited = PeerRateLimiter::new(0);
+        for _ in 0..100 {
+            unlimited.check(&peer_1).unwrap();
+        }
+    }
+}
@@ -13,8 +13,9 @@ use futures::{
 };
 use libp2p::PeerId;
 use network_p2p::{
-    identity, Event, Multiaddr, NetworkConfiguration, NetworkService, NetworkWorker, NodeKeyConfig,
-    Params, Secret, PROTOCOL_NAME,
+    identity, Event, Multiaddr, NetworkConfiguration, NetworkService, NetworkState, NetworkWorker,
+    NodeKeyConfig, NonReservedPeerMode, Params, ReputationChange, RequestResponseConfig, Secret,
+    TransportConfig, PROTOCOL_NAME,
 };
 use parity_codec::alloc::collections::HashSet;
 use parking_lot::Mutex;
@@ -165,6 +166,49 @@ impl SNetworkService {
     pub async fn get_address(&self, peer_id: PeerId) -> Vec<Multiaddr> {
         self.service.get_address(peer_id).await
     }
+
+    pub async fn network_state(&self) -> Result<NetworkState> {
+        self.service
+            .network_state()
+            .await
+            .map_err(|_| format_err!("Network worker is stopped."))
+    }
+
+    /// Add a reserved peer, the address must end with p2p/peer_id.
+    pub fn add_reserved_peer(&self, peer: String) -> Result<()> {
+        self.service
+            .add_reserved_peer(peer)
+            .map_err(|e| format_err!("{}", e))
+    }
+
+    pub fn remove_reserved_peer(&self, peer_id: PeerId) {
+        self.service.remove_reserved_peer(peer_id);
+    }
+
+    pub fn set_reserved_only(&self, reserved_only: bool) {
+        if reserved_only {
+            self.service.deny_unreserved_peers();
+        } else {
+            self.service.accept_unreserved_peers();
+        }
+    }
+
+    pub fn report_peer(&self, peer_id: PeerId, change: ReputationChange) {
+        self.service.report_peer(peer_id, change);
+    }
+
+    /// Send a request on the request-response protocol, and wait for the response.
+    pub async fn request(
+        &self,
+        peer_id: PeerId,
+        protocol: Cow<'static, str>,
+        request: Vec<u8>,
+    ) -> Result<Vec<u8>> {
+        self.service
+            .request(peer_id, protocol, request)
+            .await
+            .map_err(|e| e.into())
+    }
 }
 
 impl NetworkInner {
@@ -253,6 +297,7 @@ pub fn build_network_service(
     handle: Handle,
     genesis_hash: HashValue,
     self_info: PeerInfo,
+    rpc_protocols: Vec<RequestResponseConfig>,
 ) -> (
     SNetworkService,
     mpsc::UnboundedSender<NetworkMessage>,
@@ -270,7 +315,31 @@ pub fn build_network_service(
             .unwrap();
             NodeKeyConfig::Ed25519(Secret::Input(secret))
         },
+        in_peers: cfg.max_in_peers,
+        out_peers: cfg.max_out_peers,
+        reserved_nodes: cfg
+            .reserved_nodes
+            .iter()
+            .map(|addr| addr.to_string())
+            .collect(),
+        non_reserved_mode: if cfg.reserved_only {
+            NonReservedPeerMode::Deny
+        } else {
+            NonReservedPeerMode::Accept
+        },
+        public_addresses: cfg.public_addresses.clone(),
+        transport: if cfg.is_memory_transport() {
+            TransportConfig::MemoryOnly
+        } else {
+            TransportConfig::Normal {
+                enable_mdns: cfg.enable_mdns,
+                allow_private_ipv4: cfg.allow_private_ipv4,
+                wasm_external_transport: None,
+                use_yamux_flow_control: false,
+            }
+        },
         protocols: cfg.protocols.clone(),
+        request_response_protocols: rpc_protocols,
         genesis_hash,
         self_info,
         ..NetworkConfiguration::default()
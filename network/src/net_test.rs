@@ -83,6 +83,7 @@ mod tests {
                 handle.clone(),
                 HashValue::default(),
                 PeerInfo::default(),
+                vec![],
             );
             result.push({
                 let c: NetworkComponent = server;
@@ -257,6 +258,7 @@ mod tests {
             rt.handle().clone(),
             HashValue::default(),
             PeerInfo::default(),
+            vec![],
         );
 
         thread::sleep(Duration::from_secs(1));
@@ -275,6 +277,7 @@ mod tests {
             rt.handle().clone(),
             HashValue::default(),
             PeerInfo::default(),
+            vec![],
         );
 
         thread::sleep(Duration::from_secs(1));
@@ -289,6 +292,7 @@ mod tests {
             rt.handle().clone(),
             HashValue::default(),
             PeerInfo::default(),
+            vec![],
         );
 
         thread::sleep(Duration::from_secs(1));
@@ -324,6 +328,7 @@ mod tests {
             rt.handle().clone(),
             HashValue::default(),
             PeerInfo::default(),
+            vec![],
         );
 
         thread::sleep(Duration::from_secs(1));
@@ -333,6 +338,7 @@ mod tests {
             rt.handle().clone(),
             HashValue::default(),
             PeerInfo::default(),
+            vec![],
         );
 
         thread::sleep(Duration::from_secs(1));
@@ -1,8 +1,8 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::helper::{get_unix_ts, is_global};
-use crate::message_processor::{MessageFuture, MessageProcessor};
+use crate::helper::is_global;
+use crate::message_codec::{DecodeError, PeerMessageCodec, PeerRateLimiter};
 use crate::net::{build_network_service, SNetworkService};
 use crate::{NetworkMessage, PeerEvent, PeerMessage};
 use actix::prelude::*;
@@ -13,12 +13,13 @@ use config::NodeConfig;
 use crypto::{hash::PlainCryptoHash, HashValue};
 use futures::lock::Mutex;
 use futures::{channel::mpsc, sink::SinkExt, stream::StreamExt};
-use futures_timer::Delay;
 use libp2p::multiaddr::Protocol;
 use libp2p::PeerId;
 use lru::LruCache;
 use network_api::{messages::RawRpcRequestMessage, NetworkService};
-use network_p2p::Multiaddr;
+use network_p2p::{
+    IncomingRequest, Multiaddr, NetworkState, RequestFailure, RequestResponseConfig,
+};
 
 use crate::network_metrics::NetworkMetrics;
 use async_trait::async_trait;
@@ -26,7 +27,7 @@ use config::{
     ChainNetwork, DEV_CHAIN_CONFIG, HALLEY_CHAIN_CONFIG, MAIN_CHAIN_CONFIG, PROXIMA_CHAIN_CONFIG,
 };
 use scs::SCSCodec;
-use starcoin_sync_api::sync_messages::PeerNewBlock;
+use starcoin_sync_api::sync_messages::{PeerNewBlock, SYNC_RPC_PROTOCOLS};
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::io::Write;
@@ -36,21 +37,37 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::runtime::Handle;
 use tx_relay::*;
-use types::peer_info::PeerInfo;
+use types::peer_info::{PeerCapabilities, PeerInfo};
 use types::system_events::NewHeadBlock;
 use types::transaction::SignedUserTransaction;
 
 const LRU_CACHE_SIZE: usize = 1024;
 const PEERS_FILE_NAME: &str = "peers.json";
+/// Timeout of a rpc request, from sending the request to receiving the whole response.
+const RPC_REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// Max number of rpc requests in progress with one peer, for each protocol and direction.
+const MAX_CONCURRENT_RPC_REQUESTS_PER_PEER: usize = 16;
+/// Inbound rpc requests waiting to be forwarded, new requests are refused when it is full.
+const INBOUND_RPC_QUEUE_SIZE: usize = 256;
+
+mod rep {
+    use network_p2p::ReputationChange as Rep;
+    /// We received a message that failed to decode.
+    pub const BAD_MESSAGE: Rep = Rep::new(-(1 << 12), "Bad message");
+    /// We received a message larger than the size limit of its type.
+    pub const OVERSIZED_MESSAGE: Rep = Rep::new(-(1 << 16), "Oversized message");
+    /// Peer send messages faster than the inbound rate limit.
+    pub const RATE_LIMITED: Rep = Rep::new(-(1 << 10), "Too many messages");
+    /// Peer serves a chain which conflicts with the checkpoints.
+    pub const BAD_CHAIN: Rep = Rep::new_fatal("Bad chain");
+}
 
 #[derive(Clone)]
 pub struct NetworkAsyncService {
     addr: Addr<NetworkActor>,
-    raw_message_processor: MessageProcessor<u128, Vec<u8>>,
     tx: mpsc::UnboundedSender<NetworkMessage>,
     network_service: SNetworkService,
     peer_id: PeerId,
-    handle: Handle,
     inner: Arc<Inner>,
     metrics: Option<NetworkMetrics>,
 }
@@ -58,14 +75,14 @@ pub struct NetworkAsyncService {
 struct Inner {
     network_service: SNetworkService,
     bus: Addr<BusActor>,
-    raw_message_processor: MessageProcessor<u128, Vec<u8>>,
-    handle: Handle,
     peers: Arc<Mutex<HashMap<PeerId, PeerInfoNet>>>,
     connected_tx: mpsc::Sender<PeerEvent>,
     need_send_event: AtomicBool,
     node_config: Arc<NodeConfig>,
     peer_id: PeerId,
     rpc_tx: mpsc::UnboundedSender<RawRpcRequestMessage>,
+    codec: PeerMessageCodec,
+    rate_limiter: parking_lot::Mutex<PeerRateLimiter>,
 }
 
 #[derive(Debug)]
@@ -101,7 +118,7 @@ impl NetworkService for NetworkAsyncService {
         peer_id: PeerId,
         msg: PeerMessage,
     ) -> Result<()> {
-        let data = msg.encode()?;
+        let data = self.inner.codec.encode(&msg)?;
         self.network_service
             .send_message(peer_id, protocol_name, data)
             .await?;
@@ -128,49 +145,27 @@ impl NetworkService for NetworkAsyncService {
 
     async fn send_request_bytes(
         &self,
-        protocol_name: Cow<'static, [u8]>,
+        protocol: Cow<'static, str>,
         peer_id: PeerId,
         message: Vec<u8>,
-        time_out: Duration,
     ) -> Result<Vec<u8>> {
-        let request_id = get_unix_ts();
-        let peer_msg = PeerMessage::RawRPCRequest(request_id, message);
-        let data = peer_msg.encode()?;
-        self.network_service
-            .send_message(peer_id.clone(), protocol_name, data)
-            .await?;
-
-        let (tx, rx) = futures::channel::mpsc::channel(1);
-        let message_future = MessageFuture::new(rx);
-        self.raw_message_processor.add_future(request_id, tx).await;
-        debug!("send request to {} with id {}", peer_id, request_id);
-        let processor = self.raw_message_processor.clone();
-        let peer_id_clone = peer_id.clone();
-
+        debug!("send request {} to {}", protocol, peer_id);
         if let Some(metrics) = &self.metrics {
             metrics.request_count.inc();
         }
-
-        let metrics = self.metrics.clone();
-        let task = async move {
-            Delay::new(time_out).await;
-            let timeout = processor.remove_future(request_id).await;
-            if !timeout {
-                return;
-            }
-            debug!(
-                "send request to {} with id {} timeout",
-                peer_id_clone, request_id
-            );
-            if let Some(metrics) = metrics {
-                metrics.request_timeout_count.inc();
+        let result = self
+            .network_service
+            .request(peer_id.clone(), protocol.clone(), message)
+            .await;
+        if let Err(e) = &result {
+            debug!("request {} to {} failed: {}", protocol, peer_id, e);
+            if let Some(RequestFailure::Timeout) = e.downcast_ref::<RequestFailure>() {
+                if let Some(metrics) = &self.metrics {
+                    metrics.request_timeout_count.inc();
+                }
             }
-        };
-
-        self.handle.spawn(task);
-        let response = message_future.await;
-        debug!("receive response from {} with id {}", peer_id, request_id);
-        response
+        }
+        result
     }
 
     async fn peer_set(&self) -> Result<Vec<PeerInfo>> {
@@ -231,6 +226,30 @@ impl NetworkAsyncService {
     pub fn network_actor_addr(&self) -> Addr<NetworkActor> {
         self.addr.clone()
     }
+
+    /// Get the p2p network state for diagnostic.
+    pub async fn network_state(&self) -> Result<NetworkState> {
+        self.network_service.network_state().await
+    }
+
+    /// Add a reserved peer at runtime, the address must end with p2p/peer_id.
+    pub fn add_reserved_peer(&self, peer: String) -> Result<()> {
+        self.network_service.add_reserved_peer(peer)
+    }
+
+    pub fn remove_reserved_peer(&self, peer_id: PeerId) {
+        self.network_service.remove_reserved_peer(peer_id)
+    }
+
+    /// Disconnect and ban the peer which serves a chain conflicting with the checkpoints.
+    pub fn ban_peer(&self, peer_id: PeerId) {
+        self.network_service.report_peer(peer_id, rep::BAD_CHAIN);
+    }
+
+    /// Deny or accept connections from peers which are not reserved.
+    pub fn set_reserved_only(&self, reserved_only: bool) {
+        self.network_service.set_reserved_only(reserved_only)
+    }
 }
 
 pub struct NetworkActor {
@@ -238,6 +257,7 @@ pub struct NetworkActor {
     bus: Addr<BusActor>,
     peers: Arc<Mutex<HashMap<PeerId, PeerInfoNet>>>,
     peer_id: PeerId,
+    codec: PeerMessageCodec,
 }
 
 impl NetworkActor {
@@ -264,8 +284,26 @@ impl NetworkActor {
         }
         let has_seed = !config.seeds.is_empty();
 
-        let (service, tx, rx, event_rx, tx_command) =
-            build_network_service(&config, handle.clone(), genesis_hash, self_info.clone());
+        let (request_tx, request_rx) = mpsc::channel(INBOUND_RPC_QUEUE_SIZE);
+        let rpc_protocols = SYNC_RPC_PROTOCOLS
+            .iter()
+            .map(|protocol| RequestResponseConfig {
+                name: Cow::Borrowed(*protocol),
+                max_request_size: config.max_message_size.rpc_request,
+                max_response_size: config.max_message_size.rpc_response,
+                request_timeout: RPC_REQUEST_TIMEOUT,
+                max_concurrent_requests_per_peer: MAX_CONCURRENT_RPC_REQUESTS_PER_PEER,
+                inbound_queue: Some(request_tx.clone()),
+            })
+            .collect();
+
+        let (service, tx, rx, event_rx, tx_command) = build_network_service(
+            &config,
+            handle.clone(),
+            genesis_hash,
+            self_info.clone(),
+            rpc_protocols,
+        );
         info!(
             "network started at {} with seed {},network address is {}",
             &node_config.network.listen,
@@ -277,9 +315,6 @@ impl NetworkActor {
             service.identify()
         );
 
-        let raw_message_processor = MessageProcessor::new();
-        let raw_message_processor_clone = raw_message_processor.clone();
-
         let peer_id = service.identify().clone();
         let peer_id_clone = peer_id.clone();
 
@@ -288,15 +323,20 @@ impl NetworkActor {
         let mut peers = HashMap::new();
         peers.insert(
             self_info.peer_id.clone().into(),
-            PeerInfoNet::new(self_info),
+            PeerInfoNet::new(
+                self_info.with_protocol(network_p2p::CURRENT_VERSION, PeerCapabilities::all()),
+            ),
         );
         let peers = Arc::new(Mutex::new(peers));
         let peers_clone = peers.clone();
+        let codec = PeerMessageCodec::new(&config);
+        let codec_clone = codec.clone();
         let addr = NetworkActor::create(move |_ctx: &mut Context<NetworkActor>| NetworkActor {
             network_service: service_clone,
             bus: bus_clone,
             peers: peers_clone,
             peer_id: peer_id_clone,
+            codec: codec_clone,
         });
         let (connected_tx, mut connected_rx) = futures::channel::mpsc::channel(1);
         let need_send_event = AtomicBool::new(false);
@@ -311,14 +351,16 @@ impl NetworkActor {
         let inner = Inner {
             network_service: service.clone(),
             bus,
-            handle: handle.clone(),
-            raw_message_processor: raw_message_processor_clone,
             peers,
             connected_tx,
             need_send_event,
+            rate_limiter: parking_lot::Mutex::new(PeerRateLimiter::new(
+                node_config.network.max_inbound_messages_per_sec,
+            )),
             node_config,
             peer_id: peer_id.clone(),
             rpc_tx,
+            codec,
         };
         let inner = Arc::new(inner);
         handle.spawn(Self::start(
@@ -326,6 +368,7 @@ impl NetworkActor {
             inner.clone(),
             rx,
             event_rx,
+            request_rx,
             tx_command,
         ));
 
@@ -340,12 +383,10 @@ impl NetworkActor {
         (
             NetworkAsyncService {
                 addr,
-                raw_message_processor,
                 network_service: service,
                 tx,
                 peer_id,
                 inner,
-                handle,
                 metrics,
             },
             rpc_rx,
@@ -357,10 +398,12 @@ impl NetworkActor {
         inner: Arc<Inner>,
         net_rx: mpsc::UnboundedReceiver<NetworkMessage>,
         event_rx: mpsc::UnboundedReceiver<PeerEvent>,
+        request_rx: mpsc::Receiver<IncomingRequest>,
         close_tx: mpsc::UnboundedSender<()>,
     ) {
         let mut net_rx = net_rx.fuse();
         let mut event_rx = event_rx.fuse();
+        let mut request_rx = request_rx.fuse();
 
         loop {
             futures::select! {
@@ -372,6 +415,10 @@ impl NetworkActor {
                     handle.spawn(Inner::handle_event_receive(inner.clone(),event));
                     debug!("receive net event");
                 },
+                request = request_rx.select_next_some()=>{
+                    handle.spawn(Inner::handle_inbound_request(inner.clone(),request));
+                    debug!("receive rpc request");
+                },
                 complete => {
                     close_tx.unbounded_send(()).unwrap();
                     debug!("all stream are complete");
@@ -385,15 +432,26 @@ impl NetworkActor {
 impl Inner {
     async fn handle_network_receive(inner: Arc<Inner>, network_msg: NetworkMessage) -> Result<()> {
         debug!("receive network_message ");
-        let message = PeerMessage::decode(&network_msg.data);
-        match message {
-            Ok(msg) => {
+        let peer_id = network_msg.peer_id;
+        let checked = inner.rate_limiter.lock().check(&peer_id);
+        if let Err(e) = checked {
+            debug!("Drop message: {:?}", e);
+            inner
+                .network_service
+                .report_peer(peer_id, rep::RATE_LIMITED);
+            return Ok(());
+        }
+        match inner.codec.decode(&network_msg.data) {
+            Ok(msg) => inner.handle_network_message(peer_id, msg).await?,
+            Err(DecodeError::Oversized(size)) => {
+                debug!("Drop oversized message of {} bytes from {}", size, peer_id);
                 inner
-                    .handle_network_message(network_msg.peer_id, msg)
-                    .await?
+                    .network_service
+                    .report_peer(peer_id, rep::OVERSIZED_MESSAGE);
             }
-            Err(e) => {
-                debug!("get error {:?}", e);
+            Err(DecodeError::Invalid(e)) => {
+                debug!("Decode message from {} error: {:?}", peer_id, e);
+                inner.network_service.report_peer(peer_id, rep::BAD_MESSAGE);
             }
         }
         Ok(())
@@ -446,48 +504,25 @@ impl Inner {
                     })
                     .await?;
             }
-            PeerMessage::RawRPCRequest(id, request) => {
-                debug!("do request {} from peer {}", id, peer_id);
-                let (tx, rx) = mpsc::channel(1);
-                self.rpc_tx.unbounded_send(RawRpcRequestMessage {
-                    responder: tx,
-                    request,
-                })?;
-                let network_service = self.network_service.clone();
-                self.handle
-                    .spawn(Self::handle_response(id, peer_id, rx, network_service));
-            }
-            PeerMessage::RawRPCResponse(id, response) => {
-                debug!("do response {} from peer {}", id, peer_id);
-                self.raw_message_processor
-                    .send_response(id, response)
-                    .await?;
-            }
         }
         Ok(())
     }
 
-    async fn handle_response(
-        id: u128,
-        peer_id: PeerId,
-        mut rx: mpsc::Receiver<(Cow<'static, [u8]>, Vec<u8>)>,
-        network_service: SNetworkService,
-    ) -> Result<()> {
-        let response = rx.next().await;
-        match response {
-            Some((protocol_name, response)) => {
-                let peer_msg = PeerMessage::RawRPCResponse(id, response);
-                let data = peer_msg.encode()?;
-                network_service
-                    .send_message(peer_id, protocol_name, data)
-                    .await?;
-                Ok(())
-            }
-            None => {
-                debug!("can't get response by id {}", id);
-                Ok(())
-            }
+    /// Forward the inbound rpc request to the rpc receiver, and send back the response.
+    /// The request is refused if no response is sent.
+    async fn handle_inbound_request(inner: Arc<Inner>, request: IncomingRequest) -> Result<()> {
+        debug!("do request {} from peer {}", request.protocol, request.peer);
+        let (tx, mut rx) = mpsc::channel(1);
+        inner.rpc_tx.unbounded_send(RawRpcRequestMessage {
+            peer_id: request.peer.into(),
+            protocol: request.protocol,
+            request: request.payload,
+            responder: tx,
+        })?;
+        if let Some(response) = rx.next().await {
+            let _ = request.pending_response.send(response);
         }
+        Ok(())
     }
 
     async fn handle_event_receive(inner: Arc<Inner>, event: PeerEvent) -> Result<()> {
@@ -572,6 +607,7 @@ impl Inner {
     }
 
     async fn on_peer_disconnected(&self, peer_id: PeerId) {
+        self.rate_limiter.lock().remove(&peer_id);
         self.peers.lock().await.remove(&peer_id);
     }
 }
@@ -616,7 +652,7 @@ impl Handler<BlockMessage> for NetworkActor {
 
         let total_difficulty = block.get_total_difficulty();
         let msg = PeerMessage::Block(block);
-        let bytes = msg.encode().expect("should encode succ");
+        let bytes = self.codec.encode(&msg).expect("should encode succ");
 
         let self_info = PeerInfo::new(
             self.peer_id.clone().into(),
@@ -676,6 +712,7 @@ impl Handler<PropagateNewTransactions> for NetworkActor {
             txn_map.insert(txn.crypto_hash(), txn);
         }
         let self_peer_id = self.peer_id.clone();
+        let codec = self.codec.clone();
         Arbiter::spawn(async move {
             for (peer_id, peer_info) in peers.lock().await.iter_mut() {
                 let mut txns_unhandled = Vec::new();
@@ -688,7 +725,7 @@ impl Handler<PropagateNewTransactions> for NetworkActor {
 
                 let msg = PeerMessage::UserTransactions(txns_unhandled);
 
-                let bytes = msg.encode().expect("encode should succ");
+                let bytes = codec.encode(&msg).expect("encode should succ");
                 network_service
                     .send_message(peer_id.clone(), protocol_name.clone(), bytes)
                     .await
@@ -805,10 +842,9 @@ mod tests {
             info!("req :{:?}", request);
             let resp = network1
                 .send_request_bytes(
-                    network_p2p::PROTOCOL_NAME.into(),
+                    SYNC_RPC_PROTOCOLS[0].into(),
                     network2.identify().clone(),
                     request.encode().unwrap(),
-                    Duration::from_secs(1),
                 )
                 .await;
             info!("resp :{:?}", resp);
@@ -896,10 +932,7 @@ mod tests {
         fn handle(&mut self, msg: RawRpcRequestMessage, ctx: &mut Self::Context) -> Self::Result {
             let mut responder = msg.responder.clone();
             let f = async move {
-                responder
-                    .send((network_p2p::PROTOCOL_NAME.into(), msg.request))
-                    .await
-                    .unwrap();
+                responder.send(msg.request).await.unwrap();
             };
             let f = actix::fut::wrap_future(f);
             ctx.spawn(Box::new(f));
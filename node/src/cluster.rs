@@ -0,0 +1,326 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! In-process multi-node cluster for tests.
+//!
+//! All nodes run in the current process with `DummyConsensus`, and connect to each other by
+//! libp2p memory transport, so no tcp port is used by the p2p network.
+
+use crate::{run_node, NodeHandle, NodeService};
+use anyhow::{bail, ensure, format_err, Result};
+use starcoin_config::NodeConfig;
+use starcoin_consensus::dummy::DummyConsensus;
+use starcoin_logger::prelude::*;
+use starcoin_miner::{GenerateBlockEvent, SetMiningEvent};
+use starcoin_storage::BlockStore;
+use starcoin_types::block::BlockHeader;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Memory transport addresses are global in the process, so allocate them from a counter.
+static NEXT_MEMORY_PORT: AtomicU64 = AtomicU64::new(1);
+
+const WAIT_INTERVAL: Duration = Duration::from_millis(200);
+
+pub struct ClusterBuilder {
+    num_nodes: usize,
+    mining: Vec<bool>,
+}
+
+impl ClusterBuilder {
+    /// A cluster with `num_nodes` nodes, mining is paused on every node by default.
+    pub fn new(num_nodes: usize) -> Self {
+        Self {
+            num_nodes,
+            mining: vec![false; num_nodes],
+        }
+    }
+
+    /// Enable or disable auto mining of the node at `index` after start.
+    pub fn with_mining(mut self, index: usize, enable: bool) -> Self {
+        self.mining[index] = enable;
+        self
+    }
+
+    /// Start all nodes, every node use the first node as seed.
+    pub fn build(self) -> Result<Cluster> {
+        ensure!(self.num_nodes > 0, "Cluster must have at least one node.");
+        let mut seed = None;
+        let mut nodes = vec![];
+        for enable_mining in self.mining {
+            let mut config = NodeConfig::random_for_test();
+            let port = NEXT_MEMORY_PORT.fetch_add(1, Ordering::SeqCst);
+            config
+                .network
+                .set_listen(format!("/memory/{}", port).parse()?);
+            let self_address = config
+                .network
+                .self_address
+                .clone()
+                .expect("Self address must has been set.");
+            match &seed {
+                Some(seed) => config.network.seeds = vec![seed.clone()],
+                None => seed = Some(self_address),
+            }
+            let mut node = ClusterNode {
+                config: Arc::new(config),
+                handle: None,
+                mining: enable_mining,
+            };
+            node.start()?;
+            nodes.push(node);
+        }
+        Ok(Cluster {
+            nodes,
+            partition: None,
+        })
+    }
+}
+
+pub struct ClusterNode {
+    config: Arc<NodeConfig>,
+    handle: Option<NodeHandle>,
+    mining: bool,
+}
+
+impl ClusterNode {
+    pub fn config(&self) -> &Arc<NodeConfig> {
+        &self.config
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    pub fn service(&self) -> Result<&NodeService> {
+        self.handle
+            .as_ref()
+            .map(|handle| handle.service())
+            .ok_or_else(|| format_err!("Node is stopped."))
+    }
+
+    /// Current head block header of the node.
+    pub fn head(&self) -> Result<BlockHeader> {
+        let storage = &self.service()?.storage;
+        let startup_info = storage
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("Startup info not exist."))?;
+        storage
+            .get_block_header_by_hash(*startup_info.get_master())?
+            .ok_or_else(|| format_err!("Head block header not exist."))
+    }
+
+    /// Pause or resume auto mining.
+    pub fn set_mining(&mut self, enable: bool) -> Result<()> {
+        self.service()?
+            .set_mining
+            .do_send(SetMiningEvent { enable })
+            .map_err(|e| format_err!("Send SetMiningEvent error: {:?}", e))?;
+        self.mining = enable;
+        Ok(())
+    }
+
+    /// Generate a block on the current head, even if auto mining is paused.
+    pub fn mine_block(&self) -> Result<()> {
+        self.service()?
+            .generate_block
+            .do_send(GenerateBlockEvent { force: true })
+            .map_err(|e| format_err!("Send GenerateBlockEvent error: {:?}", e))?;
+        Ok(())
+    }
+
+    fn start(&mut self) -> Result<()> {
+        ensure!(!self.is_running(), "Node is running.");
+        let handle = run_node::<DummyConsensus>(self.config.clone());
+        handle
+            .service()
+            .set_mining
+            .do_send(SetMiningEvent {
+                enable: self.mining,
+            })
+            .map_err(|e| format_err!("Send SetMiningEvent error: {:?}", e))?;
+        self.handle = Some(handle);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        match self.handle.take() {
+            Some(handle) => handle.stop(),
+            None => bail!("Node is stopped."),
+        }
+    }
+}
+
+pub struct Cluster {
+    nodes: Vec<ClusterNode>,
+    /// Groups of nodes which can only connect to nodes in the same group.
+    partition: Option<Vec<Vec<usize>>>,
+}
+
+impl Cluster {
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node(&self, index: usize) -> &ClusterNode {
+        &self.nodes[index]
+    }
+
+    pub fn node_mut(&mut self, index: usize) -> &mut ClusterNode {
+        &mut self.nodes[index]
+    }
+
+    /// Split the cluster into groups, nodes can only connect to nodes in the same group.
+    /// Every node must be in exactly one group.
+    pub fn partition(&mut self, groups: &[&[usize]]) -> Result<()> {
+        let mut indexes: Vec<usize> = groups
+            .iter()
+            .flat_map(|group| group.iter())
+            .copied()
+            .collect();
+        indexes.sort_unstable();
+        ensure!(
+            indexes == (0..self.len()).collect::<Vec<_>>(),
+            "Every node must be in exactly one group, groups: {:?}",
+            groups
+        );
+        self.heal()?;
+        self.partition = Some(groups.iter().map(|group| group.to_vec()).collect());
+        for (index, node) in self.nodes.iter().enumerate() {
+            if node.is_running() {
+                self.apply_partition(index)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the partition, nodes reconnect to each other by discovery.
+    pub fn heal(&mut self) -> Result<()> {
+        if let Some(groups) = self.partition.take() {
+            for group in groups {
+                for index in &group {
+                    let node = &self.nodes[*index];
+                    if !node.is_running() {
+                        continue;
+                    }
+                    let network = &node.service()?.network;
+                    network.set_reserved_only(false);
+                    for peer in group.iter().filter(|peer| *peer != index) {
+                        let peer_id = self.nodes[*peer]
+                            .config
+                            .network
+                            .self_peer_id
+                            .clone()
+                            .expect("Self peer_id must has been set.");
+                        network.remove_reserved_peer(peer_id.into());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn stop_node(&mut self, index: usize) -> Result<()> {
+        info!("Stop cluster node {}", index);
+        self.nodes[index].stop()
+    }
+
+    /// Start a stopped node with the same config and data dir, the partition is applied again.
+    pub fn restart_node(&mut self, index: usize) -> Result<()> {
+        info!("Restart cluster node {}", index);
+        self.nodes[index].start()?;
+        if self.partition.is_some() {
+            self.apply_partition(index)?;
+        }
+        Ok(())
+    }
+
+    /// Wait until `condition` is true, or return error after `timeout`.
+    pub fn wait_until<F>(&self, timeout: Duration, condition: F) -> Result<()>
+    where
+        F: Fn(&Cluster) -> Result<bool>,
+    {
+        let begin = Instant::now();
+        loop {
+            if condition(self)? {
+                return Ok(());
+            }
+            if begin.elapsed() > timeout {
+                bail!("Wait cluster condition timeout after {:?}", timeout);
+            }
+            thread::sleep(WAIT_INTERVAL);
+        }
+    }
+
+    /// Wait until the head of the node at `index` reach `number`.
+    pub fn wait_for_number(&self, index: usize, number: u64, timeout: Duration) -> Result<()> {
+        self.wait_until(timeout, |cluster| {
+            Ok(cluster.node(index).head()?.number() >= number)
+        })
+    }
+
+    /// Wait until all running nodes have the same head, and return the head.
+    pub fn wait_for_converge(&self, timeout: Duration) -> Result<BlockHeader> {
+        self.wait_until(timeout, |cluster| {
+            let mut heads = cluster
+                .nodes
+                .iter()
+                .filter(|node| node.is_running())
+                .map(|node| node.head().map(|head| head.id()));
+            let first = match heads.next() {
+                Some(head) => head?,
+                None => bail!("No running node in cluster."),
+            };
+            for head in heads {
+                if head? != first {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        })?;
+        self.nodes
+            .iter()
+            .find(|node| node.is_running())
+            .expect("Running node must exist.")
+            .head()
+    }
+
+    /// Stop all running nodes.
+    pub fn stop(mut self) -> Result<()> {
+        for node in &mut self.nodes {
+            if node.is_running() {
+                node.stop()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_partition(&self, index: usize) -> Result<()> {
+        let group = match &self.partition {
+            Some(groups) => groups
+                .iter()
+                .find(|group| group.contains(&index))
+                .expect("Node must be in a group."),
+            None => return Ok(()),
+        };
+        let network = &self.nodes[index].service()?.network;
+        for peer in group.iter().filter(|peer| **peer != index) {
+            let address = self.nodes[*peer]
+                .config
+                .network
+                .self_address
+                .as_ref()
+                .expect("Self address must has been set.")
+                .to_string();
+            network.add_reserved_peer(address)?;
+        }
+        network.set_reserved_only(true);
+        Ok(())
+    }
+}
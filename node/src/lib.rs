@@ -14,15 +14,18 @@ use tokio::runtime::Runtime;
 use tokio::sync::oneshot;
 
 mod actor;
+pub mod cluster;
 pub mod message;
 mod node;
 
 pub use actor::{NodeActor, NodeRef};
+pub use node::NodeService;
 
 pub struct NodeHandle {
     runtime: Runtime,
     thread_handle: JoinHandle<()>,
     stop_sender: oneshot::Sender<()>,
+    service: NodeService,
 }
 
 #[cfg(unix)]
@@ -63,14 +66,20 @@ impl NodeHandle {
     pub fn new(
         thread_handle: std::thread::JoinHandle<()>,
         stop_sender: oneshot::Sender<()>,
+        service: NodeService,
     ) -> Self {
         Self {
             runtime: Runtime::new().unwrap(),
             thread_handle,
             stop_sender,
+            service,
         }
     }
 
+    pub fn service(&self) -> &NodeService {
+        &self.service
+    }
+
     pub fn join(mut self) -> Result<()> {
         self.runtime.block_on(async {
             platform::wait_signal().await;
@@ -154,7 +163,7 @@ where
             //let node_actor = NodeActor::<C, H>::new(config, handle);
             //let _node_ref = node_actor.start();
             //TODO fix me, this just a work around method.
-            let _handle = match node::start::<C>(config, logger_handle, handle).await {
+            let node_handle = match node::start::<C>(config, logger_handle, handle).await {
                 Err(e) => {
                     error!("Node start fail: {:?}, exist.", e);
                     System::current().stop();
@@ -162,7 +171,7 @@ where
                 }
                 Ok(handle) => handle,
             };
-            if start_sender.send(()).is_err() {
+            if start_sender.send(node_handle.service.clone()).is_err() {
                 info!("Start send error.");
             }
             if stop_receiver.await.is_err() {
@@ -172,9 +181,9 @@ where
             System::current().stop();
         });
     });
-    let result = block_on(async { start_receiver.await });
-    if result.is_err() {
-        std::process::exit(1);
-    }
-    NodeHandle::new(thread_handle, stop_sender)
+    let service = match block_on(async { start_receiver.await }) {
+        Ok(service) => service,
+        Err(_) => std::process::exit(1),
+    };
+    NodeHandle::new(thread_handle, stop_sender, service)
 }
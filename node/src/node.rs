@@ -10,8 +10,8 @@ use starcoin_config::NodeConfig;
 use starcoin_genesis::Genesis;
 use starcoin_logger::prelude::*;
 use starcoin_logger::LoggerHandle;
-use starcoin_miner::MinerActor;
 use starcoin_miner::MinerClientActor;
+use starcoin_miner::{GenerateBlockEvent, MinerActor, SetMiningEvent};
 use starcoin_network::{NetworkActor, NetworkAsyncService, RawRpcRequestMessage};
 use starcoin_rpc_server::module::PubSubService;
 use starcoin_rpc_server::RpcActor;
@@ -45,6 +45,18 @@ where
     _sync_actor: Addr<SyncActor<C>>,
     _rpc_actor: Addr<RpcActor>,
     _miner_client: Option<Addr<MinerClientActor>>,
+    pub service: NodeService,
+}
+
+/// Services of a started node, can be used outside the node thread.
+#[derive(Clone)]
+pub struct NodeService {
+    pub bus: Addr<BusActor>,
+    pub storage: Arc<Storage>,
+    pub txpool: TxPoolService,
+    pub network: NetworkAsyncService,
+    pub generate_block: Recipient<GenerateBlockEvent>,
+    pub set_mining: Recipient<SetMiningEvent>,
 }
 
 //TODO this method should in Genesis.
@@ -90,7 +102,10 @@ where
     let sync_event_receiver_future = bus.clone().channel::<SyncDone>();
     debug!("init storage.");
     let cache_storage = Arc::new(CacheStorage::new());
-    let db_storage = Arc::new(DBStorage::new(config.storage.clone().dir()));
+    let db_storage = Arc::new(DBStorage::open(
+        DBStorage::db_path(config.storage.dir()),
+        false,
+    )?);
     let storage = Arc::new(Storage::new(StorageInstance::new_cache_and_db_instance(
         cache_storage.clone(),
         db_storage.clone(),
@@ -243,7 +258,9 @@ where
         chain_state_service,
         Some(pubsub_service),
         Some(network.clone()),
+        Some(sync_metadata.clone()),
         Some(logger_handle),
+        storage.clone(),
     )?;
 
     info!("Self peer_id is: {}", peer_id.to_base58());
@@ -290,7 +307,7 @@ where
     info!("Waiting sync finished.");
     let miner = MinerActor::<C, TxPoolService, ChainActorRef<C>, Storage>::launch(
         config.clone(),
-        bus,
+        bus.clone(),
         storage.clone(),
         txpool.get_service(),
         chain.clone(),
@@ -301,10 +318,19 @@ where
     } else {
         None
     };
+    let service = NodeService {
+        bus,
+        storage,
+        txpool: txpool_service,
+        network,
+        generate_block: miner.clone().recipient(),
+        set_mining: miner.clone().recipient(),
+    };
     Ok(NodeStartHandle {
         _miner_actor: miner,
         _sync_actor: sync,
         _rpc_actor: json_rpc,
         _miner_client: miner_client,
+        service,
     })
 }
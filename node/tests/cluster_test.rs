@@ -0,0 +1,112 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{format_err, Result};
+use starcoin_chain::BlockChain;
+use starcoin_consensus::dummy::DummyConsensus;
+use starcoin_crypto::hash::PlainCryptoHash;
+use starcoin_crypto::keygen::KeyGen;
+use starcoin_executor::executor::Executor;
+use starcoin_executor::TransactionExecutor;
+use starcoin_node::cluster::{Cluster, ClusterBuilder};
+use starcoin_state_api::AccountStateReader;
+use starcoin_storage::Storage;
+use starcoin_traits::ChainReader;
+use starcoin_txpool_api::TxPoolSyncService;
+use starcoin_types::account_address;
+use starcoin_types::account_config::association_address;
+use starcoin_types::transaction::authenticator::AuthenticationKey;
+use starcoin_types::transaction::SignedUserTransaction;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+fn mine_blocks(cluster: &Cluster, index: usize, count: u64) -> Result<()> {
+    let number = cluster.node(index).head()?.number();
+    for i in 1..=count {
+        cluster.node(index).mine_block()?;
+        cluster.wait_for_number(index, number + i, TIMEOUT)?;
+    }
+    Ok(())
+}
+
+/// A mint txn sent by the association, by the head state of the node at `index`.
+fn mint_txn(cluster: &Cluster, index: usize) -> Result<SignedUserTransaction> {
+    let node = cluster.node(index);
+    let chain = BlockChain::<DummyConsensus, Storage>::new(
+        node.config().clone(),
+        node.head()?.id(),
+        node.service()?.storage.clone(),
+    )?;
+    let sequence_number = AccountStateReader::new(chain.chain_state_reader())
+        .get_account_resource(&association_address())?
+        .ok_or_else(|| format_err!("Association account not exist."))?
+        .sequence_number();
+    let (_private_key, public_key) = KeyGen::from_os_rng().generate_keypair();
+    let auth_prefix = AuthenticationKey::ed25519(&public_key).prefix().to_vec();
+    let txn = Executor::build_mint_txn(
+        account_address::from_public_key(&public_key),
+        auth_prefix,
+        sequence_number,
+        10000,
+    );
+    Ok(txn.as_signed_user_txn()?.clone())
+}
+
+#[stest::test(timeout = 600)]
+fn test_cluster_partition_and_restart() -> Result<()> {
+    let mut cluster = ClusterBuilder::new(3).build()?;
+
+    mine_blocks(&cluster, 0, 2)?;
+    let head = cluster.wait_for_converge(TIMEOUT)?;
+    assert_eq!(head.number(), 2);
+
+    cluster.partition(&[&[0], &[1, 2]])?;
+    mine_blocks(&cluster, 0, 1)?;
+    mine_blocks(&cluster, 1, 2)?;
+    let head_1 = cluster.node(1).head()?;
+    cluster.wait_until(TIMEOUT, |cluster| {
+        Ok(cluster.node(2).head()?.id() == head_1.id())
+    })?;
+    assert_eq!(cluster.node(0).head()?.number(), 3);
+
+    cluster.heal()?;
+    let head = cluster.wait_for_converge(TIMEOUT)?;
+    assert!(head.number() >= 3);
+    for index in 0..cluster.len() {
+        assert_eq!(cluster.node(index).head()?.id(), head.id());
+    }
+
+    cluster.stop_node(2)?;
+    mine_blocks(&cluster, 0, 1)?;
+    cluster.restart_node(2)?;
+    let head = cluster.wait_for_converge(TIMEOUT)?;
+    assert_eq!(head.id(), cluster.node(0).head()?.id());
+
+    cluster.stop()
+}
+
+#[stest::test(timeout = 300)]
+fn test_cluster_txn_relay() -> Result<()> {
+    let cluster = ClusterBuilder::new(2).build()?;
+    cluster.wait_for_converge(TIMEOUT)?;
+
+    let txn = mint_txn(&cluster, 0)?;
+    let txn_hash = txn.crypto_hash();
+    let txpool = &cluster.node(0).service()?.txpool;
+    txpool
+        .add_local_txns(vec![txn])
+        .pop()
+        .expect("Add txn result must exist.")?;
+    cluster.wait_until(TIMEOUT, |cluster| {
+        Ok(cluster
+            .node(1)
+            .service()?
+            .txpool
+            .get_pending_txns(None)
+            .iter()
+            .any(|txn| txn.crypto_hash() == txn_hash))
+    })?;
+
+    cluster.stop()
+}
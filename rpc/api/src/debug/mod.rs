@@ -3,6 +3,8 @@
 
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
+use starcoin_crypto::HashValue;
+use starcoin_types::transaction::TransactionTrace;
 
 pub use self::gen_client::Client as DebugClient;
 
@@ -14,4 +16,11 @@ pub trait DebugApi {
     ///Trigger the node panic, only work for dev network.
     #[rpc(name = "debug.panic")]
     fn panic(&self) -> Result<()>;
+
+    ///Re-execute a committed user txn on the state before it, return its status, the abort location
+    ///derived from the vm status, the access paths read, the write set and events.
+    ///The call tree of Move functions with gas per call is not available, gas is reported for the
+    ///whole txn.
+    #[rpc(name = "debug.trace_transaction")]
+    fn trace_transaction(&self, txn_hash: HashValue) -> Result<TransactionTrace>;
 }
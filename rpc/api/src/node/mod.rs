@@ -8,8 +8,11 @@ pub use self::gen_client::Client as NodeClient;
 use crate::FutureResult;
 use serde::{Deserialize, Serialize};
 use starcoin_config::ChainNetwork;
-use starcoin_types::peer_info::PeerInfo;
+use starcoin_txpool_api::TxPoolStatus;
+use starcoin_types::block::BlockNumber;
+use starcoin_types::peer_info::{PeerId, PeerInfo};
 use std::collections::HashMap;
+use std::time::Duration;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NodeInfo {
@@ -29,12 +32,83 @@ impl NodeInfo {
     }
 }
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct NodeHealth {
+    /// Node process and rpc service is alive.
+    pub live: bool,
+    /// Node is synced, has enough peers and is ready to serve requests.
+    pub ready: bool,
+    /// None if the sync status is unknown.
+    pub sync_done: Option<bool>,
+    pub peers: Option<usize>,
+    pub head_number: Option<BlockNumber>,
+    /// Seconds since the head block timestamp.
+    pub head_age: Option<u64>,
+    pub txpool: Option<TxPoolStatus>,
+    /// Error when read from storage.
+    pub storage_error: Option<String>,
+    /// Why the node is not ready.
+    pub not_ready_reasons: Vec<String>,
+}
+
+/// P2p network state for diagnostic, addresses are in the multiaddr format.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkState {
+    pub peer_id: String,
+    pub listened_addresses: Vec<String>,
+    pub external_addresses: Vec<String>,
+    pub connected_peers: HashMap<String, NetworkPeer>,
+    pub not_connected_peers: HashMap<String, NotConnectedPeer>,
+    /// Bytes per second of all protocols, averaged over the past few seconds.
+    pub average_download_per_sec: u64,
+    pub average_upload_per_sec: u64,
+    pub peerset: serde_json::Value,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkPeer {
+    pub endpoint: PeerEndpoint,
+    pub version_string: Option<String>,
+    pub latest_ping_time: Option<Duration>,
+    pub enabled: bool,
+    pub open: bool,
+    pub known_addresses: Vec<String>,
+    /// Notification bytes sent since the notification substream opened, rpc is not counted.
+    pub bytes_sent: u64,
+    /// Notification bytes received since the notification substream opened, rpc is not counted.
+    pub bytes_received: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotConnectedPeer {
+    pub known_addresses: Vec<String>,
+    pub version_string: Option<String>,
+    pub latest_ping_time: Option<Duration>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PeerEndpoint {
+    Dialing(String),
+    Listening {
+        local_addr: String,
+        send_back_addr: String,
+    },
+}
+
 #[rpc]
 pub trait NodeApi {
     /// Get node run status, just for api available check.
     #[rpc(name = "node.status")]
     fn status(&self) -> Result<bool>;
 
+    /// Get node health, include liveness, readiness and the details.
+    #[rpc(name = "node.health")]
+    fn health(&self) -> FutureResult<NodeHealth>;
+
     /// Get node self info.
     #[rpc(name = "node.info")]
     fn info(&self) -> FutureResult<NodeInfo>;
@@ -45,4 +119,20 @@ pub trait NodeApi {
 
     #[rpc(name = "node.metrics")]
     fn metrics(&self) -> Result<HashMap<String, String>>;
+
+    /// Get p2p network state, include connected peers, known addresses, bandwidth and peerset.
+    #[rpc(name = "node.network_state")]
+    fn network_state(&self) -> FutureResult<NetworkState>;
+
+    /// Add a reserved peer, the address must end with p2p/peer_id.
+    #[rpc(name = "node.add_reserved_peer")]
+    fn add_reserved_peer(&self, peer: String) -> Result<()>;
+
+    /// Remove a reserved peer by peer id.
+    #[rpc(name = "node.remove_reserved_peer")]
+    fn remove_reserved_peer(&self, peer_id: PeerId) -> Result<()>;
+
+    /// Only connect to reserved peers if true, else accept other peers too.
+    #[rpc(name = "node.set_reserved_only")]
+    fn set_reserved_only(&self, reserved_only: bool) -> Result<()>;
 }
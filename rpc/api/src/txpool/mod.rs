@@ -6,6 +6,7 @@ use jsonrpc_derive::rpc;
 use starcoin_types::transaction::SignedUserTransaction;
 
 pub use self::gen_client::Client as TxPoolClient;
+pub use starcoin_txpool_api::GasPriceSuggestion;
 use starcoin_types::account_address::AccountAddress;
 
 #[rpc]
@@ -17,4 +18,8 @@ pub trait TxPoolApi {
     /// or `None` if there are no pending transactions from that sender in txpool.
     #[rpc(name = "txpool.next_sequence_number")]
     fn next_sequence_number(&self, address: AccountAddress) -> FutureResult<Option<u64>>;
+
+    /// Suggest gas price by the txns of recent blocks and the pending txns in txpool.
+    #[rpc(name = "txpool.gas_price_suggestion")]
+    fn gas_price_suggestion(&self) -> FutureResult<GasPriceSuggestion>;
 }
@@ -42,4 +42,13 @@ pub trait WalletApi {
     /// Return the private key as bytes for `address`
     #[rpc(name = "wallet.export")]
     fn export(&self, address: AccountAddress, password: String) -> FutureResult<Vec<u8>>;
+
+    /// Re-encrypt the private key of `address` by `new_password`.
+    #[rpc(name = "wallet.change_password")]
+    fn change_password(
+        &self,
+        address: AccountAddress,
+        old_password: String,
+        new_password: String,
+    ) -> FutureResult<()>;
 }
@@ -18,7 +18,9 @@ use starcoin_state_api::StateWithProof;
 use starcoin_types::access_path::AccessPath;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_state::AccountState;
-use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction, TransactionInfo};
+use starcoin_types::transaction::{
+    RawUserTransaction, SignedUserTransaction, TransactionInfo, TransactionTrace,
+};
 use starcoin_wallet_api::WalletAccount;
 use std::cell::RefCell;
 use std::ops::Deref;
@@ -36,12 +38,13 @@ mod remote_state_reader;
 use crate::chain_watcher::{ChainWatcher, WatchBlock, WatchTxn};
 use crate::pubsub_client::PubSubClient;
 pub use crate::remote_state_reader::RemoteStateReader;
-use starcoin_rpc_api::node::NodeInfo;
+use starcoin_rpc_api::node::{NetworkState, NodeHealth, NodeInfo};
+use starcoin_rpc_api::txpool::GasPriceSuggestion;
 use starcoin_rpc_api::types::event::Event;
 use starcoin_rpc_api::types::pubsub::EventFilter;
 use starcoin_rpc_api::types::pubsub::ThinBlock;
 use starcoin_types::block::{Block, BlockNumber};
-use starcoin_types::peer_info::PeerInfo;
+use starcoin_types::peer_info::{PeerId, PeerInfo};
 use starcoin_types::startup_info::ChainInfo;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -186,6 +189,11 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn node_health(&self) -> anyhow::Result<NodeHealth> {
+        self.call_rpc_blocking(|inner| async move { inner.node_client.health().compat().await })
+            .map_err(map_err)
+    }
+
     pub fn node_info(&self) -> anyhow::Result<NodeInfo> {
         self.call_rpc_blocking(|inner| async move { inner.node_client.info().compat().await })
             .map_err(map_err)
@@ -200,6 +208,49 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn node_network_state(&self) -> anyhow::Result<NetworkState> {
+        self.call_rpc_blocking(
+            |inner| async move { inner.node_client.network_state().compat().await },
+        )
+        .map_err(map_err)
+    }
+
+    pub fn node_add_reserved_peer(&self, peer: String) -> anyhow::Result<()> {
+        self.call_rpc_blocking(|inner| async move {
+            inner.node_client.add_reserved_peer(peer).compat().await
+        })
+        .map_err(map_err)
+    }
+
+    pub fn node_remove_reserved_peer(&self, peer_id: PeerId) -> anyhow::Result<()> {
+        self.call_rpc_blocking(|inner| async move {
+            inner
+                .node_client
+                .remove_reserved_peer(peer_id)
+                .compat()
+                .await
+        })
+        .map_err(map_err)
+    }
+
+    pub fn node_set_reserved_only(&self, reserved_only: bool) -> anyhow::Result<()> {
+        self.call_rpc_blocking(|inner| async move {
+            inner
+                .node_client
+                .set_reserved_only(reserved_only)
+                .compat()
+                .await
+        })
+        .map_err(map_err)
+    }
+
+    pub fn gas_price_suggestion(&self) -> anyhow::Result<GasPriceSuggestion> {
+        self.call_rpc_blocking(|inner| async move {
+            inner.txpool_client.gas_price_suggestion().compat().await
+        })
+        .map_err(map_err)
+    }
+
     pub fn next_sequence_number_in_txpool(
         &self,
         address: AccountAddress,
@@ -297,6 +348,22 @@ impl RpcClient {
         .map_err(map_err)
     }
 
+    pub fn wallet_change_password(
+        &self,
+        address: AccountAddress,
+        old_password: String,
+        new_password: String,
+    ) -> anyhow::Result<()> {
+        self.call_rpc_blocking(|inner| async move {
+            inner
+                .wallet_client
+                .change_password(address, old_password, new_password)
+                .compat()
+                .await
+        })
+        .map_err(map_err)
+    }
+
     pub fn state_get(&self, access_path: AccessPath) -> anyhow::Result<Option<Vec<u8>>> {
         self.call_rpc_blocking(
             |inner| async move { inner.state_client.get(access_path).compat().await },
@@ -348,6 +415,17 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn debug_trace_transaction(&self, txn_hash: HashValue) -> anyhow::Result<TransactionTrace> {
+        self.call_rpc_blocking(|inner| async move {
+            inner
+                .debug_client
+                .trace_transaction(txn_hash)
+                .compat()
+                .await
+        })
+        .map_err(map_err)
+    }
+
     pub fn chain_head(&self) -> anyhow::Result<ChainInfo> {
         self.call_rpc_blocking(|inner| async move { inner.chain_client.head().compat().await })
             .map_err(map_err)
@@ -1,6 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2
 
+use crate::health::{HealthCheck, NodeHealthChecker};
 use crate::metadata::Metadata;
 use crate::module::{
     ChainRpcImpl, DebugRpcImpl, NodeRpcImpl, PubSubImpl, PubSubService, StateRpcImpl,
@@ -20,6 +21,8 @@ use starcoin_rpc_api::wallet::WalletApi;
 use starcoin_rpc_api::{node::NodeApi, pubsub::StarcoinPubSub, state::StateApi, txpool::TxPoolApi};
 use starcoin_rpc_middleware::MetricMiddleware;
 use starcoin_state_api::ChainStateAsyncService;
+use starcoin_storage::Store;
+use starcoin_sync_api::SyncMetadata;
 use starcoin_traits::ChainAsyncService;
 use starcoin_txpool_api::TxPoolSyncService;
 use starcoin_wallet_api::WalletAsyncService;
@@ -28,6 +31,7 @@ use std::sync::Arc;
 pub struct RpcActor {
     config: Arc<NodeConfig>,
     io_handler: MetaIoHandler<Metadata, MetricMiddleware>,
+    health_checker: Option<Arc<dyn HealthCheck>>,
     server: Option<RpcService>,
 }
 
@@ -41,7 +45,9 @@ impl RpcActor {
         pubsub_service: Option<PubSubService>,
         //TODO after network async service provide trait, remove Option.
         network_service: Option<NetworkAsyncService>,
+        sync_metadata: Option<SyncMetadata>,
         logger_handle: Option<Arc<LoggerHandle>>,
+        storage: Arc<dyn Store>,
     ) -> Result<(Addr<RpcActor>, MetaIoHandler<Metadata, MetricMiddleware>)>
     where
         CS: ChainAsyncService + 'static,
@@ -50,17 +56,29 @@ impl RpcActor {
         SS: ChainStateAsyncService + 'static,
     {
         let config_clone = config.clone();
+        let health_checker: Arc<dyn HealthCheck> = Arc::new(NodeHealthChecker::new(
+            config.clone(),
+            chain_service.clone(),
+            txpool_service.clone(),
+            network_service.clone(),
+            sync_metadata,
+        ));
         let io_handler = Self::extend_apis(
-            NodeRpcImpl::new(config.clone(), network_service),
+            NodeRpcImpl::new(
+                config.clone(),
+                network_service,
+                Some(health_checker.clone()),
+            ),
             Some(ChainRpcImpl::new(chain_service)),
             Some(TxPoolRpcImpl::new(txpool_service)),
             Some(WalletRpcImpl::new(account_service)),
             Some(StateRpcImpl::new(state_service)),
             pubsub_service.map(PubSubImpl::new),
-            logger_handle.map(|logger_handle| DebugRpcImpl::new(config_clone, logger_handle)),
+            logger_handle
+                .map(|logger_handle| DebugRpcImpl::new(config_clone, logger_handle, storage)),
         )?;
 
-        Self::launch_with_handler(config, io_handler)
+        Self::launch_with_health_checker(config, io_handler, Some(health_checker))
     }
 
     pub fn extend_apis<C, N, T, A, S, D, P>(
@@ -109,11 +127,20 @@ impl RpcActor {
     pub fn launch_with_handler(
         config: Arc<NodeConfig>,
         io_handler: MetaIoHandler<Metadata, MetricMiddleware>,
+    ) -> Result<(Addr<Self>, MetaIoHandler<Metadata, MetricMiddleware>)> {
+        Self::launch_with_health_checker(config, io_handler, None)
+    }
+
+    fn launch_with_health_checker(
+        config: Arc<NodeConfig>,
+        io_handler: MetaIoHandler<Metadata, MetricMiddleware>,
+        health_checker: Option<Arc<dyn HealthCheck>>,
     ) -> Result<(Addr<Self>, MetaIoHandler<Metadata, MetricMiddleware>)> {
         let actor = RpcActor {
             config,
             server: None,
             io_handler: io_handler.clone(),
+            health_checker,
         };
         Ok((actor.start(), io_handler))
     }
@@ -132,7 +159,11 @@ impl RpcActor {
     }
 
     fn do_start(&mut self) {
-        let server = RpcService::new(self.config.clone(), self.io_handler.clone());
+        let server = RpcService::new(
+            self.config.clone(),
+            self.io_handler.clone(),
+            self.health_checker.clone(),
+        );
         self.server = Some(server);
     }
 
@@ -170,6 +201,9 @@ mod tests {
     use super::*;
     use starcoin_chain::mock::mock_chain_service::MockChainService;
     use starcoin_state_api::mock::MockChainStateService;
+    use starcoin_storage::cache_storage::CacheStorage;
+    use starcoin_storage::storage::StorageInstance;
+    use starcoin_storage::Storage;
     use starcoin_txpool_mock_service::MockTxPoolService;
     use starcoin_wallet_api::mock::MockWalletService;
 
@@ -181,6 +215,9 @@ mod tests {
         let account_service = MockWalletService::new().unwrap();
         let state_service = MockChainStateService::new();
         let chain_service = MockChainService::default();
+        let storage = Arc::new(
+            Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap(),
+        );
         let _rpc_actor = RpcActor::launch(
             config,
             txpool,
@@ -189,7 +226,9 @@ mod tests {
             state_service,
             None,
             None,
+            None,
             Some(logger_handle),
+            storage,
         )
         .unwrap();
     }
@@ -0,0 +1,380 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::future::BoxFuture;
+use futures::{FutureExt, TryFutureExt};
+use jsonrpc_http_server::{hyper, RequestMiddleware, RequestMiddlewareAction, Response};
+use network_api::NetworkService;
+use starcoin_config::NodeConfig;
+use starcoin_rpc_api::node::NodeHealth;
+use starcoin_sync_api::SyncMetadata;
+use starcoin_traits::ChainAsyncService;
+use starcoin_txpool_api::TxPoolSyncService;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const LIVE_PATH: &str = "/health/live";
+pub const READY_PATH: &str = "/health/ready";
+
+pub trait HealthCheck: Send + Sync + 'static {
+    fn check(&self) -> BoxFuture<'static, NodeHealth>;
+}
+
+/// Check node health by sync status, peers, head block, txpool and storage.
+#[derive(Clone)]
+pub struct NodeHealthChecker<CS, TS, NS>
+where
+    CS: ChainAsyncService + 'static,
+    TS: TxPoolSyncService + 'static,
+    NS: NetworkService + 'static,
+{
+    config: Arc<NodeConfig>,
+    chain_service: CS,
+    txpool_service: TS,
+    network_service: Option<NS>,
+    sync_metadata: Option<SyncMetadata>,
+}
+
+impl<CS, TS, NS> NodeHealthChecker<CS, TS, NS>
+where
+    CS: ChainAsyncService + 'static,
+    TS: TxPoolSyncService + 'static,
+    NS: NetworkService + 'static,
+{
+    pub fn new(
+        config: Arc<NodeConfig>,
+        chain_service: CS,
+        txpool_service: TS,
+        network_service: Option<NS>,
+        sync_metadata: Option<SyncMetadata>,
+    ) -> Self {
+        Self {
+            config,
+            chain_service,
+            txpool_service,
+            network_service,
+            sync_metadata,
+        }
+    }
+
+    async fn do_check(self) -> NodeHealth {
+        let health_config = &self.config.rpc.health;
+        let mut health = NodeHealth {
+            live: true,
+            ..Default::default()
+        };
+        let mut reasons = vec![];
+
+        if let Some(sync_metadata) = self.sync_metadata.as_ref() {
+            let sync_done = sync_metadata.is_sync_done();
+            health.sync_done = Some(sync_done);
+            if !sync_done {
+                reasons.push("sync is not done".to_string());
+            }
+        }
+
+        if let Some(network_service) = self.network_service.as_ref() {
+            match network_service.get_peer_set_size().await {
+                Ok(peers) => {
+                    health.peers = Some(peers);
+                    if peers < health_config.ready_min_peers {
+                        reasons.push(format!(
+                            "connected peers {} is less than {}",
+                            peers, health_config.ready_min_peers
+                        ));
+                    }
+                }
+                Err(e) => reasons.push(format!("get peers error: {}", e)),
+            }
+        }
+
+        match self.chain_service.clone().master_head_header().await {
+            Ok(Some(header)) => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .expect("system time should after unix epoch.")
+                    .as_secs();
+                let head_age = now.saturating_sub(header.timestamp());
+                health.head_number = Some(header.number());
+                health.head_age = Some(head_age);
+                if health_config.ready_max_head_age > 0
+                    && head_age > health_config.ready_max_head_age
+                {
+                    reasons.push(format!(
+                        "head block is {} seconds old, greater than {}",
+                        head_age, health_config.ready_max_head_age
+                    ));
+                }
+                // read the head block info to make sure the storage is readable.
+                match self
+                    .chain_service
+                    .clone()
+                    .get_block_info_by_hash(&header.id())
+                    .await
+                {
+                    Ok(Some(_)) => {}
+                    Ok(None) => {
+                        health.storage_error =
+                            Some(format!("block info of head {:?} is missing", header.id()))
+                    }
+                    Err(e) => health.storage_error = Some(e.to_string()),
+                }
+            }
+            Ok(None) => reasons.push("head block is not found".to_string()),
+            Err(e) => health.storage_error = Some(e.to_string()),
+        }
+        if let Some(storage_error) = health.storage_error.as_ref() {
+            reasons.push(format!("storage error: {}", storage_error));
+        }
+
+        let txpool_status = self.txpool_service.status();
+        let usage = std::cmp::max(
+            usage_percent(txpool_status.txn_count, txpool_status.max_txn_count),
+            usage_percent(txpool_status.mem, txpool_status.max_mem),
+        );
+        if txpool_status.is_full || usage > health_config.ready_max_txpool_usage as usize {
+            reasons.push(format!("txpool usage is {}%", usage));
+        }
+        health.txpool = Some(txpool_status);
+
+        health.ready = reasons.is_empty();
+        health.not_ready_reasons = reasons;
+        health
+    }
+}
+
+fn usage_percent(used: usize, max: usize) -> usize {
+    if max == 0 {
+        0
+    } else {
+        used.saturating_mul(100) / max
+    }
+}
+
+impl<CS, TS, NS> HealthCheck for NodeHealthChecker<CS, TS, NS>
+where
+    CS: ChainAsyncService + 'static,
+    TS: TxPoolSyncService + 'static,
+    NS: NetworkService + 'static,
+{
+    fn check(&self) -> BoxFuture<'static, NodeHealth> {
+        self.clone().do_check().boxed()
+    }
+}
+
+/// Http middleware to serve `/health/live` and `/health/ready`,
+/// ready returns 503 when the node is not ready, so it can be used as a kubernetes probe.
+pub struct HealthMiddleware {
+    checker: Option<Arc<dyn HealthCheck>>,
+}
+
+impl HealthMiddleware {
+    pub fn new(checker: Option<Arc<dyn HealthCheck>>) -> Self {
+        Self { checker }
+    }
+}
+
+impl RequestMiddleware for HealthMiddleware {
+    fn on_request(&self, request: hyper::Request<hyper::Body>) -> RequestMiddlewareAction {
+        match (request.uri().path(), self.checker.as_ref()) {
+            (LIVE_PATH, _) => Response::ok(r#"{"live":true}"#).into(),
+            (READY_PATH, Some(checker)) => {
+                let fut = checker.check().map(|health| {
+                    let body = serde_json::to_string(&health)
+                        .expect("serialize node health should success.");
+                    let response = if health.ready {
+                        Response::ok(body)
+                    } else {
+                        Response::service_unavailable(body)
+                    };
+                    Ok::<_, hyper::Error>(response.into())
+                });
+                RequestMiddlewareAction::Respond {
+                    should_validate_hosts: true,
+                    response: Box::new(fut.boxed().compat()),
+                }
+            }
+            _ => RequestMiddlewareAction::Proceed {
+                should_continue_on_invalid_cors: false,
+                request,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::compat::Future01CompatExt;
+    use network_api::DummyNetworkService;
+    use starcoin_chain::mock::mock_chain_service::MockChainService;
+    use starcoin_config::HealthCheckConfig;
+    use starcoin_crypto::HashValue;
+    use starcoin_txpool_api::TxPoolStatus;
+    use starcoin_txpool_mock_service::MockTxPoolService;
+    use starcoin_types::account_address::AccountAddress;
+    use starcoin_types::accumulator_info::AccumulatorInfo;
+    use starcoin_types::block::{BlockHeader, BlockInfo};
+    use starcoin_types::peer_info::{PeerId, PeerInfo};
+    use starcoin_types::{U256, U512};
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    fn mock_header(timestamp: u64) -> BlockHeader {
+        BlockHeader::new(
+            HashValue::random(),
+            HashValue::random(),
+            timestamp,
+            10,
+            AccountAddress::random(),
+            HashValue::random(),
+            HashValue::random(),
+            0,
+            0,
+            U256::zero(),
+            vec![],
+        )
+    }
+
+    fn mock_block_info(header: &BlockHeader) -> BlockInfo {
+        let accumulator_info = AccumulatorInfo::new(HashValue::zero(), vec![], 0, 0);
+        BlockInfo::new_with_accumulator_info(
+            header.id(),
+            accumulator_info.clone(),
+            accumulator_info,
+            U512::zero(),
+        )
+    }
+
+    fn mock_network(peers: usize) -> DummyNetworkService {
+        DummyNetworkService::new(
+            PeerId::random(),
+            (0..peers)
+                .map(|_| PeerInfo::new_for_test(PeerId::random()))
+                .collect(),
+        )
+    }
+
+    fn mock_checker(
+        chain_service: MockChainService,
+        txpool_service: MockTxPoolService,
+        peers: usize,
+    ) -> NodeHealthChecker<MockChainService, MockTxPoolService, DummyNetworkService> {
+        let mut config = NodeConfig::random_for_test();
+        config.rpc.health = HealthCheckConfig {
+            ready_min_peers: 1,
+            ready_max_head_age: 60,
+            ready_max_txpool_usage: 90,
+        };
+        NodeHealthChecker::new(
+            Arc::new(config),
+            chain_service,
+            txpool_service,
+            Some(mock_network(peers)),
+            None,
+        )
+    }
+
+    fn healthy_chain() -> MockChainService {
+        let header = mock_header(now());
+        let block_info = mock_block_info(&header);
+        MockChainService::new_with_head(header, Some(block_info))
+    }
+
+    #[stest::test]
+    async fn test_node_ready() {
+        let health = mock_checker(healthy_chain(), MockTxPoolService::new(), 1)
+            .do_check()
+            .await;
+        assert!(health.live);
+        assert!(health.ready, "{:?}", health.not_ready_reasons);
+        assert_eq!(health.peers, Some(1));
+        assert_eq!(health.head_number, Some(10));
+        assert!(health.storage_error.is_none());
+    }
+
+    #[stest::test]
+    async fn test_not_ready_min_peers() {
+        let health = mock_checker(healthy_chain(), MockTxPoolService::new(), 0)
+            .do_check()
+            .await;
+        assert!(health.live);
+        assert!(!health.ready);
+        assert_eq!(health.peers, Some(0));
+        assert_eq!(health.not_ready_reasons.len(), 1);
+    }
+
+    #[stest::test]
+    async fn test_not_ready_head_age() {
+        let header = mock_header(now() - 120);
+        let block_info = mock_block_info(&header);
+        let chain_service = MockChainService::new_with_head(header, Some(block_info));
+        let health = mock_checker(chain_service, MockTxPoolService::new(), 1)
+            .do_check()
+            .await;
+        assert!(!health.ready);
+        assert!(health.head_age.unwrap() >= 120);
+        assert_eq!(health.not_ready_reasons.len(), 1);
+    }
+
+    #[stest::test]
+    async fn test_not_ready_txpool_usage() {
+        let txpool_service = MockTxPoolService::new();
+        txpool_service.set_status(TxPoolStatus {
+            txn_count: 95,
+            max_txn_count: 100,
+            ..Default::default()
+        });
+        let health = mock_checker(healthy_chain(), txpool_service.clone(), 1)
+            .do_check()
+            .await;
+        assert!(!health.ready);
+        assert_eq!(health.not_ready_reasons.len(), 1);
+
+        txpool_service.set_status(TxPoolStatus {
+            mem: 10,
+            max_mem: 100,
+            is_full: true,
+            ..Default::default()
+        });
+        let health = mock_checker(healthy_chain(), txpool_service, 1)
+            .do_check()
+            .await;
+        assert!(!health.ready);
+        assert_eq!(health.not_ready_reasons.len(), 1);
+    }
+
+    #[stest::test]
+    async fn test_not_ready_storage_error() {
+        let chain_service = MockChainService::new_with_head(mock_header(now()), None);
+        let checker = mock_checker(chain_service, MockTxPoolService::new(), 1);
+        let health = checker.clone().do_check().await;
+        assert!(!health.ready);
+        assert!(health.storage_error.is_some());
+
+        let middleware = HealthMiddleware::new(Some(Arc::new(checker)));
+        let request = hyper::Request::get(READY_PATH)
+            .body(hyper::Body::empty())
+            .unwrap();
+        match middleware.on_request(request) {
+            RequestMiddlewareAction::Respond { response, .. } => {
+                let response = response.compat().await.unwrap();
+                assert_eq!(response.status(), hyper::StatusCode::SERVICE_UNAVAILABLE);
+            }
+            _ => panic!("ready request should be responded by health middleware."),
+        }
+    }
+
+    #[test]
+    fn test_usage_percent() {
+        assert_eq!(usage_percent(0, 0), 0);
+        assert_eq!(usage_percent(10, 0), 0);
+        assert_eq!(usage_percent(50, 100), 50);
+        assert_eq!(usage_percent(100, 100), 100);
+    }
+}
@@ -3,6 +3,7 @@
 #![allow(dead_code)]
 mod actor;
 mod extractors;
+pub mod health;
 mod metadata;
 pub mod module;
 mod service;
@@ -1,23 +1,93 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::module::to_invalid_param_err;
+use crate::module::{map_err, to_invalid_param_err};
+use anyhow::{bail, format_err};
 use jsonrpc_core::Result;
 use starcoin_config::NodeConfig;
+use starcoin_crypto::HashValue;
+use starcoin_executor::{executor::Executor, TransactionExecutor};
 use starcoin_logger::prelude::LevelFilter;
 use starcoin_logger::LoggerHandle;
 use starcoin_rpc_api::debug::DebugApi;
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::Store;
+use starcoin_types::transaction::{Transaction, TransactionTrace};
 use std::str::FromStr;
 use std::sync::Arc;
 
+/// How many blocks of the master chain are searched for the txn to trace.
+const TRACE_SEARCH_BLOCKS: u64 = 1000;
+
 pub struct DebugRpcImpl {
     config: Arc<NodeConfig>,
     log_handle: Arc<LoggerHandle>,
+    storage: Arc<dyn Store>,
 }
 
 impl DebugRpcImpl {
-    pub fn new(config: Arc<NodeConfig>, log_handle: Arc<LoggerHandle>) -> Self {
-        Self { config, log_handle }
+    pub fn new(
+        config: Arc<NodeConfig>,
+        log_handle: Arc<LoggerHandle>,
+        storage: Arc<dyn Store>,
+    ) -> Self {
+        Self {
+            config,
+            log_handle,
+            storage,
+        }
+    }
+
+    /// Find the block includes the txn in master chain, and re-execute the txn on the state
+    /// root of the previous txn in the block.
+    fn do_trace_transaction(&self, txn_hash: HashValue) -> anyhow::Result<TransactionTrace> {
+        let txn = match self.storage.get_transaction(txn_hash)? {
+            Some(Transaction::UserTransaction(txn)) => txn,
+            Some(_) => bail!("txn {} is not a user txn", txn_hash),
+            None => bail!("txn {} not found", txn_hash),
+        };
+        let startup_info = self
+            .storage
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("startup info not found"))?;
+        let mut block_id = startup_info.master;
+        for _ in 0..TRACE_SEARCH_BLOCKS {
+            let header = self
+                .storage
+                .get_block_header_by_hash(block_id)?
+                .ok_or_else(|| format_err!("block header {} not found", block_id))?;
+            let txn_ids = self.storage.get_block_transactions(block_id)?;
+            if let Some(idx) = txn_ids.iter().position(|id| *id == txn_hash) {
+                let state_root = if idx == 0 {
+                    self.storage
+                        .get_block_header_by_hash(header.parent_hash())?
+                        .ok_or_else(|| {
+                            format_err!("block header {} not found", header.parent_hash())
+                        })?
+                        .state_root()
+                } else {
+                    self.storage
+                        .get_transaction_info(txn_ids[idx - 1])?
+                        .ok_or_else(|| format_err!("txn info {} not found", txn_ids[idx - 1]))?
+                        .state_root_hash()
+                };
+                let chain_state =
+                    ChainStateDB::new(self.storage.clone().into_super_arc(), Some(state_root));
+                let mut trace = Executor::trace_transaction(&chain_state, txn);
+                trace.block_id = Some(block_id);
+                trace.state_root = Some(state_root);
+                return Ok(trace);
+            }
+            if header.number() == 0 {
+                break;
+            }
+            block_id = header.parent_hash();
+        }
+        bail!(
+            "txn {} is not in the latest {} blocks of master chain",
+            txn_hash,
+            TRACE_SEARCH_BLOCKS
+        )
     }
 }
 
@@ -34,4 +104,19 @@ impl DebugApi for DebugRpcImpl {
         }
         panic!("DebugApi.panic")
     }
+
+    fn trace_transaction(&self, txn_hash: HashValue) -> Result<TransactionTrace> {
+        if self
+            .storage
+            .get_transaction_info(txn_hash)
+            .map_err(map_err)?
+            .is_none()
+        {
+            return Err(to_invalid_param_err(format_err!(
+                "txn {} is not committed",
+                txn_hash
+            )));
+        }
+        self.do_trace_transaction(txn_hash).map_err(map_err)
+    }
 }
@@ -1,27 +1,39 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::health::HealthCheck;
 use crate::module::map_err;
 use futures::future::TryFutureExt;
 use futures::FutureExt;
 use jsonrpc_core::Result;
 use network_api::NetworkService;
 use starcoin_config::NodeConfig;
-use starcoin_network::NetworkAsyncService;
-use starcoin_rpc_api::node::{NodeApi, NodeInfo};
+use starcoin_network::{network_state, NetworkAsyncService};
+use starcoin_rpc_api::node::{
+    NetworkPeer, NetworkState, NodeApi, NodeHealth, NodeInfo, NotConnectedPeer, PeerEndpoint,
+};
 use starcoin_rpc_api::FutureResult;
-use starcoin_types::peer_info::PeerInfo;
+use starcoin_types::peer_info::{PeerId, PeerInfo};
 use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct NodeRpcImpl {
     config: Arc<NodeConfig>,
     service: Option<NetworkAsyncService>,
+    health_checker: Option<Arc<dyn HealthCheck>>,
 }
 
 impl NodeRpcImpl {
-    pub fn new(config: Arc<NodeConfig>, service: Option<NetworkAsyncService>) -> Self {
-        Self { config, service }
+    pub fn new(
+        config: Arc<NodeConfig>,
+        service: Option<NetworkAsyncService>,
+        health_checker: Option<Arc<dyn HealthCheck>>,
+    ) -> Self {
+        Self {
+            config,
+            service,
+            health_checker,
+        }
     }
 }
 
@@ -31,6 +43,21 @@ impl NodeApi for NodeRpcImpl {
         Ok(true)
     }
 
+    fn health(&self) -> FutureResult<NodeHealth> {
+        let health_checker = self.health_checker.clone();
+        let fut = async move {
+            Ok::<_, jsonrpc_core::Error>(match health_checker {
+                Some(health_checker) => health_checker.check().await,
+                // without health checker, only liveness can be reported.
+                None => NodeHealth {
+                    live: true,
+                    ..Default::default()
+                },
+            })
+        };
+        Box::new(fut.boxed().compat())
+    }
+
     fn info(&self) -> FutureResult<NodeInfo> {
         let service = self.service.clone().unwrap();
         let self_address = self
@@ -58,4 +85,128 @@ impl NodeApi for NodeRpcImpl {
     fn metrics(&self) -> Result<HashMap<String, String>> {
         Ok(starcoin_metrics::get_all_metrics())
     }
+
+    fn network_state(&self) -> FutureResult<NetworkState> {
+        let service = self.service.clone().unwrap();
+        let fut = async move { service.network_state().await.map(to_rpc_network_state) };
+        Box::new(fut.map_err(map_err).boxed().compat())
+    }
+
+    fn add_reserved_peer(&self, peer: String) -> Result<()> {
+        let service = self.service.clone().unwrap();
+        service.add_reserved_peer(peer).map_err(map_err)
+    }
+
+    fn remove_reserved_peer(&self, peer_id: PeerId) -> Result<()> {
+        let service = self.service.clone().unwrap();
+        service.remove_reserved_peer(peer_id.into());
+        Ok(())
+    }
+
+    fn set_reserved_only(&self, reserved_only: bool) -> Result<()> {
+        let service = self.service.clone().unwrap();
+        service.set_reserved_only(reserved_only);
+        Ok(())
+    }
+}
+
+fn to_strings<T: ToString>(items: impl IntoIterator<Item = T>) -> Vec<String> {
+    let mut strings: Vec<String> = items.into_iter().map(|item| item.to_string()).collect();
+    strings.sort();
+    strings
+}
+
+fn to_rpc_network_state(state: network_state::NetworkState) -> NetworkState {
+    NetworkState {
+        peer_id: state.peer_id,
+        listened_addresses: to_strings(state.listened_addresses),
+        external_addresses: to_strings(state.external_addresses),
+        connected_peers: state
+            .connected_peers
+            .into_iter()
+            .map(|(peer_id, peer)| {
+                let endpoint = match peer.endpoint {
+                    network_state::PeerEndpoint::Dialing(address) => {
+                        PeerEndpoint::Dialing(address.to_string())
+                    }
+                    network_state::PeerEndpoint::Listening {
+                        local_addr,
+                        send_back_addr,
+                    } => PeerEndpoint::Listening {
+                        local_addr: local_addr.to_string(),
+                        send_back_addr: send_back_addr.to_string(),
+                    },
+                };
+                let peer = NetworkPeer {
+                    endpoint,
+                    version_string: peer.version_string,
+                    latest_ping_time: peer.latest_ping_time,
+                    enabled: peer.enabled,
+                    open: peer.open,
+                    known_addresses: to_strings(peer.known_addresses),
+                    bytes_sent: peer.bytes_sent,
+                    bytes_received: peer.bytes_received,
+                };
+                (peer_id, peer)
+            })
+            .collect(),
+        not_connected_peers: state
+            .not_connected_peers
+            .into_iter()
+            .map(|(peer_id, peer)| {
+                let peer = NotConnectedPeer {
+                    known_addresses: to_strings(peer.known_addresses),
+                    version_string: peer.version_string,
+                    latest_ping_time: peer.latest_ping_time,
+                };
+                (peer_id, peer)
+            })
+            .collect(),
+        average_download_per_sec: state.average_download_per_sec,
+        average_upload_per_sec: state.average_upload_per_sec,
+        peerset: state.peerset,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_network_state_serialize() {
+        let address = "/ip4/127.0.0.1/tcp/9840";
+        let peer = network_state::Peer {
+            endpoint: network_state::PeerEndpoint::Dialing(address.parse().unwrap()),
+            version_string: Some("starcoin".to_string()),
+            latest_ping_time: Some(Duration::from_millis(10)),
+            enabled: true,
+            open: true,
+            known_addresses: vec![address.parse().unwrap()].into_iter().collect(),
+            bytes_sent: 100,
+            bytes_received: 200,
+        };
+        let p2p_state = network_state::NetworkState {
+            peer_id: "self".to_string(),
+            listened_addresses: vec![address.parse().unwrap()].into_iter().collect(),
+            external_addresses: Default::default(),
+            connected_peers: vec![("peer".to_string(), peer)].into_iter().collect(),
+            not_connected_peers: Default::default(),
+            average_download_per_sec: 1,
+            average_upload_per_sec: 2,
+            peerset: serde_json::Value::Null,
+        };
+
+        let state = to_rpc_network_state(p2p_state);
+        assert_eq!(state.listened_addresses, vec![address.to_string()]);
+        let peer = state.connected_peers.get("peer").unwrap();
+        assert_eq!(peer.endpoint, PeerEndpoint::Dialing(address.to_string()));
+        assert_eq!(peer.bytes_sent, 100);
+        assert_eq!(peer.bytes_received, 200);
+
+        let json = serde_json::to_value(&state).unwrap();
+        assert_eq!(json["connectedPeers"]["peer"]["bytesReceived"], 200);
+        assert_eq!(json["listenedAddresses"][0], address);
+        assert_eq!(serde_json::from_value::<NetworkState>(json).unwrap(), state);
+    }
 }
@@ -3,7 +3,7 @@
 
 use futures::future::TryFutureExt;
 use starcoin_rpc_api::{txpool::TxPoolApi, FutureResult};
-use starcoin_txpool_api::TxPoolSyncService;
+use starcoin_txpool_api::{GasPriceSuggestion, TxPoolSyncService};
 use starcoin_types::transaction::SignedUserTransaction;
 
 /// Re-export the API
@@ -33,7 +33,7 @@ where
     fn submit_transaction(&self, txn: SignedUserTransaction) -> FutureResult<bool> {
         let result = self
             .service
-            .add_txns(vec![txn])
+            .add_local_txns(vec![txn])
             .pop()
             .expect("txpool should return result");
         let success = result.is_ok();
@@ -43,6 +43,10 @@ where
         let result = self.service.next_sequence_number(address);
         Box::new(futures::future::ok(result).compat())
     }
+    fn gas_price_suggestion(&self) -> FutureResult<GasPriceSuggestion> {
+        let result = self.service.gas_price_suggestion();
+        Box::new(futures::future::ok(result).compat())
+    }
 }
 
 #[cfg(test)]
@@ -110,6 +110,20 @@ where
             .map_err(|e| map_rpc_err(e.into()));
         Box::new(fut.compat())
     }
+
+    fn change_password(
+        &self,
+        address: AccountAddress,
+        old_password: String,
+        new_password: String,
+    ) -> FutureResult<()> {
+        let fut = self
+            .service
+            .clone()
+            .change_password(address, old_password, new_password)
+            .map_err(|e| map_rpc_err(e.into()));
+        Box::new(fut.compat())
+    }
 }
 
 #[cfg(test)]
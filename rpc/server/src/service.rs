@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::extractors::{RpcExtractor, WsExtractor};
+use crate::health::{HealthCheck, HealthMiddleware};
 use crate::metadata::Metadata;
 use jsonrpc_core::MetaIoHandler;
 use jsonrpc_server_utils::cors::AccessControlAllowOrigin;
@@ -22,6 +23,7 @@ impl RpcService {
     pub fn new(
         config: Arc<NodeConfig>,
         io_handler: MetaIoHandler<Metadata, MetricMiddleware>,
+        health_checker: Option<Arc<dyn HealthCheck>>,
     ) -> RpcService {
         let ipc = Self::start_ipc(&config, io_handler.clone());
         let http = match &config.rpc.http_address {
@@ -35,6 +37,7 @@ impl RpcService {
                     .threads(config.rpc.threads.unwrap_or_else(num_cpus::get))
                     .max_request_body_size(config.rpc.max_request_body_size)
                     .health_api(("/status", "status"))
+                    .request_middleware(HealthMiddleware::new(health_checker))
                     .start_http(address)
                     .expect("Unable to start RPC server.");
                 info!("Http rpc server start at :{}", address);
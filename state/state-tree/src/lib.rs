@@ -7,8 +7,9 @@ mod state_tree;
 #[cfg(test)]
 mod state_tree_test;
 
+pub use forkable_jellyfish_merkle::proof::SparseMerkleRangeProof;
 pub use starcoin_state_store_api::{StateNode, StateNodeStore};
-pub use state_tree::StateTree;
+pub use state_tree::{StateTree, StateTreeRestore};
 
 use starcoin_crypto::HashValue;
 
@@ -2,9 +2,10 @@ use anyhow::Result;
 use forkable_jellyfish_merkle::blob::Blob;
 use forkable_jellyfish_merkle::iterator::JellyfishMerkleIterator;
 use forkable_jellyfish_merkle::node_type::{Node, NodeKey};
-use forkable_jellyfish_merkle::proof::SparseMerkleProof;
+use forkable_jellyfish_merkle::proof::{SparseMerkleProof, SparseMerkleRangeProof};
+use forkable_jellyfish_merkle::restore::JellyfishMerkleRestore;
 use forkable_jellyfish_merkle::{
-    JellyfishMerkleTree, StaleNodeIndex, TreeReader, TreeUpdateBatch,
+    JellyfishMerkleTree, NodeBatch, StaleNodeIndex, TreeReader, TreeUpdateBatch, TreeWriter,
     SPARSE_MERKLE_PLACEHOLDER_HASH,
 };
 use starcoin_crypto::hash::*;
@@ -193,17 +194,34 @@ impl StateTree {
 
     /// commit the state change into underline storage.
     pub fn flush(&self) -> Result<()> {
+        let (root_hash, node_map) = self.unsaved_nodes();
+        self.storage.write_nodes(node_map).unwrap();
+        // and then advance the storage root hash
+        self.advance_storage_root(root_hash);
+        Ok(())
+    }
+
+    /// Like `flush`, but return the state nodes instead of writing them,
+    /// the caller must write them into underline storage.
+    pub fn pop_unsaved_nodes(&self) -> BTreeMap<HashValue, StateNode> {
+        let (root_hash, node_map) = self.unsaved_nodes();
+        self.advance_storage_root(root_hash);
+        node_map
+    }
+
+    fn unsaved_nodes(&self) -> (HashValue, BTreeMap<HashValue, StateNode>) {
         let (root_hash, change_sets) = self.get_change_sets();
 
         let mut node_map = BTreeMap::new();
         for (nk, n) in change_sets.node_batch.into_iter() {
             node_map.insert(nk, StateNode(n));
         }
-        self.storage.write_nodes(node_map).unwrap();
-        // and then advance the storage root hash
+        (root_hash, node_map)
+    }
+
+    fn advance_storage_root(&self, root_hash: HashValue) {
         *self.storage_root_hash.write().unwrap() = root_hash;
         self.cache.lock().unwrap().reset(root_hash);
-        Ok(())
     }
 
     /// Dump tree to state set.
@@ -225,6 +243,48 @@ impl StateTree {
         Ok(StateSet::new(states))
     }
 
+    /// Read at most `limit` kv pairs in key order, starting after `last_key`
+    /// (from the smallest key if `last_key` is None), with the range proof of the last pair.
+    /// The proof is None if no pair is returned.
+    /// NOTICE: this will only read from state tree.
+    /// Any un-committed modification will not visible to the method.
+    pub fn get_range_with_proof(
+        &self,
+        last_key: Option<HashValue>,
+        limit: usize,
+    ) -> Result<(Vec<(HashValue, Vec<u8>)>, Option<SparseMerkleRangeProof>)> {
+        let mut cache_guard = self.cache.lock().unwrap();
+        let cache = cache_guard.deref_mut();
+        let cur_root_hash = cache.root_hash;
+        let reader = Arc::new(CachedTreeReader {
+            store: self.storage.as_ref(),
+            cache,
+        });
+        let iterator = JellyfishMerkleIterator::new(
+            reader.clone(),
+            cur_root_hash,
+            last_key.unwrap_or_else(HashValue::zero),
+        )?;
+        let mut states = vec![];
+        for item in iterator {
+            let (key, blob) = item?;
+            if Some(key) == last_key {
+                continue;
+            }
+            if states.len() >= limit {
+                break;
+            }
+            states.push((key, blob.into()));
+        }
+        let proof = match states.last() {
+            Some((key, _)) => Some(
+                JellyfishMerkleTree::new(reader.as_ref()).get_range_proof(cur_root_hash, *key)?,
+            ),
+            None => None,
+        };
+        Ok((states, proof))
+    }
+
     /// passing None value with a key means delete the key
     fn updates(&self, updates: Vec<(HashValue, Option<Blob>)>) -> Result<HashValue> {
         let cur_root_hash = self.root_hash();
@@ -316,3 +376,57 @@ impl<'a> TreeReader for CachedTreeReader<'a> {
         }
     }
 }
+
+struct StateNodeStoreWriter(Arc<dyn StateNodeStore>);
+
+impl TreeWriter for StateNodeStoreWriter {
+    fn write_node_batch(&self, node_batch: &NodeBatch) -> Result<()> {
+        let nodes = node_batch
+            .iter()
+            .map(|(nk, n)| (*nk, StateNode(n.clone())))
+            .collect();
+        self.0.write_nodes(nodes)
+    }
+}
+
+/// Restore a state tree into `StateNodeStore` from kv chunks in key order,
+/// every chunk is verified against the expected root hash with its range proof.
+pub struct StateTreeRestore {
+    restore: JellyfishMerkleRestore<StateNodeStoreWriter>,
+}
+
+impl StateTreeRestore {
+    pub fn new(state_storage: Arc<dyn StateNodeStore>, expected_root_hash: HashValue) -> Self {
+        Self {
+            restore: JellyfishMerkleRestore::new(
+                Arc::new(StateNodeStoreWriter(state_storage)),
+                expected_root_hash,
+            ),
+        }
+    }
+
+    /// The last restored key, the next chunk should start after it.
+    pub fn last_key(&self) -> Option<HashValue> {
+        self.restore.previous_key()
+    }
+
+    pub fn num_keys(&self) -> u64 {
+        self.restore.num_keys_received()
+    }
+
+    /// Verify and write a chunk into storage.
+    /// If this return a error, the restore can not continue, and should restart with a new `StateTreeRestore`.
+    pub fn add_chunk(
+        &mut self,
+        chunk: Vec<(HashValue, Vec<u8>)>,
+        proof: SparseMerkleRangeProof,
+    ) -> Result<()> {
+        let chunk = chunk.into_iter().map(|(k, v)| (k, v.into())).collect();
+        self.restore.add_chunk(chunk, proof)
+    }
+
+    /// Write the rest nodes after all chunks are added, return the restored root hash.
+    pub fn finish(self) -> Result<HashValue> {
+        self.restore.finish()
+    }
+}
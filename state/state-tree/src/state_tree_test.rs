@@ -171,3 +171,33 @@ pub fn test_repeat_commit() -> Result<()> {
     assert_eq!(root_hash1, root_hash2);
     Ok(())
 }
+
+#[test]
+pub fn test_state_restore_by_chunks() -> Result<()> {
+    let s = MockStateNodeStore::new();
+    let state = StateTree::new(Arc::new(s), None);
+    for i in 0..100u8 {
+        state.put(HashValue::random(), vec![i]);
+    }
+    state.commit()?;
+    state.flush()?;
+    let root_hash = state.root_hash();
+
+    let restore_store = Arc::new(MockStateNodeStore::new());
+    let mut restore = StateTreeRestore::new(restore_store.clone(), root_hash);
+    loop {
+        let (chunk, proof) = state.get_range_with_proof(restore.last_key(), 30)?;
+        if chunk.is_empty() {
+            assert!(proof.is_none());
+            break;
+        }
+        assert!(chunk.len() <= 30);
+        restore.add_chunk(chunk, proof.unwrap())?;
+    }
+    assert_eq!(restore.num_keys(), 100);
+    assert_eq!(restore.finish()?, root_hash);
+
+    let restored = StateTree::new(restore_store, Some(root_hash));
+    assert_eq!(restored.dump()?, state.dump()?);
+    Ok(())
+}
@@ -10,7 +10,7 @@ use scs::SCSCodec;
 use starcoin_crypto::{hash::PlainCryptoHash, HashValue};
 use starcoin_logger::prelude::*;
 use starcoin_state_tree::mock::MockStateNodeStore;
-use starcoin_state_tree::{StateNodeStore, StateTree};
+use starcoin_state_tree::{StateNode, StateNodeStore, StateTree};
 use starcoin_types::{
     access_path::{self, AccessPath, DataType},
     account_address::AccountAddress,
@@ -18,6 +18,7 @@ use starcoin_types::{
     state_set::{AccountStateSet, ChainStateSet},
 };
 use starcoin_vm_types::state_view::StateView;
+use std::collections::BTreeMap;
 use std::convert::TryInto;
 use std::sync::Arc;
 use thiserror::Error;
@@ -54,6 +55,11 @@ impl CacheItem {
             CacheItem::AccountNotExist() => Ok(()),
         }
     }
+    fn pop_unsaved_nodes(&self, nodes: &mut BTreeMap<HashValue, StateNode>) {
+        if let CacheItem::AccountObject(obj) = self {
+            obj.pop_unsaved_nodes(nodes)
+        }
+    }
     fn is_dirty(&self) -> bool {
         match self {
             CacheItem::AccountObject(obj) => obj.is_dirty(),
@@ -194,6 +200,15 @@ impl AccountStateObject {
         Ok(())
     }
 
+    pub fn pop_unsaved_nodes(&self, nodes: &mut BTreeMap<HashValue, StateNode>) {
+        let trees = self.trees.lock();
+        for tree in trees.iter() {
+            if let Some(tree) = tree {
+                nodes.append(&mut tree.pop_unsaved_nodes());
+            }
+        }
+    }
+
     fn build_state(trees: MutexGuard<Vec<Option<StateTree>>>) -> AccountState {
         let storage_roots = trees
             .iter()
@@ -243,6 +258,17 @@ impl ChainStateDB {
         }
     }
 
+    /// Like `flush`, but return the state nodes instead of writing them, so the caller
+    /// can write them together with other data in one batch.
+    pub fn pop_unsaved_nodes(&self) -> BTreeMap<HashValue, StateNode> {
+        let mut nodes = BTreeMap::new();
+        for (_address_hash, state_object) in self.cache.lock().iter() {
+            state_object.pop_unsaved_nodes(&mut nodes);
+        }
+        nodes.append(&mut self.state_tree.pop_unsaved_nodes());
+        nodes
+    }
+
     fn new_state_tree(&self, root_hash: HashValue) -> StateTree {
         StateTree::new(self.store.clone(), Some(root_hash))
     }
@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::storage::{KeyCodec, ValueCodec, WriteOp};
+use crate::storage::{ColumnFamilyName, KeyCodec, ValueCodec, WriteOp};
 use anyhow::Result;
 use std::collections::BTreeMap;
 
@@ -37,3 +37,35 @@ impl WriteBatch {
         Ok(())
     }
 }
+
+/// A write batch spans several column families, all the operations in it are committed atomically.
+#[derive(Debug, Default, Clone)]
+pub struct CFWriteBatch {
+    pub batches: BTreeMap<ColumnFamilyName, WriteBatch>,
+}
+
+impl CFWriteBatch {
+    /// Creates an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an insert/update operation of the column family to the batch.
+    pub fn put<K: KeyCodec, V: ValueCodec>(
+        &mut self,
+        prefix_name: ColumnFamilyName,
+        key: K,
+        value: V,
+    ) -> Result<()> {
+        self.batches.entry(prefix_name).or_default().put(key, value)
+    }
+
+    /// Adds a delete operation of the column family to the batch.
+    pub fn delete<K: KeyCodec>(&mut self, prefix_name: ColumnFamilyName, key: K) -> Result<()> {
+        self.batches.entry(prefix_name).or_default().delete(key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.batches.values().all(|batch| batch.rows.is_empty())
+    }
+}
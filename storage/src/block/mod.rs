@@ -1,6 +1,6 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
-use crate::batch::WriteBatch;
+use crate::batch::{CFWriteBatch, WriteBatch};
 use crate::define_storage;
 use crate::storage::{CodecStorage, KeyCodec, StorageInstance, ValueCodec};
 use crate::{
@@ -259,16 +259,56 @@ impl BlockStorage {
     }
 
     pub fn commit_block(&self, block: Block, state: BlockState) -> Result<()> {
+        let mut batch = CFWriteBatch::new();
+        self.commit_block_to_batch(block, state, &mut batch)?;
+        self.block_store.write_cf_batch(batch)
+    }
+
+    /// Add header, sons relationship, number, body and block to the `batch`,
+    /// the caller should commit the batch.
+    pub fn commit_block_to_batch(
+        &self,
+        block: Block,
+        state: BlockState,
+        batch: &mut CFWriteBatch,
+    ) -> Result<()> {
         let (header, body) = block.clone().into_inner();
-        //save header
         let block_id = header.id();
-        self.save_header(header.clone()).unwrap();
+        debug!(
+            "commit block:{:?}, parent:{:?}",
+            block_id,
+            header.parent_hash()
+        );
+        //save sons relationship
+        let sons = match self.sons_store.read().unwrap().get(header.parent_hash())? {
+            Some(mut sons) => {
+                if !sons.contains(&block_id) {
+                    sons.push(block_id);
+                }
+                sons
+            }
+            None => vec![block_id],
+        };
+        batch.put(BLOCK_SONS_PREFIX_NAME, header.parent_hash(), sons)?;
         //save number
-        self.save_number(header.number(), block_id).unwrap();
+        batch.put(BLOCK_NUM_PREFIX_NAME, header.number(), block_id)?;
         //save body
-        self.save_body(block_id, body).unwrap();
-        //save block cache
-        self.save(block, state)
+        batch.put(BLOCK_BODY_PREFIX_NAME, block_id, body)?;
+        //save header
+        batch.put(BLOCK_HEADER_PREFIX_NAME, block_id, header)?;
+        //save block
+        batch.put(BLOCK_PREFIX_NAME, block_id, StorageBlock::new(block, state))?;
+        Ok(())
+    }
+
+    /// Add block's transaction hashes to the `batch`.
+    pub fn put_transactions_to_batch(
+        &self,
+        block_id: HashValue,
+        transactions: Vec<HashValue>,
+        batch: &mut CFWriteBatch,
+    ) -> Result<()> {
+        batch.put(BLOCK_TRANSATIONS_PREFIX_NAME, block_id, transactions)
     }
 
     pub fn commit_branch_block(
@@ -1,9 +1,9 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::batch::WriteBatch;
+use crate::batch::{CFWriteBatch, WriteBatch};
 use crate::metrics::{record_metrics, CACHE_ITEMS};
-use crate::storage::{InnerStore, WriteOp};
+use crate::storage::{InnerStore, KVIterator, WriteOp};
 use anyhow::{Error, Result};
 use lru::LruCache;
 use parking_lot::Mutex;
@@ -27,6 +27,23 @@ impl CacheStorage {
     }
 }
 
+impl CacheStorage {
+    /// Entries of the column family sorted by key, the cache is not ordered, so iteration
+    /// copies and sorts the whole column family.
+    fn sorted_entries(&self, prefix_name: &str) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let cf_prefix = cf_key_prefix(prefix_name);
+        let mut entries: Vec<_> = self
+            .cache
+            .lock()
+            .iter()
+            .filter(|(key, _)| key.starts_with(cf_prefix.as_slice()))
+            .map(|(key, value)| (key[cf_prefix.len()..].to_vec(), value.to_vec()))
+            .collect();
+        entries.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+        entries
+    }
+}
+
 impl Default for CacheStorage {
     fn default() -> Self {
         Self::new()
@@ -78,6 +95,13 @@ impl InnerStore for CacheStorage {
         })
     }
 
+    fn write_cf_batch(&self, batch: CFWriteBatch) -> Result<()> {
+        for (prefix_name, batch) in batch.batches {
+            self.write_batch(prefix_name, batch)?;
+        }
+        Ok(())
+    }
+
     fn get_len(&self) -> Result<u64, Error> {
         Ok(self.cache.lock().len() as u64)
     }
@@ -89,10 +113,43 @@ impl InnerStore for CacheStorage {
         }
         Ok(all_keys)
     }
+
+    fn iter_prefix(&self, prefix_name: &str, prefix: Vec<u8>) -> Result<KVIterator> {
+        let entries = self.sorted_entries(prefix_name);
+        Ok(Box::new(entries.into_iter().filter(move |(key, _)| {
+            key.starts_with(prefix.as_slice())
+        })))
+    }
+
+    fn iter_range(&self, prefix_name: &str, from: Vec<u8>, to: Vec<u8>) -> Result<KVIterator> {
+        let entries = self.sorted_entries(prefix_name);
+        Ok(Box::new(entries.into_iter().filter(move |(key, _)| {
+            key.as_slice() >= from.as_slice() && key.as_slice() < to.as_slice()
+        })))
+    }
+
+    fn iter_reverse(&self, prefix_name: &str, from: Option<Vec<u8>>) -> Result<KVIterator> {
+        let entries = self.sorted_entries(prefix_name);
+        Ok(Box::new(entries.into_iter().rev().filter(
+            move |(key, _)| match from.as_ref() {
+                Some(from) => key.as_slice() <= from.as_slice(),
+                None => true,
+            },
+        )))
+    }
+}
+
+/// Column family names may be prefix of each other, such as `block` and `block_header`,
+/// so a separator is appended to the name.
+fn cf_key_prefix(prefix_name: &str) -> Vec<u8> {
+    let mut cf_prefix = Vec::with_capacity(prefix_name.len() + 1);
+    cf_prefix.extend_from_slice(prefix_name.as_bytes());
+    cf_prefix.push(0);
+    cf_prefix
 }
 
 fn compose_key(prefix_name: String, source_key: Vec<u8>) -> Result<Vec<u8>> {
-    let temp_vec = prefix_name.as_bytes().to_vec();
+    let temp_vec = cf_key_prefix(prefix_name.as_str());
     let mut compose = Vec::with_capacity(temp_vec.len() + source_key.len());
     compose.extend(temp_vec);
     compose.extend(source_key);
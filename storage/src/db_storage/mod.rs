@@ -1,15 +1,20 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::batch::WriteBatch;
+use crate::batch::{CFWriteBatch, WriteBatch};
 use crate::metrics::record_metrics;
-use crate::storage::{ColumnFamilyName, InnerStore, WriteOp};
-use crate::{DEFAULT_PREFIX_NAME, VEC_PREFIX_NAME};
-use anyhow::{ensure, format_err, Error, Result};
+use crate::migration::{
+    MigrationRegistry, MigrationReport, MigrationStep, MigrationStepReport, CURRENT_SCHEMA_VERSION,
+    LEGACY_SCHEMA_VERSION, SCHEMA_VERSION_KEY,
+};
+use crate::storage::{ColumnFamilyName, InnerStore, KVIterator, WriteOp};
+use crate::{DEFAULT_PREFIX_NAME, META_PREFIX_NAME, VEC_PREFIX_NAME};
+use anyhow::{bail, ensure, format_err, Error, Result};
 use logger::prelude::*;
-use rocksdb::{WriteBatch as DBWriteBatch, WriteOptions, DB};
+use rocksdb::{Direction, IteratorMode, WriteBatch as DBWriteBatch, WriteOptions, DB};
 use std::collections::HashSet;
-use std::path::Path;
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
 
 pub struct DBStorage {
     db: DB,
@@ -17,9 +22,15 @@ pub struct DBStorage {
 
 impl DBStorage {
     pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        let path = db_root_path.as_ref().join("starcoindb");
+        let path = Self::db_path(db_root_path);
         Self::open(path, false).expect("Unable to open StarcoinDB")
     }
+
+    /// The rocksdb dir under the `db_root_path`.
+    pub fn db_path<P: AsRef<Path>>(db_root_path: P) -> PathBuf {
+        db_root_path.as_ref().join("starcoindb")
+    }
+
     pub fn open(path: impl AsRef<Path>, readonly: bool) -> Result<Self> {
         let column_families = VEC_PREFIX_NAME.to_vec();
         let cfs_set: HashSet<_> = column_families.iter().collect();
@@ -29,18 +40,9 @@ impl DBStorage {
                 "Duplicate column family name found.",
             );
         }
-        if Self::db_exists(path.as_ref()) {
-            let cf_vec = Self::list_cf(path.as_ref())?;
-            for cf in cf_vec {
-                if cf != DEFAULT_PREFIX_NAME && cfs_set.get(&cf.as_str()).is_none() {
-                    error!(
-                        "db path cf: {:?} is not equal,please clear dir: {:?}",
-                        path.as_ref(),
-                        cf
-                    );
-                    std::process::exit(100);
-                }
-            }
+        let db_exists = Self::db_exists(path.as_ref());
+        if db_exists {
+            Self::check_schema(path.as_ref(), &cfs_set, readonly)?;
         }
 
         let db = if readonly {
@@ -55,7 +57,209 @@ impl DBStorage {
             Self::open_inner(&db_opts, path.as_ref(), column_families)?
         };
 
-        Ok(DBStorage { db })
+        let storage = DBStorage { db };
+        if !db_exists && !readonly {
+            storage.put_schema_version(CURRENT_SCHEMA_VERSION)?;
+        }
+        Ok(storage)
+    }
+
+    /// Check the schema version and column families of an existing db.
+    /// An older db is migrated automatically if all pending steps are not destructive,
+    /// otherwise the manual `db migrate` is required.
+    fn check_schema(
+        path: &Path,
+        cfs_set: &HashSet<&ColumnFamilyName>,
+        readonly: bool,
+    ) -> Result<()> {
+        let version = Self::schema_version(path)?;
+        ensure!(
+            version <= CURRENT_SCHEMA_VERSION,
+            "db {:?} schema version {} is newer than the supported version {}, please upgrade starcoin.",
+            path,
+            version,
+            CURRENT_SCHEMA_VERSION
+        );
+        if version < CURRENT_SCHEMA_VERSION {
+            let registry = MigrationRegistry::new();
+            let auto_migrate = !readonly
+                && registry
+                    .pending_steps(version)
+                    .iter()
+                    .all(|step| !step.is_destructive());
+            ensure!(
+                auto_migrate,
+                "db {:?} schema version {} is older than the current version {}, please stop the node and run `starcoin -c offline db migrate`.",
+                path,
+                version,
+                CURRENT_SCHEMA_VERSION
+            );
+            let report = Self::migrate(path, &registry, false)?;
+            info!(
+                "db {:?} is migrated from schema version {} to {}",
+                path, report.from_version, report.to_version
+            );
+        }
+        for cf in Self::list_cf(path)? {
+            if cf != DEFAULT_PREFIX_NAME && cfs_set.get(&cf.as_str()).is_none() {
+                bail!(
+                    "db {:?} has unknown column family: {:?}, please check it by `starcoin -c offline db migrate --dry-run`.",
+                    path,
+                    cf
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Read schema version of an existing db, db without schema version record is legacy.
+    pub fn schema_version(path: impl AsRef<Path>) -> Result<u64> {
+        let cfs = Self::list_cf(path.as_ref())?;
+        if !cfs.iter().any(|cf| cf == META_PREFIX_NAME) {
+            return Ok(LEGACY_SCHEMA_VERSION);
+        }
+        let db =
+            rocksdb::DB::open_cf_for_read_only(&rocksdb::Options::default(), path, &cfs, false)?;
+        Self::read_schema_version(&db)
+    }
+
+    fn read_schema_version(db: &DB) -> Result<u64> {
+        let cf_handle = db
+            .cf_handle(META_PREFIX_NAME)
+            .ok_or_else(|| format_err!("column family {} not found", META_PREFIX_NAME))?;
+        match db.get_cf(cf_handle, SCHEMA_VERSION_KEY)? {
+            Some(bytes) => {
+                ensure!(
+                    bytes.len() == size_of::<u64>(),
+                    "invalid schema version record: {:?}",
+                    bytes
+                );
+                let mut version = [0u8; 8];
+                version.copy_from_slice(bytes.as_slice());
+                Ok(u64::from_be_bytes(version))
+            }
+            None => Ok(LEGACY_SCHEMA_VERSION),
+        }
+    }
+
+    fn put_schema_version(&self, version: u64) -> Result<()> {
+        self.put(
+            META_PREFIX_NAME,
+            SCHEMA_VERSION_KEY.to_vec(),
+            version.to_be_bytes().to_vec(),
+        )
+    }
+
+    /// Migrate the db at `path` to the latest version of `registry`.
+    /// If `dry_run` is true, only report what would change, the db is not modified.
+    pub fn migrate(
+        path: impl AsRef<Path>,
+        registry: &MigrationRegistry,
+        dry_run: bool,
+    ) -> Result<MigrationReport> {
+        let path = path.as_ref();
+        ensure!(Self::db_exists(path), "db {:?} not exists.", path);
+        let from_version = Self::schema_version(path)?;
+        let to_version = registry.latest_version();
+        ensure!(
+            from_version <= to_version,
+            "db schema version {} is newer than the supported version {}.",
+            from_version,
+            to_version
+        );
+        let steps = registry.pending_steps(from_version);
+        let on_disk_cfs = Self::list_cf(path)?;
+        let schema_cfs = registry.column_families();
+        let unknown_cfs = on_disk_cfs
+            .iter()
+            .filter(|cf| {
+                cf.as_str() != DEFAULT_PREFIX_NAME
+                    && !schema_cfs.contains(&cf.as_str())
+                    && !steps
+                        .iter()
+                        .any(|step| step.drop_cfs.contains(&cf.as_str()))
+            })
+            .cloned()
+            .collect();
+        let mut report = MigrationReport {
+            dry_run,
+            from_version,
+            to_version,
+            steps: vec![],
+            unknown_cfs,
+        };
+        let step_report = |step: &MigrationStep| MigrationStepReport {
+            version: step.version,
+            description: step.description.to_string(),
+            add_cfs: step.add_cfs.iter().map(|cf| cf.to_string()).collect(),
+            drop_cfs: step.drop_cfs.iter().map(|cf| cf.to_string()).collect(),
+            rewritten: None,
+        };
+        if dry_run || steps.is_empty() {
+            report.steps = steps.iter().map(step_report).collect();
+            return Ok(report);
+        }
+
+        let db_opts = rocksdb::Options::default();
+        let db = rocksdb::DB::open_cf_descriptors(
+            &db_opts,
+            path,
+            on_disk_cfs
+                .iter()
+                .map(|cf_name| rocksdb::ColumnFamilyDescriptor::new(cf_name, Self::cf_options())),
+        )?;
+        let mut storage = DBStorage { db };
+        for step in steps {
+            info!(
+                "migrate db {:?} to version {}: {}",
+                path, step.version, step.description
+            );
+            for cf in &step.add_cfs {
+                if storage.db.cf_handle(cf).is_none() {
+                    storage.db.create_cf(cf, &Self::cf_options())?;
+                }
+            }
+            let rewritten = match step.rewrite {
+                Some(rewrite) => Some(rewrite(&storage)?),
+                None => None,
+            };
+            for cf in &step.drop_cfs {
+                if storage.db.cf_handle(cf).is_some() {
+                    storage.db.drop_cf(cf)?;
+                }
+            }
+            storage.put_schema_version(step.version)?;
+            let mut step_report = step_report(&step);
+            step_report.rewritten = rewritten;
+            report.steps.push(step_report);
+        }
+        Ok(report)
+    }
+
+    /// Rewrite every value of the column family by `f`, `f` return None means keep the value.
+    /// Return how many values are rewritten.
+    pub fn rewrite_cf<F>(&self, prefix_name: &str, f: F) -> Result<u64>
+    where
+        F: Fn(&[u8], &[u8]) -> Result<Option<Vec<u8>>>,
+    {
+        let cf_handle = self.get_cf_handle(prefix_name)?;
+        let mut db_batch = DBWriteBatch::default();
+        let mut rewritten = 0;
+        for (key, value) in self.db.iterator_cf(cf_handle, IteratorMode::Start) {
+            if let Some(new_value) = f(&key, &value)? {
+                db_batch.put_cf(cf_handle, key, new_value);
+                rewritten += 1;
+            }
+        }
+        self.db
+            .write_opt(db_batch, &Self::default_write_options())?;
+        Ok(rewritten)
+    }
+
+    fn cf_options() -> rocksdb::Options {
+        let mut cf_opts = rocksdb::Options::default();
+        cf_opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
+        cf_opts
     }
 
     fn open_inner(
@@ -67,9 +271,7 @@ impl DBStorage {
             opts,
             path,
             column_families.iter().map(|cf_name| {
-                let mut cf_opts = rocksdb::Options::default();
-                cf_opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
-                rocksdb::ColumnFamilyDescriptor::new((*cf_name).to_string(), cf_opts)
+                rocksdb::ColumnFamilyDescriptor::new((*cf_name).to_string(), Self::cf_options())
             }),
         )?;
         Ok(inner)
@@ -181,6 +383,25 @@ impl InnerStore for DBStorage {
         })
     }
 
+    /// Writes the batches of all column families in one db WriteBatch, so they are atomic.
+    fn write_cf_batch(&self, batch: CFWriteBatch) -> Result<()> {
+        record_metrics("db", "batch", "cf_batch").end_with(|| {
+            let mut db_batch = DBWriteBatch::default();
+            for (prefix_name, batch) in &batch.batches {
+                let cf_handle = self.get_cf_handle(prefix_name)?;
+                for (key, write_op) in &batch.rows {
+                    match write_op {
+                        WriteOp::Value(value) => db_batch.put_cf(cf_handle, key, value),
+                        WriteOp::Deletion => db_batch.delete_cf(cf_handle, key),
+                    };
+                }
+            }
+            self.db
+                .write_opt(db_batch, &Self::default_write_options())?;
+            Ok(())
+        })
+    }
+
     fn get_len(&self) -> Result<u64> {
         unimplemented!()
     }
@@ -188,4 +409,41 @@ impl InnerStore for DBStorage {
     fn keys(&self) -> Result<Vec<Vec<u8>>> {
         unimplemented!()
     }
+
+    fn iter_prefix(&self, prefix_name: &str, prefix: Vec<u8>) -> Result<KVIterator> {
+        let cf_handle = self.get_cf_handle(prefix_name)?;
+        let iter = self.db.iterator_cf(
+            cf_handle,
+            IteratorMode::From(prefix.as_slice(), Direction::Forward),
+        );
+        let iter = iter
+            .take_while(move |(key, _)| key.starts_with(prefix.as_slice()))
+            .map(|(key, value)| (key.into_vec(), value.into_vec()));
+        Ok(Box::new(iter))
+    }
+
+    fn iter_range(&self, prefix_name: &str, from: Vec<u8>, to: Vec<u8>) -> Result<KVIterator> {
+        let cf_handle = self.get_cf_handle(prefix_name)?;
+        let iter = self.db.iterator_cf(
+            cf_handle,
+            IteratorMode::From(from.as_slice(), Direction::Forward),
+        );
+        let iter = iter
+            .take_while(move |(key, _)| &key[..] < to.as_slice())
+            .map(|(key, value)| (key.into_vec(), value.into_vec()));
+        Ok(Box::new(iter))
+    }
+
+    fn iter_reverse(&self, prefix_name: &str, from: Option<Vec<u8>>) -> Result<KVIterator> {
+        let cf_handle = self.get_cf_handle(prefix_name)?;
+        let mode = match from.as_ref() {
+            Some(from) => IteratorMode::From(from.as_slice(), Direction::Reverse),
+            None => IteratorMode::End,
+        };
+        let iter = self
+            .db
+            .iterator_cf(cf_handle, mode)
+            .map(|(key, value)| (key.into_vec(), value.into_vec()));
+        Ok(Box::new(iter))
+    }
 }
@@ -2,11 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::accumulator::AccumulatorStorage;
+use crate::batch::CFWriteBatch;
 use crate::block::BlockStorage;
 use crate::block_info::{BlockInfoStorage, BlockInfoStore};
 use crate::branch::BranchStorage;
 use crate::state_node::StateStorage;
-use crate::storage::{ColumnFamilyName, InnerStorage, KVStore, StorageInstance};
+use crate::storage::{ColumnFamilyName, InnerStorage, InnerStore, KVStore, StorageInstance};
 use crate::transaction::TransactionStorage;
 use crate::transaction_info::TransactionInfoStorage;
 use anyhow::{ensure, Error, Result};
@@ -36,12 +37,14 @@ pub mod branch;
 pub mod cache_storage;
 pub mod db_storage;
 mod metrics;
+pub mod migration;
 pub mod state_node;
 pub mod storage;
 #[cfg(test)]
 mod tests;
 pub mod transaction;
 pub mod transaction_info;
+pub mod verify;
 
 #[macro_use]
 pub mod storage_macros;
@@ -59,6 +62,7 @@ pub const STARTUP_INFO_PREFIX_NAME: ColumnFamilyName = "startup_info";
 pub const TRANSACTION_PREFIX_NAME: ColumnFamilyName = "transaction";
 pub const TRANSACTION_INFO_PREFIX_NAME: ColumnFamilyName = "transaction_info";
 pub const BRANCH_PREFIX_NAME: ColumnFamilyName = "branch";
+pub const META_PREFIX_NAME: ColumnFamilyName = "meta";
 ///db storage use prefix_name vec to init
 /// Please note that adding a prefix needs to be added in vec simultaneously, remember！！
 pub static VEC_PREFIX_NAME: Lazy<Vec<ColumnFamilyName>> = Lazy::new(|| {
@@ -76,9 +80,47 @@ pub static VEC_PREFIX_NAME: Lazy<Vec<ColumnFamilyName>> = Lazy::new(|| {
         TRANSACTION_PREFIX_NAME,
         TRANSACTION_INFO_PREFIX_NAME,
         BRANCH_PREFIX_NAME,
+        META_PREFIX_NAME,
     ]
 });
 
+/// All data of a block, they are saved by one atomic write batch.
+#[derive(Clone, Debug)]
+pub struct BlockData {
+    pub block: Block,
+    pub state: BlockState,
+    pub block_info: BlockInfo,
+    /// Transactions of the block, include the block metadata txn.
+    pub transactions: Vec<Transaction>,
+    pub transaction_infos: Vec<TransactionInfo>,
+    /// Accumulator nodes appended by the block.
+    pub accumulator_nodes: Vec<(AccumulatorStoreType, AccumulatorNode)>,
+    /// State nodes changed by the block.
+    pub state_nodes: BTreeMap<HashValue, StateNode>,
+}
+
+impl BlockData {
+    pub fn new(
+        block: Block,
+        state: BlockState,
+        block_info: BlockInfo,
+        transactions: Vec<Transaction>,
+        transaction_infos: Vec<TransactionInfo>,
+        accumulator_nodes: Vec<(AccumulatorStoreType, AccumulatorNode)>,
+        state_nodes: BTreeMap<HashValue, StateNode>,
+    ) -> Self {
+        Self {
+            block,
+            state,
+            block_info,
+            transactions,
+            transaction_infos,
+            accumulator_nodes,
+            state_nodes,
+        }
+    }
+}
+
 pub trait BlockStore {
     fn get_startup_info(&self) -> Result<Option<StartupInfo>>;
     fn save_startup_info(&self, startup_info: StartupInfo) -> Result<()>;
@@ -104,6 +146,10 @@ pub trait BlockStore {
 
     fn commit_block(&self, block: Block, state: BlockState) -> Result<()>;
 
+    /// Commit block, block info, transactions, transaction infos, accumulator nodes
+    /// and state nodes atomically.
+    fn commit_block_data(&self, block_data: BlockData) -> Result<()>;
+
     fn get_branch_hashes(&self, block_id: HashValue) -> Result<Vec<HashValue>>;
 
     fn get_latest_block_header(&self) -> Result<Option<BlockHeader>>;
@@ -169,6 +215,7 @@ pub struct Storage {
     block_info_storage: BlockInfoStorage,
     startup_info_storage: Arc<dyn KVStore>,
     branch_storage: BranchStorage,
+    instance: StorageInstance,
 }
 
 impl Storage {
@@ -184,7 +231,8 @@ impl Storage {
                 instance.clone(),
                 STARTUP_INFO_PREFIX_NAME,
             )),
-            branch_storage: BranchStorage::new(instance),
+            branch_storage: BranchStorage::new(instance.clone()),
+            instance,
         })
     }
 }
@@ -262,6 +310,49 @@ impl BlockStore for Storage {
         self.block_storage.commit_block(block, state)
     }
 
+    fn commit_block_data(&self, block_data: BlockData) -> Result<()> {
+        let BlockData {
+            block,
+            state,
+            block_info,
+            transactions,
+            transaction_infos,
+            accumulator_nodes,
+            state_nodes,
+        } = block_data;
+        let block_id = block.id();
+        let mut batch = CFWriteBatch::new();
+        self.block_storage
+            .commit_block_to_batch(block, state, &mut batch)?;
+        if !transactions.is_empty() {
+            let txn_ids = transactions.iter().map(|txn| txn.id()).collect();
+            self.block_storage
+                .put_transactions_to_batch(block_id, txn_ids, &mut batch)?;
+            for txn in transactions {
+                batch.put(TRANSACTION_PREFIX_NAME, txn.id(), txn)?;
+            }
+        }
+        for txn_info in transaction_infos {
+            batch.put(
+                TRANSACTION_INFO_PREFIX_NAME,
+                txn_info.transaction_hash(),
+                txn_info,
+            )?;
+        }
+        for (store_type, node) in accumulator_nodes {
+            batch.put(
+                ACCUMULATOR_NODE_PREFIX_NAME,
+                AccumulatorStorage::get_store_key(store_type, node.hash()),
+                node,
+            )?;
+        }
+        for (key, node) in state_nodes {
+            batch.put(STATE_NODE_PREFIX_NAME, key, node)?;
+        }
+        batch.put(BLOCK_INFO_PREFIX_NAME, block_id, block_info)?;
+        self.instance.write_cf_batch(batch)
+    }
+
     fn get_branch_hashes(&self, block_id: HashValue) -> Result<Vec<HashValue>> {
         self.block_storage.get_branch_hashes(block_id)
     }
@@ -0,0 +1,180 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Storage schema versioning.
+//! The schema version is saved in the meta column family, every schema change should register
+//! a `MigrationStep` to `MigrationRegistry` and increase `CURRENT_SCHEMA_VERSION`.
+
+use crate::db_storage::DBStorage;
+use crate::storage::ColumnFamilyName;
+use crate::{META_PREFIX_NAME, VEC_PREFIX_NAME};
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+/// Key of schema version in the meta column family.
+pub const SCHEMA_VERSION_KEY: &[u8] = b"schema_version";
+
+/// The schema version of db without meta column family.
+pub const LEGACY_SCHEMA_VERSION: u64 = 0;
+
+/// The schema version current code read and write.
+pub const CURRENT_SCHEMA_VERSION: u64 = 1;
+
+/// Rewrite values of the db, return how many values are rewritten.
+pub type RewriteFn = fn(&DBStorage) -> Result<u64>;
+
+/// A step migrate the db from `version - 1` to `version`.
+#[derive(Clone)]
+pub struct MigrationStep {
+    pub version: u64,
+    pub description: &'static str,
+    /// Column families to create before rewrite.
+    pub add_cfs: Vec<ColumnFamilyName>,
+    /// Column families to drop after rewrite.
+    pub drop_cfs: Vec<ColumnFamilyName>,
+    pub rewrite: Option<RewriteFn>,
+}
+
+impl MigrationStep {
+    pub fn new(version: u64, description: &'static str) -> Self {
+        Self {
+            version,
+            description,
+            add_cfs: vec![],
+            drop_cfs: vec![],
+            rewrite: None,
+        }
+    }
+
+    pub fn add_cf(mut self, cf: ColumnFamilyName) -> Self {
+        self.add_cfs.push(cf);
+        self
+    }
+
+    pub fn drop_cf(mut self, cf: ColumnFamilyName) -> Self {
+        self.drop_cfs.push(cf);
+        self
+    }
+
+    pub fn rewrite(mut self, rewrite: RewriteFn) -> Self {
+        self.rewrite = Some(rewrite);
+        self
+    }
+
+    /// A step only add column families is safe to apply automatically when open the db,
+    /// a step drop column families or rewrite values requires the manual `db migrate`.
+    pub fn is_destructive(&self) -> bool {
+        !self.drop_cfs.is_empty() || self.rewrite.is_some()
+    }
+}
+
+pub struct MigrationRegistry {
+    steps: Vec<MigrationStep>,
+}
+
+impl Default for MigrationRegistry {
+    fn default() -> Self {
+        let mut registry = Self { steps: vec![] };
+        registry
+            .register(
+                MigrationStep::new(1, "record schema version in meta column family")
+                    .add_cf(META_PREFIX_NAME),
+            )
+            .expect("register builtin migration step should success.");
+        registry
+    }
+}
+
+impl MigrationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a step, steps must be registered by version order without gap.
+    pub fn register(&mut self, step: MigrationStep) -> Result<()> {
+        let last_version = self.latest_version();
+        ensure!(
+            step.version == last_version + 1,
+            "migration step version should be {}, but got {}",
+            last_version + 1,
+            step.version
+        );
+        self.steps.push(step);
+        Ok(())
+    }
+
+    pub fn latest_version(&self) -> u64 {
+        self.steps
+            .last()
+            .map(|step| step.version)
+            .unwrap_or(LEGACY_SCHEMA_VERSION)
+    }
+
+    /// Steps need to apply for db at `version`.
+    pub fn pending_steps(&self, version: u64) -> Vec<MigrationStep> {
+        self.steps
+            .iter()
+            .filter(|step| step.version > version)
+            .cloned()
+            .collect()
+    }
+
+    /// All column families of the schema at the latest version.
+    pub fn column_families(&self) -> Vec<ColumnFamilyName> {
+        let mut cfs = VEC_PREFIX_NAME.to_vec();
+        for step in &self.steps {
+            for cf in &step.add_cfs {
+                if !cfs.contains(cf) {
+                    cfs.push(cf);
+                }
+            }
+            cfs.retain(|cf| !step.drop_cfs.contains(cf));
+        }
+        cfs
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MigrationStepReport {
+    pub version: u64,
+    pub description: String,
+    pub add_cfs: Vec<String>,
+    pub drop_cfs: Vec<String>,
+    /// Rewritten values, None if the step has no rewrite or in dry run.
+    pub rewritten: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MigrationReport {
+    pub dry_run: bool,
+    pub from_version: u64,
+    pub to_version: u64,
+    pub steps: Vec<MigrationStepReport>,
+    /// Column families on disk but unknown by the schema, they are kept as is.
+    pub unknown_cfs: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let mut registry = MigrationRegistry::new();
+        assert_eq!(registry.latest_version(), CURRENT_SCHEMA_VERSION);
+        assert!(registry
+            .register(MigrationStep::new(CURRENT_SCHEMA_VERSION + 2, "gap"))
+            .is_err());
+        registry
+            .register(
+                MigrationStep::new(CURRENT_SCHEMA_VERSION + 1, "drop meta")
+                    .drop_cf(META_PREFIX_NAME),
+            )
+            .unwrap();
+        assert_eq!(registry.pending_steps(LEGACY_SCHEMA_VERSION).len(), 2);
+        assert!(!registry.pending_steps(LEGACY_SCHEMA_VERSION)[0].is_destructive());
+        assert!(registry.pending_steps(CURRENT_SCHEMA_VERSION)[0].is_destructive());
+        assert_eq!(registry.pending_steps(CURRENT_SCHEMA_VERSION).len(), 1);
+        assert!(!registry.column_families().contains(&META_PREFIX_NAME));
+    }
+}
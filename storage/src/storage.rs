@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::batch::WriteBatch;
+use crate::batch::{CFWriteBatch, WriteBatch};
 use crate::cache_storage::CacheStorage;
 use crate::db_storage::DBStorage;
 use anyhow::{bail, Result};
@@ -13,6 +13,9 @@ use std::sync::Arc;
 /// Type alias to improve readability.
 pub type ColumnFamilyName = &'static str;
 
+/// Iterator of the raw key value pairs of a column family.
+pub type KVIterator<'a> = Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>;
+
 #[derive(Debug, Clone)]
 pub enum WriteOp {
     Value(Vec<u8>),
@@ -25,8 +28,15 @@ pub trait KVStore: Send + Sync {
     fn contains_key(&self, key: Vec<u8>) -> Result<bool>;
     fn remove(&self, key: Vec<u8>) -> Result<()>;
     fn write_batch(&self, batch: WriteBatch) -> Result<()>;
+    fn write_cf_batch(&self, batch: CFWriteBatch) -> Result<()>;
     fn get_len(&self) -> Result<u64>;
     fn keys(&self) -> Result<Vec<Vec<u8>>>;
+    /// Iterate the entries whose key starts with `prefix`, in ascending key order.
+    fn iter_prefix(&self, prefix: Vec<u8>) -> Result<KVIterator>;
+    /// Iterate the entries whose key is in [`from`, `to`), in ascending key order.
+    fn iter_range(&self, from: Vec<u8>, to: Vec<u8>) -> Result<KVIterator>;
+    /// Iterate the entries in descending key order, from the key `from` (inclusive) or the last key.
+    fn iter_reverse(&self, from: Option<Vec<u8>>) -> Result<KVIterator>;
 }
 
 pub trait InnerStore: Send + Sync {
@@ -35,8 +45,16 @@ pub trait InnerStore: Send + Sync {
     fn contains_key(&self, prefix_name: &str, key: Vec<u8>) -> Result<bool>;
     fn remove(&self, prefix_name: &str, key: Vec<u8>) -> Result<()>;
     fn write_batch(&self, prefix_name: &str, batch: WriteBatch) -> Result<()>;
+    /// Write batches of several column families atomically.
+    fn write_cf_batch(&self, batch: CFWriteBatch) -> Result<()>;
     fn get_len(&self) -> Result<u64>;
     fn keys(&self) -> Result<Vec<Vec<u8>>>;
+    /// Iterate the entries whose key starts with `prefix`, in ascending key order.
+    fn iter_prefix(&self, prefix_name: &str, prefix: Vec<u8>) -> Result<KVIterator>;
+    /// Iterate the entries whose key is in [`from`, `to`), in ascending key order.
+    fn iter_range(&self, prefix_name: &str, from: Vec<u8>, to: Vec<u8>) -> Result<KVIterator>;
+    /// Iterate the entries in descending key order, from the key `from` (inclusive) or the last key.
+    fn iter_reverse(&self, prefix_name: &str, from: Option<Vec<u8>>) -> Result<KVIterator>;
 }
 
 ///Storage instance type define
@@ -93,7 +111,7 @@ impl InnerStore for StorageInstance {
             StorageInstance::CACHE { cache } => cache.put(prefix_name, key, value),
             StorageInstance::DB { db } => db.put(prefix_name, key, value),
             StorageInstance::CacheAndDb { cache, db } => {
-                db.put(prefix_name, key.clone(), value.clone()).unwrap();
+                db.put(prefix_name, key.clone(), value.clone())?;
                 cache.put(prefix_name, key, value)
             }
         }
@@ -137,6 +155,20 @@ impl InnerStore for StorageInstance {
             }
         }
     }
+
+    fn write_cf_batch(&self, batch: CFWriteBatch) -> Result<()> {
+        match self {
+            StorageInstance::CACHE { cache } => cache.write_cf_batch(batch),
+            StorageInstance::DB { db } => db.write_cf_batch(batch),
+            StorageInstance::CacheAndDb { cache, db } => {
+                // only update the cache after the db batch is committed.
+                match db.write_cf_batch(batch.clone()) {
+                    Ok(_) => cache.write_cf_batch(batch),
+                    Err(err) => bail!("write cf batch db error: {}", err),
+                }
+            }
+        }
+    }
     fn get_len(&self) -> Result<u64> {
         match self {
             StorageInstance::CACHE { cache } => cache.get_len(),
@@ -152,6 +184,31 @@ impl InnerStore for StorageInstance {
             _ => bail!("DB instance not support keys method!"),
         }
     }
+
+    // The cache only holds part of the db, so iterate the db if it exists.
+    fn iter_prefix(&self, prefix_name: &str, prefix: Vec<u8>) -> Result<KVIterator> {
+        match self {
+            StorageInstance::CACHE { cache } => cache.iter_prefix(prefix_name, prefix),
+            StorageInstance::DB { db } => db.iter_prefix(prefix_name, prefix),
+            StorageInstance::CacheAndDb { cache: _, db } => db.iter_prefix(prefix_name, prefix),
+        }
+    }
+
+    fn iter_range(&self, prefix_name: &str, from: Vec<u8>, to: Vec<u8>) -> Result<KVIterator> {
+        match self {
+            StorageInstance::CACHE { cache } => cache.iter_range(prefix_name, from, to),
+            StorageInstance::DB { db } => db.iter_range(prefix_name, from, to),
+            StorageInstance::CacheAndDb { cache: _, db } => db.iter_range(prefix_name, from, to),
+        }
+    }
+
+    fn iter_reverse(&self, prefix_name: &str, from: Option<Vec<u8>>) -> Result<KVIterator> {
+        match self {
+            StorageInstance::CACHE { cache } => cache.iter_reverse(prefix_name, from),
+            StorageInstance::DB { db } => db.iter_reverse(prefix_name, from),
+            StorageInstance::CacheAndDb { cache: _, db } => db.iter_reverse(prefix_name, from),
+        }
+    }
 }
 
 /// Define inner storage implement
@@ -190,6 +247,10 @@ impl KVStore for InnerStorage {
         self.instance.write_batch(self.prefix_name, batch)
     }
 
+    fn write_cf_batch(&self, batch: CFWriteBatch) -> Result<()> {
+        self.instance.write_cf_batch(batch)
+    }
+
     fn get_len(&self) -> Result<u64> {
         self.instance.get_len()
     }
@@ -197,6 +258,18 @@ impl KVStore for InnerStorage {
     fn keys(&self) -> Result<Vec<Vec<u8>>> {
         self.instance.keys()
     }
+
+    fn iter_prefix(&self, prefix: Vec<u8>) -> Result<KVIterator> {
+        self.instance.iter_prefix(self.prefix_name, prefix)
+    }
+
+    fn iter_range(&self, from: Vec<u8>, to: Vec<u8>) -> Result<KVIterator> {
+        self.instance.iter_range(self.prefix_name, from, to)
+    }
+
+    fn iter_reverse(&self, from: Option<Vec<u8>>) -> Result<KVIterator> {
+        self.instance.iter_reverse(self.prefix_name, from)
+    }
 }
 
 pub trait KeyCodec: Sized + PartialEq + Debug {
@@ -256,12 +329,56 @@ where
         self.store.write_batch(batch)
     }
 
+    pub fn write_cf_batch(&self, batch: CFWriteBatch) -> Result<()> {
+        self.store.write_cf_batch(batch)
+    }
+
     pub fn get_len(&self) -> Result<u64> {
         self.store.get_len()
     }
     pub fn keys(&self) -> Result<Vec<Vec<u8>>> {
         self.store.keys()
     }
+
+    /// Iterate the entries whose encoded key starts with `prefix`, the prefix is raw bytes
+    /// because it is usually a part of the key, such as the branch id of `BranchNumber`.
+    pub fn iter_prefix(
+        &self,
+        prefix: Vec<u8>,
+    ) -> Result<impl Iterator<Item = Result<(K, V)>> + '_> {
+        Ok(self.store.iter_prefix(prefix)?.map(decode_kv))
+    }
+
+    /// Iterate the entries whose key is in [`from`, `to`) by the encoded key order.
+    pub fn iter_range(&self, from: K, to: K) -> Result<impl Iterator<Item = Result<(K, V)>> + '_> {
+        Ok(self
+            .store
+            .iter_range(from.encode_key()?, to.encode_key()?)?
+            .map(decode_kv))
+    }
+
+    /// Iterate the entries in descending key order, from the key `from` (inclusive) or the last key.
+    pub fn iter_reverse(
+        &self,
+        from: Option<K>,
+    ) -> Result<impl Iterator<Item = Result<(K, V)>> + '_> {
+        let from = match from {
+            Some(from) => Some(from.encode_key()?),
+            None => None,
+        };
+        Ok(self.store.iter_reverse(from)?.map(decode_kv))
+    }
+}
+
+fn decode_kv<K, V>((key, value): (Vec<u8>, Vec<u8>)) -> Result<(K, V)>
+where
+    K: KeyCodec,
+    V: ValueCodec,
+{
+    Ok((
+        K::decode_key(key.as_slice())?,
+        V::decode_value(value.as_slice())?,
+    ))
 }
 
 impl KeyCodec for HashValue {
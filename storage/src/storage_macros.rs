@@ -36,6 +36,10 @@ macro_rules! define_storage {
                 self.store.write_batch(batch)
             }
             #[allow(dead_code)]
+            pub fn write_cf_batch(&self, batch: $crate::batch::CFWriteBatch) -> Result<()> {
+                self.store.write_cf_batch(batch)
+            }
+            #[allow(dead_code)]
             pub fn get_len(&self) -> Result<u64> {
                 self.store.get_len()
             }
@@ -43,6 +47,28 @@ macro_rules! define_storage {
             pub fn keys(&self) -> Result<Vec<Vec<u8>>> {
                 self.store.keys()
             }
+            #[allow(dead_code)]
+            pub fn iter_prefix(
+                &self,
+                prefix: Vec<u8>,
+            ) -> Result<impl Iterator<Item = Result<($key_type, $value_type)>> + '_> {
+                self.store.iter_prefix(prefix)
+            }
+            #[allow(dead_code)]
+            pub fn iter_range(
+                &self,
+                from: $key_type,
+                to: $key_type,
+            ) -> Result<impl Iterator<Item = Result<($key_type, $value_type)>> + '_> {
+                self.store.iter_range(from, to)
+            }
+            #[allow(dead_code)]
+            pub fn iter_reverse(
+                &self,
+                from: Option<$key_type>,
+            ) -> Result<impl Iterator<Item = Result<($key_type, $value_type)>> + '_> {
+                self.store.iter_reverse(from)
+            }
         }
     };
 }
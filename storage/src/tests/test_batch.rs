@@ -1,11 +1,11 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::batch::WriteBatch;
+use crate::batch::{CFWriteBatch, WriteBatch};
 use crate::cache_storage::CacheStorage;
 use crate::db_storage::DBStorage;
-use crate::storage::{InnerStore, ValueCodec};
-use crate::DEFAULT_PREFIX_NAME;
+use crate::storage::{InnerStore, StorageInstance, ValueCodec};
+use crate::{DEFAULT_PREFIX_NAME, TRANSACTION_INFO_PREFIX_NAME};
 use crypto::{hash::PlainCryptoHash, HashValue};
 use starcoin_types::transaction::TransactionInfo;
 use starcoin_types::vm_error::StatusCode;
@@ -148,3 +148,59 @@ fn test_batch_comm() {
     let result = db.write_batch(DEFAULT_PREFIX_NAME, new_batch2);
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_cf_batch() {
+    let tmpdir = starcoin_config::temp_path();
+    let cache_storage = Arc::new(CacheStorage::new());
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let instance = StorageInstance::new_cache_and_db_instance(cache_storage.clone(), db_storage);
+    let transaction_info = TransactionInfo::new(
+        HashValue::random(),
+        HashValue::zero(),
+        HashValue::zero(),
+        vec![],
+        0,
+        StatusCode::ABORTED,
+    );
+    let id = transaction_info.crypto_hash();
+    let value = HashValue::random();
+
+    let mut batch = CFWriteBatch::new();
+    batch
+        .put(TRANSACTION_INFO_PREFIX_NAME, id, transaction_info.clone())
+        .unwrap();
+    batch.put(DEFAULT_PREFIX_NAME, id, value).unwrap();
+    instance.write_cf_batch(batch).unwrap();
+    assert_eq!(
+        TransactionInfo::decode_value(
+            &instance
+                .get(TRANSACTION_INFO_PREFIX_NAME, id.to_vec())
+                .unwrap()
+                .unwrap()
+        )
+        .unwrap(),
+        transaction_info
+    );
+    assert_eq!(
+        instance.get(DEFAULT_PREFIX_NAME, id.to_vec()).unwrap(),
+        Some(value.to_vec())
+    );
+
+    // a batch with unknown column family fails as a whole, and the cache is not updated.
+    let id2 = HashValue::random();
+    let mut batch = CFWriteBatch::new();
+    batch.put(DEFAULT_PREFIX_NAME, id2, value).unwrap();
+    batch.put("unknown_cf", id2, value).unwrap();
+    assert!(instance.write_cf_batch(batch).is_err());
+    assert_eq!(
+        instance.get(DEFAULT_PREFIX_NAME, id2.to_vec()).unwrap(),
+        None
+    );
+    assert_eq!(
+        cache_storage
+            .get(DEFAULT_PREFIX_NAME, id2.to_vec())
+            .unwrap(),
+        None
+    );
+}
@@ -7,8 +7,12 @@ use crypto::{hash::PlainCryptoHash, HashValue};
 
 use crate::cache_storage::CacheStorage;
 use crate::db_storage::DBStorage;
-use crate::storage::{InnerStore, StorageInstance, ValueCodec};
-use crate::{Storage, DEFAULT_PREFIX_NAME, TRANSACTION_INFO_PREFIX_NAME};
+use crate::migration::{MigrationRegistry, CURRENT_SCHEMA_VERSION, LEGACY_SCHEMA_VERSION};
+use crate::storage::{CodecStorage, InnerStorage, InnerStore, StorageInstance, ValueCodec};
+use crate::{
+    Storage, BLOCK_NUM_PREFIX_NAME, DEFAULT_PREFIX_NAME, META_PREFIX_NAME,
+    TRANSACTION_INFO_PREFIX_NAME, VEC_PREFIX_NAME,
+};
 use anyhow::Result;
 use starcoin_types::transaction::TransactionInfo;
 use starcoin_types::vm_error::StatusCode;
@@ -174,3 +178,124 @@ fn test_two_level_storage_read_through() -> Result<()> {
     assert_eq!(transaction_info3, transaction_info1);
     Ok(())
 }
+
+#[test]
+fn test_migrate_legacy_db() {
+    let tmpdir = starcoin_config::temp_path();
+    let path = DBStorage::db_path(tmpdir.path());
+    let key = HashValue::random();
+    {
+        // a db created before the schema version is recorded.
+        let mut db_opts = rocksdb::Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+        let legacy_cfs: Vec<_> = VEC_PREFIX_NAME
+            .iter()
+            .filter(|cf| **cf != META_PREFIX_NAME)
+            .collect();
+        let db = rocksdb::DB::open_cf(&db_opts, path.as_path(), legacy_cfs).unwrap();
+        let cf_handle = db.cf_handle(TRANSACTION_INFO_PREFIX_NAME).unwrap();
+        db.put_cf(cf_handle, key.to_vec(), key.to_vec()).unwrap();
+    }
+    assert_eq!(
+        DBStorage::schema_version(path.as_path()).unwrap(),
+        LEGACY_SCHEMA_VERSION
+    );
+    // the legacy db only miss the meta column family, readonly open can not add it.
+    assert!(DBStorage::open(path.as_path(), true).is_err());
+
+    let registry = MigrationRegistry::new();
+    let report = DBStorage::migrate(path.as_path(), &registry, true).unwrap();
+    assert_eq!(report.from_version, LEGACY_SCHEMA_VERSION);
+    assert_eq!(report.to_version, CURRENT_SCHEMA_VERSION);
+    assert_eq!(report.steps.len(), 1);
+    // dry run does not change the db.
+    assert_eq!(
+        DBStorage::schema_version(path.as_path()).unwrap(),
+        LEGACY_SCHEMA_VERSION
+    );
+
+    let report = DBStorage::migrate(path.as_path(), &registry, false).unwrap();
+    assert_eq!(report.steps.len(), 1);
+    assert_eq!(
+        DBStorage::schema_version(path.as_path()).unwrap(),
+        CURRENT_SCHEMA_VERSION
+    );
+    let db = DBStorage::open(path.as_path(), false).unwrap();
+    assert_eq!(
+        db.get(TRANSACTION_INFO_PREFIX_NAME, key.to_vec()).unwrap(),
+        Some(key.to_vec())
+    );
+}
+
+#[test]
+fn test_auto_migrate_legacy_db() {
+    let tmpdir = starcoin_config::temp_path();
+    let path = DBStorage::db_path(tmpdir.path());
+    let key = HashValue::random();
+    {
+        let mut db_opts = rocksdb::Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+        let legacy_cfs: Vec<_> = VEC_PREFIX_NAME
+            .iter()
+            .filter(|cf| **cf != META_PREFIX_NAME)
+            .collect();
+        let db = rocksdb::DB::open_cf(&db_opts, path.as_path(), legacy_cfs).unwrap();
+        let cf_handle = db.cf_handle(TRANSACTION_INFO_PREFIX_NAME).unwrap();
+        db.put_cf(cf_handle, key.to_vec(), key.to_vec()).unwrap();
+    }
+    // the pending step only add column family, so it is applied when open.
+    let db = DBStorage::open(path.as_path(), false).unwrap();
+    assert_eq!(
+        db.get(TRANSACTION_INFO_PREFIX_NAME, key.to_vec()).unwrap(),
+        Some(key.to_vec())
+    );
+    drop(db);
+    assert_eq!(
+        DBStorage::schema_version(path.as_path()).unwrap(),
+        CURRENT_SCHEMA_VERSION
+    );
+}
+
+fn check_iter(instance: StorageInstance) {
+    let store = CodecStorage::<u64, HashValue>::new(Arc::new(InnerStorage::new(
+        instance,
+        BLOCK_NUM_PREFIX_NAME,
+    )));
+    let ids: Vec<HashValue> = (0..10u64).map(|_| HashValue::random()).collect();
+    for (number, id) in ids.iter().enumerate() {
+        store.put(number as u64, *id).unwrap();
+    }
+    let range: Vec<(u64, HashValue)> = store
+        .iter_range(3, 6)
+        .unwrap()
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(
+        range,
+        vec![(3, ids[3]), (4, ids[4]), (5, ids[5])],
+        "range should exclude the end"
+    );
+    let reverse: Vec<u64> = store
+        .iter_reverse(Some(2))
+        .unwrap()
+        .map(|kv| kv.unwrap().0)
+        .collect();
+    assert_eq!(reverse, vec![2, 1, 0]);
+    let last = store.iter_reverse(None).unwrap().next().unwrap().unwrap();
+    assert_eq!(last, (9, ids[9]));
+    // all numbers less than 256 have the same first 7 bytes.
+    let prefix = 0u64.to_be_bytes()[..7].to_vec();
+    assert_eq!(store.iter_prefix(prefix).unwrap().count(), 10);
+    assert_eq!(store.iter_prefix(vec![1]).unwrap().count(), 0);
+}
+
+#[test]
+fn test_iter() {
+    let tmpdir = starcoin_config::temp_path();
+    check_iter(StorageInstance::new_db_instance(Arc::new(DBStorage::new(
+        tmpdir.path(),
+    ))));
+    check_iter(StorageInstance::new_cache_instance(CacheStorage::new()));
+}
@@ -0,0 +1,186 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db_storage::DBStorage;
+use crate::storage::StorageInstance;
+use crate::verify::{StorageVerifier, VerifyIssueKind, VerifyOptions};
+use crate::{BlockData, BlockInfoStore, BlockStore, Storage};
+use crypto::HashValue;
+use forkable_jellyfish_merkle::blob::Blob;
+use forkable_jellyfish_merkle::node_type::Node;
+use forkable_jellyfish_merkle::SPARSE_MERKLE_PLACEHOLDER_HASH;
+use starcoin_accumulator::node::{AccumulatorStoreType, ACCUMULATOR_PLACEHOLDER_HASH};
+use starcoin_accumulator::{Accumulator, MerkleAccumulator};
+use starcoin_config::DataDirPath;
+use starcoin_state_store_api::{StateNode, StateNodeStore};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_state::AccountState;
+use starcoin_types::accumulator_info::AccumulatorInfo;
+use starcoin_types::block::{Block, BlockBody, BlockHeader, BlockInfo, BlockState};
+use starcoin_types::startup_info::StartupInfo;
+use starcoin_types::{U256, U512};
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::sync::Arc;
+
+fn new_storage() -> (DataDirPath, Arc<Storage>) {
+    let tmpdir = starcoin_config::temp_path();
+    let storage = Arc::new(
+        Storage::new(StorageInstance::new_db_instance(Arc::new(DBStorage::new(
+            tmpdir.path(),
+        ))))
+        .unwrap(),
+    );
+    (tmpdir, storage)
+}
+
+fn init_genesis(storage: Arc<Storage>, state_root: HashValue) -> HashValue {
+    let accumulator_root = HashValue::random();
+    let header = BlockHeader::new(
+        HashValue::zero(),
+        *ACCUMULATOR_PLACEHOLDER_HASH,
+        0,
+        0,
+        AccountAddress::random(),
+        accumulator_root,
+        state_root,
+        0,
+        0,
+        U256::zero(),
+        vec![],
+    );
+    let block_id = header.id();
+    let block_accumulator = MerkleAccumulator::new(
+        *ACCUMULATOR_PLACEHOLDER_HASH,
+        vec![],
+        0,
+        0,
+        AccumulatorStoreType::Block,
+        storage.clone(),
+    )
+    .unwrap();
+    block_accumulator.append(&[block_id]).unwrap();
+    let block_accumulator_info: AccumulatorInfo = (&block_accumulator).try_into().unwrap();
+    let block_info = BlockInfo::new_with_accumulator_info(
+        block_id,
+        AccumulatorInfo::new(accumulator_root, vec![accumulator_root], 1, 1),
+        block_accumulator_info,
+        U512::zero(),
+    );
+    storage
+        .commit_block_data(BlockData::new(
+            Block::new(header, BlockBody::default()),
+            BlockState::Executed,
+            block_info,
+            vec![],
+            vec![],
+            block_accumulator
+                .pop_unsaved_nodes()
+                .into_iter()
+                .map(|node| (AccumulatorStoreType::Block, node))
+                .collect(),
+            BTreeMap::new(),
+        ))
+        .unwrap();
+    storage
+        .save_startup_info(StartupInfo::new(block_id, vec![]))
+        .unwrap();
+    block_id
+}
+
+#[test]
+fn test_verify_and_repair() {
+    let (_tmpdir, storage) = new_storage();
+    let genesis_id = init_genesis(storage.clone(), *SPARSE_MERKLE_PLACEHOLDER_HASH);
+
+    let report = StorageVerifier::new(storage.clone(), VerifyOptions::default())
+        .verify()
+        .unwrap();
+    assert!(report.is_ok(), "{:?}", report.issues);
+    assert_eq!(report.head, Some(genesis_id));
+    assert_eq!(report.checked_blocks, 1);
+    assert_eq!(report.checked_state_roots, 1);
+
+    // break the number index.
+    storage
+        .block_storage
+        .save_number(0, HashValue::random())
+        .unwrap();
+    let report = StorageVerifier::new(storage.clone(), VerifyOptions::default())
+        .verify()
+        .unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(report.issues[0].kind, VerifyIssueKind::NumberIndexMismatch);
+
+    let report = StorageVerifier::new(
+        storage.clone(),
+        VerifyOptions {
+            repair: true,
+            ..Default::default()
+        },
+    )
+    .verify()
+    .unwrap();
+    assert!(report.is_ok());
+    assert!(report.issues[0].repaired);
+    assert_eq!(storage.get_number(0).unwrap(), Some(genesis_id));
+}
+
+fn verify_issue_kinds(storage: Arc<Storage>) -> Vec<VerifyIssueKind> {
+    let report = StorageVerifier::new(storage, VerifyOptions::default())
+        .verify()
+        .unwrap();
+    report.issues.iter().map(|issue| issue.kind).collect()
+}
+
+#[test]
+fn test_verify_corrupted_block_info() {
+    let (_tmpdir, storage) = new_storage();
+    let genesis_id = init_genesis(storage.clone(), *SPARSE_MERKLE_PLACEHOLDER_HASH);
+    let mut block_info = storage.get_block_info(genesis_id).unwrap().unwrap();
+    block_info.accumulator_root = HashValue::random();
+    storage.save_block_info(block_info).unwrap();
+    assert_eq!(
+        verify_issue_kinds(storage),
+        vec![VerifyIssueKind::TxnAccumulatorMismatch]
+    );
+}
+
+#[test]
+fn test_verify_corrupted_accumulator() {
+    let (_tmpdir, storage) = new_storage();
+    let genesis_id = init_genesis(storage.clone(), *SPARSE_MERKLE_PLACEHOLDER_HASH);
+    let mut block_info = storage.get_block_info(genesis_id).unwrap().unwrap();
+    block_info.block_accumulator_info = AccumulatorInfo::new(HashValue::random(), vec![], 1, 1);
+    storage.save_block_info(block_info).unwrap();
+    assert_eq!(
+        verify_issue_kinds(storage),
+        vec![VerifyIssueKind::BlockAccumulatorMismatch]
+    );
+}
+
+#[test]
+fn test_verify_corrupted_state_root() {
+    let (_tmpdir, storage) = new_storage();
+    let account_state: Vec<u8> = AccountState::default().try_into().unwrap();
+    let root = StateNode(Node::new_leaf(
+        HashValue::random(),
+        Blob::from(account_state.clone()),
+    ));
+    let state_root = root.0.hash();
+    StateNodeStore::put(storage.as_ref(), state_root, root).unwrap();
+    init_genesis(storage.clone(), state_root);
+    assert!(verify_issue_kinds(storage.clone()).is_empty());
+
+    // save another node to the key of the state root.
+    let corrupted = StateNode(Node::new_leaf(
+        HashValue::random(),
+        Blob::from(account_state),
+    ));
+    StateNodeStore::put(storage.as_ref(), state_root, corrupted).unwrap();
+    assert_eq!(
+        verify_issue_kinds(storage),
+        vec![VerifyIssueKind::StateNodeHashMismatch]
+    );
+}
@@ -0,0 +1,473 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline consistency check of the chain data.
+//! The verifier walks the master chain from genesis to the head, checks the block index,
+//! recomputes the accumulators from the stored data, and re-hashes the state tree nodes
+//! reachable from the sampled state roots.
+
+use crate::{BlockInfoStore, BlockStore, Storage, TransactionInfoStore};
+use anyhow::{format_err, Result};
+use crypto::hash::PlainCryptoHash;
+use crypto::HashValue;
+use forkable_jellyfish_merkle::nibble::Nibble;
+use forkable_jellyfish_merkle::node_type::Node;
+use forkable_jellyfish_merkle::SPARSE_MERKLE_PLACEHOLDER_HASH;
+use logger::prelude::*;
+use serde::{Deserialize, Serialize};
+use starcoin_accumulator::node::{AccumulatorStoreType, ACCUMULATOR_PLACEHOLDER_HASH};
+use starcoin_accumulator::{Accumulator, MerkleAccumulator};
+use starcoin_state_store_api::StateNodeStore;
+use starcoin_types::account_state::AccountState;
+use starcoin_types::accumulator_info::AccumulatorInfo;
+use starcoin_types::block::{BlockHeader, BlockInfo};
+use starcoin_types::transaction::{SignedUserTransaction, Transaction};
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum VerifyIssueKind {
+    MissingHeader,
+    MissingBody,
+    MissingBlock,
+    MissingBlockInfo,
+    InvalidNumber,
+    /// The block number index does not point to the master block.
+    NumberIndexMismatch,
+    /// The block transactions index does not match the block body.
+    BlockTransactionsMismatch,
+    MissingTransactionInfo,
+    TxnAccumulatorMismatch,
+    BlockAccumulatorMismatch,
+    MissingStateNode,
+    StateNodeHashMismatch,
+    InvalidAccountState,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerifyIssue {
+    pub kind: VerifyIssueKind,
+    pub block_number: Option<u64>,
+    pub block_id: HashValue,
+    pub detail: String,
+    /// The issue is fixed by `--repair`.
+    pub repaired: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct VerifyReport {
+    pub head: Option<HashValue>,
+    pub checked_blocks: u64,
+    pub checked_state_roots: u64,
+    pub checked_state_nodes: u64,
+    pub issues: Vec<VerifyIssue>,
+}
+
+impl VerifyReport {
+    /// All found issues are repaired or there is no issue.
+    pub fn is_ok(&self) -> bool {
+        self.issues.iter().all(|issue| issue.repaired)
+    }
+
+    fn add_issue(
+        &mut self,
+        kind: VerifyIssueKind,
+        block_number: Option<u64>,
+        block_id: HashValue,
+        detail: String,
+    ) {
+        warn!("verify {:?} of block {:?} fail: {}", kind, block_id, detail);
+        self.issues.push(VerifyIssue {
+            kind,
+            block_number,
+            block_id,
+            detail,
+            repaired: false,
+        });
+    }
+
+    fn mark_last_repaired(&mut self) {
+        if let Some(issue) = self.issues.last_mut() {
+            issue.repaired = true;
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct VerifyOptions {
+    /// Verify the state tree of every `state_sample_interval` blocks, the head state is always
+    /// verified, 0 means only verify the head state.
+    pub state_sample_interval: u64,
+    /// Rebuild the derived indexes (block number and block transactions) when they are wrong,
+    /// the storage must be writable.
+    pub repair: bool,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        Self {
+            state_sample_interval: 1000,
+            repair: false,
+        }
+    }
+}
+
+pub struct StorageVerifier {
+    storage: Arc<Storage>,
+    options: VerifyOptions,
+}
+
+impl StorageVerifier {
+    pub fn new(storage: Arc<Storage>, options: VerifyOptions) -> Self {
+        Self { storage, options }
+    }
+
+    pub fn verify(&self) -> Result<VerifyReport> {
+        let mut report = VerifyReport::default();
+        let head = self
+            .storage
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("startup info not found, the db may not be initialized"))?
+            .master;
+        report.head = Some(head);
+
+        let master_ids = match self.collect_master_ids(head, &mut report)? {
+            Some(master_ids) => master_ids,
+            None => return Ok(report),
+        };
+        let head_number = master_ids.len() as u64 - 1;
+
+        let mut parent_info: Option<BlockInfo> = None;
+        let mut visited_state_nodes = HashSet::new();
+        for (number, block_id) in master_ids.into_iter().rev().enumerate() {
+            let number = number as u64;
+            let header = self
+                .storage
+                .get_block_header_by_hash(block_id)?
+                .ok_or_else(|| format_err!("header of {:?} should exist", block_id))?;
+            let block_info =
+                self.verify_block(number, &header, parent_info.as_ref(), &mut report)?;
+            let sample_state = number == head_number
+                || (self.options.state_sample_interval > 0
+                    && number % self.options.state_sample_interval == 0);
+            if sample_state {
+                self.verify_state(
+                    number,
+                    block_id,
+                    header.state_root(),
+                    &mut visited_state_nodes,
+                    &mut report,
+                )?;
+            }
+            report.checked_blocks += 1;
+            if number % 10000 == 0 {
+                info!("verified {} blocks of {}", number, head_number + 1);
+            }
+            parent_info = block_info;
+        }
+        Ok(report)
+    }
+
+    /// Walk back from the head to genesis by parent hash, return ids of the master chain in
+    /// reverse order, or None if the chain is broken.
+    fn collect_master_ids(
+        &self,
+        head: HashValue,
+        report: &mut VerifyReport,
+    ) -> Result<Option<Vec<HashValue>>> {
+        let mut master_ids = vec![];
+        let mut block_id = head;
+        let mut child_number: Option<u64> = None;
+        loop {
+            let header = match self.storage.get_block_header_by_hash(block_id)? {
+                Some(header) => header,
+                None => {
+                    report.add_issue(
+                        VerifyIssueKind::MissingHeader,
+                        child_number.and_then(|number| number.checked_sub(1)),
+                        block_id,
+                        "header of master block not found, the chain is broken".to_string(),
+                    );
+                    return Ok(None);
+                }
+            };
+            if let Some(child_number) = child_number {
+                if header.number() + 1 != child_number {
+                    report.add_issue(
+                        VerifyIssueKind::InvalidNumber,
+                        Some(header.number()),
+                        block_id,
+                        format!("child block number is {}", child_number),
+                    );
+                    return Ok(None);
+                }
+            }
+            master_ids.push(block_id);
+            if header.number() == 0 {
+                return Ok(Some(master_ids));
+            }
+            child_number = Some(header.number());
+            block_id = header.parent_hash();
+        }
+    }
+
+    fn verify_block(
+        &self,
+        number: u64,
+        header: &BlockHeader,
+        parent_info: Option<&BlockInfo>,
+        report: &mut VerifyReport,
+    ) -> Result<Option<BlockInfo>> {
+        let block_id = header.id();
+        if self.storage.get_number(number)? != Some(block_id) {
+            report.add_issue(
+                VerifyIssueKind::NumberIndexMismatch,
+                Some(number),
+                block_id,
+                "number index does not point to the master block".to_string(),
+            );
+            if self.options.repair {
+                self.storage.block_storage.save_number(number, block_id)?;
+                report.mark_last_repaired();
+            }
+        }
+        if self.storage.get_block(block_id)?.is_none() {
+            report.add_issue(
+                VerifyIssueKind::MissingBlock,
+                Some(number),
+                block_id,
+                "block not found".to_string(),
+            );
+        }
+        let body = self.storage.get_body(block_id)?;
+        if body.is_none() {
+            report.add_issue(
+                VerifyIssueKind::MissingBody,
+                Some(number),
+                block_id,
+                "block body not found".to_string(),
+            );
+        }
+        let block_info = match self.storage.get_block_info(block_id)? {
+            Some(block_info) => block_info,
+            None => {
+                report.add_issue(
+                    VerifyIssueKind::MissingBlockInfo,
+                    Some(number),
+                    block_id,
+                    "block info not found".to_string(),
+                );
+                return Ok(None);
+            }
+        };
+        if block_info.accumulator_root != header.accumulator_root() {
+            report.add_issue(
+                VerifyIssueKind::TxnAccumulatorMismatch,
+                Some(number),
+                block_id,
+                format!(
+                    "accumulator root of block info {:?} not match header {:?}",
+                    block_info.accumulator_root,
+                    header.accumulator_root()
+                ),
+            );
+        }
+
+        let (parent_txn_accumulator, parent_block_accumulator) = match parent_info {
+            Some(parent_info) => (
+                Some(parent_info.get_txn_accumulator_info()),
+                parent_info.get_block_accumulator_info().clone(),
+            ),
+            // genesis txn info is not saved, so only the block accumulator of genesis is
+            // recomputed.
+            None if number == 0 => (
+                None,
+                AccumulatorInfo::new(*ACCUMULATOR_PLACEHOLDER_HASH, vec![], 0, 0),
+            ),
+            // parent block info is missing, it is already reported.
+            None => return Ok(Some(block_info)),
+        };
+
+        if let (Some(parent_txn_accumulator), Some(body)) = (parent_txn_accumulator, body) {
+            let user_txns: Vec<SignedUserTransaction> = body.into();
+            let mut txn_ids: Vec<HashValue> = user_txns
+                .into_iter()
+                .map(|txn| Transaction::UserTransaction(txn).id())
+                .collect();
+            // the block metadata txn is executed and saved at the end.
+            txn_ids.push(Transaction::BlockMetadata(header.clone().into_metadata()).id());
+            if self.storage.get_block_transactions(block_id).ok().as_ref() != Some(&txn_ids) {
+                report.add_issue(
+                    VerifyIssueKind::BlockTransactionsMismatch,
+                    Some(number),
+                    block_id,
+                    "block transactions index does not match the block body".to_string(),
+                );
+                if self.options.repair {
+                    self.storage
+                        .save_block_transactions(block_id, txn_ids.clone())?;
+                    report.mark_last_repaired();
+                }
+            }
+            let txn_count = txn_ids.len();
+            let mut txn_info_hashes = Vec::with_capacity(txn_count);
+            for txn_id in txn_ids {
+                match self.storage.get_transaction_info(txn_id)? {
+                    Some(txn_info) => txn_info_hashes.push(txn_info.crypto_hash()),
+                    None => report.add_issue(
+                        VerifyIssueKind::MissingTransactionInfo,
+                        Some(number),
+                        block_id,
+                        format!("transaction info of txn {:?} not found", txn_id),
+                    ),
+                }
+            }
+            // can not recompute the accumulator if some txn info is missing.
+            if txn_info_hashes.len() == txn_count {
+                self.verify_accumulator(
+                    number,
+                    block_id,
+                    VerifyIssueKind::TxnAccumulatorMismatch,
+                    &parent_txn_accumulator,
+                    AccumulatorStoreType::Transaction,
+                    &txn_info_hashes,
+                    &block_info.get_txn_accumulator_info(),
+                    report,
+                );
+            }
+        }
+
+        self.verify_accumulator(
+            number,
+            block_id,
+            VerifyIssueKind::BlockAccumulatorMismatch,
+            &parent_block_accumulator,
+            AccumulatorStoreType::Block,
+            &[block_id],
+            block_info.get_block_accumulator_info(),
+            report,
+        );
+        Ok(Some(block_info))
+    }
+
+    /// Append `leaves` to the parent accumulator in memory, and compare the result with the
+    /// saved accumulator info, nothing is flushed to storage.
+    #[allow(clippy::too_many_arguments)]
+    fn verify_accumulator(
+        &self,
+        number: u64,
+        block_id: HashValue,
+        kind: VerifyIssueKind,
+        parent: &AccumulatorInfo,
+        store_type: AccumulatorStoreType,
+        leaves: &[HashValue],
+        expect: &AccumulatorInfo,
+        report: &mut VerifyReport,
+    ) {
+        let result = MerkleAccumulator::new(
+            *parent.get_accumulator_root(),
+            parent.get_frozen_subtree_roots().clone(),
+            parent.get_num_leaves(),
+            parent.get_num_nodes(),
+            store_type,
+            self.storage.clone(),
+        )
+        .and_then(|accumulator| {
+            accumulator.append(leaves)?;
+            Ok((accumulator.root_hash(), accumulator.num_leaves()))
+        });
+        match result {
+            Ok((root, num_leaves)) => {
+                if root != *expect.get_accumulator_root() || num_leaves != expect.get_num_leaves() {
+                    report.add_issue(
+                        kind,
+                        Some(number),
+                        block_id,
+                        format!(
+                            "recomputed root {:?} with {} leaves, but saved root {:?} with {} leaves",
+                            root,
+                            num_leaves,
+                            expect.get_accumulator_root(),
+                            expect.get_num_leaves()
+                        ),
+                    );
+                }
+            }
+            Err(e) => report.add_issue(
+                kind,
+                Some(number),
+                block_id,
+                format!("recompute accumulator error: {:?}", e),
+            ),
+        }
+    }
+
+    /// Re-hash every state node reachable from `state_root`, include the account storage trees.
+    /// Nodes in `visited` are skipped, so sampled states only pay for the changed nodes.
+    fn verify_state(
+        &self,
+        number: u64,
+        block_id: HashValue,
+        state_root: HashValue,
+        visited: &mut HashSet<HashValue>,
+        report: &mut VerifyReport,
+    ) -> Result<()> {
+        report.checked_state_roots += 1;
+        // (node hash, is the node of the global state tree)
+        let mut pending = vec![(state_root, true)];
+        while let Some((hash, is_global)) = pending.pop() {
+            if hash == *SPARSE_MERKLE_PLACEHOLDER_HASH || !visited.insert(hash) {
+                continue;
+            }
+            let node = match StateNodeStore::get(self.storage.as_ref(), &hash)? {
+                Some(node) => node.0,
+                None => {
+                    report.add_issue(
+                        VerifyIssueKind::MissingStateNode,
+                        Some(number),
+                        block_id,
+                        format!("state node {:?} not found", hash),
+                    );
+                    continue;
+                }
+            };
+            report.checked_state_nodes += 1;
+            if node.hash() != hash {
+                report.add_issue(
+                    VerifyIssueKind::StateNodeHashMismatch,
+                    Some(number),
+                    block_id,
+                    format!("state node {:?} is hashed to {:?}", hash, node.hash()),
+                );
+                continue;
+            }
+            match node {
+                Node::Internal(internal) => {
+                    for nibble in 0..16u8 {
+                        if let Some(child) = internal.child(Nibble::from(nibble)) {
+                            pending.push((child.hash, is_global));
+                        }
+                    }
+                }
+                Node::Leaf(leaf) if is_global => {
+                    match AccountState::try_from(leaf.blob().as_ref()) {
+                        Ok(account_state) => {
+                            for storage_root in account_state.storage_roots().iter().flatten() {
+                                pending.push((*storage_root, false));
+                            }
+                        }
+                        Err(e) => report.add_issue(
+                            VerifyIssueKind::InvalidAccountState,
+                            Some(number),
+                            block_id,
+                            format!("decode account state of leaf {:?} error: {:?}", hash, e),
+                        ),
+                    }
+                }
+                Node::Leaf(_) | Node::Null => {}
+            }
+        }
+        Ok(())
+    }
+}
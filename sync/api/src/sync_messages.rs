@@ -1,10 +1,10 @@
 use actix::prelude::*;
-use anyhow::Result;
+use network_api::RpcProtocol;
 use serde::{Deserialize, Serialize};
 use starcoin_accumulator::node::AccumulatorStoreType;
 use starcoin_accumulator::AccumulatorNode;
 use starcoin_crypto::HashValue;
-use starcoin_state_tree::StateNode;
+use starcoin_state_tree::{SparseMerkleRangeProof, StateNode};
 use starcoin_types::peer_info::PeerId;
 use starcoin_types::{
     block::{Block, BlockHeader, BlockInfo},
@@ -37,26 +37,74 @@ impl PeerNewBlock {
     }
 }
 
-#[derive(Message, Clone, Serialize, Deserialize, Debug)]
-#[rtype(result = "Result<()>")]
-pub enum SyncRpcRequest {
-    GetHashByNumberMsg(GetHashByNumberMsg),
-    GetDataByHashMsg(GetDataByHashMsg),
-    GetStateNodeByNodeHash(HashValue),
-    GetAccumulatorNodeByNodeHash(HashValue, AccumulatorStoreType),
-    GetTxns(GetTxns),
+pub struct GetTxnsProtocol;
+
+impl RpcProtocol for GetTxnsProtocol {
+    const NAME: &'static str = "/starcoin/sync/txns/1";
+    type Request = GetTxns;
+    type Response = TransactionsData;
 }
 
-#[derive(Message, Clone, Serialize, Deserialize)]
-#[rtype(result = "Result<()>")]
-pub enum SyncRpcResponse {
-    BatchHashByNumberMsg(BatchHashByNumberMsg),
-    BatchHeaderAndBodyMsg(BatchHeaderMsg, BatchBodyMsg, BatchBlockInfo),
-    GetStateNodeByNodeHash(StateNode),
-    GetAccumulatorNodeByNodeHash(AccumulatorNode),
-    GetTxns(TransactionsData),
+pub struct GetHashByNumberProtocol;
+
+impl RpcProtocol for GetHashByNumberProtocol {
+    const NAME: &'static str = "/starcoin/sync/hash_by_number/1";
+    type Request = GetHashByNumberMsg;
+    type Response = BatchHashByNumberMsg;
 }
 
+/// Get headers, bodies and infos of blocks by hash.
+pub struct GetBlocksProtocol;
+
+impl RpcProtocol for GetBlocksProtocol {
+    const NAME: &'static str = "/starcoin/sync/blocks/1";
+    type Request = GetDataByHashMsg;
+    type Response = (BatchHeaderMsg, BatchBodyMsg, BatchBlockInfo);
+}
+
+pub struct GetHeadersProtocol;
+
+impl RpcProtocol for GetHeadersProtocol {
+    const NAME: &'static str = "/starcoin/sync/headers/1";
+    type Request = Vec<HashValue>;
+    type Response = BatchHeaderMsg;
+}
+
+pub struct GetStateNodeProtocol;
+
+impl RpcProtocol for GetStateNodeProtocol {
+    const NAME: &'static str = "/starcoin/sync/state_node/1";
+    type Request = HashValue;
+    type Response = StateNode;
+}
+
+pub struct GetAccumulatorNodeProtocol;
+
+impl RpcProtocol for GetAccumulatorNodeProtocol {
+    const NAME: &'static str = "/starcoin/sync/accumulator_node/1";
+    type Request = (HashValue, AccumulatorStoreType);
+    type Response = AccumulatorNode;
+}
+
+pub struct GetStateChunkProtocol;
+
+impl RpcProtocol for GetStateChunkProtocol {
+    const NAME: &'static str = "/starcoin/sync/state_chunk/1";
+    type Request = GetStateChunk;
+    type Response = StateChunk;
+}
+
+/// Request-response protocols served by the sync process.
+pub const SYNC_RPC_PROTOCOLS: &[&str] = &[
+    GetTxnsProtocol::NAME,
+    GetHashByNumberProtocol::NAME,
+    GetBlocksProtocol::NAME,
+    GetHeadersProtocol::NAME,
+    GetStateNodeProtocol::NAME,
+    GetAccumulatorNodeProtocol::NAME,
+    GetStateChunkProtocol::NAME,
+];
+
 #[derive(Debug, Message, Clone, Serialize, Deserialize)]
 #[rtype(result = "()")]
 pub enum SyncNotify {
@@ -73,6 +121,25 @@ pub struct TransactionsData {
     pub txns: Vec<SignedUserTransaction>,
 }
 
+/// Max number of kv pairs in a `StateChunk`.
+pub const MAX_STATE_CHUNK_SIZE: u64 = 1000;
+
+/// Request kv pairs of the state tree `state_root` in key order, starting after `last_key`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetStateChunk {
+    pub state_root: HashValue,
+    pub last_key: Option<HashValue>,
+    pub limit: u64,
+}
+
+/// Ordered kv pairs of a state tree, with the range proof of the last pair against the state root.
+/// An empty chunk means there is no more key after `last_key`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StateChunk {
+    pub blobs: Vec<(HashValue, Vec<u8>)>,
+    pub proof: Option<SparseMerkleRangeProof>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetHashByNumberMsg {
     pub numbers: Vec<u64>,
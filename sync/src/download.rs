@@ -2,16 +2,20 @@
 use crate::pool::TTLPool;
 use actix::prelude::*;
 use actix::{Actor, Addr, AsyncContext, Context, Handler};
-use anyhow::{format_err, Result};
+use anyhow::{ensure, format_err, Result};
 use bus::{BusActor, Subscription};
 use chain::ChainActorRef;
 use futures::channel::mpsc;
-use parking_lot::RwLock;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use parking_lot::Mutex;
 // use itertools;
 use crate::helper::{get_block_by_hash, get_hash_by_number, get_header_by_hash};
 use crate::state_sync::StateSyncTaskActor;
-use crate::sync_metrics::{LABEL_BLOCK, LABEL_HASH, LABEL_STATE, SYNC_METRICS};
-use config::NodeConfig;
+use crate::sync_metrics::{
+    LABEL_BLOCK, LABEL_EXPIRED, LABEL_FULL, LABEL_HASH, LABEL_HEADER, LABEL_PARENT, LABEL_QUOTA,
+    LABEL_STATE, SYNC_METRICS,
+};
+use config::{Checkpoint, NodeConfig};
 use crypto::HashValue;
 use futures_timer::Delay;
 use logger::prelude::*;
@@ -23,15 +27,17 @@ use starcoin_sync_api::sync_messages::{
     GetHashByNumberMsg, HashWithNumber, SyncNotify,
 };
 use starcoin_sync_api::SyncMetadata;
-use std::collections::{HashMap, HashSet};
+use std::cmp::min;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use traits::ChainAsyncService;
 use traits::{is_ok, ConnectBlockError, Consensus};
 use types::{
     block::{Block, BlockHeader, BlockInfo, BlockNumber, BlockState},
-    peer_info::PeerId,
+    peer_info::{PeerId, PeerInfo},
     system_events::SyncBegin,
 };
 
@@ -75,11 +81,12 @@ where
         storage: Arc<dyn Store>,
         sync_metadata: SyncMetadata,
     ) -> Result<Addr<DownloadActor<C>>> {
+        let checkpoints = node_config.checkpoints();
         let download_actor = DownloadActor::create(move |ctx| {
             let (sync_event_sender, sync_event_receiver) = mpsc::channel(100);
             ctx.add_message_stream(sync_event_receiver);
             DownloadActor {
-                downloader: Arc::new(Downloader::new(chain_reader)),
+                downloader: Arc::new(Downloader::new(chain_reader, checkpoints)),
                 self_peer_id: peer_id,
                 network,
                 bus,
@@ -213,7 +220,7 @@ where
                     );
                 }
             }
-            SyncNotify::NewHeadBlock(_peer_id, block) => self.do_block_and_child(*block, None),
+            SyncNotify::NewHeadBlock(peer_id, block) => self.do_block_from_peer(peer_id, *block),
             SyncNotify::ClosePeerMsg(peer_id) => {
                 debug!("close peer: {:?}", peer_id);
             }
@@ -317,6 +324,13 @@ where
 
                 // 2. pivot
                 let latest_number = best_peer.get_block_number();
+                Downloader::verify_peer_checkpoints(
+                    downloader.clone(),
+                    &network,
+                    best_peer.get_peer_id(),
+                    latest_number,
+                )
+                .await?;
                 let min_behind = if main_network {
                     MAIN_MIN_BLOCKS_BEHIND
                 } else {
@@ -423,7 +437,7 @@ where
     fn sync_block_from_best_peer(
         sync_metadata: SyncMetadata,
         syncing: Arc<AtomicBool>,
-        _self_peer_id: PeerId,
+        self_peer_id: PeerId,
         downloader: Arc<Downloader<C>>,
         network: NetworkAsyncService,
     ) {
@@ -434,7 +448,8 @@ where
                 .inc();
             let full_mode = sync_metadata.state_syncing();
             if let Err(e) =
-                Self::sync_block_from_best_peer_inner(downloader, network, full_mode).await
+                Self::sync_block_from_best_peer_inner(self_peer_id, downloader, network, full_mode)
+                    .await
             {
                 error!("sync block from best peer failed : {:?}", e);
             } else {
@@ -450,13 +465,14 @@ where
     }
 
     async fn sync_block_from_best_peer_inner(
+        self_peer_id: PeerId,
         downloader: Arc<Downloader<C>>,
         network: NetworkAsyncService,
         full_mode: bool,
     ) -> Result<()> {
         if let Some(best_peer) = network.best_peer().await? {
             if let Some(header) = downloader.chain_reader.clone().master_head_header().await? {
-                let mut begin_number = header.number();
+                let begin_number = header.number();
                 let head_executed = if let Some(head_state) = downloader
                     .chain_reader
                     .clone()
@@ -478,71 +494,25 @@ where
                 )
                 .await?
                 {
-                    begin_number = hash_number.number + 1;
-                    //1. sync hash
-                    while let Some((get_hash_by_number_msg, end, next_number)) =
-                        Downloader::<C>::get_hash_by_number_msg_forward(
-                            network.clone(),
-                            best_peer.get_peer_id(),
-                            begin_number,
-                        )
+                    let ancestor = downloader
+                        .chain_reader
+                        .clone()
+                        .get_header_by_hash(&hash_number.hash)
                         .await?
-                    {
-                        begin_number = next_number;
-                        SYNC_METRICS
-                            .sync_total_count
-                            .with_label_values(&[LABEL_HASH])
-                            .inc_by(get_hash_by_number_msg.numbers.len() as i64);
-                        let hash_timer = SYNC_METRICS
-                            .sync_done_time
-                            .with_label_values(&[LABEL_HASH])
-                            .start_timer();
-                        let batch_hash_by_number_msg = get_hash_by_number(
-                            &network,
-                            best_peer.get_peer_id(),
-                            get_hash_by_number_msg,
-                        )
-                        .await?;
-                        hash_timer.observe_duration();
-
-                        Downloader::put_hash_2_hash_pool(
-                            downloader.clone(),
-                            best_peer.get_peer_id(),
-                            batch_hash_by_number_msg,
-                        );
-
-                        let hashs = Downloader::take_task_from_hash_pool(downloader.clone());
-                        if !hashs.is_empty() {
-                            SYNC_METRICS
-                                .sync_total_count
-                                .with_label_values(&[LABEL_BLOCK])
-                                .inc_by(hashs.len() as i64);
-                            let block_timer = SYNC_METRICS
-                                .sync_done_time
-                                .with_label_values(&[LABEL_BLOCK])
-                                .start_timer();
-                            let (headers, bodies, infos) =
-                                get_block_by_hash(&network, best_peer.get_peer_id(), hashs).await?;
-                            block_timer.observe_duration();
-                            SYNC_METRICS
-                                .sync_succ_count
-                                .with_label_values(&[LABEL_BLOCK])
-                                .inc_by(headers.headers.len() as i64);
-                            Downloader::do_blocks(
-                                downloader.clone(),
-                                headers.headers,
-                                bodies.bodies,
-                                infos.infos,
-                            )
-                            .await;
-                        } else {
-                            debug!("{:?}", "hash pool is empty.");
-                        }
-
-                        if end {
-                            break;
-                        }
-                    }
+                        .ok_or_else(|| {
+                            format_err!("Ancestor header {:?} is none.", hash_number.hash)
+                        })?;
+                    let peers =
+                        Downloader::<C>::sync_peers(&network, &self_peer_id, ancestor.number())
+                            .await?;
+                    Downloader::sync_blocks_from_peers(
+                        downloader.clone(),
+                        network.clone(),
+                        best_peer,
+                        peers,
+                        ancestor,
+                    )
+                    .await?;
                 }
             } else {
                 return Err(format_err!("{:?}", "block header is none."));
@@ -558,78 +528,214 @@ where
     pub fn do_block_and_child(&self, block: Block, block_info: Option<BlockInfo>) {
         let downloader = self.downloader.clone();
         Arbiter::spawn(async move {
-            Downloader::do_block_and_child(downloader, block, block_info).await;
+            Downloader::do_block_and_child(downloader, block, block_info, None).await;
+        });
+    }
+
+    pub fn do_block_from_peer(&self, peer_id: PeerId, block: Block) {
+        let downloader = self.downloader.clone();
+        let network = self.network.clone();
+        Arbiter::spawn(async move {
+            Downloader::do_block_from_peer(downloader, network, peer_id, block).await;
         });
     }
 }
 
+/// Max number of future blocks in the pool.
+const MAX_FUTURE_BLOCKS: usize = 1024;
+/// Max number of future blocks announced by one peer in the pool.
+const MAX_FUTURE_BLOCKS_PER_PEER: usize = 128;
+/// Future blocks which wait longer than it for their parent are dropped.
+const FUTURE_BLOCK_TTL: Duration = Duration::from_secs(30 * 60);
+/// Max number of missing ancestors fetched for a block announced by a peer.
+const MAX_PARENT_FETCH_DEPTH: usize = 16;
+
+struct FutureBlock {
+    block: Block,
+    block_info: Option<BlockInfo>,
+    peer_id: Option<PeerId>,
+    insert_time: Instant,
+}
+
+#[derive(Default)]
+struct FutureBlocks {
+    blocks: HashMap<HashValue, FutureBlock>,
+    child: HashMap<HashValue, HashSet<HashValue>>,
+    peer_counts: HashMap<PeerId, usize>,
+    /// Block ids in insert order, entries of removed blocks are skipped lazily.
+    order: VecDeque<(HashValue, Instant)>,
+}
+
+impl FutureBlocks {
+    fn remove(&mut self, block_id: &HashValue) -> Option<FutureBlock> {
+        let future_block = self.blocks.remove(block_id)?;
+        let parent_id = future_block.block.header().parent_hash();
+        if let Some(child) = self.child.get_mut(&parent_id) {
+            child.remove(block_id);
+            if child.is_empty() {
+                self.child.remove(&parent_id);
+            }
+        }
+        if let Some(peer_id) = &future_block.peer_id {
+            if let Some(count) = self.peer_counts.get_mut(peer_id) {
+                *count -= 1;
+                if *count == 0 {
+                    self.peer_counts.remove(peer_id);
+                }
+            }
+        }
+        Some(future_block)
+    }
+
+    /// Remove the oldest block which inserted before `deadline`, or the oldest block if `deadline` is none.
+    fn remove_oldest(&mut self, deadline: Option<Instant>) -> bool {
+        while let Some((block_id, insert_time)) = self.order.front().cloned() {
+            let alive = self
+                .blocks
+                .get(&block_id)
+                .map(|future_block| future_block.insert_time == insert_time)
+                .unwrap_or(false);
+            if alive && deadline.map(|d| insert_time >= d).unwrap_or(false) {
+                return false;
+            }
+            self.order.pop_front();
+            if alive {
+                self.remove(&block_id);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Drop the entries of removed blocks from `order`.
+    fn compact(&mut self) {
+        let blocks = &self.blocks;
+        self.order.retain(|(block_id, insert_time)| {
+            blocks
+                .get(block_id)
+                .map(|future_block| future_block.insert_time == *insert_time)
+                .unwrap_or(false)
+        });
+    }
+
+    fn descendants(&self, parent_id: &HashValue) -> Vec<HashValue> {
+        let mut descendants = Vec::new();
+        let mut parents = vec![*parent_id];
+        while let Some(parent_id) = parents.pop() {
+            if let Some(child) = self.child.get(&parent_id) {
+                for id in child {
+                    descendants.push(*id);
+                    parents.push(*id);
+                }
+            }
+        }
+        descendants
+    }
+}
+
+/// Blocks whose parent is not connected yet, bounded by size, age and per-peer quota.
 struct FutureBlockPool {
-    child: Arc<RwLock<HashMap<HashValue, HashSet<HashValue>>>>,
-    blocks: Arc<RwLock<HashMap<HashValue, (Block, Option<BlockInfo>)>>>,
+    inner: Mutex<FutureBlocks>,
+    ttl: Duration,
 }
 
 impl FutureBlockPool {
     pub fn new() -> Self {
+        Self::new_with_ttl(FUTURE_BLOCK_TTL)
+    }
+
+    fn new_with_ttl(ttl: Duration) -> Self {
         FutureBlockPool {
-            child: Arc::new(RwLock::new(HashMap::new())),
-            blocks: Arc::new(RwLock::new(HashMap::new())),
+            inner: Mutex::new(FutureBlocks::default()),
+            ttl,
         }
     }
 
-    pub fn add_future_block(&self, block: Block, block_info: Option<BlockInfo>) {
+    /// Add the block to pool, unless it already exists or `peer_id` exceeds its quota.
+    /// Expired blocks are dropped first, then the oldest blocks if the pool is full.
+    pub fn add_future_block(
+        &self,
+        block: Block,
+        block_info: Option<BlockInfo>,
+        peer_id: Option<PeerId>,
+    ) {
         let block_id = block.header().id();
         let parent_id = block.header().parent_hash();
-        if !self.blocks.read().contains_key(&block_id) {
-            self.blocks.write().insert(block_id, (block, block_info));
-        }
-        let mut lock = self.child.write();
-        if lock.contains_key(&parent_id) {
-            lock.get_mut(&parent_id)
-                .expect("parent not exist.")
-                .insert(block_id);
-        } else {
-            let mut child = HashSet::new();
-            child.insert(block_id);
-            lock.insert(parent_id, child);
+        let now = Instant::now();
+        let mut inner = self.inner.lock();
+        if let Some(deadline) = now.checked_sub(self.ttl) {
+            while inner.remove_oldest(Some(deadline)) {
+                SYNC_METRICS
+                    .orphan_drop_count
+                    .with_label_values(&[LABEL_EXPIRED])
+                    .inc();
+            }
+            SYNC_METRICS.orphan_count.set(inner.blocks.len() as i64);
         }
-    }
-
-    fn descendants(&self, parent_id: &HashValue) -> Vec<HashValue> {
-        let mut child = Vec::new();
-        let lock = self.child.read();
-        if lock.contains_key(parent_id) {
-            lock.get(parent_id)
-                .expect("parent not exist.")
-                .iter()
-                .for_each(|id| {
-                    child.push(*id);
-                });
-
-            if !child.is_empty() {
-                child.clone().iter().for_each(|new_parent_id| {
-                    let mut new_child = self.descendants(new_parent_id);
-                    if !new_child.is_empty() {
-                        child.append(&mut new_child);
-                    }
-                })
+        if inner.blocks.contains_key(&block_id) {
+            return;
+        }
+        if let Some(peer_id) = &peer_id {
+            if inner.peer_counts.get(peer_id).cloned().unwrap_or(0) >= MAX_FUTURE_BLOCKS_PER_PEER {
+                debug!("Peer {:?} exceeds future block quota.", peer_id);
+                SYNC_METRICS
+                    .orphan_drop_count
+                    .with_label_values(&[LABEL_QUOTA])
+                    .inc();
+                return;
             }
-        };
+            *inner.peer_counts.entry(peer_id.clone()).or_insert(0) += 1;
+        }
+        while inner.blocks.len() >= MAX_FUTURE_BLOCKS && inner.remove_oldest(None) {
+            SYNC_METRICS
+                .orphan_drop_count
+                .with_label_values(&[LABEL_FULL])
+                .inc();
+        }
+        inner.blocks.insert(
+            block_id,
+            FutureBlock {
+                block,
+                block_info,
+                peer_id,
+                insert_time: now,
+            },
+        );
+        inner
+            .child
+            .entry(parent_id)
+            .or_insert_with(HashSet::new)
+            .insert(block_id);
+        inner.order.push_back((block_id, now));
+        if inner.order.len() > 2 * MAX_FUTURE_BLOCKS {
+            inner.compact();
+        }
+        SYNC_METRICS.orphan_count.set(inner.blocks.len() as i64);
+    }
 
-        child
+    pub fn contains(&self, block_id: &HashValue) -> bool {
+        self.inner.lock().blocks.contains_key(block_id)
     }
 
-    pub fn take_child(&self, parent_id: &HashValue) -> Option<Vec<(Block, Option<BlockInfo>)>> {
-        let descendants = self.descendants(parent_id);
+    pub fn take_child(
+        &self,
+        parent_id: &HashValue,
+    ) -> Option<Vec<(Block, Option<BlockInfo>, Option<PeerId>)>> {
+        let mut inner = self.inner.lock();
+        let descendants = inner.descendants(parent_id);
         if !descendants.is_empty() {
-            let mut child = Vec::new();
-            let mut child_lock = self.child.write();
-            let mut block_lock = self.blocks.write();
-            descendants.iter().for_each(|id| {
-                let _ = child_lock.remove(id);
-                if let Some((block, block_info)) = block_lock.remove(id) {
-                    child.push((block, block_info));
-                }
-            });
+            let child = descendants
+                .iter()
+                .filter_map(|id| inner.remove(id))
+                .map(|future_block| {
+                    (
+                        future_block.block,
+                        future_block.block_info,
+                        future_block.peer_id,
+                    )
+                })
+                .collect();
+            SYNC_METRICS.orphan_count.set(inner.blocks.len() as i64);
             Some(child)
         } else {
             None
@@ -637,19 +743,35 @@ impl FutureBlockPool {
     }
 }
 
+/// Blocks of a download window, and the peer which served them.
+struct BlockWindow {
+    /// Peers to try for the window, in order.
+    peers: Vec<PeerId>,
+    peer_id: PeerId,
+    headers: Vec<BlockHeader>,
+    bodies: Vec<BlockBody>,
+    infos: Vec<BlockInfo>,
+}
+
 /// Send download message
 pub struct Downloader<C>
 where
     C: Consensus + Sync + Send + 'static + Clone,
 {
-    hash_pool: TTLPool<HashWithNumber>,
     _header_pool: TTLPool<BlockHeader>,
     _body_pool: TTLPool<BlockBody>,
     chain_reader: ChainActorRef<C>,
     future_blocks: FutureBlockPool,
+    checkpoints: Vec<Checkpoint>,
 }
 
 const HEAD_CT: u64 = 10;
+/// Max number of headers fetched and verified before their bodies are downloaded.
+const HEADER_BATCH_SIZE: u64 = 200;
+/// Number of blocks requested from one peer at a time.
+const DOWNLOAD_WINDOW_SIZE: usize = 20;
+/// Max number of windows downloaded from peers concurrently.
+const MAX_WINDOWS_IN_FLIGHT: usize = 8;
 const MIN_BLOCKS_BEHIND: u64 = 10;
 const MAIN_MIN_BLOCKS_BEHIND: u64 = 100;
 
@@ -657,13 +779,13 @@ impl<C> Downloader<C>
 where
     C: Consensus + Sync + Send + 'static + Clone,
 {
-    pub fn new(chain_reader: ChainActorRef<C>) -> Self {
+    pub fn new(chain_reader: ChainActorRef<C>, checkpoints: Vec<Checkpoint>) -> Self {
         Downloader {
-            hash_pool: TTLPool::new(),
             _header_pool: TTLPool::new(),
             _body_pool: TTLPool::new(),
             chain_reader,
             future_blocks: FutureBlockPool::new(),
+            checkpoints,
         }
     }
 
@@ -705,38 +827,6 @@ where
         }
     }
 
-    pub async fn get_hash_by_number_msg_forward(
-        network: NetworkAsyncService,
-        peer_id: PeerId,
-        begin_number: u64,
-    ) -> Result<Option<(GetHashByNumberMsg, bool, u64)>> {
-        if let Some(peer_info) = network.get_peer(&peer_id.clone().into()).await? {
-            let number = peer_info.get_block_number();
-            if begin_number < number {
-                let mut numbers = Vec::new();
-                let mut end = false;
-                let next_number = if (number - begin_number) < HEAD_CT {
-                    for i in begin_number..(number + 1) {
-                        numbers.push(i);
-                        end = true;
-                    }
-                    number + 1
-                } else {
-                    for i in begin_number..(begin_number + HEAD_CT) {
-                        numbers.push(i);
-                    }
-                    begin_number + HEAD_CT
-                };
-
-                Ok(Some((GetHashByNumberMsg { numbers }, end, next_number)))
-            } else {
-                Ok(None)
-            }
-        } else {
-            Err(format_err!("peer {:?} not exist.", peer_id))
-        }
-    }
-
     pub async fn find_ancestor(
         downloader: Arc<Downloader<C>>,
         peer_id: PeerId,
@@ -761,7 +851,6 @@ where
                 get_hash_by_number(&network, peer_id.clone(), get_hash_by_number_msg).await?;
             hash_with_number = Downloader::do_ancestor(
                 downloader.clone(),
-                peer_id.clone(),
                 batch_hash_by_number_msg,
                 need_executed,
             )
@@ -780,14 +869,11 @@ where
 
     pub async fn do_ancestor(
         downloader: Arc<Downloader<C>>,
-        peer: PeerId,
         batch_hash_by_number_msg: BatchHashByNumberMsg,
         need_executed: bool,
     ) -> Result<Option<HashWithNumber>> {
-        let mut exist_ancestor = false;
         let mut ancestor = None;
-        let mut hashs = batch_hash_by_number_msg.hashs.clone();
-        let mut not_exist_hash = Vec::new();
+        let mut hashs = batch_hash_by_number_msg.hashs;
         hashs.reverse();
         for hash in hashs {
             if let Some(block_state) = downloader
@@ -797,46 +883,15 @@ where
                 .await?
             {
                 if !need_executed || block_state == BlockState::Executed {
-                    exist_ancestor = true;
                     ancestor = Some(hash);
                     break;
                 }
-            } else {
-                not_exist_hash.push(hash);
             }
         }
 
-        if exist_ancestor {
-            for hash in not_exist_hash {
-                downloader.hash_pool.insert(peer.clone(), hash.number, hash);
-            }
-        }
         Ok(ancestor)
     }
 
-    pub fn put_hash_2_hash_pool(
-        downloader: Arc<Downloader<C>>,
-        peer: PeerId,
-        batch_hash_by_number_msg: BatchHashByNumberMsg,
-    ) {
-        for hash in batch_hash_by_number_msg.hashs {
-            downloader.hash_pool.insert(peer.clone(), hash.number, hash);
-            SYNC_METRICS
-                .sync_succ_count
-                .with_label_values(&[LABEL_HASH])
-                .inc();
-        }
-    }
-
-    pub fn take_task_from_hash_pool(downloader: Arc<Downloader<C>>) -> Vec<HashValue> {
-        let hash_vec = downloader.hash_pool.take(100);
-        if !hash_vec.is_empty() {
-            hash_vec.iter().map(|hash| hash.hash).collect()
-        } else {
-            Vec::new()
-        }
-    }
-
     pub async fn _put_header_2_header_pool(
         downloader: Arc<Downloader<C>>,
         peer: PeerId,
@@ -866,46 +921,463 @@ where
         }
     }
 
+    /// Refuse the hashs of `peer_id` if any of them conflicts with a checkpoint.
+    fn check_checkpoints(&self, peer_id: &PeerId, hashs: &[HashWithNumber]) -> Result<()> {
+        for hash in hashs {
+            if let Some(checkpoint) = self
+                .checkpoints
+                .iter()
+                .find(|checkpoint| checkpoint.number == hash.number)
+            {
+                ensure!(
+                    checkpoint.hash == hash.hash,
+                    "Peer {:?} conflicts with checkpoint {}.",
+                    peer_id,
+                    checkpoint
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Refuse and ban `peer_id` unless its chain contains all the checkpoints not higher than
+    /// `latest_number`.
+    pub async fn verify_peer_checkpoints(
+        downloader: Arc<Downloader<C>>,
+        network: &NetworkAsyncService,
+        peer_id: PeerId,
+        latest_number: BlockNumber,
+    ) -> Result<()> {
+        let numbers: Vec<BlockNumber> = downloader
+            .checkpoints
+            .iter()
+            .map(|checkpoint| checkpoint.number)
+            .filter(|number| *number <= latest_number)
+            .collect();
+        if numbers.is_empty() {
+            return Ok(());
+        }
+        let batch_hash_by_number_msg = get_hash_by_number(
+            network,
+            peer_id.clone(),
+            GetHashByNumberMsg {
+                numbers: numbers.clone(),
+            },
+        )
+        .await?;
+        let hashs = batch_hash_by_number_msg.hashs;
+        let checked = if !match_numbers(&hashs, &numbers) {
+            Err(format_err!(
+                "Peer {:?} misses some checkpoint blocks.",
+                peer_id
+            ))
+        } else {
+            downloader.check_checkpoints(&peer_id, &hashs)
+        };
+        if checked.is_err() {
+            SYNC_METRICS
+                .sync_verify_fail_count
+                .with_label_values(&[LABEL_HASH])
+                .inc();
+            network.ban_peer(peer_id.into());
+        }
+        checked
+    }
+
+    /// Peers which can serve blocks after `ancestor_number`, best first.
+    pub async fn sync_peers(
+        network: &NetworkAsyncService,
+        self_peer_id: &PeerId,
+        ancestor_number: BlockNumber,
+    ) -> Result<Vec<PeerInfo>> {
+        Ok(network
+            .best_peer_set()
+            .await?
+            .into_iter()
+            .filter(|peer| {
+                &peer.get_peer_id() != self_peer_id && peer.get_block_number() > ancestor_number
+            })
+            .collect())
+    }
+
+    /// Sync blocks after `ancestor` up to the head of `best_peer`.
+    /// The hashes come from `best_peer`. Headers are fetched from all `peers` in parallel and
+    /// verified as a chain before any body is downloaded. Bodies are then downloaded in windows
+    /// from all `peers` too, and the blocks are connected in order.
+    pub async fn sync_blocks_from_peers(
+        downloader: Arc<Downloader<C>>,
+        network: NetworkAsyncService,
+        best_peer: PeerInfo,
+        peers: Vec<PeerInfo>,
+        ancestor: BlockHeader,
+    ) -> Result<()> {
+        let target_number = best_peer.get_block_number();
+        let mut parent = ancestor;
+        while parent.number() < target_number {
+            let begin_number = parent.number() + 1;
+            let end_number = min(begin_number + HEADER_BATCH_SIZE, target_number + 1);
+
+            //1. sync hash
+            let numbers: Vec<BlockNumber> = (begin_number..end_number).collect();
+            SYNC_METRICS
+                .sync_total_count
+                .with_label_values(&[LABEL_HASH])
+                .inc_by(numbers.len() as i64);
+            let hash_timer = SYNC_METRICS
+                .sync_done_time
+                .with_label_values(&[LABEL_HASH])
+                .start_timer();
+            let batch_hash_by_number_msg = get_hash_by_number(
+                &network,
+                best_peer.get_peer_id(),
+                GetHashByNumberMsg { numbers },
+            )
+            .await?;
+            hash_timer.observe_duration();
+            downloader
+                .check_checkpoints(&best_peer.get_peer_id(), &batch_hash_by_number_msg.hashs)?;
+            let hashs: Vec<HashValue> = batch_hash_by_number_msg
+                .hashs
+                .iter()
+                .zip(begin_number..end_number)
+                .take_while(|(hash, number)| hash.number == *number)
+                .map(|(hash, _)| hash.hash)
+                .collect();
+            if hashs.is_empty() {
+                return Err(format_err!(
+                    "Peer {:?} has no block hash from {:?}.",
+                    best_peer.get_peer_id(),
+                    begin_number
+                ));
+            }
+            SYNC_METRICS
+                .sync_succ_count
+                .with_label_values(&[LABEL_HASH])
+                .inc_by(hashs.len() as i64);
+
+            //2. sync and verify header
+            SYNC_METRICS
+                .sync_total_count
+                .with_label_values(&[LABEL_HEADER])
+                .inc_by(hashs.len() as i64);
+            let header_timer = SYNC_METRICS
+                .sync_done_time
+                .with_label_values(&[LABEL_HEADER])
+                .start_timer();
+            let headers: Vec<BlockHeader> = stream::iter(
+                hashs
+                    .chunks(DOWNLOAD_WINDOW_SIZE)
+                    .enumerate()
+                    .map(|(index, window)| {
+                        let last_number =
+                            begin_number + (index * DOWNLOAD_WINDOW_SIZE + window.len()) as u64 - 1;
+                        Self::fetch_headers(
+                            network.clone(),
+                            window_peers(&peers, index, last_number),
+                            window.to_vec(),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .buffered(MAX_WINDOWS_IN_FLIGHT)
+            .try_concat()
+            .await?;
+            header_timer.observe_duration();
+            Self::verify_header_chain(downloader.clone(), &parent, &headers).await?;
+            SYNC_METRICS
+                .sync_succ_count
+                .with_label_values(&[LABEL_HEADER])
+                .inc_by(headers.len() as i64);
+
+            //3. sync body and connect block in order
+            SYNC_METRICS
+                .sync_total_count
+                .with_label_values(&[LABEL_BLOCK])
+                .inc_by(headers.len() as i64);
+            let mut windows = stream::iter(
+                headers
+                    .chunks(DOWNLOAD_WINDOW_SIZE)
+                    .enumerate()
+                    .map(|(index, window)| {
+                        let last_number = window.last().expect("window is not empty").number();
+                        Self::fetch_blocks(
+                            network.clone(),
+                            window_peers(&peers, index, last_number),
+                            window.to_vec(),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .buffered(MAX_WINDOWS_IN_FLIGHT);
+            while let Some(window) = windows.next().await {
+                let mut window = window?;
+                let mut failed_peers = Vec::new();
+                loop {
+                    let connected = Self::connect_blocks(
+                        downloader.clone(),
+                        &window.headers,
+                        window.bodies,
+                        window.infos,
+                    )
+                    .await;
+                    SYNC_METRICS
+                        .sync_succ_count
+                        .with_label_values(&[LABEL_BLOCK])
+                        .inc_by(connected as i64);
+                    if connected == window.headers.len() {
+                        break;
+                    }
+
+                    // the peer served a bad block, fetch the rest of the window from other peers.
+                    let failed_id = window.headers[connected].id();
+                    debug!(
+                        "Connect block {:?} from peer {:?} failed.",
+                        failed_id, window.peer_id
+                    );
+                    SYNC_METRICS
+                        .sync_verify_fail_count
+                        .with_label_values(&[LABEL_BLOCK])
+                        .inc();
+                    failed_peers.push(window.peer_id.clone());
+                    let candidates = window.peers;
+                    let other_peers = candidates
+                        .iter()
+                        .filter(|peer_id| !failed_peers.contains(peer_id))
+                        .cloned()
+                        .collect();
+                    window = Self::fetch_blocks(
+                        network.clone(),
+                        other_peers,
+                        window.headers.split_off(connected),
+                    )
+                    .await
+                    .map_err(|e| format_err!("Connect block {:?} failed : {:?}", failed_id, e))?;
+                    window.peers = candidates;
+                }
+            }
+
+            parent = headers.last().expect("headers is not empty").clone();
+        }
+        Ok(())
+    }
+
+    /// Fetch the headers of `hashs`, trying `peers` in order until one returns all of them.
+    async fn fetch_headers(
+        network: NetworkAsyncService,
+        peers: Vec<PeerId>,
+        hashs: Vec<HashValue>,
+    ) -> Result<Vec<BlockHeader>> {
+        let (network, hashs) = (&network, &hashs);
+        fetch_from_peers(peers, LABEL_HEADER, |peer_id| async move {
+            let batch_header_msg = get_header_by_hash(network, peer_id, hashs.clone()).await?;
+            Ok::<_, anyhow::Error>(if match_hashs(&batch_header_msg.headers, hashs) {
+                Some(batch_header_msg.headers)
+            } else {
+                None
+            })
+        })
+        .await
+        .map(|(_, headers)| headers)
+        .map_err(|e| format_err!("{} from {:?}.", e, hashs.first()))
+    }
+
+    /// Fetch the blocks of the verified `headers`, trying `peers` in order until one returns
+    /// all of them and they match the headers.
+    async fn fetch_blocks(
+        network: NetworkAsyncService,
+        peers: Vec<PeerId>,
+        headers: Vec<BlockHeader>,
+    ) -> Result<BlockWindow> {
+        let hashs: Vec<HashValue> = headers.iter().map(|header| header.id()).collect();
+        let (network, hashs_ref, headers_ref) = (&network, &hashs, &headers);
+        let (peer_id, (bodies, infos)) =
+            fetch_from_peers(peers.clone(), LABEL_BLOCK, |peer_id| async move {
+                let block_timer = SYNC_METRICS
+                    .sync_done_time
+                    .with_label_values(&[LABEL_BLOCK])
+                    .start_timer();
+                let (batch_header_msg, batch_body_msg, batch_block_info) =
+                    get_block_by_hash(network, peer_id, hashs_ref.clone()).await?;
+                block_timer.observe_duration();
+                Ok::<_, anyhow::Error>(
+                    if match_hashs(&batch_header_msg.headers, hashs_ref)
+                        && match_blocks(
+                            headers_ref,
+                            &batch_body_msg.bodies,
+                            &batch_block_info.infos,
+                        )
+                    {
+                        Some((batch_body_msg.bodies, batch_block_info.infos))
+                    } else {
+                        None
+                    },
+                )
+            })
+            .await
+            .map_err(|e| format_err!("{} from {:?}.", e, hashs.first()))?;
+        Ok(BlockWindow {
+            peers,
+            peer_id,
+            headers,
+            bodies,
+            infos,
+        })
+    }
+
+    /// Check that `headers` extend `parent` one by one and pass the consensus verification.
+    async fn verify_header_chain(
+        downloader: Arc<Downloader<C>>,
+        parent: &BlockHeader,
+        headers: &[BlockHeader],
+    ) -> Result<()> {
+        let mut parent = parent;
+        for header in headers {
+            let result =
+                if header.parent_hash() != parent.id() || header.number() != parent.number() + 1 {
+                    Err(format_err!(
+                        "Header {:?} does not extend its parent.",
+                        header.id()
+                    ))
+                } else {
+                    downloader
+                        .chain_reader
+                        .clone()
+                        .verify_header(header.clone())
+                        .await
+                };
+            if let Err(e) = result {
+                SYNC_METRICS
+                    .sync_verify_fail_count
+                    .with_label_values(&[LABEL_HEADER])
+                    .inc();
+                return Err(format_err!(
+                    "Verify header {:?} failed : {:?}",
+                    header.id(),
+                    e
+                ));
+            }
+            parent = header;
+        }
+        Ok(())
+    }
+
     pub async fn do_blocks(
         downloader: Arc<Downloader<C>>,
         headers: Vec<BlockHeader>,
         bodies: Vec<BlockBody>,
         infos: Vec<BlockInfo>,
-    ) {
+    ) -> Result<()> {
         assert_eq!(headers.len(), bodies.len());
         assert_eq!(headers.len(), infos.len());
-        for i in 0..headers.len() {
-            if let Some(header) = headers.get(i) {
-                if let Some(body) = bodies.get(i) {
-                    if let Some(info) = infos.get(i) {
-                        let block = Block::new(header.clone(), body.clone().transactions);
-                        Self::do_block_and_child(downloader.clone(), block, Some(info.clone()))
-                            .await;
+        let connected = Self::connect_blocks(downloader, &headers, bodies, infos).await;
+        ensure!(
+            connected == headers.len(),
+            "Connect block {:?} failed.",
+            headers[connected].id()
+        );
+        Ok(())
+    }
+
+    /// Connect the blocks in order, return the number of blocks connected before the first
+    /// failure.
+    async fn connect_blocks(
+        downloader: Arc<Downloader<C>>,
+        headers: &[BlockHeader],
+        bodies: Vec<BlockBody>,
+        infos: Vec<BlockInfo>,
+    ) -> usize {
+        for (index, ((header, body), info)) in headers.iter().zip(bodies).zip(infos).enumerate() {
+            let block = Block::new(header.clone(), body.transactions);
+            if !Self::do_block_and_child(downloader.clone(), block, Some(info), None).await {
+                return index;
+            }
+        }
+        headers.len()
+    }
+
+    /// Connect the block announced by `peer_id`. If it waits for a missing parent,
+    /// fetch the ancestors from the same peer until one of them can be connected.
+    pub async fn do_block_from_peer(
+        downloader: Arc<Downloader<C>>,
+        network: NetworkAsyncService,
+        peer_id: PeerId,
+        block: Block,
+    ) {
+        let mut block = block;
+        for _ in 0..MAX_PARENT_FETCH_DEPTH {
+            let block_id = block.id();
+            let parent_id = block.header().parent_hash();
+            Self::do_block_and_child(downloader.clone(), block, None, Some(peer_id.clone())).await;
+            if !downloader.future_blocks.contains(&block_id)
+                || downloader.future_blocks.contains(&parent_id)
+            {
+                break;
+            }
+
+            SYNC_METRICS
+                .sync_total_count
+                .with_label_values(&[LABEL_PARENT])
+                .inc();
+            block = match get_block_by_hash(&network, peer_id.clone(), vec![parent_id]).await {
+                Ok((mut batch_header_msg, mut batch_body_msg, _)) => {
+                    match (batch_header_msg.headers.pop(), batch_body_msg.bodies.pop()) {
+                        (Some(header), Some(body)) if header.id() == parent_id => {
+                            SYNC_METRICS
+                                .sync_succ_count
+                                .with_label_values(&[LABEL_PARENT])
+                                .inc();
+                            Block::new(header, body.transactions)
+                        }
+                        _ => {
+                            debug!("Peer {:?} has no parent block {:?}.", peer_id, parent_id);
+                            SYNC_METRICS
+                                .sync_fail_count
+                                .with_label_values(&[LABEL_PARENT])
+                                .inc();
+                            break;
+                        }
                     }
                 }
-            }
+                Err(e) => {
+                    debug!(
+                        "Get parent block {:?} from peer {:?} failed : {:?}",
+                        parent_id, peer_id, e
+                    );
+                    SYNC_METRICS
+                        .sync_fail_count
+                        .with_label_values(&[LABEL_PARENT])
+                        .inc();
+                    break;
+                }
+            };
         }
     }
 
+    /// Connect the block and then its future children, return whether the block is connected.
     pub async fn do_block_and_child(
         downloader: Arc<Downloader<C>>,
         block: Block,
         block_info: Option<BlockInfo>,
-    ) {
+        peer_id: Option<PeerId>,
+    ) -> bool {
         let block_id = block.header().id();
-        if Self::do_block(downloader.clone(), block, block_info).await {
-            if let Some(child) = downloader.future_blocks.take_child(&block_id) {
-                for (son_block, son_block_info) in child {
-                    let _ = Self::do_block(downloader.clone(), son_block, son_block_info).await;
-                }
+        if !Self::do_block(downloader.clone(), block, block_info, peer_id).await {
+            return false;
+        }
+        if let Some(child) = downloader.future_blocks.take_child(&block_id) {
+            for (son_block, son_block_info, son_peer_id) in child {
+                let _ = Self::do_block(downloader.clone(), son_block, son_block_info, son_peer_id)
+                    .await;
             }
         }
+        true
     }
 
     async fn do_block(
         downloader: Arc<Downloader<C>>,
         block: Block,
         block_info: Option<BlockInfo>,
+        peer_id: Option<PeerId>,
     ) -> bool {
         let connect_result = if block_info.is_some() {
             downloader
@@ -932,7 +1404,9 @@ where
                 } else if let Err(err) = connect {
                     match err {
                         ConnectBlockError::FutureBlock => {
-                            downloader.future_blocks.add_future_block(block, block_info)
+                            downloader
+                                .future_blocks
+                                .add_future_block(block, block_info, peer_id);
                         }
                         _ => debug!("Connect block {:?} failed, because : {:?}", block_id, err),
                     }
@@ -944,3 +1418,440 @@ where
         false
     }
 }
+
+/// Peers to try, in order, for the `index`th download window whose last block is `last_number`.
+/// Windows are spread round-robin over the peers whose head is not lower than `last_number`,
+/// and each window falls back to the remaining peers when its peer fails or times out.
+fn window_peers(peers: &[PeerInfo], index: usize, last_number: BlockNumber) -> Vec<PeerId> {
+    let candidates: Vec<PeerId> = peers
+        .iter()
+        .filter(|peer| peer.get_block_number() >= last_number)
+        .map(|peer| peer.get_peer_id())
+        .collect();
+    if candidates.is_empty() {
+        return candidates;
+    }
+    let start = index % candidates.len();
+    candidates[start..]
+        .iter()
+        .chain(candidates[..start].iter())
+        .cloned()
+        .collect()
+}
+
+/// Try `peers` in order until `fetch` returns valid data from one of them, return the peer
+/// and its data. `fetch` returns `Ok(None)` if the data of the peer is invalid.
+async fn fetch_from_peers<T, F, Fut>(
+    peers: Vec<PeerId>,
+    label: &str,
+    fetch: F,
+) -> Result<(PeerId, T)>
+where
+    F: Fn(PeerId) -> Fut,
+    Fut: Future<Output = Result<Option<T>>>,
+{
+    for peer_id in peers {
+        match fetch(peer_id.clone()).await {
+            Ok(Some(data)) => return Ok((peer_id, data)),
+            Ok(None) => debug!("Peer {:?} returned mismatched {}.", peer_id, label),
+            Err(e) => debug!("Get {} from peer {:?} failed : {:?}", label, peer_id, e),
+        }
+        SYNC_METRICS
+            .sync_fail_count
+            .with_label_values(&[label])
+            .inc();
+    }
+    Err(format_err!("No peer can serve the {}", label))
+}
+
+/// Check the bodies and infos returned by a peer against the verified `headers`.
+/// A body is only fully verified by executing it when the block is connected, here the txn count
+/// of a body is checked by the txn accumulator leaves of the infos.
+fn match_blocks(headers: &[BlockHeader], bodies: &[BlockBody], infos: &[BlockInfo]) -> bool {
+    if bodies.len() != headers.len() || infos.len() != headers.len() {
+        return false;
+    }
+    let infos_match = headers.iter().zip(infos).all(|(header, info)| {
+        info.block_id == header.id()
+            && info.accumulator_root == header.accumulator_root()
+            && info.block_accumulator_info.num_leaves == header.number() + 1
+    });
+    // every block appends its txns and the block metadata txn to the txn accumulator.
+    infos_match
+        && infos
+            .windows(2)
+            .zip(bodies.iter().skip(1))
+            .all(|(pair, body)| {
+                pair[1].num_leaves == pair[0].num_leaves + body.transactions.len() as u64 + 1
+            })
+}
+
+/// Whether the `hashs` returned by a peer are exactly of the requested `numbers`, in order.
+fn match_numbers(hashs: &[HashWithNumber], numbers: &[BlockNumber]) -> bool {
+    hashs.len() == numbers.len()
+        && hashs
+            .iter()
+            .zip(numbers)
+            .all(|(hash, number)| hash.number == *number)
+}
+
+fn match_hashs(headers: &[BlockHeader], hashs: &[HashValue]) -> bool {
+    headers.len() == hashs.len()
+        && headers
+            .iter()
+            .zip(hashs.iter())
+            .all(|(header, hash)| header.id() == *hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain::{BlockChain, ChainActor};
+    use consensus::argon::{ArgonConsensus, ArgonConsensusHeader};
+    use consensus::dev::DevConsensus;
+    use starcoin_genesis::Genesis;
+    use starcoin_storage::{cache_storage::CacheStorage, storage::StorageInstance, Storage};
+    use starcoin_wallet_api::WalletAccount;
+    use traits::ChainReader;
+    use txpool::TxPool;
+    use types::{
+        account_address::AccountAddress, accumulator_info::AccumulatorInfo,
+        transaction::SignedUserTransaction, U256, U512,
+    };
+
+    fn gen_block(parent_hash: HashValue, number: BlockNumber) -> Block {
+        let header = BlockHeader::new(
+            parent_hash,
+            HashValue::random(),
+            0,
+            number,
+            AccountAddress::default(),
+            HashValue::random(),
+            HashValue::random(),
+            0,
+            0,
+            U256::zero(),
+            Vec::<u8>::new(),
+        );
+        Block::new(header, Vec::<SignedUserTransaction>::new())
+    }
+
+    #[test]
+    fn test_future_block_expired() {
+        let pool = FutureBlockPool::new_with_ttl(Duration::from_millis(100));
+        let old_block = gen_block(HashValue::random(), 10);
+        pool.add_future_block(old_block.clone(), None, None);
+        assert!(pool.contains(&old_block.id()));
+
+        std::thread::sleep(Duration::from_millis(200));
+        let new_block = gen_block(HashValue::random(), 10);
+        pool.add_future_block(new_block.clone(), None, None);
+        assert!(!pool.contains(&old_block.id()));
+        assert!(pool.contains(&new_block.id()));
+        assert!(pool.take_child(&old_block.header().parent_hash()).is_none());
+    }
+
+    #[test]
+    fn test_future_block_pool_full() {
+        let pool = FutureBlockPool::new();
+        let blocks: Vec<Block> = (0..=MAX_FUTURE_BLOCKS)
+            .map(|i| gen_block(HashValue::random(), i as BlockNumber))
+            .collect();
+        for block in &blocks[..MAX_FUTURE_BLOCKS] {
+            pool.add_future_block(block.clone(), None, None);
+        }
+        assert_eq!(pool.inner.lock().blocks.len(), MAX_FUTURE_BLOCKS);
+
+        // the oldest block is dropped for the new one.
+        pool.add_future_block(blocks[MAX_FUTURE_BLOCKS].clone(), None, None);
+        assert_eq!(pool.inner.lock().blocks.len(), MAX_FUTURE_BLOCKS);
+        assert!(!pool.contains(&blocks[0].id()));
+        assert!(pool.contains(&blocks[1].id()));
+        assert!(pool.contains(&blocks[MAX_FUTURE_BLOCKS].id()));
+    }
+
+    #[test]
+    fn test_future_block_peer_quota() {
+        let pool = FutureBlockPool::new();
+        let peer_id = PeerId::random();
+        for i in 0..MAX_FUTURE_BLOCKS_PER_PEER {
+            pool.add_future_block(
+                gen_block(HashValue::random(), i as BlockNumber),
+                None,
+                Some(peer_id.clone()),
+            );
+        }
+        let refused = gen_block(HashValue::random(), 0);
+        pool.add_future_block(refused.clone(), None, Some(peer_id.clone()));
+        assert!(!pool.contains(&refused.id()));
+        assert_eq!(
+            pool.inner.lock().peer_counts.get(&peer_id).cloned(),
+            Some(MAX_FUTURE_BLOCKS_PER_PEER)
+        );
+
+        // other peers are not limited by the quota of `peer_id`.
+        let accepted = gen_block(HashValue::random(), 0);
+        pool.add_future_block(accepted.clone(), None, Some(PeerId::random()));
+        assert!(pool.contains(&accepted.id()));
+    }
+
+    #[test]
+    fn test_future_block_take_child() {
+        let pool = FutureBlockPool::new();
+        let peer_id = PeerId::random();
+        let parent_id = HashValue::random();
+        let block1 = gen_block(parent_id, 1);
+        let block2 = gen_block(block1.id(), 2);
+        let block3 = gen_block(block2.id(), 3);
+        let uncle = gen_block(parent_id, 1);
+        for block in &[&block3, &block2, &uncle, &block1] {
+            pool.add_future_block((*block).clone(), None, Some(peer_id.clone()));
+        }
+        assert_eq!(
+            pool.inner.lock().peer_counts.get(&peer_id).cloned(),
+            Some(4)
+        );
+
+        let child: Vec<HashValue> = pool
+            .take_child(&parent_id)
+            .expect("child must exist.")
+            .into_iter()
+            .map(|(block, _, _)| block.id())
+            .collect();
+        assert_eq!(child.len(), 4);
+        // parents are returned before their children.
+        let position = |id: HashValue| child.iter().position(|c| *c == id).unwrap();
+        assert!(position(block1.id()) < position(block2.id()));
+        assert!(position(block2.id()) < position(block3.id()));
+        assert!(child.contains(&uncle.id()));
+
+        let inner = pool.inner.lock();
+        assert!(inner.blocks.is_empty());
+        assert!(inner.child.is_empty());
+        assert!(inner.peer_counts.get(&peer_id).is_none());
+    }
+
+    fn gen_peer(block_number: BlockNumber) -> PeerInfo {
+        let mut peer = PeerInfo::new_for_test(PeerId::random());
+        peer.block_number = block_number;
+        peer
+    }
+
+    #[test]
+    fn test_window_peers() {
+        let peers = vec![gen_peer(100), gen_peer(50), gen_peer(80)];
+        let ids: Vec<PeerId> = peers.iter().map(|peer| peer.get_peer_id()).collect();
+
+        // windows are spread round-robin, and fall back to the other peers in order.
+        assert_eq!(window_peers(&peers, 0, 40), ids);
+        assert_eq!(
+            window_peers(&peers, 1, 40),
+            vec![ids[1].clone(), ids[2].clone(), ids[0].clone()]
+        );
+        assert_eq!(
+            window_peers(&peers, 5, 40),
+            vec![ids[2].clone(), ids[0].clone(), ids[1].clone()]
+        );
+
+        // peers lower than the last block of the window are skipped.
+        assert_eq!(
+            window_peers(&peers, 1, 60),
+            vec![ids[2].clone(), ids[0].clone()]
+        );
+        assert_eq!(window_peers(&peers, 3, 100), vec![ids[0].clone()]);
+        assert!(window_peers(&peers, 0, 101).is_empty());
+    }
+
+    /// Launch a dev chain with one block, return the downloader and the genesis and block headers.
+    async fn gen_downloader<C>() -> (Arc<Downloader<C>>, BlockHeader, BlockHeader)
+    where
+        C: Consensus + Sync + Send + 'static + Clone,
+    {
+        let node_config = Arc::new(NodeConfig::random_for_test());
+        let storage = Arc::new(
+            Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap(),
+        );
+        let genesis = Genesis::build(node_config.net()).unwrap();
+        let genesis_header = genesis.block().header().clone();
+        let startup_info = genesis.execute(storage.clone()).unwrap();
+        let bus = BusActor::launch();
+        let txpool_service = TxPool::start(
+            node_config.tx_pool.clone(),
+            storage.clone(),
+            *startup_info.get_master(),
+            bus.clone(),
+        )
+        .get_service();
+        let sync_metadata = SyncMetadata::new(node_config.clone(), bus.clone());
+        let chain = ChainActor::<C>::launch(
+            node_config.clone(),
+            startup_info.clone(),
+            storage.clone(),
+            None,
+            bus,
+            txpool_service,
+            sync_metadata,
+        )
+        .unwrap();
+
+        let block_chain = BlockChain::<DevConsensus, Storage>::new(
+            node_config.clone(),
+            startup_info.master,
+            storage,
+        )
+        .unwrap();
+        let miner_account = WalletAccount::random();
+        let (block_template, _) = block_chain
+            .create_block_template(
+                *miner_account.address(),
+                Some(miner_account.get_auth_key().prefix().to_vec()),
+                None,
+                Vec::new(),
+            )
+            .unwrap();
+        let block = DevConsensus::create_block(node_config, &block_chain, block_template).unwrap();
+        (
+            Arc::new(Downloader::new(chain, vec![])),
+            genesis_header,
+            block.header().clone(),
+        )
+    }
+
+    #[actix_rt::test]
+    async fn test_verify_header_chain() {
+        let (downloader, genesis_header, header) = gen_downloader::<DevConsensus>().await;
+        assert!(Downloader::verify_header_chain(
+            downloader.clone(),
+            &genesis_header,
+            &[header.clone()]
+        )
+        .await
+        .is_ok());
+
+        // the header does not extend the parent.
+        assert!(
+            Downloader::verify_header_chain(downloader.clone(), &header, &[header.clone()])
+                .await
+                .is_err()
+        );
+        // the second header does not extend the first one.
+        assert!(Downloader::verify_header_chain(
+            downloader,
+            &genesis_header,
+            &[header.clone(), header]
+        )
+        .await
+        .is_err());
+    }
+
+    #[actix_rt::test]
+    async fn test_verify_header_chain_by_consensus() {
+        let (downloader, genesis_header, _) = gen_downloader::<ArgonConsensus>().await;
+        // the header extends the genesis, but the nonce can not meet the max difficulty.
+        let header = BlockHeader::new(
+            genesis_header.id(),
+            HashValue::random(),
+            genesis_header.timestamp() + 1,
+            genesis_header.number() + 1,
+            AccountAddress::default(),
+            HashValue::random(),
+            HashValue::random(),
+            0,
+            0,
+            U256::max_value(),
+            ArgonConsensusHeader { nonce: 0 },
+        );
+        assert!(
+            Downloader::verify_header_chain(downloader, &genesis_header, &[header])
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_fetch_from_peers_fallback() {
+        let peers = vec![PeerId::random(), PeerId::random(), PeerId::random()];
+        let (failed, mismatched, served) = (peers[0].clone(), peers[1].clone(), peers[2].clone());
+        let tried = Mutex::new(Vec::new());
+        let fetch = |peer_id: PeerId| {
+            tried.lock().push(peer_id.clone());
+            let (failed, mismatched) = (failed.clone(), mismatched.clone());
+            async move {
+                if peer_id == failed {
+                    Err(format_err!("request timeout."))
+                } else if peer_id == mismatched {
+                    Ok(None)
+                } else {
+                    Ok(Some(10))
+                }
+            }
+        };
+
+        let (peer_id, data) =
+            futures::executor::block_on(fetch_from_peers(peers.clone(), LABEL_BLOCK, &fetch))
+                .unwrap();
+        assert_eq!(peer_id, served);
+        assert_eq!(data, 10);
+        assert_eq!(*tried.lock(), peers);
+
+        // no peer left after the failed ones.
+        assert!(futures::executor::block_on(fetch_from_peers(
+            vec![failed.clone(), mismatched.clone()],
+            LABEL_BLOCK,
+            &fetch
+        ))
+        .is_err());
+    }
+
+    fn gen_info(header: &BlockHeader, txn_leaves: u64) -> BlockInfo {
+        BlockInfo::new_with_accumulator_info(
+            header.id(),
+            AccumulatorInfo::new(header.accumulator_root(), vec![], txn_leaves, 0),
+            AccumulatorInfo::new(HashValue::random(), vec![], header.number() + 1, 0),
+            U512::zero(),
+        )
+    }
+
+    fn gen_body(header: &BlockHeader, txn_count: usize) -> BlockBody {
+        let txn = SignedUserTransaction::mock();
+        BlockBody {
+            hash: header.id(),
+            transactions: vec![txn; txn_count],
+        }
+    }
+
+    #[test]
+    fn test_match_blocks() {
+        let block1 = gen_block(HashValue::random(), 1);
+        let block2 = gen_block(block1.id(), 2);
+        let headers = vec![block1.header().clone(), block2.header().clone()];
+        let bodies = vec![gen_body(&headers[0], 0), gen_body(&headers[1], 2)];
+        let infos = vec![gen_info(&headers[0], 5), gen_info(&headers[1], 8)];
+        assert!(match_blocks(&headers, &bodies, &infos));
+
+        // the body misses a txn.
+        let short_bodies = vec![gen_body(&headers[0], 0), gen_body(&headers[1], 1)];
+        assert!(!match_blocks(&headers, &short_bodies, &infos));
+        // the info is of another block.
+        let other_infos = vec![gen_info(&headers[1], 5), gen_info(&headers[1], 8)];
+        assert!(!match_blocks(&headers, &bodies, &other_infos));
+        // a block is missing.
+        assert!(!match_blocks(&headers, &bodies[..1], &infos[..1]));
+    }
+
+    #[test]
+    fn test_match_numbers() {
+        let hashs: Vec<HashWithNumber> = vec![3, 7]
+            .into_iter()
+            .map(|number| HashWithNumber {
+                hash: HashValue::random(),
+                number,
+            })
+            .collect();
+        assert!(match_numbers(&hashs, &[3, 7]));
+        assert!(!match_numbers(&hashs, &[7, 3]));
+        assert!(!match_numbers(&hashs, &[3, 8]));
+        assert!(!match_numbers(&hashs, &[3, 7, 9]));
+        assert!(!match_numbers(&hashs[..1], &[3, 7]));
+    }
+}
@@ -67,14 +67,17 @@ where
             for peer in peers {
                 match get_block_by_hash(&network, peer.get_peer_id().clone(), hashs.clone()).await {
                     Ok((_, bodies, infos)) => {
-                        Downloader::do_blocks(
-                            downloader,
+                        match Downloader::do_blocks(
+                            downloader.clone(),
                             headers.clone(),
                             bodies.bodies,
                             infos.infos,
                         )
-                        .await;
-                        break;
+                        .await
+                        {
+                            Ok(()) => break,
+                            Err(e) => error!("{:?}", e),
+                        }
                     }
                     Err(e) => {
                         error!("{:?}", e);
@@ -1,9 +1,8 @@
-use crate::helper::do_response_get_txns;
+use crate::helper::do_response;
 use anyhow::Result;
 use futures::channel::mpsc;
-use starcoin_sync_api::sync_messages::{GetTxns, TransactionsData};
+use starcoin_sync_api::sync_messages::{GetTxns, GetTxnsProtocol, TransactionsData};
 use starcoin_txpool_api::TxPoolSyncService;
-use std::borrow::Cow;
 use txpool::TxPoolService;
 
 #[derive(Clone)]
@@ -18,12 +17,8 @@ impl GetTxnsHandler {
 }
 // TODO: we can do more logic here
 impl GetTxnsHandler {
-    pub async fn handle(
-        self,
-        responder: mpsc::Sender<(Cow<'static, [u8]>, Vec<u8>)>,
-        _msg: GetTxns,
-    ) -> Result<()> {
+    pub async fn handle(self, responder: mpsc::Sender<Vec<u8>>, _msg: GetTxns) -> Result<()> {
         let data = self.pool.get_pending_txns(None);
-        do_response_get_txns(responder, TransactionsData { txns: data }).await
+        do_response::<GetTxnsProtocol>(responder, &TransactionsData { txns: data }).await
     }
 }
@@ -1,49 +1,27 @@
-use crate::{do_duration, DELAY_TIME};
 use anyhow::{format_err, Result};
 use crypto::hash::HashValue;
 use futures::channel::mpsc::Sender;
 use futures::sink::SinkExt;
 use network::NetworkAsyncService;
-use network_api::NetworkService;
+use network_api::{send_request, RpcProtocol};
 use starcoin_accumulator::node::AccumulatorStoreType;
 use starcoin_accumulator::AccumulatorNode;
-use starcoin_canonical_serialization::SCSCodec;
+use starcoin_canonical_serialization as scs;
 use starcoin_state_tree::StateNode;
 use starcoin_sync_api::sync_messages::{
-    BatchBlockInfo, BatchBodyMsg, BatchHashByNumberMsg, BatchHeaderMsg, DataType, GetDataByHashMsg,
-    GetHashByNumberMsg, GetTxns, SyncRpcRequest, SyncRpcResponse, TransactionsData,
+    BatchBlockInfo, BatchBodyMsg, BatchHashByNumberMsg, BatchHeaderMsg, DataType,
+    GetAccumulatorNodeProtocol, GetBlocksProtocol, GetDataByHashMsg, GetHashByNumberMsg,
+    GetHashByNumberProtocol, GetHeadersProtocol, GetStateChunk, GetStateChunkProtocol,
+    GetStateNodeProtocol, GetTxns, GetTxnsProtocol, StateChunk, TransactionsData,
 };
-use std::borrow::Cow;
-use types::{peer_info::PeerId, CHAIN_PROTOCOL_NAME};
-
-async fn do_request(
-    network: &NetworkAsyncService,
-    peer_id: PeerId,
-    req: SyncRpcRequest,
-) -> Result<SyncRpcResponse> {
-    let request = req.encode()?;
-    let response = network
-        .send_request_bytes(
-            CHAIN_PROTOCOL_NAME.into(),
-            peer_id.into(),
-            request,
-            do_duration(DELAY_TIME),
-        )
-        .await?;
-    SyncRpcResponse::decode(&response)
-}
+use types::peer_info::PeerId;
 
 pub async fn get_txns(
     network: &NetworkAsyncService,
     peer_id: PeerId,
     req: GetTxns,
 ) -> Result<TransactionsData> {
-    let request = SyncRpcRequest::GetTxns(req);
-    if let SyncRpcResponse::GetTxns(txn_data) = do_request(&network, peer_id, request).await? {
-        Ok(txn_data)
-    } else {
-        Err(format_err!("{:?}", "error SyncRpcResponse type."))
-    }
+    send_request::<_, GetTxnsProtocol>(network, peer_id.into(), &req).await
 }
 
 pub async fn get_hash_by_number(
@@ -51,15 +29,7 @@ pub async fn get_hash_by_number(
     peer_id: PeerId,
     req: GetHashByNumberMsg,
 ) -> Result<BatchHashByNumberMsg> {
-    let request = SyncRpcRequest::GetHashByNumberMsg(req);
-
-    if let SyncRpcResponse::BatchHashByNumberMsg(batch_hash_by_number_msg) =
-        do_request(&network, peer_id, request).await?
-    {
-        Ok(batch_hash_by_number_msg)
-    } else {
-        Err(format_err!("{:?}", "error SyncRpcResponse type."))
-    }
+    send_request::<_, GetHashByNumberProtocol>(network, peer_id.into(), &req).await
 }
 
 pub async fn get_block_by_hash(
@@ -67,17 +37,11 @@ pub async fn get_block_by_hash(
     peer_id: PeerId,
     hashs: Vec<HashValue>,
 ) -> Result<(BatchHeaderMsg, BatchBodyMsg, BatchBlockInfo)> {
-    let get_data_by_hash_req = SyncRpcRequest::GetDataByHashMsg(GetDataByHashMsg {
+    let req = GetDataByHashMsg {
         hashs,
         data_type: DataType::HEADER,
-    });
-    if let SyncRpcResponse::BatchHeaderAndBodyMsg(headers, bodies, infos) =
-        do_request(&network, peer_id, get_data_by_hash_req).await?
-    {
-        Ok((headers, bodies, infos))
-    } else {
-        Err(format_err!("{:?}", "error SyncRpcResponse type."))
-    }
+    };
+    send_request::<_, GetBlocksProtocol>(network, peer_id.into(), &req).await
 }
 
 pub async fn get_header_by_hash(
@@ -85,17 +49,7 @@ pub async fn get_header_by_hash(
     peer_id: PeerId,
     hashs: Vec<HashValue>,
 ) -> Result<BatchHeaderMsg> {
-    let get_data_by_hash_req = SyncRpcRequest::GetDataByHashMsg(GetDataByHashMsg {
-        hashs,
-        data_type: DataType::HEADER,
-    });
-    if let SyncRpcResponse::BatchHeaderAndBodyMsg(headers, _bodies, _infos) =
-        do_request(&network, peer_id, get_data_by_hash_req).await?
-    {
-        Ok(headers)
-    } else {
-        Err(format_err!("{:?}", "error SyncRpcResponse type."))
-    }
+    send_request::<_, GetHeadersProtocol>(network, peer_id.into(), &hashs).await
 }
 
 pub async fn get_body_by_hash(
@@ -119,17 +73,15 @@ pub async fn get_state_node_by_node_hash(
     peer_id: PeerId,
     node_key: HashValue,
 ) -> Result<StateNode> {
-    if let SyncRpcResponse::GetStateNodeByNodeHash(state_node) = do_request(
-        &network,
-        peer_id,
-        SyncRpcRequest::GetStateNodeByNodeHash(node_key),
-    )
-    .await?
-    {
-        Ok(state_node)
-    } else {
-        Err(format_err!("{:?}", "error SyncRpcResponse type."))
-    }
+    send_request::<_, GetStateNodeProtocol>(network, peer_id.into(), &node_key).await
+}
+
+pub async fn get_state_chunk(
+    network: &NetworkAsyncService,
+    peer_id: PeerId,
+    req: GetStateChunk,
+) -> Result<StateChunk> {
+    send_request::<_, GetStateChunkProtocol>(network, peer_id.into(), &req).await
 }
 
 pub async fn get_accumulator_node_by_node_hash(
@@ -138,82 +90,24 @@ pub async fn get_accumulator_node_by_node_hash(
     node_key: HashValue,
     accumulator_type: AccumulatorStoreType,
 ) -> Result<AccumulatorNode> {
-    if let SyncRpcResponse::GetAccumulatorNodeByNodeHash(accumulator_node) = do_request(
-        &network,
-        peer_id,
-        SyncRpcRequest::GetAccumulatorNodeByNodeHash(node_key, accumulator_type),
+    send_request::<_, GetAccumulatorNodeProtocol>(
+        network,
+        peer_id.into(),
+        &(node_key, accumulator_type),
     )
-    .await?
-    {
-        Ok(accumulator_node)
-    } else {
-        Err(format_err!("{:?}", "error SyncRpcResponse type."))
-    }
+    .await
 }
 
 /////////////////////////////////////////////////////////////////////////
 
-async fn do_response(
-    responder: Sender<(Cow<'static, [u8]>, Vec<u8>)>,
-    resp: Vec<u8>,
+/// Encode the response of protocol `P` and send it back to the requester.
+pub async fn do_response<P: RpcProtocol>(
+    mut responder: Sender<Vec<u8>>,
+    response: &P::Response,
 ) -> Result<()> {
-    if let Err(e) = responder
-        .clone()
-        .send((CHAIN_PROTOCOL_NAME.into(), resp))
+    let resp = scs::to_bytes(response)?;
+    responder
+        .send(resp)
         .await
-    {
-        Err(format_err!("{:?}", e))
-    } else {
-        Ok(())
-    }
-}
-
-pub async fn do_get_hash_by_number(
-    responder: Sender<(Cow<'static, [u8]>, Vec<u8>)>,
-    batch_hash_by_number_msg: BatchHashByNumberMsg,
-) -> Result<()> {
-    let resp = SyncRpcResponse::encode(&SyncRpcResponse::BatchHashByNumberMsg(
-        batch_hash_by_number_msg,
-    ))?;
-    do_response(responder, resp).await
-}
-
-pub async fn do_get_block_by_hash(
-    responder: Sender<(Cow<'static, [u8]>, Vec<u8>)>,
-    batch_header_msg: BatchHeaderMsg,
-    batch_body_msg: BatchBodyMsg,
-    batch_block_info_msg: BatchBlockInfo,
-) -> Result<()> {
-    let resp = SyncRpcResponse::encode(&SyncRpcResponse::BatchHeaderAndBodyMsg(
-        batch_header_msg,
-        batch_body_msg,
-        batch_block_info_msg,
-    ))?;
-    do_response(responder, resp).await
-}
-
-pub async fn do_state_node(
-    responder: Sender<(Cow<'static, [u8]>, Vec<u8>)>,
-    state_node: StateNode,
-) -> Result<()> {
-    let resp = SyncRpcResponse::encode(&SyncRpcResponse::GetStateNodeByNodeHash(state_node))?;
-    do_response(responder, resp).await
-}
-
-pub async fn do_accumulator_node(
-    responder: Sender<(Cow<'static, [u8]>, Vec<u8>)>,
-    accumulator_node: AccumulatorNode,
-) -> Result<()> {
-    let resp = SyncRpcResponse::encode(&SyncRpcResponse::GetAccumulatorNodeByNodeHash(
-        accumulator_node,
-    ))?;
-    do_response(responder, resp).await
-}
-
-pub async fn do_response_get_txns(
-    responder: Sender<(Cow<'static, [u8]>, Vec<u8>)>,
-    txns_data: TransactionsData,
-) -> Result<()> {
-    let resp = SyncRpcResponse::encode(&SyncRpcResponse::GetTxns(txns_data))?;
-    do_response(responder, resp).await
+        .map_err(|e| format_err!("{:?}", e))
 }
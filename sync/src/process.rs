@@ -1,24 +1,27 @@
 use crate::get_txns_handler::GetTxnsHandler;
-use crate::helper::{
-    do_accumulator_node, do_get_block_by_hash, do_get_hash_by_number, do_state_node,
-};
+use crate::helper::do_response;
 use actix::prelude::*;
 use actix::{Actor, Addr, AsyncContext, Context, StreamHandler};
-use anyhow::Result;
+use anyhow::{bail, Result};
 use chain::ChainActorRef;
 use crypto::hash::HashValue;
 use logger::prelude::*;
 use network::RawRpcRequestMessage;
+use network_api::RpcProtocol;
 use starcoin_accumulator::node::AccumulatorStoreType;
 use starcoin_accumulator::AccumulatorNode;
 use starcoin_canonical_serialization::SCSCodec;
-use starcoin_state_tree::StateNode;
+use starcoin_state_tree::{StateNode, StateTree};
 use starcoin_storage::Store;
 /// Sync message which inbound
 use starcoin_sync_api::sync_messages::{
     BatchBlockInfo, BatchBodyMsg, BatchHashByNumberMsg, BatchHeaderMsg, BlockBody, DataType,
-    GetDataByHashMsg, GetHashByNumberMsg, HashWithNumber, SyncRpcRequest,
+    GetAccumulatorNodeProtocol, GetBlocksProtocol, GetDataByHashMsg, GetHashByNumberMsg,
+    GetHashByNumberProtocol, GetHeadersProtocol, GetStateChunk, GetStateChunkProtocol,
+    GetStateNodeProtocol, GetTxns, GetTxnsProtocol, HashWithNumber, StateChunk,
+    MAX_STATE_CHUNK_SIZE,
 };
+use std::cmp::min;
 use std::sync::Arc;
 use traits::ChainAsyncService;
 use traits::Consensus;
@@ -64,106 +67,108 @@ where
     C: Consensus + Sync + Send + 'static + Clone,
 {
     fn handle(&mut self, msg: RawRpcRequestMessage, _ctx: &mut Self::Context) {
-        let responder = msg.responder.clone();
         let processor = self.processor.clone();
-        if let Ok(req) = SyncRpcRequest::decode(msg.request.as_slice()) {
-            Arbiter::spawn(async move {
-                match req {
-                    SyncRpcRequest::GetHashByNumberMsg(get_hash_by_number_msg) => {
-                        let batch_hash_by_number_msg = Processor::handle_get_hash_by_number_msg(
-                            processor.clone(),
-                            get_hash_by_number_msg,
-                        )
-                        .await;
-                        if let Err(e) =
-                            do_get_hash_by_number(responder, batch_hash_by_number_msg).await
-                        {
-                            error!("do get_hash_by_number request failed : {:?}", e);
-                        }
-                    }
-                    SyncRpcRequest::GetDataByHashMsg(get_data_by_hash_msg) => {
-                        if let DataType::HEADER = get_data_by_hash_msg.data_type {
-                            let batch_header_msg = Processor::handle_get_header_by_hash_msg(
-                                processor.clone(),
-                                get_data_by_hash_msg.clone(),
-                            )
-                            .await;
-                            let batch_body_msg = Processor::handle_get_body_by_hash_msg(
-                                processor.clone(),
-                                get_data_by_hash_msg.clone(),
-                            )
-                            .await;
-                            let batch_block_info_msg =
-                                Processor::handle_get_block_info_by_hash_msg(
-                                    processor.clone(),
-                                    get_data_by_hash_msg,
-                                )
-                                .await;
+        Arbiter::spawn(async move {
+            let protocol = msg.protocol.clone();
+            let peer_id = msg.peer_id.clone();
+            if let Err(e) = Self::handle_request(processor, msg).await {
+                error!(
+                    "do {} request from {:?} failed : {:?}",
+                    protocol, peer_id, e
+                );
+            }
+        });
+    }
+}
 
-                            if let Err(e) = do_get_block_by_hash(
-                                responder,
-                                batch_header_msg,
-                                batch_body_msg,
-                                batch_block_info_msg,
-                            )
-                            .await
-                            {
-                                error!("do get_block_by_hash request failed : {:?}", e);
-                            }
-                        }
-                    }
-                    SyncRpcRequest::GetStateNodeByNodeHash(state_node_key) => {
-                        let mut keys = Vec::new();
-                        keys.push(state_node_key);
-                        let mut state_nodes =
-                            Processor::handle_state_node_msg(processor.clone(), keys).await;
-                        if let Some((_, state_node_res)) = state_nodes.pop() {
-                            if let Some(state_node) = state_node_res {
-                                if let Err(e) = do_state_node(responder, state_node).await {
-                                    error!("do state_node request failed : {:?}", e);
-                                }
-                            } else {
-                                debug!("{:?}", "state_node is none.");
-                            }
-                        } else {
-                            debug!("{:?}", "state_nodes is none.");
-                        }
-                    }
-                    SyncRpcRequest::GetAccumulatorNodeByNodeHash(
-                        accumulator_node_key,
-                        accumulator_type,
-                    ) => {
-                        let mut keys = Vec::new();
-                        keys.push(accumulator_node_key);
-                        let mut accumulator_nodes = Processor::handle_accumulator_node_msg(
-                            processor.clone(),
-                            keys,
-                            accumulator_type,
-                        )
+impl<C> ProcessActor<C>
+where
+    C: Consensus + Sync + Send + 'static + Clone,
+{
+    async fn handle_request(processor: Arc<Processor<C>>, msg: RawRpcRequestMessage) -> Result<()> {
+        let responder = msg.responder;
+        let request = msg.request.as_slice();
+        match msg.protocol.as_ref() {
+            GetHashByNumberProtocol::NAME => {
+                let get_hash_by_number_msg = GetHashByNumberMsg::decode(request)?;
+                let batch_hash_by_number_msg =
+                    Processor::handle_get_hash_by_number_msg(processor, get_hash_by_number_msg)
                         .await;
-                        if let Some((_, accumulator_node_res)) = accumulator_nodes.pop() {
-                            if let Some(accumulator_node) = accumulator_node_res {
-                                if let Err(e) =
-                                    do_accumulator_node(responder, accumulator_node).await
-                                {
-                                    error!("do accumulator_node request failed : {:?}", e);
-                                }
-                            } else {
-                                debug!("accumulator_node {:?} is none.", accumulator_node_key);
-                            }
-                        } else {
-                            debug!("{:?}", "accumulator_nodes is none.");
-                        }
+                do_response::<GetHashByNumberProtocol>(responder, &batch_hash_by_number_msg).await
+            }
+            GetBlocksProtocol::NAME => {
+                let get_data_by_hash_msg = GetDataByHashMsg::decode(request)?;
+                if let DataType::HEADER = get_data_by_hash_msg.data_type {
+                    let batch_header_msg = Processor::handle_get_header_by_hash_msg(
+                        processor.clone(),
+                        get_data_by_hash_msg.hashs.clone(),
+                    )
+                    .await;
+                    let batch_body_msg = Processor::handle_get_body_by_hash_msg(
+                        processor.clone(),
+                        get_data_by_hash_msg.clone(),
+                    )
+                    .await;
+                    let batch_block_info_msg = Processor::handle_get_block_info_by_hash_msg(
+                        processor,
+                        get_data_by_hash_msg,
+                    )
+                    .await;
+                    do_response::<GetBlocksProtocol>(
+                        responder,
+                        &(batch_header_msg, batch_body_msg, batch_block_info_msg),
+                    )
+                    .await
+                } else {
+                    bail!("Unsupported data type {:?}", get_data_by_hash_msg.data_type)
+                }
+            }
+            GetHeadersProtocol::NAME => {
+                let hashs = Vec::<HashValue>::decode(request)?;
+                let batch_header_msg =
+                    Processor::handle_get_header_by_hash_msg(processor, hashs).await;
+                do_response::<GetHeadersProtocol>(responder, &batch_header_msg).await
+            }
+            GetStateNodeProtocol::NAME => {
+                let state_node_key = HashValue::decode(request)?;
+                let mut state_nodes =
+                    Processor::handle_state_node_msg(processor, vec![state_node_key]).await;
+                match state_nodes.pop() {
+                    Some((_, Some(state_node))) => {
+                        do_response::<GetStateNodeProtocol>(responder, &state_node).await
                     }
-                    SyncRpcRequest::GetTxns(msg) => {
-                        let handler = GetTxnsHandler::new(processor.txpool.clone());
-                        let result = handler.handle(responder, msg).await;
-                        if let Err(e) = result {
-                            warn!("handle get txn fail, error: {:?}", e);
-                        }
+                    _ => bail!("state_node {:?} is none.", state_node_key),
+                }
+            }
+            GetAccumulatorNodeProtocol::NAME => {
+                let (accumulator_node_key, accumulator_type) =
+                    <(HashValue, AccumulatorStoreType)>::decode(request)?;
+                let mut accumulator_nodes = Processor::handle_accumulator_node_msg(
+                    processor,
+                    vec![accumulator_node_key],
+                    accumulator_type,
+                )
+                .await;
+                match accumulator_nodes.pop() {
+                    Some((_, Some(accumulator_node))) => {
+                        do_response::<GetAccumulatorNodeProtocol>(responder, &accumulator_node)
+                            .await
                     }
+                    _ => bail!("accumulator_node {:?} is none.", accumulator_node_key),
                 }
-            });
+            }
+            GetTxnsProtocol::NAME => {
+                let msg = GetTxns::decode(request)?;
+                let handler = GetTxnsHandler::new(processor.txpool.clone());
+                handler.handle(responder, msg).await
+            }
+            GetStateChunkProtocol::NAME => {
+                let get_state_chunk = GetStateChunk::decode(request)?;
+                let state_chunk =
+                    Processor::handle_state_chunk_msg(processor, get_state_chunk).await?;
+                do_response::<GetStateChunkProtocol>(responder, &state_chunk).await
+            }
+            protocol => bail!("Unknown sync rpc protocol {}", protocol),
         }
     }
 }
@@ -225,10 +230,10 @@ where
 
     pub async fn handle_get_header_by_hash_msg(
         processor: Arc<Processor<C>>,
-        get_header_by_hash_msg: GetDataByHashMsg,
+        hashs: Vec<HashValue>,
     ) -> BatchHeaderMsg {
         let mut headers = Vec::new();
-        for hash in get_header_by_hash_msg.hashs {
+        for hash in hashs {
             if let Ok(Some(header)) = processor
                 .chain_reader
                 .clone()
@@ -292,6 +297,19 @@ where
         state_nodes
     }
 
+    pub async fn handle_state_chunk_msg(
+        processor: Arc<Processor<C>>,
+        get_state_chunk: GetStateChunk,
+    ) -> Result<StateChunk> {
+        let state_tree = StateTree::new(
+            processor.storage.clone().into_super_arc(),
+            Some(get_state_chunk.state_root),
+        );
+        let limit = min(get_state_chunk.limit, MAX_STATE_CHUNK_SIZE) as usize;
+        let (blobs, proof) = state_tree.get_range_with_proof(get_state_chunk.last_key, limit)?;
+        Ok(StateChunk { blobs, proof })
+    }
+
     pub async fn handle_accumulator_node_msg(
         processor: Arc<Processor<C>>,
         nodes_hash: Vec<HashValue>,
@@ -1,11 +1,10 @@
 use crate::download::{DownloadActor, SyncEvent};
-use crate::helper::{get_accumulator_node_by_node_hash, get_state_node_by_node_hash};
+use crate::helper::{get_accumulator_node_by_node_hash, get_state_chunk};
 use crate::sync_metrics::{LABEL_ACCUMULATOR, LABEL_STATE, SYNC_METRICS};
 use actix::prelude::*;
 use actix::{Actor, Addr, Context, Handler};
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use crypto::hash::HashValue;
-use forkable_jellyfish_merkle::node_type::Node;
 use forkable_jellyfish_merkle::SPARSE_MERKLE_PLACEHOLDER_HASH;
 use futures::executor::block_on;
 use logger::prelude::*;
@@ -14,8 +13,9 @@ use network_api::NetworkService;
 use parking_lot::Mutex;
 use starcoin_accumulator::node::{AccumulatorStoreType, ACCUMULATOR_PLACEHOLDER_HASH};
 use starcoin_accumulator::AccumulatorNode;
-use starcoin_state_tree::StateNode;
+use starcoin_state_tree::{SparseMerkleRangeProof, StateTreeRestore};
 use starcoin_storage::Store;
+use starcoin_sync_api::sync_messages::{GetStateChunk, StateChunk, MAX_STATE_CHUNK_SIZE};
 use starcoin_sync_api::{StateSyncReset, SyncMetadata};
 use std::collections::{HashMap, VecDeque};
 use std::convert::TryFrom;
@@ -117,8 +117,9 @@ async fn sync_accumulator_node<C>(
     };
 }
 
-async fn sync_state_node<C>(
-    node_key: HashValue,
+async fn sync_state_chunk<C>(
+    state_root: HashValue,
+    last_key: Option<HashValue>,
     peer_id: PeerId,
     network_service: NetworkAsyncService,
     address: Addr<StateSyncTaskActor<C>>,
@@ -129,41 +130,35 @@ async fn sync_state_node<C>(
         .sync_done_time
         .with_label_values(&[LABEL_STATE])
         .start_timer();
-    let state_node =
-        match get_state_node_by_node_hash(&network_service, peer_id.clone(), node_key).await {
-            Ok(state_node) => {
-                if node_key == state_node.0.hash() {
-                    SYNC_METRICS
-                        .sync_succ_count
-                        .with_label_values(&[LABEL_STATE])
-                        .inc();
-                    Some(state_node)
-                } else {
-                    SYNC_METRICS
-                        .sync_verify_fail_count
-                        .with_label_values(&[LABEL_STATE])
-                        .inc();
-                    warn!(
-                        "state node hash miss match {} :{:?}",
-                        node_key,
-                        state_node.0.hash()
-                    );
-                    None
-                }
-            }
-            Err(e) => {
-                SYNC_METRICS
-                    .sync_fail_count
-                    .with_label_values(&[LABEL_STATE])
-                    .inc();
-                debug!("{:?}", e);
-                None
-            }
-        };
+    let req = GetStateChunk {
+        state_root,
+        last_key,
+        limit: MAX_STATE_CHUNK_SIZE,
+    };
+    let state_chunk = match get_state_chunk(&network_service, peer_id.clone(), req).await {
+        Ok(state_chunk) => {
+            SYNC_METRICS
+                .sync_succ_count
+                .with_label_values(&[LABEL_STATE])
+                .inc();
+            Some(state_chunk)
+        }
+        Err(e) => {
+            SYNC_METRICS
+                .sync_fail_count
+                .with_label_values(&[LABEL_STATE])
+                .inc();
+            debug!("{:?}", e);
+            None
+        }
+    };
     state_timer.observe_duration();
 
-    if let Err(err) = address.try_send(StateSyncTaskEvent::new_state(peer_id, node_key, state_node))
-    {
+    if let Err(err) = address.try_send(StateSyncTaskEvent::new_state(
+        peer_id,
+        state_root,
+        state_chunk,
+    )) {
         error!("Send state StateSyncTaskEvent failed : {:?}", err);
     };
 }
@@ -219,17 +214,21 @@ enum TaskType {
 struct StateSyncTaskEvent {
     peer_id: PeerId,
     node_key: HashValue,
-    state_node: Option<StateNode>,
+    state_chunk: Option<StateChunk>,
     accumulator_node: Option<AccumulatorNode>,
     task_type: TaskType,
 }
 
 impl StateSyncTaskEvent {
-    pub fn new_state(peer_id: PeerId, node_key: HashValue, state_node: Option<StateNode>) -> Self {
+    pub fn new_state(
+        peer_id: PeerId,
+        node_key: HashValue,
+        state_chunk: Option<StateChunk>,
+    ) -> Self {
         StateSyncTaskEvent {
             peer_id,
             node_key,
-            state_node,
+            state_chunk,
             accumulator_node: None,
             task_type: TaskType::STATE,
         }
@@ -244,7 +243,7 @@ impl StateSyncTaskEvent {
         StateSyncTaskEvent {
             peer_id,
             node_key,
-            state_node: None,
+            state_chunk: None,
             accumulator_node,
             task_type: match accumulator_type {
                 AccumulatorStoreType::Block => TaskType::BlockAccumulator,
@@ -254,6 +253,13 @@ impl StateSyncTaskEvent {
     }
 }
 
+/// A state tree which is restoring chunk by chunk.
+struct StateRestoreTask {
+    state_root: HashValue,
+    is_global: bool,
+    restore: StateTreeRestore,
+}
+
 pub struct StateSyncTaskActor<C>
 where
     C: Consensus + Sync + Send + 'static + Clone,
@@ -263,7 +269,9 @@ where
     storage: Arc<dyn Store>,
     network_service: NetworkAsyncService,
     sync_metadata: SyncMetadata,
+    /// Roots of the global state tree and account storage trees to sync.
     state_sync_task: Arc<Mutex<SyncTask<(HashValue, bool)>>>,
+    state_restore: Option<StateRestoreTask>,
     txn_accumulator_sync_task: Arc<Mutex<SyncTask<HashValue>>>,
     block_accumulator_sync_task: Arc<Mutex<SyncTask<HashValue>>>,
     connect_address: Addr<DownloadActor<C>>,
@@ -304,6 +312,10 @@ impl<T> SyncTask<T> {
         self.wait_2_sync.push_back(value)
     }
 
+    pub fn push_front(&mut self, value: T) {
+        self.wait_2_sync.push_front(value)
+    }
+
     pub fn pop_front(&mut self) -> Option<T> {
         self.wait_2_sync.pop_front()
     }
@@ -353,6 +365,7 @@ where
             network_service,
             sync_metadata,
             state_sync_task: Arc::new(Mutex::new(state_sync_task)),
+            state_restore: None,
             txn_accumulator_sync_task: Arc::new(Mutex::new(txn_accumulator_sync_task)),
             block_accumulator_sync_task: Arc::new(Mutex::new(block_accumulator_sync_task)),
             connect_address: address,
@@ -370,114 +383,201 @@ where
             self.block_accumulator_sync_task.lock().task_info(),
         );
         self.state_sync_task.lock().is_empty()
+            && self.state_restore.is_none()
             && self.txn_accumulator_sync_task.lock().is_empty()
             && self.block_accumulator_sync_task.lock().is_empty()
     }
 
     fn exe_state_sync_task(&mut self, address: Addr<StateSyncTaskActor<C>>) {
         let mut lock = self.state_sync_task.lock();
-        let value = lock.pop_front();
-        if let Some((node_key, is_global)) = value {
-            SYNC_METRICS
-                .sync_total_count
-                .with_label_values(&[LABEL_STATE])
-                .inc();
-            if let Ok(Some(state_node)) = self.storage.get(&node_key) {
-                debug!("find state_node {:?} in db.", node_key);
-                lock.insert(self.self_peer_id.clone(), (node_key, is_global));
-                if let Err(err) = address.try_send(StateSyncTaskEvent::new_state(
-                    self.self_peer_id.clone(),
-                    node_key,
-                    Some(state_node),
-                )) {
-                    error!("Send state StateSyncTaskEvent failed : {:?}", err);
-                };
-            } else {
-                let best_peer_info = get_best_peer_info(self.network_service.clone());
-                debug!(
-                    "sync state node {:?} from peer {:?}.",
-                    node_key, best_peer_info
-                );
-                if let Some(best_peer) = best_peer_info {
-                    if self.self_peer_id != best_peer.get_peer_id() {
-                        let network_service = self.network_service.clone();
-                        lock.insert(best_peer.get_peer_id(), (node_key, is_global));
-                        Arbiter::spawn(async move {
-                            sync_state_node(
-                                node_key,
-                                best_peer.get_peer_id(),
-                                network_service,
-                                address,
-                            )
-                            .await;
-                        });
-                    }
-                } else {
-                    warn!("{:?}", "best peer is none, state sync may be failed.");
-                    self.sync_metadata.update_failed(true);
+        if self.state_restore.is_none() {
+            if let Some((state_root, is_global)) = lock.pop_front() {
+                self.state_restore = Some(StateRestoreTask {
+                    state_root,
+                    is_global,
+                    restore: StateTreeRestore::new(
+                        self.storage.clone().into_super_arc(),
+                        state_root,
+                    ),
+                });
+            }
+        }
+        let (state_root, is_global, last_key) = match &self.state_restore {
+            Some(task) => (task.state_root, task.is_global, task.restore.last_key()),
+            None => return,
+        };
+        SYNC_METRICS
+            .sync_total_count
+            .with_label_values(&[LABEL_STATE])
+            .inc();
+        // The root node is written after all of the tree nodes, so an account storage tree is
+        // complete if its root exists. The global tree is always synced, for the storage roots.
+        let in_db = last_key.is_none()
+            && (state_root == *SPARSE_MERKLE_PLACEHOLDER_HASH
+                || (!is_global && matches!(self.storage.get(&state_root), Ok(Some(_)))));
+        if in_db {
+            debug!("find state tree {:?} in db.", state_root);
+            lock.insert(self.self_peer_id.clone(), (state_root, is_global));
+            let empty_chunk = StateChunk {
+                blobs: Vec::new(),
+                proof: None,
+            };
+            if let Err(err) = address.try_send(StateSyncTaskEvent::new_state(
+                self.self_peer_id.clone(),
+                state_root,
+                Some(empty_chunk),
+            )) {
+                error!("Send state StateSyncTaskEvent failed : {:?}", err);
+            };
+        } else {
+            let best_peer_info = get_best_peer_info(self.network_service.clone());
+            debug!(
+                "sync state chunk of {:?} after {:?} from peer {:?}.",
+                state_root, last_key, best_peer_info
+            );
+            if let Some(best_peer) = best_peer_info {
+                if self.self_peer_id != best_peer.get_peer_id() {
+                    let network_service = self.network_service.clone();
+                    lock.insert(best_peer.get_peer_id(), (state_root, is_global));
+                    Arbiter::spawn(async move {
+                        sync_state_chunk(
+                            state_root,
+                            last_key,
+                            best_peer.get_peer_id(),
+                            network_service,
+                            address,
+                        )
+                        .await;
+                    });
                 }
+            } else {
+                warn!("{:?}", "best peer is none, state sync may be failed.");
+                self.sync_metadata.update_failed(true);
             }
         }
     }
 
     fn handle_state_sync(&mut self, task_event: StateSyncTaskEvent) {
         let mut lock = self.state_sync_task.lock();
-        if let Some((state_node_hash, is_global)) = lock.get(&task_event.peer_id) {
+        if let Some((state_root, is_global)) = lock.get(&task_event.peer_id) {
             let is_global = *is_global;
-            //1. push back
-            let current_node_key = task_event.node_key;
-            if state_node_hash != &current_node_key {
-                debug!(
-                    "hash miss match {:} : {:?}",
-                    state_node_hash, current_node_key
-                );
+            let current_root = task_event.node_key;
+            if state_root != &current_root {
+                debug!("hash miss match {:} : {:?}", state_root, current_root);
                 return;
             }
             let _ = lock.remove(&task_event.peer_id);
-            if let Some(state_node) = task_event.state_node {
-                if let Err(e) = self.storage.put(current_node_key, state_node.clone()) {
-                    debug!("{:?}, retry {:?}.", e, current_node_key);
-                    lock.push_back((current_node_key, is_global));
-                } else {
+            let mut task = match self.state_restore.take() {
+                Some(task) if task.state_root == current_root => task,
+                other => {
+                    self.state_restore = other;
+                    debug!("discard state event : {:?}", task_event);
+                    return;
+                }
+            };
+            // Fetch failed, retry the same chunk.
+            let state_chunk = match task_event.state_chunk {
+                Some(state_chunk) => state_chunk,
+                None => {
+                    self.state_restore = Some(task);
+                    return;
+                }
+            };
+
+            if state_chunk.blobs.is_empty() {
+                if task.restore.num_keys() > 0 {
+                    Self::finish_state_restore(task, &mut lock);
+                } else if task_event.peer_id == self.self_peer_id {
                     lock.do_one_task();
-                    match state_node.inner() {
-                        Node::Leaf(leaf) => {
-                            if !is_global {
-                                return;
-                            }
-                            match AccountState::try_from(leaf.blob().as_ref()) {
-                                Err(e) => {
-                                    error!("AccountState decode from blob failed : {:?}", e);
-                                }
-                                Ok(account_state) => {
-                                    account_state.storage_roots().iter().for_each(|key| {
-                                        if let Some(hash) = key {
-                                            if *hash != *SPARSE_MERKLE_PLACEHOLDER_HASH {
-                                                lock.push_back((*hash, false));
-                                            }
-                                        }
-                                    });
-                                }
-                            }
-                        }
-                        Node::Internal(n) => {
-                            for child in n.all_child() {
-                                lock.push_back((child, is_global));
-                            }
+                } else {
+                    SYNC_METRICS
+                        .sync_verify_fail_count
+                        .with_label_values(&[LABEL_STATE])
+                        .inc();
+                    warn!(
+                        "state tree {:?} is empty from peer {:?}.",
+                        current_root, task_event.peer_id
+                    );
+                    self.state_restore = Some(task);
+                }
+                return;
+            }
+
+            let mut storage_roots = Vec::new();
+            if is_global {
+                for (_, blob) in state_chunk.blobs.iter() {
+                    match AccountState::try_from(blob.as_slice()) {
+                        Err(e) => {
+                            error!("AccountState decode from blob failed : {:?}", e);
                         }
-                        _ => {
-                            debug!("node {:?} is null.", current_node_key);
+                        Ok(account_state) => {
+                            account_state.storage_roots().iter().for_each(|key| {
+                                if let Some(hash) = key {
+                                    if *hash != *SPARSE_MERKLE_PLACEHOLDER_HASH {
+                                        storage_roots.push(*hash);
+                                    }
+                                }
+                            });
                         }
                     }
                 }
-            } else {
-                lock.push_back((current_node_key, is_global));
+            }
+            let mut is_last_chunk = false;
+            let result = match state_chunk.proof {
+                Some(proof) => {
+                    is_last_chunk = is_last_state_chunk(&proof);
+                    task.restore.add_chunk(state_chunk.blobs, proof)
+                }
+                None => Err(format_err!("state chunk without proof.")),
+            };
+            match result {
+                Err(e) => {
+                    SYNC_METRICS
+                        .sync_verify_fail_count
+                        .with_label_values(&[LABEL_STATE])
+                        .inc();
+                    warn!(
+                        "verify state chunk of {:?} failed : {:?}, restart the state tree.",
+                        current_root, e
+                    );
+                    lock.push_front((current_root, is_global));
+                }
+                Ok(()) => {
+                    for storage_root in storage_roots {
+                        lock.push_back((storage_root, false));
+                    }
+                    // a peer may return less than the limit, so only the verified proof
+                    // tells whether there are more keys.
+                    if is_last_chunk {
+                        Self::finish_state_restore(task, &mut lock);
+                    } else {
+                        self.state_restore = Some(task);
+                    }
+                }
             }
         } else {
             debug!("discard state event : {:?}", task_event);
         }
     }
 
+    fn finish_state_restore(task: StateRestoreTask, lock: &mut SyncTask<(HashValue, bool)>) {
+        let state_root = task.state_root;
+        let is_global = task.is_global;
+        if let Err(e) = task.restore.finish() {
+            SYNC_METRICS
+                .sync_verify_fail_count
+                .with_label_values(&[LABEL_STATE])
+                .inc();
+            warn!(
+                "finish state tree {:?} failed : {:?}, restart the state tree.",
+                state_root, e
+            );
+            lock.push_front((state_root, is_global));
+        } else {
+            lock.do_one_task();
+        }
+    }
+
     fn exe_accumulator_sync_task(
         &self,
         address: Addr<StateSyncTaskActor<C>>,
@@ -630,6 +730,7 @@ where
         let mut state_lock = self.state_sync_task.lock();
         let old_state_is_empty = state_lock.is_empty();
         state_lock.clear();
+        self.state_restore = None;
         state_lock.push_back((*self.roots.state_root(), true));
         drop(state_lock);
         let mut txn_accumulator_lock = self.txn_accumulator_sync_task.lock();
@@ -757,12 +858,63 @@ where
     }
 }
 
+/// The chunk is the last one of the state tree if there is no key on the right of its last key,
+/// that is all the right siblings of the range proof are placeholders.
+fn is_last_state_chunk(proof: &SparseMerkleRangeProof) -> bool {
+    proof
+        .right_siblings()
+        .iter()
+        .all(|sibling| *sibling == *SPARSE_MERKLE_PLACEHOLDER_HASH)
+}
+
+/// The best peer except self.
 fn get_best_peer_info(network_service: NetworkAsyncService) -> Option<PeerInfo> {
     block_on(async move {
-        if let Ok(peer_info) = network_service.best_peer().await {
-            peer_info
-        } else {
-            None
+        let self_peer_id: PeerId = network_service.identify().clone().into();
+        match network_service.best_peer_set().await {
+            Ok(peers) => peers
+                .into_iter()
+                .find(|peer| peer.get_peer_id() != self_peer_id),
+            Err(_) => None,
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_state_tree::mock::MockStateNodeStore;
+    use starcoin_state_tree::StateTree;
+
+    #[test]
+    fn test_restore_by_short_chunks() -> Result<()> {
+        let state = StateTree::new(Arc::new(MockStateNodeStore::new()), None);
+        for i in 0..50u8 {
+            state.put(HashValue::random(), vec![i]);
+        }
+        state.commit()?;
+        state.flush()?;
+        let root_hash = state.root_hash();
+
+        // the peer returns chunks much shorter than the requested limit.
+        let short_limit = 7;
+        assert!((short_limit as u64) < MAX_STATE_CHUNK_SIZE);
+        let mut restore = StateTreeRestore::new(Arc::new(MockStateNodeStore::new()), root_hash);
+        let mut chunks = 0;
+        loop {
+            let (chunk, proof) = state.get_range_with_proof(restore.last_key(), short_limit)?;
+            let proof = proof.expect("non empty chunk should have proof.");
+            let is_last_chunk = is_last_state_chunk(&proof);
+            restore.add_chunk(chunk, proof)?;
+            chunks += 1;
+            if is_last_chunk {
+                break;
+            }
+            assert!(restore.num_keys() < 50);
+        }
+        assert_eq!(chunks, 8);
+        assert_eq!(restore.num_keys(), 50);
+        assert_eq!(restore.finish()?, root_hash);
+        Ok(())
+    }
+}
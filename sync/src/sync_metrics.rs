@@ -1,7 +1,7 @@
 use once_cell::sync::Lazy;
 use starcoin_metrics::{
-    register_histogram_vec, register_int_counter_vec, HistogramOpts, HistogramVec, IntCounterVec,
-    Opts, PrometheusError,
+    register_histogram_vec, register_int_counter_vec, register_int_gauge, HistogramOpts,
+    HistogramVec, IntCounterVec, IntGauge, Opts, PrometheusError,
 };
 
 const SC_NS: &str = "starcoin";
@@ -9,8 +9,14 @@ const PRIFIX: &str = "starcoin_sync_";
 
 pub const LABEL_BLOCK: &str = "block";
 pub const LABEL_HASH: &str = "hash";
+pub const LABEL_HEADER: &str = "header";
 pub const LABEL_STATE: &str = "state";
 pub const LABEL_ACCUMULATOR: &str = "accumulator";
+pub const LABEL_PARENT: &str = "parent";
+
+pub const LABEL_EXPIRED: &str = "expired";
+pub const LABEL_FULL: &str = "full";
+pub const LABEL_QUOTA: &str = "quota";
 
 pub static SYNC_METRICS: Lazy<SyncMetrics> = Lazy::new(|| SyncMetrics::register().unwrap());
 
@@ -24,6 +30,8 @@ pub struct SyncMetrics {
     pub sync_done_time: HistogramVec,
     pub sync_count: IntCounterVec,
     pub sync_done_count: IntCounterVec,
+    pub orphan_count: IntGauge,
+    pub orphan_drop_count: IntCounterVec,
 }
 
 impl SyncMetrics {
@@ -96,6 +104,20 @@ impl SyncMetrics {
             .namespace(SC_NS),
             &["sync_done_count"]
         )?;
+        let orphan_count = register_int_gauge!(Opts::new(
+            format!("{}{}", PRIFIX, "orphan_count"),
+            "orphan block count".to_string()
+        )
+        .namespace(SC_NS))?;
+
+        let orphan_drop_count = register_int_counter_vec!(
+            Opts::new(
+                format!("{}{}", PRIFIX, "orphan_drop_count"),
+                "orphan block drop count".to_string()
+            )
+            .namespace(SC_NS),
+            &["orphan_drop_count"]
+        )?;
         Ok(Self {
             sync_total_count,
             sync_succ_count,
@@ -105,6 +127,8 @@ impl SyncMetrics {
             sync_done_time,
             sync_count,
             sync_done_count,
+            orphan_count,
+            orphan_drop_count,
         })
     }
 }
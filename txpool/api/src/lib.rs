@@ -3,6 +3,7 @@
 
 use anyhow::Result;
 use futures_channel::mpsc;
+use serde::{Deserialize, Serialize};
 use starcoin_crypto::hash::HashValue;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::{transaction, transaction::SignedUserTransaction};
@@ -10,12 +11,66 @@ use std::sync::Arc;
 
 pub type TxnStatusFullEvent = Arc<Vec<(HashValue, transaction::TxStatus)>>;
 
+/// Light status of the txpool.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TxPoolStatus {
+    pub txn_count: usize,
+    pub max_txn_count: usize,
+    pub mem: usize,
+    pub max_mem: usize,
+    pub senders: usize,
+    pub is_full: bool,
+}
+
+/// Gas price suggestion by the gas prices of recently included txns and pending txns.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GasPriceSuggestion {
+    /// The 20th percentile, the txn may wait a long time to be included.
+    pub low: u64,
+    pub median: u64,
+    /// The 80th percentile, for txns want to be included quickly.
+    pub high: u64,
+    /// Number of sampled gas prices.
+    pub samples: usize,
+}
+
+impl GasPriceSuggestion {
+    pub const LOW_PERCENTILE: usize = 20;
+    pub const HIGH_PERCENTILE: usize = 80;
+
+    /// Compute the suggestion from sampled gas prices, every price is at least `floor`.
+    pub fn from_samples(mut samples: Vec<u64>, floor: u64) -> Self {
+        samples.sort_unstable();
+        let percentile = |p: usize| -> u64 {
+            if samples.is_empty() {
+                return floor;
+            }
+            let idx = (samples.len() - 1) * p / 100;
+            std::cmp::max(samples[idx], floor)
+        };
+        Self {
+            low: percentile(Self::LOW_PERCENTILE),
+            median: percentile(50),
+            high: percentile(Self::HIGH_PERCENTILE),
+            samples: samples.len(),
+        }
+    }
+}
+
 pub trait TxPoolSyncService: Clone + Send + Sync + Unpin {
+    /// Add txns received from peers.
     fn add_txns(
         &self,
         txns: Vec<SignedUserTransaction>,
     ) -> Vec<Result<(), transaction::TransactionError>>;
 
+    /// Add txns submitted to this node, they are tracked as local txns,
+    /// ignore the per sender limit and are journaled in `Local` journal mode.
+    fn add_local_txns(
+        &self,
+        txns: Vec<SignedUserTransaction>,
+    ) -> Vec<Result<(), transaction::TransactionError>>;
+
     /// Removes transaction from the pool.
     ///
     /// Attempts to "cancel" a transaction. If it was not propagated yet (or not accepted by other peers)
@@ -32,6 +87,12 @@ pub trait TxPoolSyncService: Clone + Send + Sync + Unpin {
     /// subscribe
     fn subscribe_txns(&self) -> mpsc::UnboundedReceiver<TxnStatusFullEvent>;
 
+    /// Current status of the pool.
+    fn status(&self) -> TxPoolStatus;
+
+    /// Suggest gas price by recently included txns and pending txns in the pool.
+    fn gas_price_suggestion(&self) -> GasPriceSuggestion;
+
     /// rollback
     fn rollback(
         &self,
@@ -39,3 +100,27 @@ pub trait TxPoolSyncService: Clone + Send + Sync + Unpin {
         retracted: Vec<SignedUserTransaction>,
     ) -> Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_price_suggestion() {
+        let suggestion = GasPriceSuggestion::from_samples(vec![], 1);
+        assert_eq!(
+            (suggestion.low, suggestion.median, suggestion.high),
+            (1, 1, 1)
+        );
+        let samples = (1..=100).rev().collect();
+        let suggestion = GasPriceSuggestion::from_samples(samples, 1);
+        assert_eq!(suggestion.samples, 100);
+        assert_eq!(
+            (suggestion.low, suggestion.median, suggestion.high),
+            (20, 50, 80)
+        );
+        let suggestion = GasPriceSuggestion::from_samples(vec![1, 2, 3], 10);
+        assert_eq!(suggestion.low, 10);
+        assert_eq!(suggestion.high, 10);
+    }
+}
@@ -4,7 +4,7 @@
 use anyhow::Result;
 use crypto::hash::HashValue;
 use futures_channel::mpsc;
-use starcoin_txpool_api::TxPoolSyncService;
+use starcoin_txpool_api::{GasPriceSuggestion, TxPoolStatus, TxPoolSyncService};
 use std::iter::Iterator;
 use std::sync::{Arc, Mutex};
 use types::account_address::AccountAddress;
@@ -14,6 +14,7 @@ use types::transaction::SignedUserTransaction;
 #[derive(Clone, Default)]
 pub struct MockTxPoolService {
     pool: Arc<Mutex<Vec<SignedUserTransaction>>>,
+    status: Arc<Mutex<Option<TxPoolStatus>>>,
 }
 
 impl MockTxPoolService {
@@ -24,8 +25,14 @@ impl MockTxPoolService {
     pub fn new_with_txns(txns: Vec<SignedUserTransaction>) -> Self {
         MockTxPoolService {
             pool: Arc::new(Mutex::new(txns)),
+            status: Arc::new(Mutex::new(None)),
         }
     }
+
+    /// Mock the pool status, instead of counting the pool txns.
+    pub fn set_status(&self, status: TxPoolStatus) {
+        *self.status.lock().unwrap() = Some(status);
+    }
 }
 
 impl TxPoolSyncService for MockTxPoolService {
@@ -40,6 +47,13 @@ impl TxPoolSyncService for MockTxPoolService {
         results
     }
 
+    fn add_local_txns(
+        &self,
+        txns: Vec<SignedUserTransaction>,
+    ) -> Vec<Result<(), transaction::TransactionError>> {
+        self.add_txns(txns)
+    }
+
     /// Removes transaction from the pool.
     ///
     /// Attempts to "cancel" a transaction. If it was not propagated yet (or not accepted by other peers)
@@ -76,6 +90,30 @@ impl TxPoolSyncService for MockTxPoolService {
         todo!()
     }
 
+    fn status(&self) -> TxPoolStatus {
+        if let Some(status) = self.status.lock().unwrap().as_ref() {
+            return status.clone();
+        }
+        let txn_count = self.pool.lock().unwrap().len();
+        TxPoolStatus {
+            txn_count,
+            max_txn_count: usize::max_value(),
+            senders: txn_count,
+            ..Default::default()
+        }
+    }
+
+    fn gas_price_suggestion(&self) -> GasPriceSuggestion {
+        let samples = self
+            .pool
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|txn| txn.gas_unit_price())
+            .collect();
+        GasPriceSuggestion::from_samples(samples, 1)
+    }
+
     /// rollback
     fn rollback(
         &self,
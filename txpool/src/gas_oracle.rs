@@ -0,0 +1,53 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use common_crypto::hash::HashValue;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use storage::Store;
+use types::block::BlockHeader;
+
+/// Sample gas prices of the txns included in recent blocks.
+/// The samples are cached until the chain head changes.
+#[derive(Clone)]
+pub(crate) struct GasPriceOracle {
+    sample_blocks: u64,
+    storage: Arc<dyn Store>,
+    cache: Arc<Mutex<Option<(HashValue, Vec<u64>)>>>,
+}
+
+impl GasPriceOracle {
+    pub fn new(sample_blocks: u64, storage: Arc<dyn Store>) -> Self {
+        Self {
+            sample_blocks,
+            storage,
+            cache: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Gas prices of the txns in the last `sample_blocks` blocks, ended with `head`.
+    pub fn block_gas_prices(&self, head: &BlockHeader) -> Result<Vec<u64>> {
+        let head_id = head.id();
+        if let Some((cached_id, prices)) = self.cache.lock().as_ref() {
+            if *cached_id == head_id {
+                return Ok(prices.clone());
+            }
+        }
+        let mut prices = vec![];
+        let mut block_id = head_id;
+        for _ in 0..self.sample_blocks {
+            let block = match self.storage.get_block_by_hash(block_id)? {
+                Some(block) => block,
+                None => break,
+            };
+            prices.extend(block.transactions().iter().map(|t| t.gas_unit_price()));
+            if block.header().number() == 0 {
+                break;
+            }
+            block_id = block.header().parent_hash();
+        }
+        *self.cache.lock() = Some((head_id, prices.clone()));
+        Ok(prices)
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Journal of pending txns, so they survive the node restart.
+
+use anyhow::Result;
+use starcoin_config::TxPoolJournalMode;
+use std::fs;
+use std::path::PathBuf;
+use types::transaction::SignedUserTransaction;
+
+#[derive(Clone, Debug)]
+pub(crate) struct TxnJournal {
+    mode: TxPoolJournalMode,
+    path: PathBuf,
+}
+
+impl TxnJournal {
+    pub fn new(mode: TxPoolJournalMode, path: PathBuf) -> Self {
+        Self { mode, path }
+    }
+
+    pub fn mode(&self) -> &TxPoolJournalMode {
+        &self.mode
+    }
+
+    /// Load the journaled txns, return empty if the journal does not exist.
+    pub fn load(&self) -> Result<Vec<SignedUserTransaction>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        let bytes = fs::read(self.path.as_path())?;
+        Ok(scs::from_bytes(bytes.as_slice())?)
+    }
+
+    /// Rewrite the journal with `txns`, write to a temp file first, so a crash during the
+    /// write does not corrupt the previous journal.
+    pub fn save(&self, txns: &[SignedUserTransaction]) -> Result<()> {
+        let bytes = scs::to_bytes(&txns.to_vec())?;
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(tmp_path.as_path(), bytes)?;
+        fs::rename(tmp_path, self.path.as_path())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_save_and_load() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let journal = TxnJournal::new(
+            TxPoolJournalMode::Local,
+            tmpdir.path().join("txpool_journal"),
+        );
+        assert!(journal.load().unwrap().is_empty());
+        let txns = vec![SignedUserTransaction::mock(), SignedUserTransaction::mock()];
+        journal.save(&txns).unwrap();
+        assert_eq!(journal.load().unwrap(), txns);
+        journal.save(&[]).unwrap();
+        assert!(journal.load().unwrap().is_empty());
+    }
+}
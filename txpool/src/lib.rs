@@ -15,7 +15,7 @@ use common_crypto::hash::HashValue;
 use starcoin_bus::{Bus, BusActor};
 use starcoin_config::TxPoolConfig;
 use starcoin_txpool_api::TxnStatusFullEvent;
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, sync::Arc, time::Duration};
 use storage::Store;
 use tx_relay::{PeerTransactions, PropagateNewTransactions};
 use types::system_events::NewHeadBlock;
@@ -26,6 +26,8 @@ use tx_pool_service_impl::Inner;
 pub use tx_pool_service_impl::TxPoolService;
 
 mod counters;
+mod gas_oracle;
+mod journal;
 mod pool;
 mod pool_client;
 #[cfg(test)]
@@ -33,6 +35,10 @@ mod test;
 pub mod test_helper;
 mod tx_pool_service_impl;
 
+/// Interval to save the journal if the pool changed, so a burst of txns does not rewrite the
+/// journal on every txn status event.
+const JOURNAL_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
 #[derive(Clone, Debug)]
 pub struct TxPool {
     inner: Inner,
@@ -57,6 +63,9 @@ impl TxPool {
         let best_block_header = best_block.into_inner().0;
         let service = TxPoolService::new(pool_config, storage, best_block_header);
         let inner = service.get_inner();
+        if let Err(e) = inner.load_journal() {
+            error!("fail to load txpool journal, err: {:?}", e);
+        }
         let pool = TxPoolActor::new(inner.clone(), bus);
         let pool_addr = pool.start();
         Self {
@@ -95,6 +104,8 @@ pub struct TxPoolRef {
 pub(crate) struct TxPoolActor {
     inner: Inner,
     bus: actix::Addr<BusActor>,
+    /// The pool changed since the journal was last saved.
+    journal_dirty: bool,
 }
 impl std::fmt::Debug for TxPoolActor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -109,13 +120,24 @@ impl std::fmt::Debug for TxPoolActor {
 
 impl TxPoolActor {
     pub fn new(inner: Inner, bus: actix::Addr<BusActor>) -> Self {
-        Self { bus, inner }
+        Self {
+            bus,
+            inner,
+            journal_dirty: false,
+        }
     }
 
     pub fn launch(self) -> TxPoolRef {
         let addr = self.start();
         TxPoolRef { addr }
     }
+
+    fn save_journal(&mut self) {
+        if let Err(e) = self.inner.save_journal() {
+            error!("fail to save txpool journal, err: {:?}", e);
+        }
+        self.journal_dirty = false;
+    }
 }
 
 impl actix::Actor for TxPoolActor {
@@ -153,9 +175,20 @@ impl actix::Actor for TxPoolActor {
             .wait(ctx);
 
         ctx.add_stream(self.inner.subscribe_txns());
+        // keep the journal in sync with the pool, so the pending txns survive a crash.
+        ctx.run_interval(JOURNAL_SAVE_INTERVAL, |act, _ctx| {
+            if act.journal_dirty {
+                act.save_journal();
+            }
+        });
 
         info!("txn pool started");
     }
+
+    fn stopped(&mut self, _ctx: &mut Self::Context) {
+        self.save_journal();
+        info!("txn pool stopped");
+    }
 }
 /// Listen to txn status, and propagate to remote peers if necessary.
 impl StreamHandler<TxnStatusFullEvent> for TxPoolActor {
@@ -175,6 +208,7 @@ impl StreamHandler<TxnStatusFullEvent> for TxPoolActor {
                 .with_label_values(&["count"])
                 .set(txn_count as i64);
         }
+        self.journal_dirty = true;
         // TODO: need peer info to do more accurate sending.
         let mut txns = vec![];
         for (h, s) in item.iter() {
@@ -11,6 +11,7 @@ use crate::{pool, pool::PoolTransaction};
 use common_crypto::hash::HashValue;
 use futures_channel::mpsc;
 use parking_lot::RwLock;
+use starcoin_txpool_api::TxPoolStatus;
 use std::{
     cmp,
     collections::{BTreeMap, HashMap},
@@ -72,6 +73,20 @@ impl fmt::Display for Status {
     }
 }
 
+impl From<Status> for TxPoolStatus {
+    fn from(status: Status) -> Self {
+        TxPoolStatus {
+            txn_count: status.status.transaction_count,
+            max_txn_count: status.limits.max_count,
+            mem: status.status.mem_usage,
+            max_mem: status.limits.max_mem_usage,
+            senders: status.status.senders,
+            is_full: status.status.transaction_count >= status.limits.max_count
+                || status.status.mem_usage >= status.limits.max_mem_usage,
+        }
+    }
+}
+
 #[derive(Debug)]
 struct CachedPending {
     block_number: u64,
@@ -205,13 +220,14 @@ impl TransactionQueue {
         limits: tx_pool::Options,
         verification_options: verifier::Options,
         strategy: PrioritizationStrategy,
+        price_bump_percent: u64,
     ) -> Self {
         let max_count = limits.max_count;
         TransactionQueue {
             insertion_id: Default::default(),
             pool: RwLock::new(tx_pool::Pool::new(
                 Default::default(),
-                scoring::SeqNumberAndGasPrice(strategy),
+                scoring::SeqNumberAndGasPrice::new(strategy, price_bump_percent),
                 limits,
             )),
             options: RwLock::new(verification_options),
@@ -320,11 +336,11 @@ impl TransactionQueue {
         results
     }
 
-    // /// Returns all transactions in the queue without explicit ordering.
-    // pub fn all_transactions(&self) -> Vec<Arc<pool::VerifiedTransaction>> {
-    //     let ready = |_tx: &pool::VerifiedTransaction| tx_pool::Readiness::Ready;
-    //     self.pool.unordered_pending(ready).collect()
-    // }
+    /// Returns all transactions in the queue without explicit ordering.
+    pub fn all_transactions(&self) -> Vec<Arc<pool::VerifiedTransaction>> {
+        let ready = |_tx: &pool::VerifiedTransaction| tx_pool::Readiness::Ready;
+        self.pool.read().unordered_pending(ready).collect()
+    }
 
     // /// Returns all transaction hashes in the queue without explicit ordering.
     // pub fn all_transaction_hashes(&self) -> Vec<HashValue> {
@@ -9,8 +9,8 @@
 //! May have some overlap with `Readiness` since we don't want to keep around
 //! stalled transactions.
 use crate::pool::{
-    client::Client, scoring, Gas, GasPrice, PoolTransaction, Priority, UnverifiedUserTransaction,
-    VerifiedTransaction,
+    client::Client, scoring, Gas, GasPrice, PoolTransaction, Priority, ScoredTransaction,
+    UnverifiedUserTransaction, VerifiedTransaction,
 };
 use std::sync::{atomic::AtomicUsize, Arc};
 use types::transaction;
@@ -81,6 +81,20 @@ impl<C: Client> tx_pool::Verifier<PoolTransaction>
         let hash = tx.hash();
         let is_local_txn = tx.is_local();
         let is_retracted = tx.is_retracted();
+
+        // The pool is full, reject the txn which can not beat the worst one before the expensive checks.
+        if let Some((ref scoring, ref worst)) = self.transaction_to_replace {
+            if !is_local_txn && scoring.should_reject_early(worst, &tx) {
+                debug!(
+                    target: "txqueue",
+                    "[{:?}] Rejected tx early, cheaper than the worst tx {:?}", hash, worst.hash
+                );
+                return Err(transaction::TransactionError::TooCheapToReplace {
+                    prev: Some(worst.gas_price()),
+                    new: Some(tx.gas_price()),
+                });
+            }
+        }
         let verified_txn = match tx {
             PoolTransaction::Unverified(unverified) | PoolTransaction::Retracted(unverified) => {
                 match self.client.verify_transaction(unverified) {
@@ -110,7 +124,7 @@ impl<C: Client> tx_pool::Verifier<PoolTransaction>
         let priority = match (is_local_txn, is_retracted) {
             (true, _) => Priority::Local,
             (false, true) => Priority::Retracted,
-            (false, false) => Priority::Local,
+            (false, false) => Priority::Regular,
         };
         Ok(VerifiedTransaction {
             transaction: verified_txn,
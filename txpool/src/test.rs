@@ -1,15 +1,27 @@
 use super::test_helper;
-use crate::pool::AccountSeqNumberClient;
+use crate::pool::{
+    AccountSeqNumberClient, Client, PoolTransaction, PrioritizationStrategy, TransactionQueue,
+    UnverifiedUserTransaction,
+};
+use crate::TxPool;
 use anyhow::Result;
+use common_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
 use common_crypto::hash::PlainCryptoHash;
 use common_crypto::keygen::KeyGen;
 use parking_lot::RwLock;
+use starcoin_bus::BusActor;
+use starcoin_config::NodeConfig;
 use starcoin_executor::executor::Executor;
 use starcoin_executor::TransactionExecutor;
+use starcoin_genesis::Genesis;
 use starcoin_txpool_api::TxPoolSyncService;
 use std::collections::HashMap;
 use std::sync::Arc;
+use storage::{cache_storage::CacheStorage, storage::StorageInstance, Storage};
 use types::account_address::{self, AccountAddress};
+use types::transaction::{
+    PendingTransaction, SignatureCheckedTransaction, SignedUserTransaction, TransactionError,
+};
 use types::{account_config, transaction::authenticator::AuthenticationKey};
 
 #[derive(Clone, Debug)]
@@ -38,6 +50,136 @@ impl AccountSeqNumberClient for MockNonceClient {
     }
 }
 
+impl Client for MockNonceClient {
+    fn verify_transaction(
+        &self,
+        tx: UnverifiedUserTransaction,
+    ) -> Result<SignatureCheckedTransaction, TransactionError> {
+        SignedUserTransaction::from(tx)
+            .check_signature()
+            .map_err(|e| TransactionError::InvalidSignature(e.to_string()))
+    }
+}
+
+fn new_queue(max_count: usize, max_per_sender: usize) -> TransactionQueue {
+    TransactionQueue::new(
+        tx_pool::Options {
+            max_count,
+            max_mem_usage: usize::max_value(),
+            max_per_sender,
+        },
+        Default::default(),
+        PrioritizationStrategy::GasPriceOnly,
+        10,
+    )
+}
+
+fn build_txn(
+    keypair: &(Ed25519PrivateKey, Ed25519PublicKey),
+    seq_num: u64,
+    gas_price: u64,
+    amount: u64,
+) -> SignedUserTransaction {
+    let sender = account_address::from_public_key(&keypair.1);
+    Executor::build_transfer_txn(
+        sender,
+        account_config::association_address(),
+        vec![],
+        seq_num,
+        amount,
+        gas_price,
+        10000,
+    )
+    .sign(&keypair.0, keypair.1.clone())
+    .unwrap()
+    .into_inner()
+}
+
+fn import(
+    queue: &TransactionQueue,
+    txn: SignedUserTransaction,
+    local: bool,
+) -> Result<(), TransactionError> {
+    let txn = if local {
+        PoolTransaction::Local(PendingTransaction::from(txn))
+    } else {
+        PoolTransaction::Unverified(UnverifiedUserTransaction::from(txn))
+    };
+    queue
+        .import(MockNonceClient::default(), vec![txn])
+        .pop()
+        .unwrap()
+}
+
+#[test]
+fn test_replace_by_fee() {
+    let queue = new_queue(16, 16);
+    let keypair = KeyGen::from_os_rng().generate_keypair();
+    assert!(import(&queue, build_txn(&keypair, 0, 100, 1), false).is_ok());
+
+    // same gas price resubmission can not replace the pending txn.
+    let result = import(&queue, build_txn(&keypair, 0, 100, 2), false);
+    assert!(matches!(
+        result,
+        Err(TransactionError::TooCheapToReplace { .. })
+    ));
+    // the bump is less than 10%.
+    let result = import(&queue, build_txn(&keypair, 0, 109, 1), false);
+    assert!(matches!(
+        result,
+        Err(TransactionError::TooCheapToReplace { .. })
+    ));
+
+    let txn = build_txn(&keypair, 0, 110, 1);
+    assert!(import(&queue, txn.clone(), false).is_ok());
+    let txns = queue.all_transactions();
+    assert_eq!(txns.len(), 1);
+    assert_eq!(txns[0].signed().crypto_hash(), txn.crypto_hash());
+}
+
+#[test]
+fn test_reject_early_when_pool_full() {
+    let queue = new_queue(2, 16);
+    for _ in 0..2 {
+        let keypair = KeyGen::from_os_rng().generate_keypair();
+        assert!(import(&queue, build_txn(&keypair, 0, 10, 1), false).is_ok());
+    }
+
+    // can not beat the worst txn.
+    for &gas_price in &[5, 10] {
+        let keypair = KeyGen::from_os_rng().generate_keypair();
+        let result = import(&queue, build_txn(&keypair, 0, gas_price, 1), false);
+        assert_eq!(
+            result,
+            Err(TransactionError::TooCheapToReplace {
+                prev: Some(10),
+                new: Some(gas_price),
+            })
+        );
+    }
+
+    let keypair = KeyGen::from_os_rng().generate_keypair();
+    assert!(import(&queue, build_txn(&keypair, 0, 20, 1), false).is_ok());
+    // local txns are never rejected early.
+    let keypair = KeyGen::from_os_rng().generate_keypair();
+    assert!(import(&queue, build_txn(&keypair, 0, 1, 1), true).is_ok());
+    assert_eq!(queue.all_transactions().len(), 2);
+}
+
+#[test]
+fn test_local_sender_ignore_limit() {
+    let queue = new_queue(16, 1);
+    let keypair = KeyGen::from_os_rng().generate_keypair();
+    assert!(import(&queue, build_txn(&keypair, 0, 10, 1), false).is_ok());
+    assert!(import(&queue, build_txn(&keypair, 1, 10, 1), false).is_err());
+
+    let keypair = KeyGen::from_os_rng().generate_keypair();
+    for seq_num in 0..3 {
+        assert!(import(&queue, build_txn(&keypair, seq_num, 10, 1), true).is_ok());
+    }
+    assert_eq!(queue.all_transactions().len(), 4);
+}
+
 #[actix_rt::test]
 async fn test_tx_pool() -> Result<()> {
     let pool = test_helper::start_txpool();
@@ -92,3 +234,104 @@ async fn test_rollback() -> Result<()> {
     assert_eq!(pending.crypto_hash(), new_txn.crypto_hash());
     Ok(())
 }
+
+#[actix_rt::test]
+async fn test_gas_price_suggestion() -> Result<()> {
+    let pool = test_helper::start_txpool();
+    let txpool_service = pool.get_service();
+    let suggestion = txpool_service.gas_price_suggestion();
+    assert_eq!(suggestion.samples, 0);
+    assert_eq!(suggestion.median, 1);
+
+    let txn = {
+        let (_private_key, public_key) = KeyGen::from_os_rng().generate_keypair();
+        let account_address = account_address::from_public_key(&public_key);
+        let auth_prefix = AuthenticationKey::ed25519(&public_key).prefix().to_vec();
+        let txn = Executor::build_mint_txn(account_address, auth_prefix, 1, 10000);
+        txn.as_signed_user_txn()?.clone()
+    };
+    let gas_price = txn.gas_unit_price();
+    assert!(txpool_service.add_txns(vec![txn]).pop().unwrap().is_ok());
+    let suggestion = txpool_service.gas_price_suggestion();
+    assert_eq!(suggestion.samples, 1);
+    assert_eq!(suggestion.median, std::cmp::max(gas_price, 1));
+    assert!(suggestion.low <= suggestion.median && suggestion.median <= suggestion.high);
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_txpool_journal() -> Result<()> {
+    let node_config = NodeConfig::random_for_test();
+    let storage = Arc::new(Storage::new(StorageInstance::new_cache_instance(
+        CacheStorage::new(),
+    ))?);
+    let startup_info = Genesis::build(node_config.net())?.execute(storage.clone())?;
+    let bus = BusActor::launch();
+
+    let txn = {
+        let (_private_key, public_key) = KeyGen::from_os_rng().generate_keypair();
+        let account_address = account_address::from_public_key(&public_key);
+        let auth_prefix = AuthenticationKey::ed25519(&public_key).prefix().to_vec();
+        let txn = Executor::build_mint_txn(account_address, auth_prefix, 1, 10000);
+        txn.as_signed_user_txn()?.clone()
+    };
+    let pool = TxPool::start(
+        node_config.tx_pool.clone(),
+        storage.clone(),
+        *startup_info.get_master(),
+        bus.clone(),
+    );
+    assert!(pool
+        .get_service()
+        .add_local_txns(vec![txn.clone()])
+        .pop()
+        .unwrap()
+        .is_ok());
+    pool.inner.save_journal()?;
+
+    // the restarted pool re-imports the journaled txn.
+    let restarted_pool = TxPool::start(
+        node_config.tx_pool.clone(),
+        storage,
+        *startup_info.get_master(),
+        bus,
+    );
+    let pending = restarted_pool.get_service().get_pending_txns(None);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].crypto_hash(), txn.crypto_hash());
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_peer_txns_not_local() -> Result<()> {
+    let pool = test_helper::start_txpool();
+    let txpool_service = pool.get_service();
+    let build_mint_txn = || -> Result<SignedUserTransaction> {
+        let (_private_key, public_key) = KeyGen::from_os_rng().generate_keypair();
+        let account_address = account_address::from_public_key(&public_key);
+        let auth_prefix = AuthenticationKey::ed25519(&public_key).prefix().to_vec();
+        let txn = Executor::build_mint_txn(account_address, auth_prefix, 1, 10000);
+        Ok(txn.as_signed_user_txn()?.clone())
+    };
+    // txns synced from peers are not local.
+    let peer_txn = build_mint_txn()?;
+    assert!(txpool_service
+        .add_txns(vec![peer_txn.clone()])
+        .pop()
+        .unwrap()
+        .is_ok());
+    assert_eq!(txpool_service.get_pending_txns(None).len(), 1);
+    assert!(pool.inner.queue().local_transactions().is_empty());
+
+    let local_txn = build_mint_txn()?;
+    assert!(txpool_service
+        .add_local_txns(vec![local_txn.clone()])
+        .pop()
+        .unwrap()
+        .is_ok());
+    let local_txns = pool.inner.queue().local_transactions();
+    assert_eq!(local_txns.len(), 1);
+    assert!(local_txns.contains_key(&local_txn.crypto_hash()));
+    assert!(!local_txns.contains_key(&peer_txn.crypto_hash()));
+    Ok(())
+}
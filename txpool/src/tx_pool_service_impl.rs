@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::counters::TXPOOL_SERVICE_HISTOGRAM;
+use crate::gas_oracle::GasPriceOracle;
+use crate::journal::TxnJournal;
 use crate::{
     pool,
     pool::{
@@ -15,8 +17,8 @@ use anyhow::Result;
 use common_crypto::hash::{HashValue, PlainCryptoHash};
 use futures_channel::mpsc;
 use parking_lot::RwLock;
-use starcoin_config::TxPoolConfig;
-use starcoin_txpool_api::TxPoolSyncService;
+use starcoin_config::{TxPoolConfig, TxPoolJournalMode};
+use starcoin_txpool_api::{GasPriceSuggestion, TxPoolStatus, TxPoolSyncService};
 use std::sync::Arc;
 use storage::Store;
 use types::{
@@ -48,13 +50,21 @@ impl TxPoolService {
             },
             verifier_options,
             PrioritizationStrategy::GasPriceOnly,
+            pool_config.price_bump_percent,
         );
         let queue = Arc::new(queue);
+        let journal = pool_config
+            .journal_file()
+            .map(|path| Arc::new(TxnJournal::new(pool_config.journal.clone(), path)));
+        let gas_oracle = GasPriceOracle::new(pool_config.gas_price_sample_blocks, storage.clone());
         let inner = Inner {
             queue,
             storage,
+            gas_oracle,
+            minimal_gas_price: pool_config.minimal_gas_price,
             chain_header: Arc::new(RwLock::new(chain_header)),
             sequence_number_cache: NonceCache::new(128),
+            journal,
         };
 
         Self { inner }
@@ -81,6 +91,16 @@ impl TxPoolSyncService for TxPoolService {
         self.inner.import_txns(txns)
     }
 
+    fn add_local_txns(
+        &self,
+        txns: Vec<SignedUserTransaction>,
+    ) -> Vec<Result<(), transaction::TransactionError>> {
+        let _timer = TXPOOL_SERVICE_HISTOGRAM
+            .with_label_values(&["add_local_txns"])
+            .start_timer();
+        self.inner.import_local_txns(txns)
+    }
+
     fn remove_txn(&self, txn_hash: HashValue, is_invalid: bool) -> Option<SignedUserTransaction> {
         let _timer = TXPOOL_SERVICE_HISTOGRAM
             .with_label_values(&["remove_txn"])
@@ -118,6 +138,20 @@ impl TxPoolSyncService for TxPoolService {
         self.inner.subscribe_txns()
     }
 
+    fn status(&self) -> TxPoolStatus {
+        let _timer = TXPOOL_SERVICE_HISTOGRAM
+            .with_label_values(&["status"])
+            .start_timer();
+        self.inner.pool_status().into()
+    }
+
+    fn gas_price_suggestion(&self) -> GasPriceSuggestion {
+        let _timer = TXPOOL_SERVICE_HISTOGRAM
+            .with_label_values(&["gas_price_suggestion"])
+            .start_timer();
+        self.inner.gas_price_suggestion()
+    }
+
     /// rollback
     fn rollback(
         &self,
@@ -137,7 +171,10 @@ pub(crate) struct Inner {
     queue: Arc<TxnQueue>,
     chain_header: Arc<RwLock<BlockHeader>>,
     storage: Arc<dyn Store>,
+    gas_oracle: GasPriceOracle,
+    minimal_gas_price: u64,
     sequence_number_cache: NonceCache,
+    journal: Option<Arc<TxnJournal>>,
 }
 impl std::fmt::Debug for Inner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -183,6 +220,93 @@ impl Inner {
             .map(|t| PoolTransaction::Unverified(UnverifiedUserTransaction::from(t)));
         self.queue.import(self.get_pool_client(), txns)
     }
+
+    /// Import txns submitted by this node, they are tracked as local txns.
+    pub(crate) fn import_local_txns(
+        &self,
+        txns: Vec<transaction::SignedUserTransaction>,
+    ) -> Vec<Result<(), transaction::TransactionError>> {
+        let txns = txns
+            .into_iter()
+            .map(|t| PoolTransaction::Local(transaction::PendingTransaction::from(t)));
+        self.queue.import(self.get_pool_client(), txns)
+    }
+
+    /// Txns should be journaled, ordered by sender and sequence number.
+    fn journal_txns(&self, mode: &TxPoolJournalMode) -> Vec<SignedUserTransaction> {
+        let mut txns: Vec<SignedUserTransaction> = match mode {
+            TxPoolJournalMode::Off => vec![],
+            TxPoolJournalMode::Local => self
+                .queue
+                .local_transactions()
+                .into_iter()
+                .filter_map(|(_, status)| match status {
+                    pool::local_transactions::Status::Pending(txn) => Some(txn.signed().clone()),
+                    _ => None,
+                })
+                .collect(),
+            TxPoolJournalMode::All => self
+                .queue
+                .all_transactions()
+                .into_iter()
+                .map(|txn| txn.signed().clone())
+                .collect(),
+        };
+        txns.sort_by_key(|txn| (txn.sender(), txn.sequence_number()));
+        txns
+    }
+
+    /// Rewrite the journal by current pending txns.
+    pub(crate) fn save_journal(&self) -> Result<()> {
+        if let Some(journal) = self.journal.as_ref() {
+            journal.save(self.journal_txns(journal.mode()).as_slice())?;
+        }
+        Ok(())
+    }
+
+    /// Re-import the journaled txns, txns already included or expired are rejected by the
+    /// verification against the head state.
+    pub(crate) fn load_journal(&self) -> Result<()> {
+        let journal = match self.journal.as_ref() {
+            Some(journal) => journal,
+            None => return Ok(()),
+        };
+        let txns = journal.load()?;
+        if txns.is_empty() {
+            return Ok(());
+        }
+        let total = txns.len();
+        let results = match journal.mode() {
+            TxPoolJournalMode::All => self.import_txns(txns),
+            _ => self.import_local_txns(txns),
+        };
+        let imported = results.iter().filter(|result| result.is_ok()).count();
+        info!(
+            "re-import {} of {} journaled txns to txpool.",
+            imported, total
+        );
+        self.cull();
+        Ok(())
+    }
+
+    /// Suggest gas price by the txns of recent blocks and the pending txns in the pool.
+    pub(crate) fn gas_price_suggestion(&self) -> GasPriceSuggestion {
+        let mut samples = self
+            .gas_oracle
+            .block_gas_prices(&self.get_chain_header())
+            .unwrap_or_else(|e| {
+                warn!("fail to sample gas price of recent blocks, err: {:?}", e);
+                vec![]
+            });
+        samples.extend(
+            self.queue
+                .all_transactions()
+                .iter()
+                .map(|txn| txn.signed().gas_unit_price()),
+        );
+        GasPriceSuggestion::from_samples(samples, std::cmp::max(self.minimal_gas_price, 1))
+    }
+
     pub(crate) fn remove_txn(
         &self,
         txn_hash: HashValue,
@@ -139,12 +139,54 @@ impl fmt::Display for PeerId {
     }
 }
 
+/// Optional protocol features of a peer, exchanged in the network handshake.
+/// The features of the min supported protocol version are served by every connected peer, so they
+/// are not capabilities. It is a bit set, so unknown capabilities of newer peers are ignored
+/// instead of failing the decode.
+#[derive(Eq, PartialEq, Hash, Deserialize, Serialize, Clone, Copy, Debug, Default)]
+pub struct PeerCapabilities(u64);
+
+impl PeerCapabilities {
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// All capabilities supported by current node, no optional feature yet.
+    pub const fn all() -> Self {
+        Self(0)
+    }
+
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    pub const fn contains(self, other: PeerCapabilities) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: PeerCapabilities) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: PeerCapabilities) {
+        self.0 &= !other.0;
+    }
+}
+
 #[derive(Eq, PartialEq, Hash, Deserialize, Serialize, Clone, Debug)]
 pub struct PeerInfo {
     pub peer_id: PeerId,
     pub block_number: BlockNumber,
     pub total_difficulty: U512,
     pub block_id: HashValue,
+    /// Network protocol version of the peer, set by the handshake.
+    pub protocol_version: u32,
+    /// Capabilities of the peer, set by the handshake.
+    pub capabilities: PeerCapabilities,
 }
 
 impl PeerInfo {
@@ -154,6 +196,8 @@ impl PeerInfo {
             block_number: 0,
             total_difficulty: U512::zero(),
             block_id: HashValue::random(),
+            protocol_version: 0,
+            capabilities: PeerCapabilities::empty(),
         }
     }
 
@@ -168,6 +212,8 @@ impl PeerInfo {
             block_number,
             total_difficulty,
             block_id,
+            protocol_version: 0,
+            capabilities: PeerCapabilities::empty(),
         }
     }
 
@@ -179,12 +225,53 @@ impl PeerInfo {
         self.block_number
     }
 
+    /// Set the protocol version and capabilities negotiated by the handshake.
+    pub fn with_protocol(mut self, protocol_version: u32, capabilities: PeerCapabilities) -> Self {
+        self.protocol_version = protocol_version;
+        self.capabilities = capabilities;
+        self
+    }
+
+    pub fn supports(&self, capabilities: PeerCapabilities) -> bool {
+        self.capabilities.contains(capabilities)
+    }
+
     pub fn default() -> Self {
         Self {
             peer_id: PeerId::random(),
             block_number: 0,
             total_difficulty: U512::from(0),
             block_id: HashValue::default(),
+            protocol_version: 0,
+            capabilities: PeerCapabilities::empty(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scs::SCSCodec;
+
+    #[test]
+    fn test_peer_capabilities() {
+        let feature_1 = PeerCapabilities::from_bits(1);
+        let feature_2 = PeerCapabilities::from_bits(1 << 1);
+        let mut capabilities = PeerCapabilities::empty();
+        assert!(!capabilities.contains(feature_1));
+        capabilities.insert(feature_1);
+        assert!(capabilities.contains(feature_1));
+        assert!(!capabilities.contains(feature_2));
+        capabilities.remove(feature_1);
+        assert_eq!(capabilities, PeerCapabilities::empty());
+
+        // unknown capabilities of newer peer are kept but ignored.
+        let info = PeerInfo::new_for_test(PeerId::random())
+            .with_protocol(3, PeerCapabilities::from_bits((1 << 63) | 1));
+        let info = PeerInfo::decode(&info.encode().unwrap()).unwrap();
+        assert_eq!(info.protocol_version, 3);
+        assert!(info.supports(feature_1));
+        assert!(!info.supports(feature_2));
+        assert!(info.supports(PeerCapabilities::all()));
+    }
+}
@@ -30,6 +30,7 @@ pub mod authenticator {
 mod error;
 pub mod helpers;
 mod pending_transaction;
+mod trace;
 
 pub use error::CallError;
 pub use error::Error as TransactionError;
@@ -38,6 +39,7 @@ pub use starcoin_vm_types::{
     transaction::{ChangeSet, Module, Script},
     transaction_argument::{parse_as_transaction_argument, TransactionArgument},
 };
+pub use trace::{AbortLocation, TransactionPhase, TransactionTrace};
 
 pub type Version = u64; // Height - also used for MVCC in StateDB
 
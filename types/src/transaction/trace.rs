@@ -0,0 +1,127 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    access_path::AccessPath,
+    contract_event::ContractEvent,
+    vm_error::{StatusCode, VMStatus},
+    write_set::WriteOp,
+};
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::HashValue;
+
+/// The phase of a user transaction execution.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TransactionPhase {
+    /// Signature check and the prologue, a txn failed in this phase is discarded.
+    Validation,
+    /// The txn payload and the epilogue.
+    Execution,
+}
+
+/// Where a transaction aborted, derived from the vm status.
+/// The vm reports the location of a Move `abort` in the status message, other errors only
+/// have the phase.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AbortLocation {
+    pub phase: TransactionPhase,
+    /// `address::Module` of the aborted function.
+    pub module: Option<String>,
+    pub function: Option<String>,
+    /// Bytecode offset in the aborted function.
+    pub code_offset: Option<u64>,
+}
+
+impl AbortLocation {
+    const CODE_OFFSET_SEPARATOR: &'static str = " at code offset ";
+
+    pub fn from_vm_status(phase: TransactionPhase, status: &VMStatus) -> Self {
+        let mut location = Self {
+            phase,
+            module: None,
+            function: None,
+            code_offset: None,
+        };
+        let message = match status.message.as_ref() {
+            Some(message) => message,
+            None => return location,
+        };
+        // the message of an abort is like `0x1::Account::prologue at code offset 12`.
+        if let Some(index) = message.find(Self::CODE_OFFSET_SEPARATOR) {
+            let (head, tail) = message.split_at(index);
+            let offset = tail[Self::CODE_OFFSET_SEPARATOR.len()..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect::<String>();
+            location.code_offset = offset.parse().ok();
+            let function_path = head.rsplit(char::is_whitespace).next().unwrap_or(head);
+            let mut parts = function_path.rsplitn(2, "::");
+            if let (Some(function), Some(module)) = (parts.next(), parts.next()) {
+                location.function = Some(function.to_string());
+                location.module = Some(module.to_string());
+            }
+        }
+        location
+    }
+}
+
+/// The result of re-executing a user transaction: the status, the abort location derived from
+/// the status, and the state accessed by the txn.
+/// The call tree of Move functions with gas per call is out of scope, the Move VM of this version
+/// exposes neither its interpreter frames nor the gas of each call, so gas is only reported for
+/// the whole txn.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransactionTrace {
+    pub txn_hash: HashValue,
+    /// The block which includes the txn, None if the txn is traced against an arbitrary state.
+    pub block_id: Option<HashValue>,
+    /// The state root the txn executed on.
+    pub state_root: Option<HashValue>,
+    pub major_status: StatusCode,
+    pub sub_status: Option<u64>,
+    pub status_message: Option<String>,
+    /// None if the txn executed successfully.
+    pub abort_location: Option<AbortLocation>,
+    /// Access paths read by the txn, in the order of the first read.
+    pub reads: Vec<AccessPath>,
+    pub writes: Vec<(AccessPath, WriteOp)>,
+    pub events: Vec<ContractEvent>,
+    pub gas_used: u64,
+    /// Whether the txn output is kept or discarded.
+    pub kept: bool,
+}
+
+impl TransactionTrace {
+    pub fn is_executed(&self) -> bool {
+        self.major_status == StatusCode::EXECUTED
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abort_location_from_vm_status() {
+        let status = VMStatus::new(StatusCode::ABORTED)
+            .with_sub_status(7)
+            .with_message("0x1::Account::pay_from_sender at code offset 12".to_string());
+        let location = AbortLocation::from_vm_status(TransactionPhase::Execution, &status);
+        assert_eq!(location.phase, TransactionPhase::Execution);
+        assert_eq!(location.module.as_deref(), Some("0x1::Account"));
+        assert_eq!(location.function.as_deref(), Some("pay_from_sender"));
+        assert_eq!(location.code_offset, Some(12));
+
+        let status = VMStatus::new(StatusCode::SEQUENCE_NUMBER_TOO_OLD);
+        let location = AbortLocation::from_vm_status(TransactionPhase::Validation, &status);
+        assert_eq!(location.phase, TransactionPhase::Validation);
+        assert!(location.module.is_none());
+        assert!(location.function.is_none());
+        assert!(location.code_offset.is_none());
+
+        let status = VMStatus::new(StatusCode::ARITHMETIC_ERROR)
+            .with_message("arithmetic overflow".to_string());
+        let location = AbortLocation::from_vm_status(TransactionPhase::Execution, &status);
+        assert!(location.function.is_none());
+    }
+}
@@ -48,6 +48,7 @@ pub fn raw_peer_to_peer_txn(
     gas_price: u64,
     max_gas: u64,
     coin_type: TypeTag,
+    expiration_time: Duration,
 ) -> RawUserTransaction {
     let mut args: Vec<TransactionArgument> = Vec::new();
     args.push(TransactionArgument::Address(receiver));
@@ -60,7 +61,7 @@ pub fn raw_peer_to_peer_txn(
         TransactionPayload::Script(Script::new(PEER_TO_PEER_TXN.clone(), vec![coin_type], args)),
         max_gas,
         gas_price,
-        Duration::from_secs(DEFAULT_EXPIRATION_TIME),
+        expiration_time,
     )
 }
 
@@ -10,11 +10,13 @@ use move_vm_state::{
 use once_cell::sync::Lazy;
 use starcoin_logger::prelude::*;
 use starcoin_types::{
+    access_path::AccessPath,
     account_config,
     block_metadata::BlockMetadata,
     transaction::{
-        ChangeSet, SignatureCheckedTransaction, SignedUserTransaction, Transaction,
-        TransactionArgument, TransactionOutput, TransactionPayload, TransactionStatus,
+        AbortLocation, ChangeSet, SignatureCheckedTransaction, SignedUserTransaction, Transaction,
+        TransactionArgument, TransactionOutput, TransactionPayload, TransactionPhase,
+        TransactionStatus, TransactionTrace,
     },
     vm_error::{sub_status, StatusCode, VMStatus},
     write_set::WriteSet,
@@ -30,7 +32,7 @@ use starcoin_vm_types::{
     transaction_metadata::TransactionMetadata,
     values::Value,
 };
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 pub static KEEP_STATUS: Lazy<TransactionStatus> =
     Lazy::new(|| TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED)));
@@ -520,6 +522,100 @@ impl StarcoinVM {
     ) -> Result<Vec<TransactionOutput>> {
         self.execute_block_transactions(state_view, transactions, None)
     }
+
+    /// Re-execute a user txn on `state_view`, and record its status and the state it accesses.
+    /// The move vm has no hook into the interpreter, so function frames are not traced, the abort
+    /// location is derived from the vm status.
+    pub fn trace_user_transaction(
+        &mut self,
+        state_view: &dyn StateView,
+        txn: SignedUserTransaction,
+    ) -> TransactionTrace {
+        let txn_hash = Transaction::UserTransaction(txn.clone()).id();
+        let recorder = ReadRecorder::new(state_view);
+        let mut data_cache = BlockDataCache::new(&recorder);
+        self.load_configs_impl(&data_cache);
+
+        let txn_data = TransactionMetadata::new(&txn.clone().into());
+        let verified_payload = txn
+            .check_signature()
+            .map_err(|_| VMStatus::new(StatusCode::INVALID_SIGNATURE))
+            .and_then(|txn| self.verify_transaction_impl(&txn, &data_cache, &txn_data));
+        let (output, phase) = match verified_payload {
+            Ok(payload) => (
+                self.execute_verified_payload(&mut data_cache, &txn_data, payload),
+                TransactionPhase::Execution,
+            ),
+            Err(e) => (discard_error_output(e), TransactionPhase::Validation),
+        };
+        drop(data_cache);
+
+        let status = output.status().vm_status().clone();
+        let abort_location = if status.major_status == StatusCode::EXECUTED {
+            None
+        } else {
+            Some(AbortLocation::from_vm_status(phase, &status))
+        };
+        let kept = match output.status() {
+            TransactionStatus::Keep(_) => true,
+            TransactionStatus::Discard(_) => false,
+        };
+        let (write_set, events, gas_used, _) = output.into_inner();
+        TransactionTrace {
+            txn_hash,
+            block_id: None,
+            state_root: None,
+            major_status: status.major_status,
+            sub_status: status.sub_status,
+            status_message: status.message,
+            abort_location,
+            reads: recorder.into_reads(),
+            writes: write_set.into_iter().collect(),
+            events,
+            gas_used,
+            kept,
+        }
+    }
+}
+
+/// A `StateView` records the access paths read through it.
+struct ReadRecorder<'a> {
+    inner: &'a dyn StateView,
+    reads: Mutex<Vec<AccessPath>>,
+}
+
+impl<'a> ReadRecorder<'a> {
+    fn new(inner: &'a dyn StateView) -> Self {
+        Self {
+            inner,
+            reads: Mutex::new(vec![]),
+        }
+    }
+
+    fn into_reads(self) -> Vec<AccessPath> {
+        self.reads.into_inner().unwrap()
+    }
+}
+
+impl<'a> StateView for ReadRecorder<'a> {
+    fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
+        let mut reads = self.reads.lock().unwrap();
+        if !reads.contains(access_path) {
+            reads.push(access_path.clone());
+        }
+        self.inner.get(access_path)
+    }
+
+    fn multi_get(&self, access_paths: &[AccessPath]) -> Result<Vec<Option<Vec<u8>>>> {
+        access_paths
+            .iter()
+            .map(|access_path| self.get(access_path))
+            .collect()
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.inner.is_genesis()
+    }
 }
 
 pub enum TransactionBlock {
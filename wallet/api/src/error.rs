@@ -42,6 +42,10 @@ impl From<WalletError> for AccountServiceError {
                 format_err!("no private key data associate with address {}", a),
             ),
             WalletError::StoreError(e) => AccountServiceError::AccountError(e),
+            WalletError::NotSupported(op) => AccountServiceError::AccountError(format_err!(
+                "{} is not supported by the wallet",
+                op
+            )),
         }
     }
 }
@@ -73,4 +77,6 @@ pub enum WalletError {
     AccountPrivateKeyMissing(AccountAddress),
     #[error("account vault store error, {0:?}")]
     StoreError(#[from] anyhow::Error),
+    #[error("{0} is not supported by the wallet")]
+    NotSupported(&'static str),
 }
@@ -135,6 +135,16 @@ where
         Ok(())
     }
 
+    fn change_password(
+        &self,
+        _address: &AccountAddress,
+        _old_password: &str,
+        _new_password: &str,
+    ) -> WalletResult<()> {
+        //do nothing
+        Ok(())
+    }
+
     fn sign_txn(&self, raw_txn: RawUserTransaction) -> WalletResult<SignedUserTransaction> {
         let address = raw_txn.sender();
         if !self.contains(&address)? {
@@ -74,4 +74,15 @@ impl WalletAsyncService for MockWalletService {
     ) -> ServiceResult<Vec<u8>> {
         Ok(self.wallet.export_account(&address, password.as_str())?)
     }
+
+    async fn change_password(
+        self,
+        address: AccountAddress,
+        old_password: String,
+        new_password: String,
+    ) -> ServiceResult<()> {
+        Ok(self
+            .wallet
+            .change_password(&address, old_password.as_str(), new_password.as_str())?)
+    }
 }
@@ -40,4 +40,12 @@ pub trait WalletAsyncService: Clone + std::marker::Unpin + Send + Sync {
         address: AccountAddress,
         password: String,
     ) -> ServiceResult<Vec<u8>>;
+
+    /// Re-encrypt the private key of `address` by `new_password`.
+    async fn change_password(
+        self,
+        address: AccountAddress,
+        old_password: String,
+        new_password: String,
+    ) -> ServiceResult<()>;
 }
@@ -35,6 +35,14 @@ pub trait Wallet {
 
     fn lock_account(&self, address: AccountAddress) -> WalletResult<()>;
 
+    /// Re-encrypt the account's private key by `new_password`, with the wallet's current kdf.
+    fn change_password(
+        &self,
+        address: &AccountAddress,
+        old_password: &str,
+        new_password: &str,
+    ) -> WalletResult<()>;
+
     /// Sign transaction by txn sender's Account.
     /// If the wallet is protected by password, should unlock the sender's account first.
     fn sign_txn(&self, raw_txn: RawUserTransaction) -> WalletResult<SignedUserTransaction>;
@@ -51,3 +59,75 @@ pub trait Wallet {
     /// Wallet must ensure that the default account can not bean removed.
     fn remove_account(&self, address: &AccountAddress) -> WalletResult<()>;
 }
+
+impl<W> Wallet for Box<W>
+where
+    W: Wallet + ?Sized,
+{
+    fn create_account(&self, password: &str) -> WalletResult<WalletAccount> {
+        (**self).create_account(password)
+    }
+
+    fn get_account(&self, address: &AccountAddress) -> WalletResult<Option<WalletAccount>> {
+        (**self).get_account(address)
+    }
+
+    fn import_account(
+        &self,
+        address: AccountAddress,
+        private_key: Vec<u8>,
+        password: &str,
+    ) -> WalletResult<WalletAccount> {
+        (**self).import_account(address, private_key, password)
+    }
+
+    fn export_account(&self, address: &AccountAddress, password: &str) -> WalletResult<Vec<u8>> {
+        (**self).export_account(address, password)
+    }
+
+    fn contains(&self, address: &AccountAddress) -> WalletResult<bool> {
+        (**self).contains(address)
+    }
+
+    fn unlock_account(
+        &self,
+        address: AccountAddress,
+        password: &str,
+        duration: Duration,
+    ) -> WalletResult<()> {
+        (**self).unlock_account(address, password, duration)
+    }
+
+    fn lock_account(&self, address: AccountAddress) -> WalletResult<()> {
+        (**self).lock_account(address)
+    }
+
+    fn change_password(
+        &self,
+        address: &AccountAddress,
+        old_password: &str,
+        new_password: &str,
+    ) -> WalletResult<()> {
+        (**self).change_password(address, old_password, new_password)
+    }
+
+    fn sign_txn(&self, raw_txn: RawUserTransaction) -> WalletResult<SignedUserTransaction> {
+        (**self).sign_txn(raw_txn)
+    }
+
+    fn get_default_account(&self) -> WalletResult<Option<WalletAccount>> {
+        (**self).get_default_account()
+    }
+
+    fn get_accounts(&self) -> WalletResult<Vec<WalletAccount>> {
+        (**self).get_accounts()
+    }
+
+    fn set_default(&self, address: &AccountAddress) -> WalletResult<()> {
+        (**self).set_default(address)
+    }
+
+    fn remove_account(&self, address: &AccountAddress) -> WalletResult<()> {
+        (**self).remove_account(address)
+    }
+}
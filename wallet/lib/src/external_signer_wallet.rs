@@ -0,0 +1,351 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Wallet backed by an external signer process.
+//!
+//! The node only holds the accounts' public keys, every txn to sign is forwarded to the signer
+//! by a unix socket. Every request and response is a json object in a single line, one request
+//! per connection.
+
+use anyhow::{bail, ensure, format_err};
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::ed25519::Ed25519PublicKey;
+use starcoin_types::{
+    account_address::{self, AccountAddress},
+    transaction::{RawUserTransaction, SignedUserTransaction},
+};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use wallet_api::{error::WalletError, Wallet, WalletAccount, WalletStore};
+
+pub type Result<T> = std::result::Result<T, WalletError>;
+
+/// The signer may wait for a manual confirmation, so the timeout is generous.
+const SIGNER_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum SignerRequest {
+    /// List public keys of all accounts held by the signer.
+    Accounts,
+    /// Generate a new key, return its public key.
+    CreateAccount,
+    SignTxn(RawUserTransaction),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerResponse {
+    Accounts(Vec<Ed25519PublicKey>),
+    Account(Ed25519PublicKey),
+    SignedTxn(SignedUserTransaction),
+    Error(String),
+}
+
+/// Send one request to the signer listening on `socket`, and wait the response.
+pub fn call_signer(socket: &Path, request: &SignerRequest) -> anyhow::Result<SignerResponse> {
+    let mut stream = UnixStream::connect(socket).map_err(|e| {
+        format_err!(
+            "fail to connect external signer at {}: {}",
+            socket.display(),
+            e
+        )
+    })?;
+    stream.set_read_timeout(Some(SIGNER_TIMEOUT))?;
+    stream.set_write_timeout(Some(SIGNER_TIMEOUT))?;
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    stream.flush()?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+    ensure!(
+        !response.is_empty(),
+        "external signer closed the connection without response"
+    );
+    match serde_json::from_str(response.as_str())? {
+        SignerResponse::Error(e) => bail!("external signer error: {}", e),
+        response => Ok(response),
+    }
+}
+
+pub struct ExternalSignerWallet<TKeyStore> {
+    store: TKeyStore,
+    socket: PathBuf,
+    default_account: Mutex<Option<WalletAccount>>,
+}
+
+impl<TKeyStore> ExternalSignerWallet<TKeyStore>
+where
+    TKeyStore: WalletStore,
+{
+    pub fn new(store: TKeyStore, socket: PathBuf) -> Self {
+        Self {
+            store,
+            socket,
+            default_account: Mutex::new(None),
+        }
+    }
+
+    /// Save the accounts held by the signer but not known by the node,
+    /// the first account is default if there is no account yet.
+    pub fn sync_accounts(&self) -> Result<Vec<WalletAccount>> {
+        let public_keys = match call_signer(self.socket.as_path(), &SignerRequest::Accounts)? {
+            SignerResponse::Accounts(public_keys) => public_keys,
+            response => return Err(unexpected_response(response)),
+        };
+        let mut added = vec![];
+        for public_key in public_keys {
+            let address = account_address::from_public_key(&public_key);
+            if self.store.get_account(&address)?.is_none() {
+                added.push(self.save_public_key(address, public_key)?);
+            }
+        }
+        Ok(added)
+    }
+
+    fn save_public_key(
+        &self,
+        address: AccountAddress,
+        public_key: Ed25519PublicKey,
+    ) -> Result<WalletAccount> {
+        //first account is default.
+        let is_default = self.store.get_accounts()?.is_empty();
+        let account = WalletAccount::new(address, public_key, is_default);
+        self.store.save_account(account.clone())?;
+        Ok(account)
+    }
+}
+
+fn unexpected_response(response: SignerResponse) -> WalletError {
+    WalletError::StoreError(format_err!(
+        "unexpected external signer response: {:?}",
+        response
+    ))
+}
+
+impl<TKeyStore> Wallet for ExternalSignerWallet<TKeyStore>
+where
+    TKeyStore: WalletStore,
+{
+    /// The key is generated and encrypted by the signer, so the password is ignored.
+    fn create_account(&self, _password: &str) -> Result<WalletAccount> {
+        let public_key = match call_signer(self.socket.as_path(), &SignerRequest::CreateAccount)? {
+            SignerResponse::Account(public_key) => public_key,
+            response => return Err(unexpected_response(response)),
+        };
+        let address = account_address::from_public_key(&public_key);
+        self.save_public_key(address, public_key)
+    }
+
+    fn get_account(&self, address: &AccountAddress) -> Result<Option<WalletAccount>> {
+        Ok(self.store.get_account(address)?)
+    }
+
+    fn import_account(
+        &self,
+        _address: AccountAddress,
+        _private_key: Vec<u8>,
+        _password: &str,
+    ) -> Result<WalletAccount> {
+        Err(WalletError::NotSupported("import account"))
+    }
+
+    fn export_account(&self, _address: &AccountAddress, _password: &str) -> Result<Vec<u8>> {
+        Err(WalletError::NotSupported("export account"))
+    }
+
+    fn contains(&self, address: &AccountAddress) -> Result<bool> {
+        self.get_account(address).map(|w| w.is_some())
+    }
+
+    /// The signer guards the keys itself, so unlock does nothing.
+    fn unlock_account(
+        &self,
+        _address: AccountAddress,
+        _password: &str,
+        _duration: Duration,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn lock_account(&self, _address: AccountAddress) -> Result<()> {
+        Ok(())
+    }
+
+    fn change_password(
+        &self,
+        _address: &AccountAddress,
+        _old_password: &str,
+        _new_password: &str,
+    ) -> Result<()> {
+        Err(WalletError::NotSupported("change password"))
+    }
+
+    fn sign_txn(&self, raw_txn: RawUserTransaction) -> Result<SignedUserTransaction> {
+        let address = raw_txn.sender();
+        let account = self
+            .get_account(&address)?
+            .ok_or_else(|| WalletError::AccountNotExist(address))?;
+        let signed_txn = match call_signer(
+            self.socket.as_path(),
+            &SignerRequest::SignTxn(raw_txn.clone()),
+        )
+        .map_err(WalletError::TransactionSignError)?
+        {
+            SignerResponse::SignedTxn(signed_txn) => signed_txn,
+            response => return Err(unexpected_response(response)),
+        };
+        // never trust the signer, check it signs the requested txn by the account's key.
+        if signed_txn.raw_txn() != &raw_txn
+            || signed_txn.public_key().to_bytes() != account.public_key.to_bytes()
+        {
+            return Err(WalletError::TransactionSignError(format_err!(
+                "external signer returns a txn mismatch the request"
+            )));
+        }
+        signed_txn
+            .clone()
+            .check_signature()
+            .map_err(WalletError::TransactionSignError)?;
+        Ok(signed_txn)
+    }
+
+    fn get_default_account(&self) -> Result<Option<WalletAccount>> {
+        let default_account = self.default_account.lock().unwrap().as_ref().cloned();
+        match default_account {
+            Some(a) => Ok(Some(a)),
+            None => {
+                let default_account = self
+                    .store
+                    .get_accounts()?
+                    .into_iter()
+                    .find(|account| account.is_default);
+                *self.default_account.lock().unwrap() = default_account.clone();
+                Ok(default_account)
+            }
+        }
+    }
+
+    fn get_accounts(&self) -> Result<Vec<WalletAccount>> {
+        Ok(self.store.get_accounts()?)
+    }
+
+    fn set_default(&self, address: &AccountAddress) -> Result<()> {
+        let mut target = self
+            .get_account(address)?
+            .ok_or_else(|| WalletError::AccountNotExist(*address))?;
+
+        let default = self.get_default_account()?;
+        if let Some(mut default) = default {
+            if &default.address == address {
+                return Ok(());
+            }
+            default.is_default = false;
+            self.store.save_account(default)?;
+        }
+
+        target.is_default = true;
+        self.store.save_account(target.clone())?;
+        *self.default_account.lock().unwrap() = Some(target);
+        Ok(())
+    }
+
+    fn remove_account(&self, address: &AccountAddress) -> Result<()> {
+        if let Some(account) = self.get_account(address)? {
+            if account.is_default {
+                return Err(WalletError::RemoveDefaultAccountError(*address));
+            }
+            self.store.remove_account(address)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_wallet_store::FileWalletStore;
+    use starcoin_crypto::ed25519::Ed25519PrivateKey;
+    use starcoin_crypto::keygen::KeyGen;
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+
+    /// A signer holds keys in memory, serves `max_requests` requests then exits.
+    fn start_mock_signer(socket: PathBuf, max_requests: usize) -> thread::JoinHandle<()> {
+        let listener = UnixListener::bind(socket).unwrap();
+        thread::spawn(move || {
+            let mut keys: Vec<(Ed25519PrivateKey, Ed25519PublicKey)> = vec![];
+            for stream in listener.incoming().take(max_requests) {
+                let mut stream = stream.unwrap();
+                let mut line = String::new();
+                BufReader::new(stream.try_clone().unwrap())
+                    .read_line(&mut line)
+                    .unwrap();
+                let response = match serde_json::from_str(line.as_str()).unwrap() {
+                    SignerRequest::Accounts => {
+                        SignerResponse::Accounts(keys.iter().map(|k| k.1.clone()).collect())
+                    }
+                    SignerRequest::CreateAccount => {
+                        let keypair = KeyGen::from_os_rng().generate_keypair();
+                        let public_key = keypair.1.clone();
+                        keys.push(keypair);
+                        SignerResponse::Account(public_key)
+                    }
+                    SignerRequest::SignTxn(raw_txn) => {
+                        let sender = raw_txn.sender();
+                        match keys
+                            .iter()
+                            .find(|k| account_address::from_public_key(&k.1) == sender)
+                        {
+                            Some((private_key, public_key)) => SignerResponse::SignedTxn(
+                                raw_txn
+                                    .sign(private_key, public_key.clone())
+                                    .unwrap()
+                                    .into_inner(),
+                            ),
+                            None => SignerResponse::Error(format!("unknown sender {}", sender)),
+                        }
+                    }
+                };
+                let mut response = serde_json::to_string(&response).unwrap();
+                response.push('\n');
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        })
+    }
+
+    #[test]
+    fn test_external_signer_wallet() -> anyhow::Result<()> {
+        let tmp_path = tempfile::tempdir()?;
+        let socket = tmp_path.path().join("signer.ipc");
+        let signer = start_mock_signer(socket.clone(), 3);
+
+        let wallet =
+            ExternalSignerWallet::new(FileWalletStore::new(tmp_path.path().join("vault")), socket);
+        let account = wallet.create_account("")?;
+        assert!(account.is_default);
+        assert_eq!(
+            wallet.get_default_account()?.unwrap().address,
+            account.address
+        );
+
+        let raw_txn = RawUserTransaction::mock_by_sender(account.address);
+        let signed_txn = wallet.sign_txn(raw_txn.clone())?;
+        assert_eq!(signed_txn.raw_txn(), &raw_txn);
+
+        // the signer does not hold the key of an unknown sender.
+        let unknown = WalletAccount::random();
+        wallet.store.save_account(unknown.clone())?;
+        let raw_txn = RawUserTransaction::mock_by_sender(unknown.address);
+        assert!(wallet.sign_txn(raw_txn).is_err());
+
+        assert!(wallet.import_account(unknown.address, vec![], "").is_err());
+        signer.join().unwrap();
+        Ok(())
+    }
+}
@@ -5,7 +5,7 @@ use anyhow::format_err;
 use rand::prelude::*;
 use starcoin_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
 use starcoin_crypto::Uniform;
-use starcoin_decrypt::{decrypt, encrypt};
+use starcoin_decrypt::{decrypt, encrypt_with_kdf, kdf_of, KdfParams};
 use starcoin_types::transaction::helpers::TransactionSigner;
 use starcoin_types::{
     account_address::{self, AccountAddress},
@@ -27,6 +27,7 @@ pub type Result<T> = std::result::Result<T, WalletError>;
 #[derive(Default, Debug)]
 pub struct KeyStoreWallet<TKeyStore> {
     store: TKeyStore,
+    kdf: KdfParams,
     default_account: Mutex<Option<WalletAccount>>,
     key_cache: RwLock<KeyCache>,
 }
@@ -114,6 +115,7 @@ where
         duration: Duration,
     ) -> Result<()> {
         let keypair = self.unlock_prikey(&address, password)?;
+        self.upgrade_encryption(&address, &keypair, password)?;
         let address = account_address::from_public_key(&keypair.public_key);
         let ttl = std::time::Instant::now().add(duration);
         self.key_cache
@@ -128,6 +130,16 @@ where
         Ok(())
     }
 
+    fn change_password(
+        &self,
+        address: &AccountAddress,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<()> {
+        let keypair = self.unlock_prikey(address, old_password)?;
+        self.save_private_key(address, &keypair, new_password)
+    }
+
     fn sign_txn(&self, raw_txn: RawUserTransaction) -> Result<SignedUserTransaction> {
         let address = raw_txn.sender();
         if !self.contains(&address)? {
@@ -208,8 +220,14 @@ where
     TKeyStore: WalletStore,
 {
     pub fn new(keystore: TKeyStore) -> Result<Self> {
+        Self::new_with_kdf(keystore, KdfParams::default())
+    }
+
+    /// Create a wallet which encrypts the private keys with `kdf`.
+    pub fn new_with_kdf(keystore: TKeyStore, kdf: KdfParams) -> Result<Self> {
         let wallet = Self {
             store: keystore,
+            kdf,
             default_account: Mutex::new(None),
             key_cache: RwLock::new(KeyCache::default()),
         };
@@ -224,15 +242,45 @@ where
     ) -> Result<()> {
         let address = account.address;
         self.store.save_account(account)?;
-        let encrypted_prikey = encrypt(password.as_bytes(), &key_pair.private_key.to_bytes());
+        self.save_private_key(&address, &key_pair, password.as_str())
+    }
+
+    fn save_private_key(
+        &self,
+        address: &AccountAddress,
+        key_pair: &KeyPair,
+        password: &str,
+    ) -> Result<()> {
+        let encrypted_prikey = encrypt_with_kdf(
+            password.as_bytes(),
+            &key_pair.private_key.to_bytes(),
+            self.kdf,
+        )?;
         self.store.save_to_account(
-            &address,
+            address,
             KEY_NAME_ENCRYPTED_PRIVATE_KEY.to_string(),
             encrypted_prikey,
         )?;
         Ok(())
     }
 
+    /// Re-encrypt the private key if it is encrypted by an outdated kdf, such as the legacy pbkdf2.
+    fn upgrade_encryption(
+        &self,
+        address: &AccountAddress,
+        key_pair: &KeyPair,
+        password: &str,
+    ) -> Result<()> {
+        let key_data = self
+            .store
+            .get_from_account(address, KEY_NAME_ENCRYPTED_PRIVATE_KEY)?
+            .ok_or_else(|| WalletError::AccountPrivateKeyMissing(*address))?;
+        if kdf_of(&key_data)? != self.kdf {
+            self.save_private_key(address, key_pair, password)?;
+        }
+        Ok(())
+    }
+
     fn unlock_prikey(&self, address: &AccountAddress, password: &str) -> Result<KeyPair> {
         let cached_public_key = {
             let mut cache_guard = self.key_cache.write().unwrap();
@@ -276,11 +324,13 @@ mod tests {
     use super::KeyStoreWallet;
     use super::RawUserTransaction;
     use super::Wallet;
+    use super::{kdf_of, KdfParams, KEY_NAME_ENCRYPTED_PRIVATE_KEY};
     use crate::file_wallet_store::FileWalletStore;
     use crate::keystore_wallet::gen_keypair;
     use anyhow::Result;
     use starcoin_types::account_address;
     use std::time::Duration;
+    use wallet_api::WalletStore;
 
     #[test]
     fn test_wallet() -> Result<()> {
@@ -325,6 +375,58 @@ mod tests {
         Ok(())
     }
 
+    fn light_kdf() -> KdfParams {
+        KdfParams::Scrypt {
+            log_n: 10,
+            r: 8,
+            p: 1,
+        }
+    }
+
+    #[test]
+    fn test_wallet_change_password() -> Result<()> {
+        let tmp_path = tempfile::tempdir()?;
+        let wallet =
+            KeyStoreWallet::new_with_kdf(FileWalletStore::new(tmp_path.path()), light_kdf())?;
+        let account = wallet.create_account("old")?;
+        wallet.change_password(&account.address, "old", "new")?;
+        assert!(wallet
+            .unlock_account(account.address, "old", Duration::from_secs(5))
+            .is_err());
+        wallet.unlock_account(account.address, "new", Duration::from_secs(5))?;
+        assert!(wallet
+            .change_password(&account.address, "old", "new2")
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_wallet_upgrade_encryption() -> Result<()> {
+        let tmp_path = tempfile::tempdir()?;
+        let pbkdf2 = KdfParams::Pbkdf2 { iterations: 1000 };
+        let account = {
+            let old_wallet =
+                KeyStoreWallet::new_with_kdf(FileWalletStore::new(tmp_path.path()), pbkdf2)?;
+            old_wallet.create_account("pass")?
+        };
+        let store = FileWalletStore::new(tmp_path.path());
+        let key_data = store
+            .get_from_account(&account.address, KEY_NAME_ENCRYPTED_PRIVATE_KEY)?
+            .unwrap();
+        assert_eq!(kdf_of(&key_data)?, pbkdf2);
+
+        let wallet = KeyStoreWallet::new_with_kdf(store, light_kdf())?;
+        wallet.unlock_account(account.address, "pass", Duration::from_secs(5))?;
+        let key_data = wallet
+            .store
+            .get_from_account(&account.address, KEY_NAME_ENCRYPTED_PRIVATE_KEY)?
+            .unwrap();
+        assert_eq!(kdf_of(&key_data)?, light_kdf());
+        wallet.lock_account(account.address)?;
+        wallet.unlock_account(account.address, "pass", Duration::from_secs(5))?;
+        Ok(())
+    }
+
     #[test]
     fn test_wallet_get_account_details() -> Result<()> {
         let tmp_path = tempfile::tempdir()?;
@@ -1,6 +1,8 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(unix)]
+pub mod external_signer_wallet;
 pub mod file_wallet_store;
 pub mod keystore_wallet;
 
@@ -3,35 +3,74 @@
 
 use crate::message::{WalletRequest, WalletResponse};
 use crate::service::WalletServiceImpl;
-use actix::{Actor, Addr, Context, Handler};
+use actix::{Actor, Addr, Handler, SyncArbiter, SyncContext};
 use anyhow::Result;
-use starcoin_config::NodeConfig;
+use starcoin_config::{KeyStoreKdf, NodeConfig};
+use starcoin_decrypt::KdfParams;
+use starcoin_logger::prelude::*;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
+#[cfg(unix)]
+use starcoin_wallet_lib::external_signer_wallet::ExternalSignerWallet;
 use starcoin_wallet_lib::{file_wallet_store::FileWalletStore, keystore_wallet::KeyStoreWallet};
 
 use starcoin_wallet_api::error::AccountServiceError;
 use starcoin_wallet_api::{ServiceResult, Wallet, WalletAccount, WalletAsyncService, WalletResult};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 pub struct WalletActor {
-    service: WalletServiceImpl<KeyStoreWallet<FileWalletStore>>,
+    service: Arc<WalletServiceImpl<Box<dyn Wallet + Send + Sync>>>,
 }
 
 impl WalletActor {
     pub fn launch(config: Arc<NodeConfig>) -> Result<WalletActorRef> {
         let vault_config = &config.vault;
         let file_store = FileWalletStore::new(vault_config.dir());
-        let wallet = KeyStoreWallet::new(file_store)?;
-        let actor = WalletActor {
-            service: WalletServiceImpl::new(wallet),
+        let wallet: Box<dyn Wallet + Send + Sync> = match vault_config.external_signer_socket() {
+            Some(socket) => Self::external_signer_wallet(file_store, socket)?,
+            None => {
+                let kdf = match vault_config.kdf {
+                    KeyStoreKdf::Pbkdf2 { iterations } => KdfParams::Pbkdf2 { iterations },
+                    KeyStoreKdf::Scrypt { log_n, r, p } => KdfParams::Scrypt { log_n, r, p },
+                };
+                Box::new(KeyStoreWallet::new_with_kdf(file_store, kdf)?)
+            }
         };
-        Ok(WalletActorRef(actor.start()))
+        let service = Arc::new(WalletServiceImpl::new(wallet));
+        // key derivation and the external signer block the caller, so run the actor on a
+        // dedicated thread instead of the system arbiter.
+        let addr = SyncArbiter::start(1, move || WalletActor {
+            service: service.clone(),
+        });
+        Ok(WalletActorRef(addr))
+    }
+
+    #[cfg(unix)]
+    fn external_signer_wallet(
+        file_store: FileWalletStore,
+        socket: PathBuf,
+    ) -> Result<Box<dyn Wallet + Send + Sync>> {
+        let wallet = ExternalSignerWallet::new(file_store, socket);
+        // the signer may start later, the accounts can be synced on next launch.
+        match wallet.sync_accounts() {
+            Ok(accounts) => info!("sync {} accounts from external signer.", accounts.len()),
+            Err(e) => warn!("fail to sync accounts from external signer: {:?}", e),
+        }
+        Ok(Box::new(wallet))
+    }
+
+    #[cfg(not(unix))]
+    fn external_signer_wallet(
+        _file_store: FileWalletStore,
+        _socket: PathBuf,
+    ) -> Result<Box<dyn Wallet + Send + Sync>> {
+        anyhow::bail!("external signer wallet is only supported on unix.")
     }
 }
 
 impl Actor for WalletActor {
-    type Context = Context<Self>;
+    type Context = SyncContext<Self>;
 }
 
 impl Handler<WalletRequest> for WalletActor {
@@ -63,6 +102,18 @@ impl Handler<WalletRequest> for WalletActor {
                 let data = self.service.export_account(&address, password.as_str())?;
                 WalletResponse::ExportAccountResponse(data)
             }
+            WalletRequest::ChangePassword {
+                address,
+                old_password,
+                new_password,
+            } => {
+                self.service.change_password(
+                    &address,
+                    old_password.as_str(),
+                    new_password.as_str(),
+                )?;
+                WalletResponse::None
+            }
             WalletRequest::ImportAccount {
                 address,
                 password,
@@ -216,6 +267,28 @@ impl WalletAsyncService for WalletActorRef {
             panic!("Unexpect response type.")
         }
     }
+
+    async fn change_password(
+        self,
+        address: AccountAddress,
+        old_password: String,
+        new_password: String,
+    ) -> ServiceResult<()> {
+        let response = self
+            .0
+            .send(WalletRequest::ChangePassword {
+                address,
+                old_password,
+                new_password,
+            })
+            .await
+            .map_err(|e| AccountServiceError::OtherError(Box::new(e)))??;
+        if let WalletResponse::None = response {
+            Ok(())
+        } else {
+            panic!("Unexpect response type.")
+        }
+    }
 }
 
 #[cfg(test)]
@@ -24,6 +24,11 @@ pub enum WalletRequest {
         address: AccountAddress,
         password: String,
     },
+    ChangePassword {
+        address: AccountAddress,
+        old_password: String,
+        new_password: String,
+    },
 }
 
 impl Message for WalletRequest {
@@ -67,6 +67,16 @@ where
         self.wallet.lock_account(address)
     }
 
+    fn change_password(
+        &self,
+        address: &AccountAddress,
+        old_password: &str,
+        new_password: &str,
+    ) -> WalletResult<()> {
+        self.wallet
+            .change_password(address, old_password, new_password)
+    }
+
     fn sign_txn(&self, raw_txn: RawUserTransaction) -> WalletResult<SignedUserTransaction> {
         self.wallet.sign_txn(raw_txn)
     }